@@ -82,11 +82,7 @@ fn main() -> Result<(), FitbitError> {
             println!("\n=== Activity Summary ===");
             println!("Steps: {}", activity_data.summary.steps);
             println!("Calories burned: {}", activity_data.summary.calories_out);
-            println!(
-                "Active minutes: {}",
-                activity_data.summary.fairly_active_minutes
-                    + activity_data.summary.very_active_minutes
-            );
+            println!("Active minutes: {}", activity_data.total_active_minutes());
 
             // Display heart rate data if available
             if !activity_data.summary.heart_rate_zones.is_empty() {
@@ -94,34 +90,27 @@ fn main() -> Result<(), FitbitError> {
                 for zone in &activity_data.summary.heart_rate_zones {
                     println!("  {:?}: {} minutes", zone.name, zone.minutes);
                 }
-
-                println!(
-                    "\nResting heart rate: {}",
-                    activity_data.summary.resting_heart_rate
-                );
+            }
+            if let Some(resting_heart_rate) = activity_data.summary.resting_heart_rate {
+                println!("\nResting heart rate: {}", resting_heart_rate);
             }
 
             // Display goal progress
             println!("\nGoal Progress:");
-            println!(
-                "  Steps: {}/{} ({}%)",
-                activity_data.summary.steps,
-                activity_data.goals.steps,
-                (activity_data.summary.steps as f64 / activity_data.goals.steps as f64 * 100.0)
-                    .round()
-            );
-
-            println!(
-                "  Active minutes: {}/{} ({}%)",
-                activity_data.summary.fairly_active_minutes
-                    + activity_data.summary.very_active_minutes,
-                activity_data.goals.active_minutes,
-                ((activity_data.summary.fairly_active_minutes
-                    + activity_data.summary.very_active_minutes) as f64
-                    / activity_data.goals.active_minutes as f64
-                    * 100.0)
-                    .round()
-            );
+            if let Some(progress) = activity_data.steps_goal_progress() {
+                println!(
+                    "  Steps: {}/{} ({})",
+                    activity_data.summary.steps, activity_data.goals.steps, progress
+                );
+            }
+            if let Some(progress) = activity_data.active_minutes_goal_progress() {
+                println!(
+                    "  Active minutes: {}/{} ({})",
+                    activity_data.total_active_minutes(),
+                    activity_data.goals.active_minutes,
+                    progress
+                );
+            }
         }
         Err(err) => {
             eprintln!("Failed to fetch activity data: {}", err);
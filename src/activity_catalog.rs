@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+/// The catalog of activity types (`/1/activities.json`)
+///
+/// Logging an activity requires the id of an activity type, and optionally one of its
+/// levels, from this catalog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityCatalog {
+    pub categories: Vec<ActivityCategory>,
+}
+
+impl ActivityCatalog {
+    /// Returns all activity types of all categories and subcategories
+    pub fn activities(&self) -> Vec<&ActivityDefinition> {
+        let mut activities = Vec::new();
+        for category in &self.categories {
+            category.collect_activities(&mut activities);
+        }
+
+        activities
+    }
+
+    /// Returns the activity type with the given id, if it is part of the catalog
+    pub fn find(&self, id: u64) -> Option<&ActivityDefinition> {
+        self.activities()
+            .into_iter()
+            .find(|activity| activity.id == id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityCategory {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub activities: Vec<ActivityDefinition>,
+    #[serde(default)]
+    pub sub_categories: Vec<ActivityCategory>,
+}
+
+impl ActivityCategory {
+    fn collect_activities<'a>(&'a self, activities: &mut Vec<&'a ActivityDefinition>) {
+        activities.extend(&self.activities);
+        for sub_category in &self.sub_categories {
+            sub_category.collect_activities(activities);
+        }
+    }
+}
+
+/// A type of activity that can be logged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDefinition {
+    pub id: u64,
+    pub name: String,
+    /// `PUBLIC` for built-in activities, `PRIVATE` for the user's custom activities
+    pub access_level: String,
+    /// `true` if the activity can be logged with a distance
+    pub has_speed: bool,
+    /// Metabolic equivalent; not reported for activities that only have per-level values
+    pub mets: Option<f64>,
+    /// Intensity levels with their own MET values, e.g. speed ranges for cycling
+    #[serde(default)]
+    pub activity_levels: Vec<ActivityDefinitionLevel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDefinitionLevel {
+    pub id: u64,
+    pub name: String,
+    pub mets: f64,
+    /// -1 if the level has no lower speed bound
+    #[serde(rename = "minSpeedMPH")]
+    pub min_speed_mph: f64,
+    /// -1 if the level has no upper speed bound
+    #[serde(rename = "maxSpeedMPH")]
+    pub max_speed_mph: f64,
+}
+
+/// Response of the single activity type endpoint (`/1/activities/{id}.json`)
+#[derive(Debug, Deserialize)]
+pub(crate) struct ActivityDefinitionResponse {
+    pub activity: ActivityDefinition,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_activity_catalog() {
+        let json_str = r#"{
+            "categories": [
+                {
+                    "activities": [
+                        {
+                            "accessLevel": "PUBLIC",
+                            "activityLevels": [
+                                {"id": 3016, "maxSpeedMPH": -1, "mets": 8.5, "minSpeedMPH": -1, "name": "Moderate"},
+                                {"id": 3017, "maxSpeedMPH": 12, "mets": 6.8, "minSpeedMPH": 10, "name": "10 - 12 mph"}
+                            ],
+                            "hasSpeed": true,
+                            "id": 90001,
+                            "name": "Bicycling"
+                        },
+                        {"accessLevel": "PUBLIC", "hasSpeed": true, "id": 90009, "mets": 7, "name": "Run"}
+                    ],
+                    "id": 0,
+                    "name": "Sports and Workouts",
+                    "subCategories": [
+                        {
+                            "activities": [
+                                {"accessLevel": "PUBLIC", "hasSpeed": false, "id": 15000, "mets": 4, "name": "Aerobic, general"}
+                            ],
+                            "id": 1,
+                            "name": "Aerobics"
+                        }
+                    ]
+                }
+            ]
+        }"#;
+
+        let catalog: ActivityCatalog =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(catalog.categories.len(), 1);
+        assert_eq!(catalog.activities().len(), 3);
+
+        let cycling = catalog.find(90001).expect("Missing activity");
+        assert_eq!(cycling.mets, None);
+        assert_eq!(cycling.activity_levels[1].min_speed_mph, 10.0);
+        assert_eq!(cycling.activity_levels[1].mets, 6.8);
+
+        // Activities of subcategories are found as well
+        assert_eq!(catalog.find(15000).unwrap().name, "Aerobic, general");
+        assert!(catalog.find(1).is_none());
+    }
+}
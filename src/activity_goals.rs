@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+/// The period an activity goal applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GoalPeriod {
+    Daily,
+    Weekly,
+}
+
+impl GoalPeriod {
+    /// Returns the period path segment used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalPeriod::Daily => "daily",
+            GoalPeriod::Weekly => "weekly",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ActivityGoalsResponse {
+    pub goals: ActivityGoals,
+}
+
+/// Activity goals for a [`GoalPeriod`]
+///
+/// Weekly goals don't include `calories_out` and `active_minutes`. When updating goals,
+/// only the goals that are set are changed; `active_zone_minutes` can't be updated.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityGoals {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calories_out: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steps: Option<u32>,
+    /// Distance in the client's distance unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub floors: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_minutes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_zone_minutes: Option<u32>,
+}
+
+impl ActivityGoals {
+    /// Returns the form parameters for updating the goals that are set
+    pub(crate) fn to_form(&self) -> Vec<(&'static str, String)> {
+        let mut form = Vec::new();
+        if let Some(calories_out) = self.calories_out {
+            form.push(("caloriesOut", calories_out.to_string()));
+        }
+        if let Some(steps) = self.steps {
+            form.push(("steps", steps.to_string()));
+        }
+        if let Some(distance) = self.distance {
+            form.push(("distance", distance.to_string()));
+        }
+        if let Some(floors) = self.floors {
+            form.push(("floors", floors.to_string()));
+        }
+        if let Some(active_minutes) = self.active_minutes {
+            form.push(("activeMinutes", active_minutes.to_string()));
+        }
+
+        form
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_goals() {
+        let daily: ActivityGoalsResponse = serde_json::from_str(
+            r#"{"goals": {"activeMinutes": 30, "activeZoneMinutes": 22, "caloriesOut": 2691,
+                "distance": 8.05, "floors": 10, "steps": 10000}}"#,
+        )
+        .expect("Failed to parse JSON");
+        assert_eq!(daily.goals.calories_out, Some(2691));
+        assert_eq!(daily.goals.active_zone_minutes, Some(22));
+
+        let weekly: ActivityGoalsResponse = serde_json::from_str(
+            r#"{"goals": {"activeZoneMinutes": 150, "distance": 56.33, "floors": 70, "steps": 70000}}"#,
+        )
+        .expect("Failed to parse JSON");
+        assert_eq!(weekly.goals.steps, Some(70000));
+        assert_eq!(weekly.goals.calories_out, None);
+        assert_eq!(weekly.goals.active_minutes, None);
+    }
+
+    #[test]
+    fn test_goals_update_form() {
+        let goals = ActivityGoals {
+            steps: Some(12000),
+            distance: Some(9.5),
+            active_zone_minutes: Some(30),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            goals.to_form(),
+            [
+                ("steps", "12000".to_string()),
+                ("distance", "9.5".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use crate::activity_summary::{Activity, HeartRateZone, deserialize_heart_rate_zones};
+use crate::pagination::Pagination;
+use crate::units::{DistanceUnit, DistanceValue};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A page of the activity log list (`/1/user/-/activities/list.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityLogListResponse {
+    pub activities: Vec<ActivityLog>,
+    pub pagination: Pagination,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A recorded exercise, with the details that the daily activity summary omits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityLog {
+    pub log_id: u64,
+    pub activity_name: String,
+    pub activity_type_id: u64,
+    /// How the exercise was recorded, e.g. `auto_detected`, `manual` or `tracker`
+    pub log_type: String,
+    pub start_time: DateTime<FixedOffset>,
+    /// Duration in milliseconds, including pauses
+    pub duration: u64,
+    /// Duration in milliseconds, excluding pauses
+    pub active_duration: u64,
+    pub calories: u32,
+    /// Not reported for activities without steps
+    #[serde(default)]
+    pub steps: u32,
+    /// Not reported for activities without distance
+    pub distance: Option<f64>,
+    /// Unit of `distance`, e.g. `Kilometer`
+    pub distance_unit: Option<String>,
+    /// Seconds per distance unit
+    pub pace: Option<f64>,
+    /// Distance units per hour
+    pub speed: Option<f64>,
+    pub elevation_gain: Option<f64>,
+    /// Not reported if no heart rate was recorded during the exercise
+    pub average_heart_rate: Option<u32>,
+    /// Time spent in each heart rate zone during the exercise
+    #[serde(default, deserialize_with = "deserialize_heart_rate_zones")]
+    pub heart_rate_zones: Vec<HeartRateZone>,
+    /// Time spent at each activity level during the exercise
+    #[serde(default)]
+    pub activity_level: Vec<ActivityLevelMinutes>,
+    /// `true` if the exercise was recorded with GPS; the route is available from `tcx_link`
+    #[serde(default)]
+    pub has_gps: bool,
+    pub tcx_link: Option<String>,
+    /// Length of the pool; only reported for swims
+    pub pool_length: Option<f64>,
+    /// Unit of `pool_length`, e.g. `Meter` or `Yard`
+    pub pool_length_unit: Option<String>,
+    /// Number of pool lengths swum; only reported for swims
+    pub swim_lengths: Option<u32>,
+    pub last_modified: DateTime<Utc>,
+}
+
+impl ActivityLog {
+    /// Returns the duration of the exercise including pauses
+    pub fn get_duration(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.duration as i64)
+    }
+
+    /// Returns the duration of the exercise excluding pauses
+    pub fn get_active_duration(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.active_duration as i64)
+    }
+
+    /// Returns the distance with its unit, or `None` if the exercise has no distance or its
+    /// unit is not a distance unit this crate knows
+    pub fn distance_value(&self) -> Option<DistanceValue> {
+        let unit = match self.distance_unit.as_deref()? {
+            "Kilometer" => DistanceUnit::Kilometers,
+            "Mile" => DistanceUnit::Miles,
+            _ => return None,
+        };
+
+        self.distance
+            .map(|distance| DistanceValue::new(distance, unit))
+    }
+
+    /// Returns the average time per pool length, if this is a swim with lengths
+    ///
+    /// The API doesn't report stroke counts, so this is the closest available measure to
+    /// SWOLF.
+    pub fn time_per_swim_length(&self) -> Option<chrono::Duration> {
+        let lengths = self.swim_lengths.filter(|&lengths| lengths > 0)?;
+
+        Some(chrono::Duration::milliseconds(
+            (self.active_duration / lengths as u64) as i64,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityLevelMinutes {
+    /// The activity level, e.g. `sedentary`, `lightly`, `fairly` or `very`
+    pub name: String,
+    pub minutes: u32,
+}
+
+impl ActivityLevelMinutes {
+    /// Returns the time spent at the activity level
+    pub fn duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes as i64)
+    }
+}
+
+/// The exercise to create with [`log_activity`](crate::FitbitClientTrait::log_activity)
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use fitbit_rs::LogActivityRequest;
+///
+/// let start = NaiveDate::from_ymd_opt(2025, 3, 29)
+///     .unwrap()
+///     .and_hms_opt(7, 30, 0)
+///     .unwrap();
+///
+/// // A 5 km run, using the "Run" activity type from the catalog
+/// let run = LogActivityRequest::with_activity_id(90009, start, chrono::Duration::minutes(28))
+///     .distance(5.0, "Kilometer");
+///
+/// // A custom activity needs the burned calories
+/// let climbing = LogActivityRequest::with_activity_name(
+///     "Bouldering",
+///     420,
+///     start,
+///     chrono::Duration::minutes(90),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogActivityRequest {
+    activity: LoggedActivity,
+    start_time: NaiveDateTime,
+    duration: chrono::Duration,
+    distance: Option<(f64, String)>,
+    manual_calories: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LoggedActivity {
+    Id(u64),
+    Name(String),
+}
+
+impl LogActivityRequest {
+    /// Creates a request for an activity type from the catalog
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The id of the activity type or one of its levels
+    /// * `start_time` - The time the exercise started (minute precision)
+    /// * `duration` - The duration of the exercise
+    pub fn with_activity_id(
+        activity_id: u64,
+        start_time: NaiveDateTime,
+        duration: chrono::Duration,
+    ) -> Self {
+        Self {
+            activity: LoggedActivity::Id(activity_id),
+            start_time,
+            duration,
+            distance: None,
+            manual_calories: None,
+        }
+    }
+
+    /// Creates a request for a custom activity that is not in the catalog
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_name` - The name to log the exercise under
+    /// * `manual_calories` - The calories burned, which Fitbit can't estimate for custom
+    ///   activities
+    /// * `start_time` - The time the exercise started (minute precision)
+    /// * `duration` - The duration of the exercise
+    pub fn with_activity_name(
+        activity_name: impl Into<String>,
+        manual_calories: u32,
+        start_time: NaiveDateTime,
+        duration: chrono::Duration,
+    ) -> Self {
+        Self {
+            activity: LoggedActivity::Name(activity_name.into()),
+            start_time,
+            duration,
+            distance: None,
+            manual_calories: Some(manual_calories),
+        }
+    }
+
+    /// Sets the distance covered, in the given unit (e.g. `Kilometer` or `Mile`)
+    pub fn distance(mut self, distance: f64, unit: impl Into<String>) -> Self {
+        self.distance = Some((distance, unit.into()));
+        self
+    }
+
+    /// Overrides the calories Fitbit estimates for the exercise
+    pub fn manual_calories(mut self, calories: u32) -> Self {
+        self.manual_calories = Some(calories);
+        self
+    }
+
+    /// Returns the duration of the exercise
+    pub(crate) fn duration(&self) -> chrono::Duration {
+        self.duration
+    }
+
+    /// Returns the form parameters of the request
+    pub(crate) fn to_form(&self) -> Vec<(&'static str, String)> {
+        let mut form = match &self.activity {
+            LoggedActivity::Id(id) => vec![("activityId", id.to_string())],
+            LoggedActivity::Name(name) => vec![("activityName", name.clone())],
+        };
+        form.push(("startTime", self.start_time.format("%H:%M").to_string()));
+        form.push((
+            "durationMillis",
+            self.duration.num_milliseconds().to_string(),
+        ));
+        form.push(("date", self.start_time.format("%Y-%m-%d").to_string()));
+        if let Some((distance, unit)) = &self.distance {
+            form.push(("distance", distance.to_string()));
+            form.push(("distanceUnit", unit.clone()));
+        }
+        if let Some(calories) = self.manual_calories {
+            form.push(("manualCalories", calories.to_string()));
+        }
+
+        form
+    }
+}
+
+/// Response of the create activity log endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatedActivityLog {
+    pub activity_log: Activity,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity_summary::HeartRateZoneName;
+
+    #[test]
+    fn test_parse_activity_log_list() {
+        let json_str = r#"{
+            "activities": [
+                {
+                    "activeDuration": 1536000,
+                    "activityLevel": [
+                        {"minutes": 0, "name": "sedentary"},
+                        {"minutes": 1, "name": "lightly"},
+                        {"minutes": 4, "name": "fairly"},
+                        {"minutes": 21, "name": "very"}
+                    ],
+                    "activityName": "Walk",
+                    "activityTypeId": 90013,
+                    "averageHeartRate": 86,
+                    "calories": 204,
+                    "caloriesLink": "https://api.fitbit.com/1/user/-/activities/calories/date/2019-01-03/2019-01-03/1min/time/12:08/12:34.json",
+                    "distance": 1.2,
+                    "distanceUnit": "Kilometer",
+                    "duration": 1536000,
+                    "elevationGain": 0,
+                    "hasActiveZoneMinutes": true,
+                    "hasGps": false,
+                    "heartRateLink": "https://api.fitbit.com/1/user/-/activities/heart/date/2019-01-03/2019-01-03/1sec/time/12:08:00/12:34:00.json",
+                    "heartRateZones": [
+                        {"caloriesOut": 204.1, "max": 101, "min": 30, "minutes": 22, "name": "Out of Range"},
+                        {"caloriesOut": 0, "max": 141, "min": 101, "minutes": 4, "name": "Fat Burn"},
+                        {"caloriesOut": 0, "max": 171, "min": 141, "minutes": 0, "name": "Cardio"},
+                        {"caloriesOut": 0, "max": 220, "min": 171, "minutes": 0, "name": "Peak"}
+                    ],
+                    "lastModified": "2019-01-04T19:31:15.000Z",
+                    "logId": 19018673358,
+                    "logType": "auto_detected",
+                    "manualValuesSpecified": {"calories": false, "distance": false, "steps": false},
+                    "originalDuration": 1536000,
+                    "originalStartTime": "2019-01-03T12:08:00.000-08:00",
+                    "pace": 1280,
+                    "speed": 2.8125,
+                    "startTime": "2019-01-03T12:08:00.000-08:00",
+                    "steps": 1799,
+                    "tcxLink": "https://api.fitbit.com/1/user/-/activities/19018673358.tcx"
+                }
+            ],
+            "pagination": {
+                "afterDate": "2019-01-01",
+                "limit": 1,
+                "next": "https://api.fitbit.com/1/user/-/activities/list.json?offset=1&limit=1&sort=asc&afterDate=2019-01-01",
+                "offset": 0,
+                "previous": "",
+                "sort": "asc"
+            }
+        }"#;
+
+        let response: ActivityLogListResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.activities.len(), 1);
+        assert!(response.pagination.next_url().is_some());
+
+        let walk = &response.activities[0];
+        assert_eq!(walk.log_id, 19018673358);
+        assert_eq!(walk.activity_name, "Walk");
+        assert_eq!(walk.log_type, "auto_detected");
+        assert_eq!(
+            walk.start_time,
+            DateTime::parse_from_rfc3339("2019-01-03T12:08:00-08:00").unwrap()
+        );
+        assert_eq!(walk.get_active_duration(), chrono::Duration::seconds(1536));
+        assert!(walk.get_duration() >= walk.get_active_duration());
+        assert_eq!(walk.distance, Some(1.2));
+        assert_eq!(
+            walk.distance_value(),
+            Some(DistanceValue::new(1.2, DistanceUnit::Kilometers))
+        );
+        assert_eq!(walk.pace, Some(1280.0));
+        assert_eq!(walk.average_heart_rate, Some(86));
+        assert_eq!(walk.heart_rate_zones.len(), 4);
+        assert_eq!(walk.heart_rate_zones[1].name, HeartRateZoneName::FatBurn);
+        assert_eq!(walk.heart_rate_zones[1].minutes, 4);
+        assert_eq!(walk.activity_level[3].minutes, 21);
+        assert_eq!(
+            walk.activity_level[3].duration(),
+            chrono::Duration::minutes(21)
+        );
+        assert!(!walk.has_gps);
+        assert_eq!(walk.swim_lengths, None);
+        assert_eq!(walk.time_per_swim_length(), None);
+    }
+
+    #[test]
+    fn test_parse_swim_activity_log() {
+        let json_str = r#"{
+            "activeDuration": 1800000,
+            "activityLevel": [
+                {"minutes": 2, "name": "sedentary"},
+                {"minutes": 8, "name": "lightly"},
+                {"minutes": 12, "name": "fairly"},
+                {"minutes": 8, "name": "very"}
+            ],
+            "activityName": "Swim",
+            "activityTypeId": 90024,
+            "calories": 312,
+            "distance": 1.5,
+            "distanceUnit": "Kilometer",
+            "duration": 1800000,
+            "lastModified": "2019-01-05T08:01:12.000Z",
+            "logId": 19018673412,
+            "logType": "tracker",
+            "pace": 1200,
+            "poolLength": 25,
+            "poolLengthUnit": "Meter",
+            "speed": 3,
+            "startTime": "2019-01-05T07:30:00.000-08:00",
+            "swimLengths": 60
+        }"#;
+
+        let swim: ActivityLog = serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(swim.pool_length, Some(25.0));
+        assert_eq!(swim.pool_length_unit.as_deref(), Some("Meter"));
+        assert_eq!(swim.swim_lengths, Some(60));
+        assert_eq!(swim.pace, Some(1200.0));
+        assert_eq!(swim.steps, 0);
+        assert_eq!(
+            swim.time_per_swim_length(),
+            Some(chrono::Duration::seconds(30))
+        );
+    }
+
+    #[test]
+    fn test_log_activity_request_form() {
+        let start = chrono::NaiveDate::from_ymd_opt(2025, 3, 29)
+            .unwrap()
+            .and_hms_opt(7, 30, 0)
+            .unwrap();
+
+        let run = LogActivityRequest::with_activity_id(90009, start, chrono::Duration::minutes(28))
+            .distance(5.0, "Kilometer");
+        assert_eq!(
+            run.to_form(),
+            [
+                ("activityId", "90009".to_string()),
+                ("startTime", "07:30".to_string()),
+                ("durationMillis", "1680000".to_string()),
+                ("date", "2025-03-29".to_string()),
+                ("distance", "5".to_string()),
+                ("distanceUnit", "Kilometer".to_string()),
+            ]
+        );
+
+        let climbing = LogActivityRequest::with_activity_name(
+            "Bouldering",
+            420,
+            start,
+            chrono::Duration::minutes(90),
+        );
+        assert_eq!(
+            climbing.to_form(),
+            [
+                ("activityName", "Bouldering".to_string()),
+                ("startTime", "07:30".to_string()),
+                ("durationMillis", "5400000".to_string()),
+                ("date", "2025-03-29".to_string()),
+                ("manualCalories", "420".to_string()),
+            ]
+        );
+    }
+}
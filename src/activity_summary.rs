@@ -1,46 +1,363 @@
-use serde::Deserialize;
+use crate::heart_rate::CustomHeartRateZone;
+use crate::units::{DistanceValue, UnitSystem};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
 pub struct ActivitySummaryResponse {
     pub activities: Vec<Activity>,
     pub summary: Summary,
     pub goals: Goals,
+    /// The unit system the response was requested in, which determines the unit of
+    /// distances; set by the client
+    #[serde(default, rename = "unitSystem")]
+    pub unit_system: UnitSystem,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl ActivitySummaryResponse {
     pub fn get_steps(&self) -> u32 {
         self.summary.steps
     }
+
+    /// Returns the minutes of moderate to vigorous activity (fairly and very active), which
+    /// is what the active minutes goal counts
+    pub fn total_active_minutes(&self) -> i32 {
+        self.summary.fairly_active_minutes + self.summary.very_active_minutes
+    }
+
+    /// Returns [`total_active_minutes`](Self::total_active_minutes) as a duration
+    pub fn total_active_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.total_active_minutes() as i64)
+    }
+
+    /// Returns the calories burned, including the basal metabolic rate, as an energy
+    #[cfg(feature = "uom")]
+    pub fn energy_out(&self) -> uom::si::f64::Energy {
+        crate::units::energy(self.summary.calories_out as f64)
+    }
+
+    /// Returns the elevation climbed as a length, or `None` if it wasn't reported
+    ///
+    /// The elevation is reported in feet for US units and in meters otherwise.
+    #[cfg(feature = "uom")]
+    pub fn elevation_length(&self) -> Option<uom::si::f64::Length> {
+        use uom::si::length::{foot, meter};
+
+        self.summary
+            .elevation
+            .map(|elevation| match self.unit_system {
+                UnitSystem::Us => uom::si::f64::Length::new::<foot>(elevation),
+                UnitSystem::Metric | UnitSystem::Uk => {
+                    uom::si::f64::Length::new::<meter>(elevation)
+                }
+            })
+    }
+
+    /// Returns the distance covered during the given kind of activity, if it was reported
+    pub fn distance(&self, activity: ActivityType) -> Option<DistanceValue> {
+        self.summary
+            .distances
+            .iter()
+            .find(|distance| distance.activity == activity)
+            .map(|distance| self.distance_value(distance.distance))
+    }
+
+    /// Returns the total distance of the day
+    pub fn total_distance(&self) -> DistanceValue {
+        self.distance(ActivityType::Total)
+            .unwrap_or_else(|| self.distance_value(0.0))
+    }
+
+    /// Returns the total distance of the day in kilometers
+    pub fn total_distance_km(&self) -> f64 {
+        self.total_distance().kilometers()
+    }
+
+    /// Returns the total distance of the day in miles
+    pub fn total_distance_miles(&self) -> f64 {
+        self.total_distance().miles()
+    }
+
+    /// Returns the distance of one of the response's activities
+    pub fn activity_distance(&self, activity: &Activity) -> DistanceValue {
+        self.distance_value(activity.distance)
+    }
+
+    /// Returns the daily distance goal
+    pub fn distance_goal(&self) -> DistanceValue {
+        self.distance_value(self.goals.distance)
+    }
+
+    /// Attaches the distance unit of the response's unit system to a raw distance
+    fn distance_value(&self, distance: f64) -> DistanceValue {
+        DistanceValue::new(distance, self.unit_system.distance_unit())
+    }
+
+    /// Returns the progress towards the daily steps goal, or `None` if no goal is set
+    pub fn steps_goal_progress(&self) -> Option<Percentage> {
+        Percentage::of(self.summary.steps as f64, self.goals.steps as f64)
+    }
+
+    /// Returns the progress towards the daily calories goal, or `None` if no goal is set
+    pub fn calories_goal_progress(&self) -> Option<Percentage> {
+        Percentage::of(
+            self.summary.calories_out as f64,
+            self.goals.calories_out as f64,
+        )
+    }
+
+    /// Returns the progress towards the daily active minutes goal, or `None` if no goal is
+    /// set
+    pub fn active_minutes_goal_progress(&self) -> Option<Percentage> {
+        Percentage::of(
+            self.total_active_minutes() as f64,
+            self.goals.active_minutes as f64,
+        )
+    }
+}
+
+/// A percentage, e.g. of a goal reached; 100 means the goal was met exactly
+///
+/// Values above 100 are kept, so exceeding a goal is visible.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Percentage(pub f64);
+
+impl Percentage {
+    /// Returns `value` as a percentage of `total`, or `None` if `total` is not positive
+    pub(crate) fn of(value: f64, total: f64) -> Option<Self> {
+        (total > 0.0).then(|| Percentage(value / total * 100.0))
+    }
+
+    /// Returns the percentage value, e.g. `85.0` for 85%
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Returns `true` if the percentage is at least 100
+    pub fn is_complete(&self) -> bool {
+        self.0 >= 100.0
+    }
+}
+
+impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0}%", self.0)
+    }
 }
 
-#[derive(Debug, Deserialize)]
+/// An exercise logged for the day, either manually or by SmartTrack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Activity {
-    // TODO
+    pub log_id: u64,
+    pub activity_id: u64,
+    pub activity_parent_id: u64,
+    pub activity_parent_name: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub calories: u32,
+    /// Distance in the client's distance unit; not reported for activities without distance.
+    /// See [`ActivitySummaryResponse::activity_distance`]
+    #[serde(default)]
+    pub distance: f64,
+    /// Not reported for activities without steps
+    #[serde(default)]
+    pub steps: u32,
+    /// Duration in milliseconds
+    #[serde(rename = "duration")]
+    pub duration_ms: u64,
+    pub start_date: NaiveDate,
+    /// Only meaningful if `has_start_time` is set
+    #[serde(
+        deserialize_with = "deserialize_hour_minute",
+        serialize_with = "serialize_hour_minute"
+    )]
+    pub start_time: NaiveTime,
+    pub has_start_time: bool,
+    #[serde(default)]
+    pub has_active_zone_minutes: bool,
+    pub is_favorite: bool,
+    pub last_modified: DateTime<Utc>,
+}
+
+impl Activity {
+    /// Returns the duration of the activity
+    pub fn duration(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.duration_ms as i64)
+    }
+
+    /// Returns the start of the activity, if it was logged with a start time
+    pub fn start(&self) -> Option<NaiveDateTime> {
+        self.has_start_time
+            .then(|| self.start_date.and_time(self.start_time))
+    }
 }
 
-#[derive(Debug, Deserialize)]
+/// Deserializes the `HH:MM` start time of logged activities
+fn deserialize_hour_minute<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let time = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&time, "%H:%M").map_err(serde::de::Error::custom)
+}
+
+/// Serializes the start time of logged activities as `HH:MM`
+fn serialize_hour_minute<S>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(&time.format("%H:%M"))
+}
+
+/// Deserializes the active score, which Fitbit reports as -1 if it isn't available
+fn deserialize_active_score<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let score = Option::<i32>::deserialize(deserializer)?;
+    Ok(score.filter(|score| *score >= 0))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Summary {
     pub calories_out: i32,
     pub activity_calories: i32,
     #[serde(rename = "caloriesBMR")]
     pub calories_bmr: i32,
-    pub active_score: i32,
+    /// Not available for most devices, which Fitbit reports as -1
+    #[serde(default, deserialize_with = "deserialize_active_score")]
+    pub active_score: Option<i32>,
     pub steps: u32,
-    pub floors: i32,
-    pub elevation: f64,
+    /// Not reported for devices without an altimeter
+    pub floors: Option<i32>,
+    /// Elevation in the client's elevation unit; not reported for devices without an altimeter
+    pub elevation: Option<f64>,
     pub sedentary_minutes: i32,
     pub lightly_active_minutes: i32,
     pub fairly_active_minutes: i32,
     pub very_active_minutes: i32,
+    /// Empty if no distance was recorded
+    #[serde(default)]
     pub distances: Vec<Distance>,
+    /// Not reported by some devices
+    #[serde(default)]
     pub marginal_calories: i32,
-    pub resting_heart_rate: i32,
+    /// Not reported for devices without a heart rate sensor, or before enough heart rate
+    /// data was recorded
+    pub resting_heart_rate: Option<i32>,
+    /// Empty for devices without a heart rate sensor
+    #[serde(default, deserialize_with = "deserialize_heart_rate_zones")]
     pub heart_rate_zones: Vec<HeartRateZone>,
+    /// The user's custom zones; empty unless they configured one
+    #[serde(default)]
+    pub custom_heart_rate_zones: Vec<CustomHeartRateZone>,
+    /// Whether `calories_out` was estimated, e.g. for the time the device was not worn
+    #[serde(default)]
+    pub use_estimation: bool,
+    /// Calories burned as measured, before any estimation; not reported by some devices
+    #[serde(default)]
+    pub calories_out_unestimated: Option<i32>,
+    /// Estimated calories burned; not reported by some devices
+    #[serde(default)]
+    pub calories_estimation_mu: Option<i32>,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
+impl Summary {
+    /// Returns the heart rate zone with the given name, if heart rate data was recorded
+    pub fn heart_rate_zone(&self, name: HeartRateZoneName) -> Option<&HeartRateZone> {
+        self.heart_rate_zones.iter().find(|zone| zone.name == name)
+    }
+
+    /// Returns `sedentary_minutes` as a duration
+    pub fn sedentary_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.sedentary_minutes as i64)
+    }
+
+    /// Returns `lightly_active_minutes` as a duration
+    pub fn lightly_active_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.lightly_active_minutes as i64)
+    }
+
+    /// Returns `fairly_active_minutes` as a duration
+    pub fn fairly_active_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.fairly_active_minutes as i64)
+    }
+
+    /// Returns `very_active_minutes` as a duration
+    pub fn very_active_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.very_active_minutes as i64)
+    }
+
+    /// Returns the minutes spent in the given zone or any zone above it
+    ///
+    /// For example, `minutes_in_or_above(HeartRateZoneName::Cardio)` counts the cardio and
+    /// peak minutes. Zones not known to this crate only count when asked for by name.
+    pub fn minutes_in_or_above(&self, name: HeartRateZoneName) -> i32 {
+        self.heart_rate_zones
+            .iter()
+            .filter(|zone| {
+                zone.name == name
+                    || (!matches!(zone.name, HeartRateZoneName::Other(_)) && zone.name >= name)
+            })
+            .map(|zone| zone.minutes)
+            .sum()
+    }
+
+    /// Returns the calories burned across all heart rate zones
+    pub fn total_zone_calories(&self) -> f64 {
+        self.heart_rate_zones
+            .iter()
+            .map(|zone| zone.calories_out)
+            .sum()
+    }
+
+    /// Returns the share of the day spent in the given zone
+    ///
+    /// The day is the time with heart rate data, i.e. the minutes of all zones, so that
+    /// days that are still in progress or when the device was not worn all day are not
+    /// understated. Returns `None` if no heart rate data was recorded.
+    pub fn percent_of_day_in_zone(&self, name: HeartRateZoneName) -> Option<Percentage> {
+        let total: i32 = self.heart_rate_zones.iter().map(|zone| zone.minutes).sum();
+        let minutes = self.heart_rate_zone(name).map_or(0, |zone| zone.minutes);
+
+        Percentage::of(minutes as f64, total as f64)
+    }
+
+    /// Returns the heart rate range of each zone, from lowest to highest, e.g. to draw
+    /// zone bands on a heart rate chart
+    pub fn zone_boundaries(&self) -> Vec<HeartRateZoneBoundary> {
+        let mut boundaries: Vec<HeartRateZoneBoundary> = self
+            .heart_rate_zones
+            .iter()
+            .map(|zone| HeartRateZoneBoundary {
+                name: zone.name.clone(),
+                min_bpm: zone.min,
+                max_bpm: zone.max,
+            })
+            .collect();
+        boundaries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        boundaries
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
 pub enum ActivityType {
     Total,
     Tracker,
@@ -49,47 +366,223 @@ pub enum ActivityType {
     ModeratelyActive,
     LightlyActive,
     SedentaryActive,
+    /// An activity type not known to this crate
+    Other(String),
 }
 
-#[derive(Debug, Deserialize)]
+impl ActivityType {
+    /// Returns the value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            ActivityType::Total => "total",
+            ActivityType::Tracker => "tracker",
+            ActivityType::LoggedActivities => "loggedActivities",
+            ActivityType::VeryActive => "veryActive",
+            ActivityType::ModeratelyActive => "moderatelyActive",
+            ActivityType::LightlyActive => "lightlyActive",
+            ActivityType::SedentaryActive => "sedentaryActive",
+            ActivityType::Other(value) => value,
+        }
+    }
+}
+
+impl From<ActivityType> for String {
+    fn from(value: ActivityType) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for ActivityType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "total" => ActivityType::Total,
+            "tracker" => ActivityType::Tracker,
+            "loggedActivities" => ActivityType::LoggedActivities,
+            "veryActive" => ActivityType::VeryActive,
+            "moderatelyActive" => ActivityType::ModeratelyActive,
+            "lightlyActive" => ActivityType::LightlyActive,
+            "sedentaryActive" => ActivityType::SedentaryActive,
+            _ => ActivityType::Other(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Distance {
     pub activity: ActivityType,
     pub distance: f64,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+/// Heart rate zones, ordered from lowest to highest intensity
+///
+/// Zones not known to this crate sort after `Peak`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
 pub enum HeartRateZoneName {
-    #[serde(rename = "Out of Range")]
     OutOfRange,
-    #[serde(rename = "Fat Burn")]
     FatBurn,
     Cardio,
     Peak,
+    /// A zone not known to this crate
+    Other(String),
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+impl HeartRateZoneName {
+    /// Returns the value used by the API for English accounts
+    pub fn as_str(&self) -> &str {
+        match self {
+            HeartRateZoneName::OutOfRange => "Out of Range",
+            HeartRateZoneName::FatBurn => "Fat Burn",
+            HeartRateZoneName::Cardio => "Cardio",
+            HeartRateZoneName::Peak => "Peak",
+            HeartRateZoneName::Other(value) => value,
+        }
+    }
+}
+
+impl From<HeartRateZoneName> for String {
+    fn from(value: HeartRateZoneName) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for HeartRateZoneName {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Out of Range" => HeartRateZoneName::OutOfRange,
+            "Fat Burn" => HeartRateZoneName::FatBurn,
+            "Cardio" => HeartRateZoneName::Cardio,
+            "Peak" => HeartRateZoneName::Peak,
+            _ => HeartRateZoneName::Other(value),
+        }
+    }
+}
+
+/// The time spent in a default heart rate zone
+///
+/// The API names the zones in the language of the account. Lists of the four default zones
+/// are identified by their heart rate ranges, so `name` is also known for non-English
+/// accounts; the name as reported is kept in `display_name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawHeartRateZone", into = "RawHeartRateZone")]
 pub struct HeartRateZone {
     pub minutes: i32,
     pub calories_out: f64,
     pub name: HeartRateZoneName,
+    /// The name as reported by the API, e.g. `Fettverbrennung` for German accounts
+    pub display_name: String,
     pub min: i32,
     pub max: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+struct RawHeartRateZone {
+    minutes: i32,
+    calories_out: f64,
+    name: String,
+    min: i32,
+    max: i32,
+}
+
+impl From<RawHeartRateZone> for HeartRateZone {
+    fn from(raw: RawHeartRateZone) -> Self {
+        Self {
+            minutes: raw.minutes,
+            calories_out: raw.calories_out,
+            name: HeartRateZoneName::from(raw.name.clone()),
+            display_name: raw.name,
+            min: raw.min,
+            max: raw.max,
+        }
+    }
+}
+
+impl From<HeartRateZone> for RawHeartRateZone {
+    fn from(zone: HeartRateZone) -> Self {
+        Self {
+            minutes: zone.minutes,
+            calories_out: zone.calories_out,
+            name: zone.display_name,
+            min: zone.min,
+            max: zone.max,
+        }
+    }
+}
+
+/// Deserializes a list of heart rate zones, identifying localized default zones
+///
+/// If the list has four zones and any of their names is unknown, they are the default
+/// zones of a non-English account, and are named by their order of heart rate ranges.
+pub(crate) fn deserialize_heart_rate_zones<'de, D>(
+    deserializer: D,
+) -> Result<Vec<HeartRateZone>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    const DEFAULT_ZONES: [HeartRateZoneName; 4] = [
+        HeartRateZoneName::OutOfRange,
+        HeartRateZoneName::FatBurn,
+        HeartRateZoneName::Cardio,
+        HeartRateZoneName::Peak,
+    ];
+
+    let mut zones = Vec::<HeartRateZone>::deserialize(deserializer)?;
+    let has_unknown_name = zones
+        .iter()
+        .any(|zone| matches!(zone.name, HeartRateZoneName::Other(_)));
+    if zones.len() == DEFAULT_ZONES.len() && has_unknown_name {
+        let mut by_range: Vec<&mut HeartRateZone> = zones.iter_mut().collect();
+        by_range.sort_by_key(|zone| zone.min);
+        for (zone, name) in by_range.into_iter().zip(DEFAULT_ZONES) {
+            zone.name = name;
+        }
+    }
+
+    Ok(zones)
+}
+
+impl HeartRateZone {
+    /// Returns the time spent in the zone
+    pub fn duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes as i64)
+    }
+}
+
+/// The heart rate range of a zone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeartRateZoneBoundary {
+    pub name: HeartRateZoneName,
+    pub min_bpm: i32,
+    pub max_bpm: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Goals {
     pub calories_out: i32,
     pub steps: u32,
     pub distance: f64,
-    pub floors: i32,
+    /// Not reported for devices without an altimeter
+    pub floors: Option<i32>,
     pub active_minutes: i32,
 }
 
+impl Goals {
+    /// Returns the active minutes goal as a duration
+    pub fn active_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.active_minutes as i64)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::units::DistanceUnit;
 
     #[test]
     fn test_parse_fitbit_response() {
@@ -141,16 +634,24 @@ mod tests {
         assert_eq!(response.summary.calories_out, 1746);
         assert_eq!(response.summary.activity_calories, 62);
         assert_eq!(response.summary.calories_bmr, 668);
-        assert_eq!(response.summary.active_score, -1);
+        assert_eq!(response.summary.active_score, None);
         assert_eq!(response.summary.steps, 27);
-        assert_eq!(response.summary.floors, 0);
-        assert_eq!(response.summary.elevation, 0.0);
+        assert_eq!(response.summary.floors, Some(0));
+        assert_eq!(response.summary.elevation, Some(0.0));
         assert_eq!(response.summary.sedentary_minutes, 552);
         assert_eq!(response.summary.lightly_active_minutes, 14);
         assert_eq!(response.summary.fairly_active_minutes, 0);
         assert_eq!(response.summary.very_active_minutes, 0);
+        assert_eq!(
+            response.summary.sedentary_duration(),
+            chrono::Duration::minutes(552)
+        );
+        assert_eq!(
+            response.summary.lightly_active_duration(),
+            chrono::Duration::minutes(14)
+        );
         assert_eq!(response.summary.marginal_calories, 40);
-        assert_eq!(response.summary.resting_heart_rate, 60);
+        assert_eq!(response.summary.resting_heart_rate, Some(60));
 
         // Test distances
         assert_eq!(response.summary.distances.len(), 7);
@@ -183,7 +684,532 @@ mod tests {
         assert_eq!(response.goals.calories_out, 2545);
         assert_eq!(response.goals.steps, 8000);
         assert_eq!(response.goals.distance, 8.05);
-        assert_eq!(response.goals.floors, 10);
+        assert_eq!(response.goals.floors, Some(10));
         assert_eq!(response.goals.active_minutes, 30);
     }
+
+    #[cfg(feature = "uom")]
+    #[test]
+    fn test_uom_quantities() {
+        use crate::fixtures::ActivitySummaryBuilder;
+        use uom::si::{energy::kilojoule, length::meter};
+
+        let metric = ActivitySummaryBuilder::new()
+            .calories_out(2000)
+            .floors(Some(10))
+            .build();
+        assert!((metric.energy_out().get::<kilojoule>() - 8368.0).abs() < 1e-6);
+        assert!((metric.elevation_length().unwrap().get::<meter>() - 30.48).abs() < 1e-9);
+
+        let mut us = ActivitySummaryBuilder::new()
+            .floors(Some(10))
+            .unit_system(UnitSystem::Us)
+            .build();
+        us.summary.elevation = Some(100.0);
+        assert!((us.elevation_length().unwrap().get::<meter>() - 30.48).abs() < 1e-9);
+
+        let without_altimeter = ActivitySummaryBuilder::new().floors(None).build();
+        assert_eq!(without_altimeter.elevation_length(), None);
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        let json_str = r#"{
+            "activities": [],
+            "summary": {
+                "caloriesOut": 2800,
+                "activityCalories": 1100,
+                "caloriesBMR": 1700,
+                "activeScore": -1,
+                "steps": 6000,
+                "sedentaryMinutes": 600,
+                "lightlyActiveMinutes": 200,
+                "fairlyActiveMinutes": 15,
+                "veryActiveMinutes": 30,
+                "distances": [
+                    {"activity": "tracker", "distance": 4.0},
+                    {"activity": "total", "distance": 8.04672}
+                ],
+                "marginalCalories": 700
+            },
+            "goals": {
+                "caloriesOut": 2800,
+                "steps": 8000,
+                "distance": 8.05,
+                "activeMinutes": 0
+            }
+        }"#;
+
+        let response: ActivitySummaryResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.total_active_minutes(), 45);
+        assert_eq!(response.total_distance_km(), 8.04672);
+        assert!((response.total_distance_miles() - 5.0).abs() < 1e-9);
+
+        let steps = response.steps_goal_progress().expect("Missing steps goal");
+        assert_eq!(steps.value(), 75.0);
+        assert!(!steps.is_complete());
+        assert_eq!(steps.to_string(), "75%");
+
+        let calories = response.calories_goal_progress().unwrap();
+        assert!(calories.is_complete());
+
+        // An unset goal has no progress instead of dividing by zero
+        assert_eq!(response.active_minutes_goal_progress(), None);
+    }
+
+    #[test]
+    fn test_distances_in_us_units() {
+        let json_str = r#"{
+            "activities": [],
+            "summary": {
+                "caloriesOut": 2800,
+                "activityCalories": 1100,
+                "caloriesBMR": 1700,
+                "activeScore": -1,
+                "steps": 11000,
+                "sedentaryMinutes": 600,
+                "lightlyActiveMinutes": 200,
+                "fairlyActiveMinutes": 15,
+                "veryActiveMinutes": 30,
+                "distances": [
+                    {"activity": "total", "distance": 5.0},
+                    {"activity": "loggedActivities", "distance": 3.1}
+                ],
+                "marginalCalories": 700
+            },
+            "goals": {"caloriesOut": 2800, "steps": 8000, "distance": 5.0, "activeMinutes": 30}
+        }"#;
+
+        let mut response: ActivitySummaryResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        assert_eq!(response.unit_system, UnitSystem::Metric);
+        response.unit_system = UnitSystem::Us;
+
+        assert_eq!(
+            response.total_distance(),
+            DistanceValue::new(5.0, DistanceUnit::Miles)
+        );
+        assert_eq!(response.total_distance_miles(), 5.0);
+        assert_eq!(response.total_distance_km(), 8.04672);
+        assert_eq!(
+            response.distance(ActivityType::LoggedActivities),
+            Some(DistanceValue::new(3.1, DistanceUnit::Miles))
+        );
+        assert_eq!(response.distance(ActivityType::VeryActive), None);
+        assert_eq!(response.distance_goal().unit, DistanceUnit::Miles);
+    }
+
+    #[test]
+    fn test_parse_tracker_without_heart_rate_or_altimeter() {
+        // e.g. Inspire: no heart rate sensor and no altimeter
+        let json_str = r#"{
+            "activities": [],
+            "summary": {
+                "caloriesOut": 2036,
+                "activityCalories": 512,
+                "caloriesBMR": 1580,
+                "activeScore": -1,
+                "steps": 7312,
+                "sedentaryMinutes": 702,
+                "lightlyActiveMinutes": 188,
+                "fairlyActiveMinutes": 9,
+                "veryActiveMinutes": 4,
+                "distances": [{"activity": "total", "distance": 5.12}],
+                "marginalCalories": 301
+            },
+            "goals": {
+                "caloriesOut": 2200,
+                "steps": 8000,
+                "distance": 8.05,
+                "activeMinutes": 30
+            }
+        }"#;
+
+        let response: ActivitySummaryResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.summary.steps, 7312);
+        assert_eq!(response.summary.floors, None);
+        assert_eq!(response.summary.elevation, None);
+        assert_eq!(response.summary.resting_heart_rate, None);
+        assert!(response.summary.heart_rate_zones.is_empty());
+        assert_eq!(response.summary.active_score, None);
+        assert_eq!(response.goals.floors, None);
+    }
+
+    #[test]
+    fn test_parse_phone_only_summary() {
+        // Accounts that only track with the phone app report neither an active score nor
+        // marginal calories, and no distances on days without steps
+        let json_str = r#"{
+            "activities": [],
+            "summary": {
+                "caloriesOut": 1580,
+                "activityCalories": 0,
+                "caloriesBMR": 1580,
+                "steps": 0,
+                "sedentaryMinutes": 1440,
+                "lightlyActiveMinutes": 0,
+                "fairlyActiveMinutes": 0,
+                "veryActiveMinutes": 0
+            },
+            "goals": {
+                "caloriesOut": 2200,
+                "steps": 8000,
+                "distance": 8.05,
+                "activeMinutes": 30
+            }
+        }"#;
+
+        let response: ActivitySummaryResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.summary.active_score, None);
+        assert_eq!(response.summary.marginal_calories, 0);
+        assert!(response.summary.distances.is_empty());
+        assert_eq!(response.total_distance().value, 0.0);
+        assert_eq!(response.summary.resting_heart_rate, None);
+    }
+
+    #[test]
+    fn test_parse_tracker_without_altimeter() {
+        // e.g. Inspire HR: heart rate sensor but no altimeter; the resting heart rate is
+        // missing until enough heart rate data was recorded
+        let json_str = r#"{
+            "activities": [],
+            "summary": {
+                "caloriesOut": 1890,
+                "activityCalories": 301,
+                "caloriesBMR": 1580,
+                "activeScore": -1,
+                "steps": 4210,
+                "sedentaryMinutes": 811,
+                "lightlyActiveMinutes": 97,
+                "fairlyActiveMinutes": 0,
+                "veryActiveMinutes": 0,
+                "distances": [{"activity": "total", "distance": 2.95}],
+                "marginalCalories": 170,
+                "heartRateZones": [
+                    {"minutes": 1390, "caloriesOut": 1850.2, "name": "Out of Range", "min": 30, "max": 98},
+                    {"minutes": 12, "caloriesOut": 39.8, "name": "Fat Burn", "min": 98, "max": 137},
+                    {"minutes": 0, "caloriesOut": 0, "name": "Cardio", "min": 137, "max": 166},
+                    {"minutes": 0, "caloriesOut": 0, "name": "Peak", "min": 166, "max": 220}
+                ]
+            },
+            "goals": {
+                "caloriesOut": 2200,
+                "steps": 8000,
+                "distance": 8.05,
+                "activeMinutes": 30
+            }
+        }"#;
+
+        let response: ActivitySummaryResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.summary.floors, None);
+        assert_eq!(response.summary.elevation, None);
+        assert_eq!(response.summary.resting_heart_rate, None);
+        assert_eq!(response.summary.heart_rate_zones.len(), 4);
+        assert_eq!(response.summary.heart_rate_zones[1].minutes, 12);
+    }
+
+    #[test]
+    fn test_heart_rate_zone_helpers() {
+        let zone = |name: HeartRateZoneName, minutes, calories_out, min, max| HeartRateZone {
+            minutes,
+            calories_out,
+            display_name: name.as_str().to_string(),
+            name,
+            min,
+            max,
+        };
+        let summary = Summary {
+            calories_out: 2413,
+            activity_calories: 829,
+            calories_bmr: 1691,
+            active_score: None,
+            steps: 9201,
+            floors: None,
+            elevation: None,
+            sedentary_minutes: 688,
+            lightly_active_minutes: 221,
+            fairly_active_minutes: 12,
+            very_active_minutes: 34,
+            distances: Vec::new(),
+            marginal_calories: 512,
+            resting_heart_rate: Some(58),
+            heart_rate_zones: vec![
+                zone(HeartRateZoneName::Peak, 10, 150.0, 166, 220),
+                zone(HeartRateZoneName::OutOfRange, 1300, 1900.5, 30, 98),
+                zone(HeartRateZoneName::FatBurn, 100, 300.0, 98, 137),
+                zone(HeartRateZoneName::Cardio, 30, 250.0, 137, 166),
+            ],
+            custom_heart_rate_zones: Vec::new(),
+            use_estimation: false,
+            calories_out_unestimated: None,
+            calories_estimation_mu: None,
+        };
+
+        assert_eq!(
+            summary
+                .heart_rate_zone(HeartRateZoneName::FatBurn)
+                .map(|zone| zone.minutes),
+            Some(100)
+        );
+        assert_eq!(summary.minutes_in_or_above(HeartRateZoneName::Cardio), 40);
+        assert_eq!(
+            summary.minutes_in_or_above(HeartRateZoneName::OutOfRange),
+            1440
+        );
+        assert_eq!(summary.total_zone_calories(), 2600.5);
+        assert_eq!(
+            summary.percent_of_day_in_zone(HeartRateZoneName::FatBurn),
+            Some(Percentage(100.0 / 1440.0 * 100.0))
+        );
+
+        let boundaries = summary.zone_boundaries();
+        assert_eq!(boundaries.len(), 4);
+        assert_eq!(
+            boundaries[0],
+            HeartRateZoneBoundary {
+                name: HeartRateZoneName::OutOfRange,
+                min_bpm: 30,
+                max_bpm: 98,
+            }
+        );
+        assert_eq!(boundaries[3].name, HeartRateZoneName::Peak);
+
+        // No heart rate data
+        let summary = Summary {
+            heart_rate_zones: Vec::new(),
+            ..summary
+        };
+        assert!(summary.heart_rate_zone(HeartRateZoneName::Peak).is_none());
+        assert_eq!(summary.minutes_in_or_above(HeartRateZoneName::FatBurn), 0);
+        assert_eq!(
+            summary.percent_of_day_in_zone(HeartRateZoneName::Peak),
+            None
+        );
+        assert!(summary.zone_boundaries().is_empty());
+    }
+
+    fn logged_activities_response() -> ActivitySummaryResponse {
+        let json_str = r#"{
+            "activities": [
+                {
+                    "activityId": 90013,
+                    "activityParentId": 90013,
+                    "activityParentName": "Walk",
+                    "calories": 204,
+                    "description": "Walking less than 2 mph, strolling very slowly",
+                    "distance": 0.9,
+                    "duration": 2040000,
+                    "hasActiveZoneMinutes": false,
+                    "hasStartTime": true,
+                    "isFavorite": false,
+                    "lastModified": "2019-01-07T20:40:54.000Z",
+                    "logId": 19018673358,
+                    "name": "Walk",
+                    "startDate": "2019-01-03",
+                    "startTime": "12:08",
+                    "steps": 1799
+                },
+                {
+                    "activityId": 2131,
+                    "activityParentId": 2130,
+                    "activityParentName": "Weight lifting (free weight, nautilus or universal-type)",
+                    "calories": 150,
+                    "description": "Light or moderate effort",
+                    "duration": 1800000,
+                    "hasStartTime": false,
+                    "isFavorite": true,
+                    "lastModified": "2019-01-03T19:02:11.000Z",
+                    "logId": 19018673359,
+                    "name": "Weight lifting",
+                    "startDate": "2019-01-03",
+                    "startTime": "00:00"
+                }
+            ],
+            "summary": {
+                "caloriesOut": 2413,
+                "activityCalories": 829,
+                "caloriesBMR": 1691,
+                "activeScore": -1,
+                "steps": 9201,
+                "floors": 4,
+                "elevation": 12.19,
+                "sedentaryMinutes": 688,
+                "lightlyActiveMinutes": 221,
+                "fairlyActiveMinutes": 12,
+                "veryActiveMinutes": 34,
+                "distances": [{"activity": "total", "distance": 6.5}],
+                "marginalCalories": 512,
+                "restingHeartRate": 58,
+                "heartRateZones": []
+            },
+            "goals": {
+                "caloriesOut": 2545,
+                "steps": 8000,
+                "distance": 8.05,
+                "floors": 10,
+                "activeMinutes": 30
+            }
+        }"#;
+
+        serde_json::from_str(json_str).expect("Failed to parse JSON")
+    }
+
+    #[test]
+    fn test_parse_logged_activities() {
+        let response = logged_activities_response();
+
+        assert_eq!(response.activities.len(), 2);
+
+        let walk = &response.activities[0];
+        assert_eq!(walk.log_id, 19018673358);
+        assert_eq!(walk.name, "Walk");
+        assert_eq!(walk.calories, 204);
+        assert_eq!(walk.distance, 0.9);
+        assert_eq!(
+            response.activity_distance(walk),
+            DistanceValue::new(0.9, DistanceUnit::Kilometers)
+        );
+        assert_eq!(walk.steps, 1799);
+        assert_eq!(walk.duration(), chrono::Duration::minutes(34));
+        assert_eq!(
+            walk.start(),
+            NaiveDate::from_ymd_opt(2019, 1, 3)
+                .unwrap()
+                .and_hms_opt(12, 8, 0)
+        );
+
+        // Activities without distance, steps or start time
+        let weights = &response.activities[1];
+        assert_eq!(weights.activity_parent_id, 2130);
+        assert_eq!(weights.distance, 0.0);
+        assert_eq!(weights.steps, 0);
+        assert!(weights.is_favorite);
+        assert_eq!(weights.start(), None);
+    }
+
+    #[test]
+    fn test_parse_unknown_zone_and_activity_type() {
+        let json_str = r#"{
+            "distances": [
+                {"activity": "total", "distance": 3.2},
+                {"activity": "swimming", "distance": 1.5}
+            ],
+            "zones": [
+                {"minutes": 20, "caloriesOut": 180.0, "name": "Cardio", "min": 142, "max": 176},
+                {"minutes": 7, "caloriesOut": 70.0, "name": "Zone 5", "min": 190, "max": 220}
+            ]
+        }"#;
+
+        #[derive(Deserialize)]
+        struct Fragment {
+            distances: Vec<Distance>,
+            zones: Vec<HeartRateZone>,
+        }
+        let fragment: Fragment = serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(
+            fragment.distances[1].activity,
+            ActivityType::Other("swimming".to_string())
+        );
+        assert_eq!(fragment.distances[1].activity.as_str(), "swimming");
+        let zone_5 = HeartRateZoneName::Other("Zone 5".to_string());
+        assert_eq!(fragment.zones[1].name, zone_5);
+
+        let summary = Summary {
+            heart_rate_zones: fragment.zones,
+            ..serde_json::from_str(
+                r#"{"caloriesOut": 0, "activityCalories": 0, "caloriesBMR": 0, "activeScore": -1,
+                    "steps": 0, "sedentaryMinutes": 0, "lightlyActiveMinutes": 0,
+                    "fairlyActiveMinutes": 0, "veryActiveMinutes": 0, "distances": [],
+                    "marginalCalories": 0}"#,
+            )
+            .expect("Failed to parse JSON")
+        };
+        assert_eq!(summary.minutes_in_or_above(HeartRateZoneName::Cardio), 20);
+        assert_eq!(summary.minutes_in_or_above(zone_5), 7);
+    }
+
+    #[test]
+    fn test_parse_localized_zone_names() {
+        // German account; the zones are listed in an unusual order to check that they are
+        // identified by range
+        let json_str = r#"{
+            "caloriesOut": 2100, "activityCalories": 600, "caloriesBMR": 1500,
+            "steps": 8000, "sedentaryMinutes": 700, "lightlyActiveMinutes": 200,
+            "fairlyActiveMinutes": 10, "veryActiveMinutes": 5,
+            "heartRateZones": [
+                {"minutes": 1300, "caloriesOut": 1700.0, "name": "Außerhalb der Zonen", "min": 30, "max": 98},
+                {"minutes": 3, "caloriesOut": 30.0, "name": "Höchstleistung", "min": 166, "max": 220},
+                {"minutes": 40, "caloriesOut": 250.0, "name": "Fettverbrennung", "min": 98, "max": 137},
+                {"minutes": 12, "caloriesOut": 120.0, "name": "Kardio", "min": 137, "max": 166}
+            ]
+        }"#;
+
+        let summary: Summary = serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        let names: Vec<HeartRateZoneName> = summary
+            .heart_rate_zones
+            .iter()
+            .map(|zone| zone.name.clone())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                HeartRateZoneName::OutOfRange,
+                HeartRateZoneName::Peak,
+                HeartRateZoneName::FatBurn,
+                HeartRateZoneName::Cardio
+            ]
+        );
+        assert_eq!(summary.heart_rate_zones[2].display_name, "Fettverbrennung");
+        assert_eq!(summary.minutes_in_or_above(HeartRateZoneName::Cardio), 15);
+
+        let serialized = serde_json::to_value(&summary).expect("Failed to serialize");
+        assert_eq!(serialized["heartRateZones"][1]["name"], "Höchstleistung");
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut response = logged_activities_response();
+        response.unit_system = UnitSystem::Us;
+
+        let serialized = serde_json::to_value(&response).expect("Failed to serialize");
+        assert_eq!(serialized["activities"][0]["startTime"], "12:08");
+        assert_eq!(serialized["activities"][0]["duration"], 2040000);
+        assert_eq!(serialized["summary"]["caloriesBMR"], 1691);
+        assert_eq!(serialized["summary"]["distances"][0]["activity"], "total");
+        assert_eq!(serialized["goals"]["activeMinutes"], 30);
+        assert_eq!(serialized["unitSystem"], "en_US");
+
+        let round_tripped: ActivitySummaryResponse =
+            serde_json::from_value(serialized.clone()).expect("Failed to parse serialized JSON");
+        assert_eq!(
+            serde_json::to_value(&round_tripped).expect("Failed to serialize"),
+            serialized
+        );
+        assert_eq!(
+            round_tripped.activities[0].start(),
+            response.activities[0].start()
+        );
+        assert_eq!(round_tripped.summary.resting_heart_rate, Some(58));
+        assert_eq!(round_tripped, response);
+        assert_eq!(round_tripped.total_distance().unit, DistanceUnit::Miles);
+
+        let zone: HeartRateZone = serde_json::from_str(
+            r#"{"minutes": 1, "caloriesOut": 4.9, "name": "Fat Burn", "min": 115, "max": 141}"#,
+        )
+        .expect("Failed to parse JSON");
+        assert_eq!(
+            serde_json::to_value(&zone).expect("Failed to serialize")["name"],
+            "Fat Burn"
+        );
+    }
 }
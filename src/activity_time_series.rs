@@ -0,0 +1,912 @@
+//! Daily and intraday activity time series.
+//!
+//! The time series endpoints return one value per day for a single activity resource (e.g.
+//! steps) over a range of dates, which is much cheaper than fetching the daily activity
+//! summary for each date. The intraday endpoints break a single day down into 1 or 15
+//! minute intervals; [`fetch_activity_intraday_range`] stitches several days together.
+
+use crate::batch::{BatchResult, date_range};
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::intraday::{IntradaySamples, deserialize_samples};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// An activity resource that is available as a daily time series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityResource {
+    Steps,
+    Calories,
+    /// Distance in the client's distance unit
+    Distance,
+    Floors,
+    /// Elevation in the client's elevation unit
+    Elevation,
+    MinutesSedentary,
+    MinutesLightlyActive,
+    MinutesFairlyActive,
+    MinutesVeryActive,
+}
+
+impl ActivityResource {
+    /// Returns the resource path segment used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityResource::Steps => "steps",
+            ActivityResource::Calories => "calories",
+            ActivityResource::Distance => "distance",
+            ActivityResource::Floors => "floors",
+            ActivityResource::Elevation => "elevation",
+            ActivityResource::MinutesSedentary => "minutesSedentary",
+            ActivityResource::MinutesLightlyActive => "minutesLightlyActive",
+            ActivityResource::MinutesFairlyActive => "minutesFairlyActive",
+            ActivityResource::MinutesVeryActive => "minutesVeryActive",
+        }
+    }
+
+    /// Returns `true` if the resource is available from the intraday endpoints
+    pub fn supports_intraday(&self) -> bool {
+        matches!(
+            self,
+            ActivityResource::Steps
+                | ActivityResource::Calories
+                | ActivityResource::Distance
+                | ActivityResource::Floors
+                | ActivityResource::Elevation
+        )
+    }
+}
+
+/// Interval length of intraday data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetailLevel {
+    OneMinute,
+    FifteenMinutes,
+}
+
+impl DetailLevel {
+    /// Returns the detail level path segment used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DetailLevel::OneMinute => "1min",
+            DetailLevel::FifteenMinutes => "15min",
+        }
+    }
+}
+
+/// Daily values of a single activity resource
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityTimeSeries {
+    pub resource: ActivityResource,
+    /// One value per day, in chronological order
+    pub values: Vec<(NaiveDate, f64)>,
+}
+
+impl ActivityTimeSeries {
+    /// Returns the value for the given date, if it is part of the series
+    pub fn value_on(&self, date: NaiveDate) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|(value_date, _)| *value_date == date)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Values of a single activity resource throughout one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntradayActivity {
+    pub resource: ActivityResource,
+    pub date: NaiveDate,
+    /// The total for the day
+    pub total: f64,
+    /// Length of each interval in `dataset_type` units, e.g. 15 for 15 minute data
+    pub dataset_interval: u32,
+    /// Unit of `dataset_interval`, e.g. `minute`
+    pub dataset_type: String,
+    /// The value of each interval, keyed by the interval start, in chronological order
+    pub dataset: IntradaySamples<f64>,
+}
+
+/// Values of a single activity resource across several days, stitched together from the
+/// intraday data of each day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntradayActivityRange {
+    pub resource: ActivityResource,
+    /// The total of each day, in chronological order
+    pub daily_totals: Vec<(NaiveDate, f64)>,
+    /// The value of each interval, keyed by the interval start, in chronological order
+    pub dataset: Vec<(NaiveDateTime, f64)>,
+}
+
+/// Options for [`fetch_activity_intraday_range`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntradayRangeConfig {
+    /// Number of days fetched at the same time; 1 fetches the days one after another
+    pub parallelism: usize,
+    /// How often a day is retried after a retryable error, such as hitting the rate limit
+    /// or a server error, before giving up
+    pub max_rate_limit_retries: u32,
+    /// How long to wait before retrying if the error doesn't say
+    pub rate_limit_backoff: Duration,
+}
+
+impl Default for IntradayRangeConfig {
+    fn default() -> Self {
+        Self {
+            parallelism: 1,
+            max_rate_limit_retries: 3,
+            rate_limit_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Fetches the intraday values of an activity resource for every day of a date range
+///
+/// The intraday endpoints only return one day per request, so this issues one request per
+/// day and joins the results into a single continuous series. Requests that fail with a
+/// [retryable](FitbitError::is_retryable) error, e.g. because they hit the rate limit, are
+/// retried after waiting, as configured. Once a day fails for good, no further days are
+/// requested, since the series can't be completed anyway.
+///
+/// # Arguments
+///
+/// * `client` - The client to fetch each day with
+/// * `resource` - The activity resource to fetch, e.g. steps
+/// * `start` - The first date of the range
+/// * `end` - The last date of the range (inclusive)
+/// * `detail_level` - The interval length of the dataset
+/// * `config` - Parallelism and rate limit handling
+///
+/// # Returns
+///
+/// The stitched series, or the error of the earliest day that could not be fetched
+///
+/// # Example
+///
+/// ```no_run
+/// use chrono::NaiveDate;
+/// use fitbit_rs::{
+///     ActivityResource, DetailLevel, FitbitClient, IntradayRangeConfig,
+///     fetch_activity_intraday_range,
+/// };
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let config = IntradayRangeConfig {
+///     parallelism: 4,
+///     ..Default::default()
+/// };
+/// let steps = fetch_activity_intraday_range(
+///     &client,
+///     ActivityResource::Steps,
+///     NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+///     NaiveDate::from_ymd_opt(2025, 3, 7).unwrap(),
+///     DetailLevel::FifteenMinutes,
+///     &config,
+/// )?;
+/// println!("{} intervals", steps.dataset.len());
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn fetch_activity_intraday_range<C>(
+    client: &C,
+    resource: ActivityResource,
+    start: NaiveDate,
+    end: NaiveDate,
+    detail_level: DetailLevel,
+    config: &IntradayRangeConfig,
+) -> Result<IntradayActivityRange, FitbitError>
+where
+    C: FitbitClientTrait + Sync,
+{
+    let dates = date_range(start, end)?;
+    let days: BatchResult<IntradayActivity> =
+        fetch_intraday_days(client, resource, &dates, detail_level, config, true)
+            .into_iter()
+            .collect();
+    let days = days.into_result()?;
+
+    let mut range = IntradayActivityRange {
+        resource,
+        daily_totals: Vec::with_capacity(days.len()),
+        dataset: Vec::new(),
+    };
+    for (date, day) in days {
+        range.daily_totals.push((date, day.total));
+        range.dataset.extend(
+            day.dataset
+                .iter()
+                .map(|(time, value)| (date.and_time(time), value)),
+        );
+    }
+
+    Ok(range)
+}
+
+/// Fetches the intraday values of an activity resource for every day of a date range,
+/// keeping the days that succeeded when others fail
+///
+/// Works like [`fetch_activity_intraday_range`], including retries, but returns each day
+/// separately instead of stitching them, so one missing day does not abort a long download.
+///
+/// # Arguments
+///
+/// * `client` - The client to fetch each day with
+/// * `resource` - The activity resource to fetch, e.g. steps
+/// * `start` - The first date of the range
+/// * `end` - The last date of the range (inclusive)
+/// * `detail_level` - The interval length of the dataset
+/// * `config` - Parallelism and rate limit handling
+///
+/// # Returns
+///
+/// The fetched days and the errors of the days that could not be fetched, or an error if
+/// the range is invalid
+pub fn fetch_activity_intraday_days<C>(
+    client: &C,
+    resource: ActivityResource,
+    start: NaiveDate,
+    end: NaiveDate,
+    detail_level: DetailLevel,
+    config: &IntradayRangeConfig,
+) -> Result<BatchResult<IntradayActivity>, FitbitError>
+where
+    C: FitbitClientTrait + Sync,
+{
+    let dates = date_range(start, end)?;
+
+    Ok(
+        fetch_intraday_days(client, resource, &dates, detail_level, config, false)
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Fetches the intraday values of every date, with retries and the configured parallelism
+///
+/// With `stop_on_failure`, days that haven't been started yet are skipped once a day fails
+/// for good, so the result only holds the days that were requested.
+fn fetch_intraday_days<C>(
+    client: &C,
+    resource: ActivityResource,
+    dates: &[NaiveDate],
+    detail_level: DetailLevel,
+    config: &IntradayRangeConfig,
+    stop_on_failure: bool,
+) -> Vec<(NaiveDate, Result<IntradayActivity, FitbitError>)>
+where
+    C: FitbitClientTrait + Sync,
+{
+    let failed = AtomicBool::new(false);
+    let fetch_day = |date: NaiveDate| {
+        if stop_on_failure && failed.load(Ordering::Relaxed) {
+            return None;
+        }
+        let result = with_rate_limit_retries(config, || {
+            client.fetch_activity_intraday(resource, date, detail_level)
+        });
+        if result.is_err() {
+            failed.store(true, Ordering::Relaxed);
+        }
+        Some((date, result))
+    };
+
+    let workers = config.parallelism.clamp(1, dates.len());
+    if workers == 1 {
+        dates.iter().map_while(|&date| fetch_day(date)).collect()
+    } else {
+        let fetch_day = &fetch_day;
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| {
+                    scope.spawn(move || {
+                        dates
+                            .iter()
+                            .skip(worker)
+                            .step_by(workers)
+                            .map_while(|&date| fetch_day(date))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("intraday fetch thread panicked"))
+                .collect()
+        })
+    }
+}
+
+/// Runs `request`, retrying it after a pause whenever it fails with a retryable error
+fn with_rate_limit_retries<T>(
+    config: &IntradayRangeConfig,
+    mut request: impl FnMut() -> Result<T, FitbitError>,
+) -> Result<T, FitbitError> {
+    let mut retries = 0;
+    loop {
+        match request() {
+            Err(error) if retries < config.max_rate_limit_retries && error.is_retryable() => {
+                retries += 1;
+                thread::sleep(error.retry_after().unwrap_or(config.rate_limit_backoff));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Active Zone Minutes earned on one day
+///
+/// Minutes in the cardio and peak zones count double towards `total`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveZoneMinutes {
+    pub date: NaiveDate,
+    pub total: u32,
+    pub fat_burn: u32,
+    pub cardio: u32,
+    pub peak: u32,
+}
+
+/// Raw Active Zone Minutes time series response
+#[derive(Debug, Deserialize)]
+pub(crate) struct AzmTimeSeriesResponse {
+    #[serde(rename = "activities-active-zone-minutes")]
+    days: Vec<AzmEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzmEntry {
+    date_time: NaiveDate,
+    value: AzmValue,
+}
+
+/// Zones without minutes are omitted by the API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzmValue {
+    #[serde(default)]
+    active_zone_minutes: u32,
+    #[serde(default)]
+    fat_burn_active_zone_minutes: u32,
+    #[serde(default)]
+    cardio_active_zone_minutes: u32,
+    #[serde(default)]
+    peak_active_zone_minutes: u32,
+}
+
+impl AzmTimeSeriesResponse {
+    /// Returns the days in chronological order
+    pub(crate) fn into_days(self) -> Vec<ActiveZoneMinutes> {
+        let mut days: Vec<ActiveZoneMinutes> = self
+            .days
+            .into_iter()
+            .map(|entry| ActiveZoneMinutes {
+                date: entry.date_time,
+                total: entry.value.active_zone_minutes,
+                fat_burn: entry.value.fat_burn_active_zone_minutes,
+                cardio: entry.value.cardio_active_zone_minutes,
+                peak: entry.value.peak_active_zone_minutes,
+            })
+            .collect();
+        days.sort_by_key(|day| day.date);
+
+        days
+    }
+}
+
+/// Raw time series response, keyed by `activities-{resource}`
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct ActivityTimeSeriesResponse(HashMap<String, Vec<TimeSeriesEntry>>);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeSeriesEntry {
+    date_time: NaiveDate,
+    #[serde(deserialize_with = "deserialize_string_number")]
+    value: f64,
+}
+
+impl ActivityTimeSeriesResponse {
+    /// Extracts the series of the requested resource
+    pub(crate) fn into_series(
+        mut self,
+        resource: ActivityResource,
+    ) -> Result<ActivityTimeSeries, FitbitError> {
+        let key = format!("activities-{}", resource.as_str());
+        let entries = self.0.remove(&key).ok_or_else(|| {
+            FitbitError::json_error(format!("missing `{}` in time series response", key))
+        })?;
+
+        let mut values: Vec<(NaiveDate, f64)> = entries
+            .into_iter()
+            .map(|entry| (entry.date_time, entry.value))
+            .collect();
+        values.sort_by_key(|(date, _)| *date);
+
+        Ok(ActivityTimeSeries { resource, values })
+    }
+}
+
+/// Raw intraday response, keyed by `activities-{resource}` for the daily total and
+/// `activities-{resource}-intraday` for the dataset
+///
+/// The sections are told apart by their key, so the dataset is parsed straight into its
+/// samples instead of being buffered to try each shape.
+#[derive(Debug)]
+pub(crate) struct ActivityIntradayResponse {
+    totals: HashMap<String, Vec<TimeSeriesEntry>>,
+    datasets: HashMap<String, IntradayDataset>,
+}
+
+impl<'de> Deserialize<'de> for ActivityIntradayResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SectionsVisitor;
+
+        impl<'de> Visitor<'de> for SectionsVisitor {
+            type Value = ActivityIntradayResponse;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an intraday activity response")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut response = ActivityIntradayResponse {
+                    totals: HashMap::new(),
+                    datasets: HashMap::new(),
+                };
+                while let Some(key) = map.next_key::<String>()? {
+                    if key.ends_with("-intraday") {
+                        let dataset = map.next_value()?;
+                        response.datasets.insert(key, dataset);
+                    } else if key.starts_with("activities-") {
+                        let total = map.next_value()?;
+                        response.totals.insert(key, total);
+                    } else {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+
+                Ok(response)
+            }
+        }
+
+        deserializer.deserialize_map(SectionsVisitor)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntradayDataset {
+    #[serde(deserialize_with = "deserialize_samples::<_, IntradayEntry, _>")]
+    dataset: IntradaySamples<f64>,
+    dataset_interval: u32,
+    dataset_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntradayEntry {
+    time: NaiveTime,
+    #[serde(deserialize_with = "deserialize_string_number")]
+    value: f64,
+}
+
+impl From<IntradayEntry> for (NaiveTime, f64) {
+    fn from(entry: IntradayEntry) -> Self {
+        (entry.time, entry.value)
+    }
+}
+
+impl ActivityIntradayResponse {
+    /// Extracts the daily total and dataset of the requested resource
+    pub(crate) fn into_intraday(
+        mut self,
+        resource: ActivityResource,
+    ) -> Result<IntradayActivity, FitbitError> {
+        let total_key = format!("activities-{}", resource.as_str());
+        let dataset_key = format!("activities-{}-intraday", resource.as_str());
+        let missing =
+            |key: &str| FitbitError::json_error(format!("missing `{}` in intraday response", key));
+
+        let total = self
+            .totals
+            .remove(&total_key)
+            .and_then(|total| total.into_iter().next())
+            .ok_or_else(|| missing(&total_key))?;
+        let dataset = self
+            .datasets
+            .remove(&dataset_key)
+            .ok_or_else(|| missing(&dataset_key))?;
+
+        Ok(IntradayActivity {
+            resource,
+            date: total.date_time,
+            total: total.value,
+            dataset_interval: dataset.dataset_interval,
+            dataset_type: dataset.dataset_type,
+            dataset: dataset.dataset,
+        })
+    }
+}
+
+/// Deserializes a number that the API encodes as a string, e.g. `"8934"`
+pub(crate) fn deserialize_string_number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(f64),
+    }
+
+    match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(value) => value.parse().map_err(serde::de::Error::custom),
+        StringOrNumber::Number(value) => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+
+    fn intraday_steps(date: NaiveDate, values: &[f64]) -> IntradayActivity {
+        IntradayActivity {
+            resource: ActivityResource::Steps,
+            date,
+            total: values.iter().sum(),
+            dataset_interval: 15,
+            dataset_type: "minute".to_string(),
+            dataset: values
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| {
+                    let time = NaiveTime::from_hms_opt(0, 15 * i as u32, 0).unwrap();
+                    (time, value)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_time_series() {
+        let json_str = r#"{
+            "activities-distance": [
+                {"dateTime": "2019-01-02", "value": "5.6348"},
+                {"dateTime": "2019-01-01", "value": "3.1"},
+                {"dateTime": "2019-01-03", "value": "0"}
+            ]
+        }"#;
+
+        let response: ActivityTimeSeriesResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let series = response
+            .into_series(ActivityResource::Distance)
+            .expect("Missing series");
+
+        let date = |day| NaiveDate::from_ymd_opt(2019, 1, day).unwrap();
+        assert_eq!(series.resource, ActivityResource::Distance);
+        assert_eq!(
+            series.values,
+            [(date(1), 3.1), (date(2), 5.6348), (date(3), 0.0)]
+        );
+        assert_eq!(series.value_on(date(2)), Some(5.6348));
+        assert_eq!(series.value_on(date(4)), None);
+    }
+
+    #[test]
+    fn test_parse_intraday() {
+        let json_str = r#"{
+            "activities-steps": [{"dateTime": "2019-01-01", "value": "1249"}],
+            "activities-steps-intraday": {
+                "dataset": [
+                    {"time": "00:00:00", "value": 0},
+                    {"time": "00:15:00", "value": 13},
+                    {"time": "00:30:00", "value": 1236}
+                ],
+                "datasetInterval": 15,
+                "datasetType": "minute"
+            }
+        }"#;
+
+        let response: ActivityIntradayResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let intraday = response
+            .into_intraday(ActivityResource::Steps)
+            .expect("Missing dataset");
+
+        assert_eq!(intraday.date, NaiveDate::from_ymd_opt(2019, 1, 1).unwrap());
+        assert_eq!(intraday.total, 1249.0);
+        assert_eq!(intraday.dataset_interval, 15);
+        assert_eq!(intraday.dataset_type, "minute");
+        assert_eq!(intraday.dataset.len(), 3);
+        assert_eq!(
+            intraday.dataset.get(1),
+            Some((NaiveTime::from_hms_opt(0, 15, 0).unwrap(), 13.0))
+        );
+    }
+
+    #[test]
+    fn test_parse_azm_time_series() {
+        let json_str = r#"{
+            "activities-active-zone-minutes": [
+                {
+                    "dateTime": "2021-03-02",
+                    "value": {"activeZoneMinutes": 12, "fatBurnActiveZoneMinutes": 12}
+                },
+                {
+                    "dateTime": "2021-03-01",
+                    "value": {
+                        "activeZoneMinutes": 47,
+                        "fatBurnActiveZoneMinutes": 11,
+                        "cardioActiveZoneMinutes": 14,
+                        "peakActiveZoneMinutes": 4
+                    }
+                }
+            ]
+        }"#;
+
+        let response: AzmTimeSeriesResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let days = response.into_days();
+
+        let date = |day| NaiveDate::from_ymd_opt(2021, 3, day).unwrap();
+        assert_eq!(
+            days,
+            [
+                ActiveZoneMinutes {
+                    date: date(1),
+                    total: 47,
+                    fat_burn: 11,
+                    cardio: 14,
+                    peak: 4,
+                },
+                ActiveZoneMinutes {
+                    date: date(2),
+                    total: 12,
+                    fat_burn: 12,
+                    cardio: 0,
+                    peak: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let date = |day| NaiveDate::from_ymd_opt(2019, 1, day).unwrap();
+
+        let series = ActivityTimeSeries {
+            resource: ActivityResource::MinutesSedentary,
+            values: vec![(date(1), 652.0), (date(2), 701.0)],
+        };
+        let serialized = serde_json::to_value(&series).expect("Failed to serialize");
+        assert_eq!(serialized["resource"], "minutesSedentary");
+        assert_eq!(
+            serialized["values"][0],
+            serde_json::json!(["2019-01-01", 652.0])
+        );
+        let round_tripped: ActivityTimeSeries =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, series);
+
+        let intraday = intraday_steps(date(1), &[0.0, 13.0, 1236.0]);
+        let serialized = serde_json::to_value(&intraday).expect("Failed to serialize");
+        assert_eq!(serialized["datasetType"], "minute");
+        let round_tripped: IntradayActivity =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, intraday);
+
+        let range = IntradayActivityRange {
+            resource: ActivityResource::Steps,
+            daily_totals: vec![(date(1), 1249.0)],
+            dataset: vec![(date(1).and_hms_opt(0, 15, 0).unwrap(), 13.0)],
+        };
+        let serialized = serde_json::to_value(&range).expect("Failed to serialize");
+        assert_eq!(
+            serialized["dataset"][0],
+            serde_json::json!(["2019-01-01T00:15:00", 13.0])
+        );
+        let round_tripped: IntradayActivityRange =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, range);
+
+        let azm = ActiveZoneMinutes {
+            date: date(1),
+            total: 47,
+            fat_burn: 11,
+            cardio: 14,
+            peak: 4,
+        };
+        let serialized = serde_json::to_value(azm).expect("Failed to serialize");
+        assert_eq!(serialized["fatBurn"], 11);
+        let round_tripped: ActiveZoneMinutes =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, azm);
+    }
+
+    #[test]
+    fn test_fetch_intraday_range() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_activity_intraday()
+            .times(3)
+            .returning(move |_, date, _| {
+                let day = (date - start).num_days() as f64;
+                Ok(intraday_steps(date, &[day, day + 10.0]))
+            });
+
+        let config = IntradayRangeConfig {
+            parallelism: 2,
+            ..Default::default()
+        };
+        let range = fetch_activity_intraday_range(
+            &mock_client,
+            ActivityResource::Steps,
+            start,
+            end,
+            DetailLevel::FifteenMinutes,
+            &config,
+        )
+        .expect("Failed to fetch range");
+
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        assert_eq!(
+            range.daily_totals,
+            [(date(1), 10.0), (date(2), 12.0), (date(3), 14.0)]
+        );
+        assert_eq!(range.dataset.len(), 6);
+        assert_eq!(
+            range.dataset[2],
+            (date(2).and_hms_opt(0, 0, 0).unwrap(), 1.0)
+        );
+        assert_eq!(
+            range.dataset[5],
+            (date(3).and_hms_opt(0, 15, 0).unwrap(), 12.0)
+        );
+    }
+
+    #[test]
+    fn test_fetch_intraday_range_retries_rate_limited_days() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mut mock_client = MockFitbitClientTrait::new();
+        let mut calls = 0;
+        mock_client
+            .expect_fetch_activity_intraday()
+            .times(2)
+            .returning(move |_, date, _| {
+                calls += 1;
+                if calls == 1 {
+                    Err(FitbitError::RateLimitExceeded(0))
+                } else {
+                    Ok(intraday_steps(date, &[5.0]))
+                }
+            });
+
+        let range = fetch_activity_intraday_range(
+            &mock_client,
+            ActivityResource::Steps,
+            date,
+            date,
+            DetailLevel::FifteenMinutes,
+            &IntradayRangeConfig::default(),
+        )
+        .expect("Failed to fetch range");
+
+        assert_eq!(range.daily_totals, [(date, 5.0)]);
+    }
+
+    #[test]
+    fn test_fetch_intraday_range_stops_at_first_failure() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let mut mock_client = MockFitbitClientTrait::new();
+        // Days after the failed second day are never requested
+        mock_client
+            .expect_fetch_activity_intraday()
+            .times(2)
+            .returning(move |_, date, _| {
+                if date == start {
+                    Ok(intraday_steps(date, &[5.0]))
+                } else {
+                    Err(FitbitError::authentication_error("Expired"))
+                }
+            });
+
+        let result = fetch_activity_intraday_range(
+            &mock_client,
+            ActivityResource::Steps,
+            start,
+            end,
+            DetailLevel::FifteenMinutes,
+            &IntradayRangeConfig::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(FitbitError::AuthenticationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fetch_intraday_days_keeps_going_after_failure() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_activity_intraday()
+            .times(3)
+            .returning(move |_, date, _| {
+                if date == start {
+                    Err(FitbitError::api_error(400, "Invalid date"))
+                } else {
+                    Ok(intraday_steps(date, &[5.0]))
+                }
+            });
+
+        let days = fetch_activity_intraday_days(
+            &mock_client,
+            ActivityResource::Steps,
+            start,
+            end,
+            DetailLevel::FifteenMinutes,
+            &IntradayRangeConfig::default(),
+        )
+        .expect("Failed to fetch days");
+
+        assert_eq!(days.failed_dates(), [start]);
+        assert_eq!(days.successes.len(), 2);
+    }
+
+    #[test]
+    fn test_fetch_intraday_range_rejects_reversed_range() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mock_client = MockFitbitClientTrait::new();
+
+        assert!(matches!(
+            fetch_activity_intraday_range(
+                &mock_client,
+                ActivityResource::Steps,
+                start,
+                end,
+                DetailLevel::OneMinute,
+                &IntradayRangeConfig::default(),
+            ),
+            Err(FitbitError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_time_series_for_other_resource_is_an_error() {
+        let json_str = r#"{"activities-steps": [{"dateTime": "2019-01-01", "value": "8934"}]}"#;
+
+        let response: ActivityTimeSeriesResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert!(matches!(
+            response.into_series(ActivityResource::Floors),
+            Err(FitbitError::JsonError { .. })
+        ));
+    }
+}
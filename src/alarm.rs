@@ -0,0 +1,474 @@
+//! Alarms of Fitbit trackers.
+//!
+//! Alarm times are local to the tracker and carry the UTC offset they were set with.
+
+use chrono::{FixedOffset, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// A silent alarm of a tracker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawAlarm", into = "RawAlarm")]
+pub struct Alarm {
+    pub alarm_id: u64,
+    pub time: NaiveTime,
+    /// The UTC offset of the time zone the alarm was set in
+    pub utc_offset: FixedOffset,
+    pub enabled: bool,
+    pub recurrence: AlarmRecurrence,
+    pub label: String,
+    /// Minutes between snoozes
+    pub snooze_length: u32,
+    /// How often the alarm can be snoozed
+    pub snooze_count: u32,
+    pub vibe: AlarmVibe,
+    pub is_silent: bool,
+    pub synced_to_device: bool,
+    pub deleted: bool,
+}
+
+/// The days an alarm goes off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlarmRecurrence {
+    /// Goes off once, on the next occurrence of the day
+    Once(Weekday),
+    /// Goes off every week on the days
+    Weekly(Vec<Weekday>),
+}
+
+impl AlarmRecurrence {
+    /// Returns whether the alarm recurs and its days
+    fn to_parts(&self) -> (bool, &[Weekday]) {
+        match self {
+            AlarmRecurrence::Once(day) => (false, std::slice::from_ref(day)),
+            AlarmRecurrence::Weekly(days) => (true, days),
+        }
+    }
+}
+
+/// The vibration pattern of an alarm
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum AlarmVibe {
+    #[default]
+    Default,
+    /// A pattern this crate doesn't know yet
+    Other(String),
+}
+
+impl AlarmVibe {
+    /// Returns the vibe value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlarmVibe::Default => "DEFAULT",
+            AlarmVibe::Other(value) => value,
+        }
+    }
+}
+
+impl From<AlarmVibe> for String {
+    fn from(value: AlarmVibe) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for AlarmVibe {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "DEFAULT" => AlarmVibe::Default,
+            _ => AlarmVibe::Other(value),
+        }
+    }
+}
+
+/// Raw alarm list response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlarmListResponse {
+    pub tracker_alarms: Vec<Alarm>,
+}
+
+/// Response of the add and update alarm endpoints
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlarmResponse {
+    pub tracker_alarm: Alarm,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAlarm {
+    alarm_id: u64,
+    time: String,
+    enabled: bool,
+    recurring: bool,
+    #[serde(default)]
+    week_days: Vec<String>,
+    #[serde(default)]
+    label: String,
+    snooze_length: u32,
+    snooze_count: u32,
+    vibe: AlarmVibe,
+    is_silent: bool,
+    synced_to_device: bool,
+    deleted: bool,
+}
+
+impl TryFrom<RawAlarm> for Alarm {
+    type Error = String;
+
+    fn try_from(raw: RawAlarm) -> Result<Self, Self::Error> {
+        let (time, utc_offset) = parse_alarm_time(&raw.time)
+            .ok_or_else(|| format!("invalid alarm time {}", raw.time))?;
+        let days = raw
+            .week_days
+            .iter()
+            .map(|day| parse_weekday(day).ok_or_else(|| format!("invalid week day {}", day)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let recurrence = if raw.recurring {
+            AlarmRecurrence::Weekly(days)
+        } else {
+            let day = days
+                .first()
+                .ok_or_else(|| "non-recurring alarm without a week day".to_string())?;
+            AlarmRecurrence::Once(*day)
+        };
+
+        Ok(Alarm {
+            alarm_id: raw.alarm_id,
+            time,
+            utc_offset,
+            enabled: raw.enabled,
+            recurrence,
+            label: raw.label,
+            snooze_length: raw.snooze_length,
+            snooze_count: raw.snooze_count,
+            vibe: raw.vibe,
+            is_silent: raw.is_silent,
+            synced_to_device: raw.synced_to_device,
+            deleted: raw.deleted,
+        })
+    }
+}
+
+impl From<Alarm> for RawAlarm {
+    fn from(alarm: Alarm) -> Self {
+        let (recurring, days) = alarm.recurrence.to_parts();
+
+        RawAlarm {
+            alarm_id: alarm.alarm_id,
+            time: format_alarm_time(alarm.time, alarm.utc_offset),
+            enabled: alarm.enabled,
+            recurring,
+            week_days: days
+                .iter()
+                .map(|day| format_weekday(*day).to_string())
+                .collect(),
+            label: alarm.label,
+            snooze_length: alarm.snooze_length,
+            snooze_count: alarm.snooze_count,
+            vibe: alarm.vibe,
+            is_silent: alarm.is_silent,
+            synced_to_device: alarm.synced_to_device,
+            deleted: alarm.deleted,
+        }
+    }
+}
+
+/// Request for adding or updating an alarm
+///
+/// Label, snooze and vibe settings are only applied when updating an alarm; new alarms
+/// get the tracker's defaults.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{FixedOffset, NaiveTime, Weekday};
+/// use fitbit_rs::{AlarmRecurrence, AlarmRequest};
+///
+/// let request = AlarmRequest::new(
+///     NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+///     FixedOffset::east_opt(3600).unwrap(),
+///     AlarmRecurrence::Weekly(vec![Weekday::Mon, Weekday::Wed, Weekday::Fri]),
+/// )
+/// .label("Gym")
+/// .snooze(5, 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlarmRequest {
+    time: NaiveTime,
+    utc_offset: FixedOffset,
+    recurrence: AlarmRecurrence,
+    enabled: bool,
+    label: String,
+    snooze_length: u32,
+    snooze_count: u32,
+    vibe: AlarmVibe,
+}
+
+impl AlarmRequest {
+    /// Creates a request for an enabled alarm with a snooze length of 9 minutes and up to 3
+    /// snoozes
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time the alarm goes off (minute precision)
+    /// * `utc_offset` - The UTC offset of the tracker's time zone
+    /// * `recurrence` - The days the alarm goes off
+    pub fn new(time: NaiveTime, utc_offset: FixedOffset, recurrence: AlarmRecurrence) -> Self {
+        Self {
+            time,
+            utc_offset,
+            recurrence,
+            enabled: true,
+            label: String::new(),
+            snooze_length: 9,
+            snooze_count: 3,
+            vibe: AlarmVibe::Default,
+        }
+    }
+
+    /// Sets whether the alarm is enabled
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets the label of the alarm
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Sets the minutes between snoozes and how often the alarm can be snoozed
+    pub fn snooze(mut self, length_minutes: u32, count: u32) -> Self {
+        self.snooze_length = length_minutes;
+        self.snooze_count = count;
+        self
+    }
+
+    /// Sets the vibration pattern of the alarm
+    pub fn vibe(mut self, vibe: AlarmVibe) -> Self {
+        self.vibe = vibe;
+        self
+    }
+
+    /// Returns an error message if the API would reject the request
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match &self.recurrence {
+            AlarmRecurrence::Weekly(days) if days.is_empty() => {
+                Err("weekly alarm must go off on at least one day".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the form parameters for adding the alarm
+    pub(crate) fn to_add_form(&self) -> Vec<(&'static str, String)> {
+        let (recurring, days) = self.recurrence.to_parts();
+
+        vec![
+            ("time", format_alarm_time(self.time, self.utc_offset)),
+            ("enabled", self.enabled.to_string()),
+            ("recurring", recurring.to_string()),
+            ("weekDays", format_weekdays(days)),
+        ]
+    }
+
+    /// Returns the form parameters for updating the alarm
+    pub(crate) fn to_update_form(&self) -> Vec<(&'static str, String)> {
+        let mut form = self.to_add_form();
+        form.extend([
+            ("snoozeLength", self.snooze_length.to_string()),
+            ("snoozeCount", self.snooze_count.to_string()),
+            ("label", self.label.clone()),
+            ("vibe", self.vibe.as_str().to_string()),
+        ]);
+
+        form
+    }
+}
+
+/// Parses an alarm time with UTC offset, e.g. `07:15-08:00`
+fn parse_alarm_time(value: &str) -> Option<(NaiveTime, FixedOffset)> {
+    let (time, offset) = value.split_at_checked(5)?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+
+    let (sign, offset) = match offset.split_at_checked(1)? {
+        ("+", offset) => (1, offset),
+        ("-", offset) => (-1, offset),
+        _ => return None,
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    let seconds = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+
+    Some((time, FixedOffset::east_opt(sign * seconds)?))
+}
+
+/// Formats an alarm time with UTC offset, e.g. `07:15-08:00`
+fn format_alarm_time(time: NaiveTime, utc_offset: FixedOffset) -> String {
+    format!("{}{}", time.format("%H:%M"), utc_offset)
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MONDAY" => Some(Weekday::Mon),
+        "TUESDAY" => Some(Weekday::Tue),
+        "WEDNESDAY" => Some(Weekday::Wed),
+        "THURSDAY" => Some(Weekday::Thu),
+        "FRIDAY" => Some(Weekday::Fri),
+        "SATURDAY" => Some(Weekday::Sat),
+        "SUNDAY" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn format_weekday(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MONDAY",
+        Weekday::Tue => "TUESDAY",
+        Weekday::Wed => "WEDNESDAY",
+        Weekday::Thu => "THURSDAY",
+        Weekday::Fri => "FRIDAY",
+        Weekday::Sat => "SATURDAY",
+        Weekday::Sun => "SUNDAY",
+    }
+}
+
+/// Formats week days as the comma-separated list used by the API, e.g. `MONDAY,FRIDAY`
+fn format_weekdays(days: &[Weekday]) -> String {
+    days.iter()
+        .map(|day| format_weekday(*day))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alarm_list_response() -> AlarmListResponse {
+        let json_str = r#"{
+            "trackerAlarms": [
+                {
+                    "alarmId": 1186923,
+                    "deleted": false,
+                    "enabled": true,
+                    "isSilent": false,
+                    "label": "Gym",
+                    "recurring": true,
+                    "snoozeCount": 3,
+                    "snoozeLength": 9,
+                    "syncedToDevice": true,
+                    "time": "07:15-08:00",
+                    "vibe": "DEFAULT",
+                    "weekDays": ["MONDAY", "WEDNESDAY"]
+                },
+                {
+                    "alarmId": 1186924,
+                    "deleted": false,
+                    "enabled": false,
+                    "isSilent": true,
+                    "recurring": false,
+                    "snoozeCount": 2,
+                    "snoozeLength": 5,
+                    "syncedToDevice": false,
+                    "time": "22:30+05:30",
+                    "vibe": "FANCY",
+                    "weekDays": ["SUNDAY"]
+                }
+            ]
+        }"#;
+
+        serde_json::from_str(json_str).expect("Failed to parse JSON")
+    }
+
+    #[test]
+    fn test_parse_alarms() {
+        let response = alarm_list_response();
+
+        assert_eq!(response.tracker_alarms.len(), 2);
+        let gym = &response.tracker_alarms[0];
+        assert_eq!(gym.time, NaiveTime::from_hms_opt(7, 15, 0).unwrap());
+        assert_eq!(gym.utc_offset, FixedOffset::west_opt(8 * 3600).unwrap());
+        assert_eq!(
+            gym.recurrence,
+            AlarmRecurrence::Weekly(vec![Weekday::Mon, Weekday::Wed])
+        );
+        assert_eq!(gym.vibe, AlarmVibe::Default);
+
+        let once = &response.tracker_alarms[1];
+        assert_eq!(once.utc_offset, FixedOffset::east_opt(19800).unwrap());
+        assert_eq!(once.recurrence, AlarmRecurrence::Once(Weekday::Sun));
+        assert_eq!(once.label, "");
+        assert_eq!(once.vibe, AlarmVibe::Other("FANCY".to_string()));
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let alarms = alarm_list_response().tracker_alarms;
+
+        let serialized = serde_json::to_value(&alarms).expect("Failed to serialize");
+        assert_eq!(serialized[0]["time"], "07:15-08:00");
+        assert_eq!(serialized[0]["recurring"], true);
+        assert_eq!(
+            serialized[0]["weekDays"],
+            serde_json::json!(["MONDAY", "WEDNESDAY"])
+        );
+        assert_eq!(serialized[1]["time"], "22:30+05:30");
+        assert_eq!(serialized[1]["weekDays"], serde_json::json!(["SUNDAY"]));
+        assert_eq!(serialized[1]["vibe"], "FANCY");
+
+        let round_tripped: Vec<Alarm> =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, alarms);
+    }
+
+    #[test]
+    fn test_parse_alarm_time() {
+        assert_eq!(parse_alarm_time("07:15"), None);
+        assert_eq!(parse_alarm_time("07:15 08:00"), None);
+        assert_eq!(parse_alarm_time("7:15-08:00"), None);
+    }
+
+    #[test]
+    fn test_alarm_request_forms() {
+        let request = AlarmRequest::new(
+            NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+            FixedOffset::east_opt(3600).unwrap(),
+            AlarmRecurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]),
+        )
+        .label("Gym")
+        .snooze(5, 2);
+
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(
+            request.to_update_form(),
+            vec![
+                ("time", "06:45+01:00".to_string()),
+                ("enabled", "true".to_string()),
+                ("recurring", "true".to_string()),
+                ("weekDays", "MONDAY,FRIDAY".to_string()),
+                ("snoozeLength", "5".to_string()),
+                ("snoozeCount", "2".to_string()),
+                ("label", "Gym".to_string()),
+                ("vibe", "DEFAULT".to_string()),
+            ]
+        );
+        assert_eq!(request.to_add_form().len(), 4);
+
+        // Unknown vibes are sent back as they were received
+        let request = request.vibe(AlarmVibe::Other("FANCY".to_string()));
+        assert_eq!(
+            request.to_update_form().last(),
+            Some(&("vibe", "FANCY".to_string()))
+        );
+
+        let empty = AlarmRequest::new(
+            NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+            FixedOffset::east_opt(0).unwrap(),
+            AlarmRecurrence::Weekly(Vec::new()),
+        );
+        assert!(empty.validate().is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! Analysis helpers that work across multiple nights or days.
+//!
+//! The response types only describe a single date. The functions in this module combine
+//! several responses, e.g. to measure how consistent a sleep schedule is, or bring time
+//! series of different resolutions onto a common grid.
+
+pub mod activity;
+pub mod anomaly;
+pub mod comparison;
+pub mod correlation;
+pub mod goals;
+pub mod range;
+pub mod readiness;
+pub mod records;
+pub mod sleep;
+pub mod timeseries;
+pub mod training;
+
+pub use activity::{
+    ActivityRangeSummary, MissingDays, StepGoalStreaks, aggregate_activity, step_goal_streaks,
+};
+pub use anomaly::{Anomaly, AnomalyMethod, AnomalyReason, detect_anomalies, find_anomalies};
+pub use comparison::{MetricChange, RangeComparison, compare};
+pub use correlation::{Correlation, CorrelationConfig, CorrelationMethod, DailyMetric, correlate};
+pub use goals::{GoalMetric, GoalProgress, GoalTracker, WeeklyProjection};
+pub use range::{RangeAggregate, aggregate};
+pub use readiness::{Readiness, ReadinessConfig, ReadinessEstimator, ReadinessWeights};
+pub use records::{NewRecord, PersonalRecord, PersonalRecords, RecordKind};
+pub use sleep::{
+    Chronotype, ScheduleShift, ScheduleShiftConfig, ShiftedTime, SleepConsistency,
+    SleepRangeSummary, SleepTiming, StageAverages, aggregate_sleep, detect_schedule_shifts,
+    estimate_chronotype, sleep_consistency, sleep_timing,
+};
+pub use timeseries::{Aggregation, GapFill, align, fill_gaps, resample, sample_stages};
+pub use training::{
+    TrainingLoad, TrainingLoadConfig, TrainingLoadSource, WorkloadRatio, WorkloadRatioConfig,
+    ZoneWeights, training_load, workload_ratios,
+};
@@ -0,0 +1,347 @@
+//! Multi-day activity analysis.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use chrono::NaiveDate;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Totals, daily averages and goal achievement across several days
+///
+/// Averages are zero if no days were aggregated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityRangeSummary {
+    /// Number of days that were aggregated
+    pub days: usize,
+    pub total_steps: u64,
+    pub total_calories_out: i64,
+    pub total_distance_km: f64,
+    /// Fairly and very active minutes, as counted by the active minutes goal
+    pub total_active_minutes: i64,
+    pub average_steps: f64,
+    pub average_calories_out: f64,
+    pub average_distance_km: f64,
+    pub average_active_minutes: f64,
+    /// Number of days the steps goal was met
+    pub steps_goal_days: usize,
+    /// Number of days the calories goal was met
+    pub calories_goal_days: usize,
+    /// Number of days the active minutes goal was met
+    pub active_minutes_goal_days: usize,
+    /// The day with the most active minutes, with steps breaking ties
+    pub most_active_day: Option<NaiveDate>,
+    /// The day with the fewest active minutes, with steps breaking ties
+    pub least_active_day: Option<NaiveDate>,
+}
+
+/// Aggregates the activity of several days into totals, daily averages and goal counts
+///
+/// Days with equal activity are resolved in favor of the earliest date.
+///
+/// # Arguments
+///
+/// * `days` - The activity summaries to aggregate with their dates, e.g. one per day of a
+///   week
+///
+/// # Returns
+///
+/// The totals, averages, goal counts and most and least active days
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::aggregate_activity;
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait};
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let today = chrono::Local::now().date_naive();
+/// let week = (0..7)
+///     .map(|days_ago| {
+///         let date = today - chrono::Duration::days(days_ago);
+///         client.fetch_activity_summary(date).map(|summary| (date, summary))
+///     })
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// let summary = aggregate_activity(week.iter().map(|(date, summary)| (*date, summary)));
+/// println!("Steps goal met on {} of {} days", summary.steps_goal_days, summary.days);
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn aggregate_activity<'a>(
+    days: impl IntoIterator<Item = (NaiveDate, &'a ActivitySummaryResponse)>,
+) -> ActivityRangeSummary {
+    let days: Vec<(NaiveDate, &ActivitySummaryResponse)> = days.into_iter().collect();
+
+    let total_steps: u64 = days
+        .iter()
+        .map(|(_, response)| response.summary.steps as u64)
+        .sum();
+    let total_calories_out: i64 = days
+        .iter()
+        .map(|(_, response)| response.summary.calories_out as i64)
+        .sum();
+    let total_distance_km: f64 = days
+        .iter()
+        .map(|(_, response)| response.total_distance_km())
+        .sum();
+    let total_active_minutes: i64 = days
+        .iter()
+        .map(|(_, response)| response.total_active_minutes() as i64)
+        .sum();
+    let average = |total: f64| {
+        if days.is_empty() {
+            0.0
+        } else {
+            total / days.len() as f64
+        }
+    };
+    let goal_days = |progress: &dyn Fn(&ActivitySummaryResponse) -> bool| {
+        days.iter()
+            .filter(|(_, response)| progress(response))
+            .count()
+    };
+    let activity = |response: &ActivitySummaryResponse| {
+        (response.total_active_minutes(), response.summary.steps)
+    };
+
+    ActivityRangeSummary {
+        days: days.len(),
+        total_steps,
+        total_calories_out,
+        total_distance_km,
+        total_active_minutes,
+        average_steps: average(total_steps as f64),
+        average_calories_out: average(total_calories_out as f64),
+        average_distance_km: average(total_distance_km),
+        average_active_minutes: average(total_active_minutes as f64),
+        steps_goal_days: goal_days(&|response| {
+            response
+                .steps_goal_progress()
+                .is_some_and(|progress| progress.is_complete())
+        }),
+        calories_goal_days: goal_days(&|response| {
+            response
+                .calories_goal_progress()
+                .is_some_and(|progress| progress.is_complete())
+        }),
+        active_minutes_goal_days: goal_days(&|response| {
+            response
+                .active_minutes_goal_progress()
+                .is_some_and(|progress| progress.is_complete())
+        }),
+        most_active_day: days
+            .iter()
+            .max_by_key(|(date, response)| (activity(response), Reverse(*date)))
+            .map(|(date, _)| *date),
+        least_active_day: days
+            .iter()
+            .min_by_key(|(date, response)| (activity(response), *date))
+            .map(|(date, _)| *date),
+    }
+}
+
+/// How days without an activity summary affect a streak
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingDays {
+    /// A missing day ends the streak, as if the goal was not met
+    #[default]
+    BreakStreak,
+    /// Missing days are ignored; the streak continues across them without growing
+    Skip,
+}
+
+/// Streaks of consecutive days on which the steps goal was met
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepGoalStreaks {
+    /// Length of the streak that includes the most recent day, or zero if the goal was not
+    /// met on that day
+    pub current: u32,
+    /// Length of the longest streak
+    pub longest: u32,
+    /// First day of the longest streak; the earliest one if several are equally long
+    pub longest_start: Option<NaiveDate>,
+}
+
+/// Computes the current and longest streaks of days meeting the steps goal
+///
+/// A day meets the goal if its steps reach the steps goal of that day. The current streak
+/// ends at the most recent day in `days`, so leave out today while it is still in progress.
+///
+/// # Arguments
+///
+/// * `days` - The activity summaries keyed by date
+/// * `missing_days` - Whether gaps between the dates break a streak
+///
+/// # Returns
+///
+/// The current and longest streaks
+pub fn step_goal_streaks(
+    days: &BTreeMap<NaiveDate, ActivitySummaryResponse>,
+    missing_days: MissingDays,
+) -> StepGoalStreaks {
+    let mut streaks = StepGoalStreaks {
+        current: 0,
+        longest: 0,
+        longest_start: None,
+    };
+    let mut start = None;
+    let mut previous: Option<NaiveDate> = None;
+
+    for (&date, response) in days {
+        let gap = previous.is_some_and(|previous| (date - previous).num_days() > 1);
+        if gap && missing_days == MissingDays::BreakStreak {
+            streaks.current = 0;
+        }
+        previous = Some(date);
+
+        let goal_met = response
+            .steps_goal_progress()
+            .is_some_and(|progress| progress.is_complete());
+        if !goal_met {
+            streaks.current = 0;
+            continue;
+        }
+
+        if streaks.current == 0 {
+            start = Some(date);
+        }
+        streaks.current += 1;
+        if streaks.current > streaks.longest {
+            streaks.longest = streaks.current;
+            streaks.longest_start = start;
+        }
+    }
+
+    streaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity_summary::{ActivityType, Distance, Goals, Summary};
+    use crate::units::UnitSystem;
+
+    /// Returns an activity summary with the given steps, active minutes and total distance,
+    /// and goals of 8000 steps, 2500 calories and 30 active minutes
+    fn day(steps: u32, active_minutes: i32, distance_km: f64) -> ActivitySummaryResponse {
+        ActivitySummaryResponse {
+            activities: Vec::new(),
+            summary: Summary {
+                calories_out: 2000 + steps as i32 / 20,
+                activity_calories: steps as i32 / 20,
+                calories_bmr: 1600,
+                active_score: None,
+                steps,
+                floors: None,
+                elevation: None,
+                sedentary_minutes: 700,
+                lightly_active_minutes: 200,
+                fairly_active_minutes: active_minutes / 2,
+                very_active_minutes: active_minutes - active_minutes / 2,
+                distances: vec![Distance {
+                    activity: ActivityType::Total,
+                    distance: distance_km,
+                }],
+                marginal_calories: 0,
+                resting_heart_rate: None,
+                heart_rate_zones: Vec::new(),
+                custom_heart_rate_zones: Vec::new(),
+                use_estimation: false,
+                calories_out_unestimated: None,
+                calories_estimation_mu: None,
+            },
+            goals: Goals {
+                calories_out: 2500,
+                steps: 8000,
+                distance: 8.05,
+                floors: None,
+                active_minutes: 30,
+            },
+            unit_system: UnitSystem::Metric,
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_activity() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let days = [
+            (date(3), day(10000, 45, 7.5)),
+            (date(4), day(4000, 10, 3.0)),
+            (date(5), day(12000, 45, 9.0)),
+            (date(6), day(4000, 10, 2.5)),
+        ];
+
+        let summary = aggregate_activity(days.iter().map(|(date, response)| (*date, response)));
+
+        assert_eq!(summary.days, 4);
+        assert_eq!(summary.total_steps, 30000);
+        assert_eq!(summary.total_calories_out, 9500);
+        assert_eq!(summary.total_distance_km, 22.0);
+        assert_eq!(summary.total_active_minutes, 110);
+        assert_eq!(summary.average_steps, 7500.0);
+        assert_eq!(summary.average_calories_out, 2375.0);
+        assert_eq!(summary.average_distance_km, 5.5);
+        assert_eq!(summary.average_active_minutes, 27.5);
+        assert_eq!(summary.steps_goal_days, 2);
+        assert_eq!(summary.calories_goal_days, 2);
+        assert_eq!(summary.active_minutes_goal_days, 2);
+        // Equal active minutes are broken by steps, then by the earliest date
+        assert_eq!(summary.most_active_day, Some(date(5)));
+        assert_eq!(summary.least_active_day, Some(date(4)));
+    }
+
+    #[test]
+    fn test_step_goal_streaks() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let days: BTreeMap<NaiveDate, ActivitySummaryResponse> = [
+            (date(1), day(9000, 30, 6.0)),
+            (date(2), day(8500, 30, 6.0)),
+            (date(3), day(8000, 30, 6.0)),
+            (date(4), day(3000, 0, 2.0)),
+            (date(5), day(10000, 30, 7.0)),
+            (date(6), day(11000, 30, 8.0)),
+            // 7 is missing
+            (date(8), day(9000, 30, 6.0)),
+            (date(9), day(8200, 30, 6.0)),
+        ]
+        .into_iter()
+        .collect();
+
+        let streaks = step_goal_streaks(&days, MissingDays::BreakStreak);
+        assert_eq!(streaks.current, 2);
+        assert_eq!(streaks.longest, 3);
+        assert_eq!(streaks.longest_start, Some(date(1)));
+
+        let streaks = step_goal_streaks(&days, MissingDays::Skip);
+        assert_eq!(streaks.current, 4);
+        assert_eq!(streaks.longest, 4);
+        assert_eq!(streaks.longest_start, Some(date(5)));
+    }
+
+    #[test]
+    fn test_step_goal_streak_ends_on_missed_day() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let days: BTreeMap<NaiveDate, ActivitySummaryResponse> =
+            [(date(1), day(9000, 30, 6.0)), (date(2), day(2000, 0, 1.0))]
+                .into_iter()
+                .collect();
+
+        let streaks = step_goal_streaks(&days, MissingDays::BreakStreak);
+        assert_eq!(streaks.current, 0);
+        assert_eq!(streaks.longest, 1);
+
+        let streaks = step_goal_streaks(&BTreeMap::new(), MissingDays::BreakStreak);
+        assert_eq!(streaks.longest, 0);
+        assert_eq!(streaks.longest_start, None);
+    }
+
+    #[test]
+    fn test_aggregate_activity_without_days() {
+        let summary = aggregate_activity(std::iter::empty());
+
+        assert_eq!(summary.days, 0);
+        assert_eq!(summary.average_steps, 0.0);
+        assert_eq!(summary.most_active_day, None);
+        assert_eq!(summary.least_active_day, None);
+    }
+}
@@ -0,0 +1,281 @@
+//! Detection of days whose metrics stand out from the rest of a range.
+
+use crate::analysis::correlation::{DailyMetric, daily_values};
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::response_cache::FitbitResponseCache;
+use chrono::NaiveDate;
+use std::ops::RangeInclusive;
+
+/// Fewest days with a value a metric needs before its days can be anomalous
+const MIN_DAYS: usize = 5;
+
+/// How far from the other days a value must be to be anomalous
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnomalyMethod {
+    /// Values at least `threshold` standard deviations from the mean
+    ///
+    /// Outliers inflate the standard deviation, so in short ranges even extreme values stay
+    /// close to the mean: of `n` days, none can be more than `(n - 1) / sqrt(n)` standard
+    /// deviations away, e.g. 2.27 for a week.
+    ZScore { threshold: f64 },
+    /// Values more than `factor` interquartile ranges below the first or above the third
+    /// quartile, which outliers barely affect
+    Iqr { factor: f64 },
+}
+
+impl Default for AnomalyMethod {
+    fn default() -> Self {
+        AnomalyMethod::Iqr { factor: 1.5 }
+    }
+}
+
+/// Why a day is anomalous
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnomalyReason {
+    /// The value is far above the other days, e.g. a resting heart rate spike
+    High,
+    /// The value is far below the other days, e.g. unusually short sleep
+    Low,
+    /// No steps at all, which suggests the tracker wasn't worn
+    NotWorn,
+}
+
+/// A day whose value of a metric is anomalous
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub date: NaiveDate,
+    pub metric: DailyMetric,
+    pub value: f64,
+    pub reason: AnomalyReason,
+    /// How far the value is from the other days: the z-score for [`AnomalyMethod::ZScore`],
+    /// and the distance from the nearer quartile in interquartile ranges for
+    /// [`AnomalyMethod::Iqr`]; negative if below, 0 for days the tracker wasn't worn
+    pub score: f64,
+}
+
+/// Finds the anomalous days of a daily series
+///
+/// Days without steps are reported as [`AnomalyReason::NotWorn`] if the metric is
+/// [`DailyMetric::Steps`], and left out of the statistics the other days are compared
+/// against. Series with fewer than 5 other days, or without any spread, have no high or low
+/// days.
+///
+/// # Arguments
+///
+/// * `metric` - The metric of the series
+/// * `values` - The values of the series with their dates
+/// * `method` - How far from the other days a value must be
+///
+/// # Returns
+///
+/// The anomalous days in the order of `values`
+pub fn detect_anomalies(
+    metric: DailyMetric,
+    values: impl IntoIterator<Item = (NaiveDate, f64)>,
+    method: AnomalyMethod,
+) -> Vec<Anomaly> {
+    let values: Vec<(NaiveDate, f64)> = values.into_iter().collect();
+    let not_worn = |value: f64| metric == DailyMetric::Steps && value == 0.0;
+    let worn: Vec<f64> = values
+        .iter()
+        .map(|(_, value)| *value)
+        .filter(|value| !not_worn(*value))
+        .collect();
+    let score = if worn.len() < MIN_DAYS {
+        None
+    } else {
+        scorer(&worn, method)
+    };
+
+    values
+        .into_iter()
+        .filter_map(|(date, value)| {
+            let (reason, score) = if not_worn(value) {
+                (AnomalyReason::NotWorn, 0.0)
+            } else {
+                let score = score.as_ref()?(value)?;
+                let reason = if score > 0.0 {
+                    AnomalyReason::High
+                } else {
+                    AnomalyReason::Low
+                };
+                (reason, score)
+            };
+
+            Some(Anomaly {
+                date,
+                metric,
+                value,
+                reason,
+                score,
+            })
+        })
+        .collect()
+}
+
+/// Finds the anomalous days of several metrics over a date range
+///
+/// Responses come from the cache, and are fetched and cached if missing. Days Fitbit has no
+/// data for, or that the access token lacks the scope to read, are left out.
+///
+/// # Arguments
+///
+/// * `range` - The days to check, e.g. `start..=end`
+/// * `metrics` - The metrics to check
+/// * `method` - How far from the other days a value must be
+/// * `cache` - The cache to get the responses from
+///
+/// # Returns
+///
+/// The anomalous days in chronological order, `FitbitError::InvalidArgument` if the range is
+/// empty, or the first error that doesn't mean a day is unavailable
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::{AnomalyMethod, DailyMetric, find_anomalies};
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+///
+/// let anomalies = find_anomalies(
+///     today - chrono::Duration::days(29)..=today,
+///     &[DailyMetric::RestingHeartRate, DailyMetric::MinutesAsleep, DailyMetric::Steps],
+///     AnomalyMethod::default(),
+///     &mut cache,
+/// )?;
+/// for anomaly in anomalies {
+///     println!("{}: {:?} {:?}", anomaly.date, anomaly.metric, anomaly.reason);
+/// }
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn find_anomalies<C: FitbitClientTrait>(
+    range: RangeInclusive<NaiveDate>,
+    metrics: &[DailyMetric],
+    method: AnomalyMethod,
+    cache: &mut FitbitResponseCache<C>,
+) -> Result<Vec<Anomaly>, FitbitError> {
+    let (start, end) = range.into_inner();
+    let mut anomalies = Vec::new();
+    for &metric in metrics {
+        let values = daily_values(metric, start, end, cache)?;
+        anomalies.extend(detect_anomalies(metric, values, method));
+    }
+    anomalies.sort_by_key(|anomaly| anomaly.date);
+
+    Ok(anomalies)
+}
+
+/// Returns a function that scores a value against the given values, returning `None` for
+/// values that aren't anomalous; `None` if the values have no spread
+fn scorer(values: &[f64], method: AnomalyMethod) -> Option<Box<dyn Fn(f64) -> Option<f64>>> {
+    match method {
+        AnomalyMethod::ZScore { threshold } => {
+            let n = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / n;
+            let std_dev = (values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / n)
+                .sqrt();
+            if std_dev == 0.0 {
+                return None;
+            }
+
+            Some(Box::new(move |value| {
+                let z_score = (value - mean) / std_dev;
+                (z_score.abs() >= threshold).then_some(z_score)
+            }))
+        }
+        AnomalyMethod::Iqr { factor } => {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+            let iqr = q3 - q1;
+            if iqr == 0.0 {
+                return None;
+            }
+
+            Some(Box::new(move |value| {
+                let score = if value < q1 {
+                    (value - q1) / iqr
+                } else {
+                    (value - q3).max(0.0) / iqr
+                };
+                (score.abs() > factor).then_some(score)
+            }))
+        }
+    }
+}
+
+/// Returns a quantile of sorted values, interpolating between the two nearest ones
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: &[f64]) -> Vec<(NaiveDate, f64)> {
+        let first = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        values
+            .iter()
+            .enumerate()
+            .map(|(day, value)| (first + chrono::Duration::days(day as i64), *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_resting_heart_rate_spike() {
+        let values = series(&[58.0, 59.0, 60.0, 58.0, 59.0, 60.0, 59.0, 68.0]);
+
+        let anomalies = detect_anomalies(
+            DailyMetric::RestingHeartRate,
+            values.clone(),
+            AnomalyMethod::default(),
+        );
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].date, values[7].0);
+        assert_eq!(anomalies[0].reason, AnomalyReason::High);
+        assert_eq!(anomalies[0].score, 6.4);
+    }
+
+    #[test]
+    fn test_short_sleep_by_z_score() {
+        let values = series(&[420.0, 430.0, 410.0, 425.0, 415.0, 420.0, 200.0]);
+
+        let anomalies = detect_anomalies(
+            DailyMetric::MinutesAsleep,
+            values,
+            AnomalyMethod::ZScore { threshold: 2.0 },
+        );
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].reason, AnomalyReason::Low);
+        assert!(anomalies[0].score < -2.0);
+    }
+
+    #[test]
+    fn test_zero_step_days_are_not_worn() {
+        let values = series(&[8_000.0, 0.0, 9_000.0]);
+
+        let anomalies = detect_anomalies(DailyMetric::Steps, values, AnomalyMethod::default());
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].reason, AnomalyReason::NotWorn);
+    }
+
+    #[test]
+    fn test_quantile() {
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.25), 2.0);
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0], 0.75), 3.25);
+    }
+}
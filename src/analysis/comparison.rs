@@ -0,0 +1,192 @@
+//! Comparison of the sleep and activity of two date ranges.
+
+use crate::analysis::correlation::{DailyMetric, daily_values};
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::response_cache::FitbitResponseCache;
+use chrono::NaiveDate;
+use std::ops::RangeInclusive;
+
+/// The metrics [`compare`] compares
+const COMPARED_METRICS: [DailyMetric; 5] = [
+    DailyMetric::MinutesAsleep,
+    DailyMetric::SleepEfficiency,
+    DailyMetric::Steps,
+    DailyMetric::ActiveMinutes,
+    DailyMetric::RestingHeartRate,
+];
+
+/// How the daily average of a metric changed from one range to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricChange {
+    pub metric: DailyMetric,
+    /// The daily average in the first range
+    pub a: f64,
+    /// The daily average in the second range
+    pub b: f64,
+}
+
+impl MetricChange {
+    /// Returns `b - a`
+    pub fn delta(&self) -> f64 {
+        self.b - self.a
+    }
+
+    /// Returns the change relative to `a` in percent, e.g. `-10.0` for a drop by a tenth,
+    /// or `None` if `a` is 0
+    pub fn percent_change(&self) -> Option<f64> {
+        (self.a != 0.0).then(|| self.delta() / self.a * 100.0)
+    }
+}
+
+/// The changes of the daily averages from one date range to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeComparison {
+    pub range_a: RangeInclusive<NaiveDate>,
+    pub range_b: RangeInclusive<NaiveDate>,
+    /// The changes of sleep duration, sleep efficiency, steps, active minutes and resting
+    /// heart rate, leaving out metrics without a value in either range
+    pub changes: Vec<MetricChange>,
+}
+
+impl RangeComparison {
+    /// Returns the change of a metric, or `None` if it isn't compared or has no value in
+    /// either range
+    pub fn change(&self, metric: DailyMetric) -> Option<&MetricChange> {
+        self.changes.iter().find(|change| change.metric == metric)
+    }
+}
+
+/// Compares the daily averages of sleep and activity metrics of two date ranges
+///
+/// Averages are taken over the days that have a value, so ranges of different lengths
+/// compare fairly. Responses come from the cache, and are fetched and cached if missing.
+/// Days Fitbit has no data for, or that the access token lacks the scope to read, are left
+/// out.
+///
+/// # Arguments
+///
+/// * `range_a` - The range to compare against, e.g. last month
+/// * `range_b` - The range to compare, e.g. this month
+/// * `cache` - The cache to get the responses from
+///
+/// # Returns
+///
+/// The changes from `range_a` to `range_b`, `FitbitError::InvalidArgument` if a range is
+/// empty, or the first error that doesn't mean a day is unavailable
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::{DailyMetric, compare};
+/// use fitbit_rs::report::ReportPeriod;
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let this_month = ReportPeriod::Month(chrono::Local::now().date_naive());
+/// let last_month = ReportPeriod::Month(this_month.start() - chrono::Duration::days(1));
+///
+/// let comparison = compare(
+///     last_month.start()..=last_month.end(),
+///     this_month.start()..=this_month.end(),
+///     &mut cache,
+/// )?;
+/// if let Some(steps) = comparison.change(DailyMetric::Steps) {
+///     println!("Steps changed by {:+.1}%", steps.percent_change().unwrap_or(0.0));
+/// }
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn compare<C: FitbitClientTrait>(
+    range_a: RangeInclusive<NaiveDate>,
+    range_b: RangeInclusive<NaiveDate>,
+    cache: &mut FitbitResponseCache<C>,
+) -> Result<RangeComparison, FitbitError> {
+    let mut changes = Vec::new();
+    for metric in COMPARED_METRICS {
+        let a = average(metric, &range_a, cache)?;
+        let b = average(metric, &range_b, cache)?;
+        if let (Some(a), Some(b)) = (a, b) {
+            changes.push(MetricChange { metric, a, b });
+        }
+    }
+
+    Ok(RangeComparison {
+        range_a,
+        range_b,
+        changes,
+    })
+}
+
+/// Returns the daily average of a metric over a range, or `None` if no day has a value
+fn average<C: FitbitClientTrait>(
+    metric: DailyMetric,
+    range: &RangeInclusive<NaiveDate>,
+    cache: &mut FitbitResponseCache<C>,
+) -> Result<Option<f64>, FitbitError> {
+    let values = daily_values(metric, *range.start(), *range.end(), cache)?;
+    if values.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(values.values().sum::<f64>() / values.len() as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::ActivitySummaryBuilder;
+    use crate::fixtures::date;
+    use chrono::Datelike;
+
+    #[test]
+    fn test_percent_change() {
+        let change = MetricChange {
+            metric: DailyMetric::Steps,
+            a: 8_000.0,
+            b: 10_000.0,
+        };
+        assert_eq!(change.delta(), 2_000.0);
+        assert_eq!(change.percent_change(), Some(25.0));
+
+        let from_zero = MetricChange { a: 0.0, ..change };
+        assert_eq!(from_zero.percent_change(), None);
+    }
+
+    #[test]
+    fn test_compare() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client.expect_fetch_sleep_data().returning(|_| {
+            Err(FitbitError::NotFound {
+                message: "No data".to_string(),
+            })
+        });
+        mock_client
+            .expect_fetch_activity_summary()
+            .times(4)
+            .returning(|day| {
+                let steps = if day.day() <= 2 { 8_000 } else { 6_000 };
+                Ok(ActivitySummaryBuilder::new()
+                    .steps(steps)
+                    .resting_heart_rate(60)
+                    .build())
+            });
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+        let comparison = compare(date(1)..=date(2), date(3)..=date(4), &mut cache)?;
+
+        let steps = comparison.change(DailyMetric::Steps).unwrap();
+        assert_eq!((steps.a, steps.b), (8_000.0, 6_000.0));
+        assert_eq!(steps.percent_change(), Some(-25.0));
+        assert_eq!(
+            comparison
+                .change(DailyMetric::RestingHeartRate)
+                .unwrap()
+                .delta(),
+            0.0
+        );
+        assert_eq!(comparison.change(DailyMetric::MinutesAsleep), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+//! Correlations between daily sleep and activity metrics.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::analysis::range::available;
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::response_cache::FitbitResponseCache;
+use crate::sleep::{SleepResponseV1_2, SleepType};
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Fewest days a correlation is computed from
+const MIN_PAIRS: usize = 3;
+
+/// A metric with one value per day
+///
+/// Activity metrics come from the activity summary of the day. Sleep metrics come from the
+/// main sleep of the day's sleep response, i.e. the night that ended on the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DailyMetric {
+    Steps,
+    /// Fairly and very active minutes
+    ActiveMinutes,
+    VeryActiveMinutes,
+    SedentaryMinutes,
+    CaloriesOut,
+    RestingHeartRate,
+    MinutesAsleep,
+    TimeInBed,
+    /// The efficiency reported by Fitbit
+    SleepEfficiency,
+    /// Minutes of deep sleep; only recorded by stages records
+    DeepSleepMinutes,
+    /// Minutes of light sleep; only recorded by stages records
+    LightSleepMinutes,
+    /// Minutes of REM sleep; only recorded by stages records
+    RemSleepMinutes,
+    /// Minutes awake during the night; only recorded by stages records
+    WakeMinutes,
+}
+
+impl DailyMetric {
+    /// Checks if the metric comes from the sleep response of a day
+    pub fn is_sleep(&self) -> bool {
+        !matches!(
+            self,
+            DailyMetric::Steps
+                | DailyMetric::ActiveMinutes
+                | DailyMetric::VeryActiveMinutes
+                | DailyMetric::SedentaryMinutes
+                | DailyMetric::CaloriesOut
+                | DailyMetric::RestingHeartRate
+        )
+    }
+
+    /// Returns the value of an activity metric, or `None` for sleep metrics and values the
+    /// day doesn't have
+    fn activity_value(&self, response: &ActivitySummaryResponse) -> Option<f64> {
+        let summary = &response.summary;
+        let value = match self {
+            DailyMetric::Steps => summary.steps as f64,
+            DailyMetric::ActiveMinutes => response.total_active_minutes() as f64,
+            DailyMetric::VeryActiveMinutes => summary.very_active_minutes as f64,
+            DailyMetric::SedentaryMinutes => summary.sedentary_minutes as f64,
+            DailyMetric::CaloriesOut => summary.calories_out as f64,
+            DailyMetric::RestingHeartRate => summary.resting_heart_rate? as f64,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Returns the value of a sleep metric, or `None` for activity metrics and values the
+    /// night doesn't have
+    fn sleep_value(&self, response: &SleepResponseV1_2) -> Option<f64> {
+        let sleep = response.main_sleep()?;
+        let stages = (sleep.sleep_type == SleepType::Stages).then_some(&sleep.levels.summary);
+        let value = match self {
+            DailyMetric::MinutesAsleep => sleep.minutes_asleep,
+            DailyMetric::TimeInBed => sleep.time_in_bed,
+            DailyMetric::SleepEfficiency => sleep.efficiency? as u32,
+            DailyMetric::DeepSleepMinutes => stages?.deep.minutes,
+            DailyMetric::LightSleepMinutes => stages?.light.minutes,
+            DailyMetric::RemSleepMinutes => stages?.rem.minutes,
+            DailyMetric::WakeMinutes => stages?.wake.minutes,
+            _ => return None,
+        };
+
+        Some(value as f64)
+    }
+}
+
+/// How the correlation of two metrics is measured
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CorrelationMethod {
+    /// Linear correlation of the values
+    #[default]
+    Pearson,
+    /// Correlation of the ranks of the values, which also captures monotonic relationships
+    /// that aren't linear and is less sensitive to outliers
+    Spearman,
+}
+
+impl CorrelationMethod {
+    /// Computes the correlation coefficient of paired values
+    ///
+    /// # Returns
+    ///
+    /// The coefficient from -1 to 1, or `None` if there are fewer than 3 pairs or one of the
+    /// values is constant
+    pub fn coefficient(&self, pairs: &[(f64, f64)]) -> Option<f64> {
+        if pairs.len() < MIN_PAIRS {
+            return None;
+        }
+
+        match self {
+            CorrelationMethod::Pearson => pearson(pairs),
+            CorrelationMethod::Spearman => {
+                let xs = ranks(pairs.iter().map(|(x, _)| *x));
+                let ys = ranks(pairs.iter().map(|(_, y)| *y));
+                let ranked: Vec<(f64, f64)> = xs.into_iter().zip(ys).collect();
+                pearson(&ranked)
+            }
+        }
+    }
+}
+
+/// Parameters for [`correlate`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CorrelationConfig {
+    pub method: CorrelationMethod,
+    /// Days between the value of `x` and the value of `y` it's paired with
+    ///
+    /// Sleep is dated by the day it ends, so a lag of 1 pairs the activity of a day with the
+    /// following night.
+    pub lag_days: i64,
+}
+
+/// The correlation of two daily metrics over a date range
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correlation {
+    pub x: DailyMetric,
+    pub y: DailyMetric,
+    pub config: CorrelationConfig,
+    /// The days of `x` that both metrics have a value for, with the values of `x` and `y`
+    pub pairs: Vec<(NaiveDate, f64, f64)>,
+    /// The coefficient from -1 to 1, or `None` if there are fewer than 3 pairs or one of the
+    /// metrics is constant
+    pub coefficient: Option<f64>,
+}
+
+/// Correlates two daily metrics over a date range
+///
+/// Responses come from the cache, and are fetched and cached if missing. Days Fitbit has no
+/// data for, or that the access token lacks the scope to read, are left out.
+///
+/// # Arguments
+///
+/// * `range` - The days of `x`, e.g. `start..=end`
+/// * `x` - The first metric
+/// * `y` - The second metric, taken `config.lag_days` after each day of `x`
+/// * `config` - The method and lag
+/// * `cache` - The cache to get the responses from
+///
+/// # Returns
+///
+/// The correlation, `FitbitError::InvalidArgument` if the range is empty, or the first error
+/// that doesn't mean a day is unavailable
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::{CorrelationConfig, CorrelationMethod, DailyMetric, correlate};
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+/// let config = CorrelationConfig {
+///     method: CorrelationMethod::Spearman,
+///     lag_days: 1,
+/// };
+///
+/// let correlation = correlate(
+///     today - chrono::Duration::days(30)..=today - chrono::Duration::days(1),
+///     DailyMetric::VeryActiveMinutes,
+///     DailyMetric::DeepSleepMinutes,
+///     &config,
+///     &mut cache,
+/// )?;
+/// println!("{:?} over {} days", correlation.coefficient, correlation.pairs.len());
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn correlate<C: FitbitClientTrait>(
+    range: RangeInclusive<NaiveDate>,
+    x: DailyMetric,
+    y: DailyMetric,
+    config: &CorrelationConfig,
+    cache: &mut FitbitResponseCache<C>,
+) -> Result<Correlation, FitbitError> {
+    let (start, end) = range.into_inner();
+    let lag = Duration::days(config.lag_days);
+
+    let xs = daily_values(x, start, end, cache)?;
+    let ys = daily_values(y, start + lag, end + lag, cache)?;
+    let pairs: Vec<(NaiveDate, f64, f64)> = xs
+        .into_iter()
+        .filter_map(|(date, x)| ys.get(&(date + lag)).map(|y| (date, x, *y)))
+        .collect();
+    let values: Vec<(f64, f64)> = pairs.iter().map(|(_, x, y)| (*x, *y)).collect();
+
+    Ok(Correlation {
+        x,
+        y,
+        config: config.clone(),
+        coefficient: config.method.coefficient(&values),
+        pairs,
+    })
+}
+
+/// Returns the values of a metric for the days from `start` to `end` that have one
+pub(super) fn daily_values<C: FitbitClientTrait>(
+    metric: DailyMetric,
+    start: NaiveDate,
+    end: NaiveDate,
+    cache: &mut FitbitResponseCache<C>,
+) -> Result<BTreeMap<NaiveDate, f64>, FitbitError> {
+    let values = if metric.is_sleep() {
+        available(cache.get_sleep_responses(start, end)?)?
+            .into_iter()
+            .filter_map(|(date, response)| Some((date, metric.sleep_value(response)?)))
+            .collect()
+    } else {
+        available(cache.get_activity_summary_responses(start, end)?)?
+            .into_iter()
+            .filter_map(|(date, response)| Some((date, metric.activity_value(response)?)))
+            .collect()
+    };
+
+    Ok(values)
+}
+
+/// Returns the Pearson correlation coefficient, or `None` if a variable is constant
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+
+    Some((covariance / (variance_x * variance_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Returns the ranks of values, starting at 1, with ties sharing their average rank
+fn ranks(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    let values: Vec<f64> = values.collect();
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut first = 0;
+    while first < order.len() {
+        let mut last = first;
+        while last + 1 < order.len() && values[order[last + 1]] == values[order[first]] {
+            last += 1;
+        }
+        let rank = (first + last) as f64 / 2.0 + 1.0;
+        for &index in &order[first..=last] {
+            ranks[index] = rank;
+        }
+        first = last + 1;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::date;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::sleep::SleepLevel;
+    use chrono::Datelike;
+
+    #[test]
+    fn test_coefficients() {
+        let linear = [(1.0, 2.0), (2.0, 4.0), (3.0, 6.0), (4.0, 8.0)];
+        assert_eq!(CorrelationMethod::Pearson.coefficient(&linear), Some(1.0));
+
+        // Monotonic but not linear
+        let cubic = [(1.0, 1.0), (2.0, 8.0), (3.0, 27.0), (4.0, 64.0)];
+        assert!(CorrelationMethod::Pearson.coefficient(&cubic).unwrap() < 1.0);
+        assert_eq!(CorrelationMethod::Spearman.coefficient(&cubic), Some(1.0));
+
+        let inverse = [(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)];
+        assert_eq!(
+            CorrelationMethod::Spearman.coefficient(&inverse),
+            Some(-1.0)
+        );
+
+        let constant = [(1.0, 5.0), (2.0, 5.0), (3.0, 5.0)];
+        assert_eq!(CorrelationMethod::Pearson.coefficient(&constant), None);
+        assert_eq!(CorrelationMethod::Pearson.coefficient(&linear[..2]), None);
+    }
+
+    #[test]
+    fn test_ranks_average_ties() {
+        let ranks = ranks([10.0, 20.0, 10.0, 30.0].into_iter());
+
+        assert_eq!(ranks, vec![1.5, 3.0, 1.5, 4.0]);
+    }
+
+    #[test]
+    fn test_correlate_activity_with_next_night() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_activity_summary()
+            .times(4)
+            .returning(|day| match day.day() {
+                4 => Err(FitbitError::NotFound {
+                    message: "No data".to_string(),
+                }),
+                day => Ok(ActivitySummaryBuilder::new()
+                    .activity_minutes(0, 0, 0, day as i32 * 10)
+                    .build()),
+            });
+        mock_client
+            .expect_fetch_sleep_data()
+            .times(4)
+            .returning(|day| {
+                let start = (day - Duration::days(1)).and_hms_opt(23, 0, 0).unwrap();
+                Ok(SleepDataBuilder::new(start)
+                    .stage(SleepLevel::Light, 240)
+                    .stage(SleepLevel::Deep, 30 + day.day() * 10)
+                    .build_response())
+            });
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+        let config = CorrelationConfig {
+            method: CorrelationMethod::Pearson,
+            lag_days: 1,
+        };
+        let correlation = correlate(
+            date(1)..=date(4),
+            DailyMetric::VeryActiveMinutes,
+            DailyMetric::DeepSleepMinutes,
+            &config,
+            &mut cache,
+        )?;
+
+        assert_eq!(
+            correlation.pairs,
+            vec![
+                (date(1), 10.0, 50.0),
+                (date(2), 20.0, 60.0),
+                (date(3), 30.0, 70.0),
+            ]
+        );
+        assert_eq!(correlation.coefficient, Some(1.0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,334 @@
+//! Progress towards daily and weekly activity goals.
+
+use crate::activity_goals::ActivityGoals;
+use crate::activity_summary::{ActivitySummaryResponse, Percentage};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+
+/// Days in a goal week
+const DAYS_PER_WEEK: u32 = 7;
+
+/// An activity metric that goals can be set for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GoalMetric {
+    Steps,
+    /// Distance in the client's distance unit
+    Distance,
+    Floors,
+    CaloriesOut,
+    /// Fairly and very active minutes
+    ActiveMinutes,
+}
+
+impl GoalMetric {
+    /// Returns the value of the metric on a day, or `None` if the device doesn't record it
+    fn actual(&self, response: &ActivitySummaryResponse) -> Option<f64> {
+        let summary = &response.summary;
+        match self {
+            GoalMetric::Steps => Some(summary.steps as f64),
+            GoalMetric::Distance => Some(response.total_distance().value),
+            GoalMetric::Floors => summary.floors.map(|floors| floors as f64),
+            GoalMetric::CaloriesOut => Some(summary.calories_out as f64),
+            GoalMetric::ActiveMinutes => Some(response.total_active_minutes() as f64),
+        }
+    }
+
+    /// Returns the daily goal of a day, or `None` if no goal is set
+    fn daily_goal(&self, response: &ActivitySummaryResponse) -> Option<f64> {
+        let goals = &response.goals;
+        let goal = match self {
+            GoalMetric::Steps => goals.steps as f64,
+            GoalMetric::Distance => goals.distance,
+            GoalMetric::Floors => goals.floors? as f64,
+            GoalMetric::CaloriesOut => goals.calories_out as f64,
+            GoalMetric::ActiveMinutes => goals.active_minutes as f64,
+        };
+
+        (goal > 0.0).then_some(goal)
+    }
+
+    /// Returns the weekly goal, or `None` if no goal is set
+    fn weekly_goal(&self, goals: &ActivityGoals) -> Option<f64> {
+        let goal = match self {
+            GoalMetric::Steps => goals.steps? as f64,
+            GoalMetric::Distance => goals.distance?,
+            GoalMetric::Floors => goals.floors? as f64,
+            GoalMetric::CaloriesOut => goals.calories_out? as f64,
+            GoalMetric::ActiveMinutes => goals.active_minutes? as f64,
+        };
+
+        (goal > 0.0).then_some(goal)
+    }
+}
+
+/// Progress towards the daily goals of a metric across several days
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub metric: GoalMetric,
+    /// Number of days with a goal and a value for the metric
+    pub days: usize,
+    /// Number of those days the goal was met
+    pub days_met: usize,
+    /// Sum of the values of those days
+    pub total: f64,
+    /// Sum of the goals of those days
+    pub goal_total: f64,
+}
+
+impl GoalProgress {
+    /// Returns the total as a percentage of the summed goals, or `None` if there were no
+    /// days with a goal
+    pub fn achieved(&self) -> Option<Percentage> {
+        Percentage::of(self.total, self.goal_total)
+    }
+
+    /// Returns the share of days the goal was met, from 0 to 1, or 0 if there were no days
+    /// with a goal
+    pub fn met_rate(&self) -> f64 {
+        if self.days == 0 {
+            0.0
+        } else {
+            self.days_met as f64 / self.days as f64
+        }
+    }
+}
+
+/// Progress towards a weekly goal, and what it takes to reach it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklyProjection {
+    pub metric: GoalMetric,
+    /// The first day of the week
+    pub week_start: NaiveDate,
+    pub goal: f64,
+    /// The total of the week so far
+    pub total: f64,
+    /// The total at the end of the week if the daily average so far continues
+    pub projected_total: f64,
+    /// Number of days left in the week after the current day
+    pub remaining_days: u32,
+    /// The average needed on each remaining day to reach the goal; 0 if it is already
+    /// reached, `None` if the week is over without reaching it
+    pub pace_needed: Option<f64>,
+}
+
+impl WeeklyProjection {
+    /// Returns the progress towards the weekly goal so far
+    pub fn progress(&self) -> Option<Percentage> {
+        Percentage::of(self.total, self.goal)
+    }
+
+    /// Checks if the goal is reached at the end of the week if the daily average so far
+    /// continues
+    pub fn is_on_track(&self) -> bool {
+        self.projected_total >= self.goal
+    }
+}
+
+/// Compares activity against the daily goals of each day and against weekly goals
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::{GoalMetric, GoalTracker};
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait, FitbitResponseCache, GoalPeriod};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+/// let weekly_goals = cache.client().fetch_activity_goals(GoalPeriod::Weekly)?;
+///
+/// let days = cache.get_activity_summary_responses(today - chrono::Duration::days(6), today)?;
+/// let tracker = GoalTracker::new(days.successes.iter().map(|(date, day)| (*date, *day)))
+///     .with_weekly_goals(weekly_goals);
+///
+/// if let Some(steps) = tracker.weekly_projection(GoalMetric::Steps, today) {
+///     println!("{:.0} steps a day needed to reach the weekly goal", steps.pace_needed.unwrap_or(0.0));
+/// }
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct GoalTracker<'a> {
+    days: BTreeMap<NaiveDate, &'a ActivitySummaryResponse>,
+    weekly_goals: Option<ActivityGoals>,
+    week_start: Weekday,
+}
+
+impl<'a> GoalTracker<'a> {
+    /// Creates a tracker for the activity of several days
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The activity summaries with their dates
+    pub fn new(days: impl IntoIterator<Item = (NaiveDate, &'a ActivitySummaryResponse)>) -> Self {
+        Self {
+            days: days.into_iter().collect(),
+            weekly_goals: None,
+            week_start: Weekday::Mon,
+        }
+    }
+
+    /// Sets the weekly goals, as returned by
+    /// [`fetch_activity_goals`](crate::FitbitClientTrait::fetch_activity_goals)
+    pub fn with_weekly_goals(mut self, goals: ActivityGoals) -> Self {
+        self.weekly_goals = Some(goals);
+        self
+    }
+
+    /// Sets the first day of a goal week; the default is Monday
+    pub fn with_week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// Returns the progress towards the daily goals of a metric
+    ///
+    /// Days without a goal or without a value for the metric, e.g. floors on devices
+    /// without an altimeter, are left out.
+    pub fn progress(&self, metric: GoalMetric) -> GoalProgress {
+        let mut progress = GoalProgress {
+            metric,
+            days: 0,
+            days_met: 0,
+            total: 0.0,
+            goal_total: 0.0,
+        };
+        for response in self.days.values() {
+            let (Some(actual), Some(goal)) = (metric.actual(response), metric.daily_goal(response))
+            else {
+                continue;
+            };
+
+            progress.days += 1;
+            progress.total += actual;
+            progress.goal_total += goal;
+            if actual >= goal {
+                progress.days_met += 1;
+            }
+        }
+
+        progress
+    }
+
+    /// Projects the weekly total of a metric from the days of the week so far
+    ///
+    /// `today` counts as a full day, so the projection is low while it is still in progress.
+    /// Days of the week without an activity summary count as zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The metric to project
+    /// * `today` - The current day of the week
+    ///
+    /// # Returns
+    ///
+    /// The projection, or `None` if no weekly goal is set for the metric
+    pub fn weekly_projection(
+        &self,
+        metric: GoalMetric,
+        today: NaiveDate,
+    ) -> Option<WeeklyProjection> {
+        let goal = metric.weekly_goal(self.weekly_goals.as_ref()?)?;
+        let elapsed_days = (DAYS_PER_WEEK + today.weekday().num_days_from_monday()
+            - self.week_start.num_days_from_monday())
+            % DAYS_PER_WEEK
+            + 1;
+        let week_start = today - Duration::days(elapsed_days as i64 - 1);
+
+        let total: f64 = self
+            .days
+            .range(week_start..=today)
+            .filter_map(|(_, response)| metric.actual(response))
+            .sum();
+        let remaining_days = DAYS_PER_WEEK - elapsed_days;
+        let remaining = (goal - total).max(0.0);
+        let pace_needed = match remaining_days {
+            _ if remaining == 0.0 => Some(0.0),
+            0 => None,
+            days => Some(remaining / days as f64),
+        };
+
+        Some(WeeklyProjection {
+            metric,
+            week_start,
+            goal,
+            total,
+            projected_total: total / elapsed_days as f64 * DAYS_PER_WEEK as f64,
+            remaining_days,
+            pace_needed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::ActivitySummaryBuilder;
+    use crate::fixtures::date;
+
+    #[test]
+    fn test_daily_goal_progress() {
+        let days = [
+            ActivitySummaryBuilder::new().steps(12_000).build(),
+            ActivitySummaryBuilder::new().steps(8_000).build(),
+            ActivitySummaryBuilder::new()
+                .steps(5_000)
+                .floors(None)
+                .build(),
+        ];
+        let tracker = GoalTracker::new(
+            days.iter()
+                .enumerate()
+                .map(|(i, day)| (date(3 + i as u32), day)),
+        );
+
+        let steps = tracker.progress(GoalMetric::Steps);
+        assert_eq!(steps.days, 3);
+        assert_eq!(steps.days_met, 1);
+        assert_eq!(steps.total, 25_000.0);
+        assert_eq!(
+            steps.achieved().unwrap().value(),
+            25_000.0 / 30_000.0 * 100.0
+        );
+        assert_eq!(steps.met_rate(), 1.0 / 3.0);
+
+        // The third day has no altimeter
+        assert_eq!(tracker.progress(GoalMetric::Floors).days, 2);
+    }
+
+    #[test]
+    fn test_weekly_projection() {
+        let days = [
+            ActivitySummaryBuilder::new().steps(10_000).build(),
+            ActivitySummaryBuilder::new().steps(14_000).build(),
+        ];
+        let weekly_goals = ActivityGoals {
+            steps: Some(70_000),
+            ..Default::default()
+        };
+        let tracker = GoalTracker::new([(date(3), &days[0]), (date(4), &days[1])])
+            .with_weekly_goals(weekly_goals.clone());
+
+        let projection = tracker
+            .weekly_projection(GoalMetric::Steps, date(4))
+            .unwrap();
+        assert_eq!(projection.week_start, date(3));
+        assert_eq!(projection.total, 24_000.0);
+        assert_eq!(projection.projected_total, 84_000.0);
+        assert!(projection.is_on_track());
+        assert_eq!(projection.remaining_days, 5);
+        assert_eq!(projection.pace_needed, Some(9_200.0));
+        assert_eq!(
+            tracker.weekly_projection(GoalMetric::CaloriesOut, date(4)),
+            None
+        );
+
+        // A week starting on Tuesday only counts the second day
+        let projection = GoalTracker::new([(date(3), &days[0]), (date(4), &days[1])])
+            .with_weekly_goals(weekly_goals)
+            .with_week_start(Weekday::Tue)
+            .weekly_projection(GoalMetric::Steps, date(4))
+            .unwrap();
+        assert_eq!(projection.week_start, date(4));
+        assert_eq!(projection.total, 14_000.0);
+        assert_eq!(projection.remaining_days, 6);
+    }
+}
@@ -0,0 +1,203 @@
+//! Sleep and activity aggregates of a date range, fetched through the cache.
+
+use crate::analysis::activity::{ActivityRangeSummary, aggregate_activity};
+use crate::analysis::sleep::{SleepRangeSummary, aggregate_sleep};
+use crate::batch::{BatchResult, date_range};
+use crate::daily_record::unless_unavailable;
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::response_cache::FitbitResponseCache;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+/// Combined sleep and activity aggregates of a date range
+///
+/// Days Fitbit has no data for, or that the access token lacks the scope to read, are
+/// listed as missing and left out of the aggregates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeAggregate {
+    /// The first day of the range
+    pub start: NaiveDate,
+    /// The last day of the range (inclusive)
+    pub end: NaiveDate,
+    /// The sleep of the days with a sleep response
+    pub sleep: SleepRangeSummary,
+    /// The activity of the days with an activity summary
+    pub activity: ActivityRangeSummary,
+    /// Share of the days with an activity summary that met the steps goal, from 0 to 1
+    pub steps_goal_rate: f64,
+    /// Share of the days with an activity summary that met the calories goal, from 0 to 1
+    pub calories_goal_rate: f64,
+    /// Share of the days with an activity summary that met the active minutes goal, from 0
+    /// to 1
+    pub active_minutes_goal_rate: f64,
+    /// The days without a main sleep, in chronological order
+    pub missing_sleep_days: Vec<NaiveDate>,
+    /// The days without an activity summary, in chronological order
+    pub missing_activity_days: Vec<NaiveDate>,
+}
+
+impl RangeAggregate {
+    /// Returns the number of days in the range
+    pub fn days(&self) -> usize {
+        ((self.end - self.start).num_days() + 1) as usize
+    }
+}
+
+/// Aggregates the sleep and activity of every day in a range
+///
+/// Responses come from the cache, and are fetched and cached if missing.
+///
+/// # Arguments
+///
+/// * `range` - The days to aggregate, e.g. `start..=end`
+/// * `cache` - The cache to get the responses from
+///
+/// # Returns
+///
+/// The aggregates, `FitbitError::InvalidArgument` if the range is empty, or the first error
+/// that doesn't mean a day is unavailable
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::aggregate;
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+///
+/// let week = aggregate(today - chrono::Duration::days(6)..=today, &mut cache)?;
+/// println!(
+///     "Slept {:.0} minutes a night, met the steps goal on {:.0}% of days",
+///     week.sleep.average_minutes_asleep,
+///     week.steps_goal_rate * 100.0
+/// );
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn aggregate<C: FitbitClientTrait>(
+    range: RangeInclusive<NaiveDate>,
+    cache: &mut FitbitResponseCache<C>,
+) -> Result<RangeAggregate, FitbitError> {
+    let (start, end) = range.into_inner();
+    let dates = date_range(start, end)?;
+
+    let sleep_days = available(cache.get_sleep_responses(start, end)?)?;
+    let sleep = aggregate_sleep(sleep_days.iter().map(|(_, response)| *response));
+    let missing_sleep_days = missing(
+        &dates,
+        sleep_days
+            .iter()
+            .filter(|(_, response)| response.main_sleep().is_some())
+            .map(|(date, _)| *date),
+    );
+
+    let activity_days = available(cache.get_activity_summary_responses(start, end)?)?;
+    let activity = aggregate_activity(
+        activity_days
+            .iter()
+            .map(|(date, response)| (*date, *response)),
+    );
+    let missing_activity_days = missing(&dates, activity_days.iter().map(|(date, _)| *date));
+
+    let rate = |goal_days: usize| {
+        if activity.days == 0 {
+            0.0
+        } else {
+            goal_days as f64 / activity.days as f64
+        }
+    };
+
+    Ok(RangeAggregate {
+        start,
+        end,
+        steps_goal_rate: rate(activity.steps_goal_days),
+        calories_goal_rate: rate(activity.calories_goal_days),
+        active_minutes_goal_rate: rate(activity.active_minutes_goal_days),
+        sleep,
+        activity,
+        missing_sleep_days,
+        missing_activity_days,
+    })
+}
+
+/// Returns the days of a batch, failing on errors that don't mean a day is unavailable
+pub(super) fn available<T>(batch: BatchResult<T>) -> Result<Vec<(NaiveDate, T)>, FitbitError> {
+    for (_, error) in batch.failures {
+        unless_unavailable::<()>(Err(error))?;
+    }
+
+    Ok(batch.successes)
+}
+
+/// Returns the dates that are not among the `present` ones
+fn missing(dates: &[NaiveDate], present: impl Iterator<Item = NaiveDate>) -> Vec<NaiveDate> {
+    let present: BTreeSet<NaiveDate> = present.collect();
+    dates
+        .iter()
+        .filter(|date| !present.contains(date))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::date;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::sleep::{SleepLevel, SleepResponseV1_2};
+    use chrono::Datelike;
+
+    #[test]
+    fn test_aggregate() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_sleep_data()
+            .times(3)
+            .returning(|day| match day.day() {
+                1 => Ok(SleepDataBuilder::new(day.and_hms_opt(23, 0, 0).unwrap())
+                    .stage(SleepLevel::Light, 240)
+                    .stage(SleepLevel::Deep, 120)
+                    .build_response()),
+                2 => Ok(SleepResponseV1_2::default()),
+                _ => Err(FitbitError::NotFound {
+                    message: "No data".to_string(),
+                }),
+            });
+        mock_client
+            .expect_fetch_activity_summary()
+            .times(3)
+            .returning(|day| match day.day() {
+                3 => Err(FitbitError::NotFound {
+                    message: "No data".to_string(),
+                }),
+                steps => Ok(ActivitySummaryBuilder::new().steps(steps * 6_000).build()),
+            });
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+        let aggregate = aggregate(date(1)..=date(3), &mut cache)?;
+
+        assert_eq!(aggregate.days(), 3);
+        assert_eq!(aggregate.sleep.total_minutes_asleep, 360);
+        assert_eq!(aggregate.missing_sleep_days, vec![date(2), date(3)]);
+        assert_eq!(aggregate.activity.total_steps, 18_000);
+        assert_eq!(aggregate.steps_goal_rate, 0.5);
+        assert_eq!(aggregate.missing_activity_days, vec![date(3)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_fails_on_other_errors() {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_sleep_data()
+            .returning(|_| Err(FitbitError::api_error(500, "Oops")));
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+
+        assert!(aggregate(date(1)..=date(2), &mut cache).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+//! A readiness score from recent sleep, resting heart rate and activity.
+
+use crate::daily_record::DailyRecord;
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Fewest baseline days a deviation is measured against
+const MIN_BASELINE_DAYS: usize = 3;
+
+/// Relative weights of the readiness components
+///
+/// Components without data are left out and the weights of the others scaled up, so the
+/// weights don't need to sum to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadinessWeights {
+    pub sleep_debt: f64,
+    pub resting_heart_rate: f64,
+    pub activity_load: f64,
+    pub hrv: f64,
+}
+
+impl Default for ReadinessWeights {
+    fn default() -> Self {
+        Self {
+            sleep_debt: 0.35,
+            resting_heart_rate: 0.25,
+            activity_load: 0.2,
+            hrv: 0.2,
+        }
+    }
+}
+
+/// Parameters for [`ReadinessEstimator`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadinessConfig {
+    pub weights: ReadinessWeights,
+    /// Sleep needed per night
+    pub sleep_need: Duration,
+    /// Number of nights up to the day the sleep debt accumulates over; 0 leaves out the
+    /// sleep component
+    pub sleep_debt_nights: usize,
+    /// Sleep debt at which the sleep component drops to 0; the sleep component is left out
+    /// unless it is positive
+    pub max_sleep_debt: Duration,
+    /// Number of days before the day that resting heart rate, activity load and HRV are
+    /// compared against
+    pub baseline_days: usize,
+    /// Resting heart rate above the baseline, in bpm, at which its component drops to 0
+    pub max_resting_heart_rate_increase: f64,
+    /// Previous-day activity load, as a multiple of the baseline, at which its component
+    /// drops to 0
+    pub max_activity_load_ratio: f64,
+    /// HRV below the baseline, as a fraction of the baseline, at which its component drops
+    /// to 0
+    pub max_hrv_drop: f64,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            weights: ReadinessWeights::default(),
+            sleep_need: Duration::hours(8),
+            sleep_debt_nights: 7,
+            max_sleep_debt: Duration::hours(10),
+            baseline_days: 28,
+            max_resting_heart_rate_increase: 10.0,
+            max_activity_load_ratio: 2.5,
+            max_hrv_drop: 0.25,
+        }
+    }
+}
+
+/// The readiness of a day and its components
+///
+/// Component scores range from 0 to 100 and are `None` if their data is missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Readiness {
+    pub date: NaiveDate,
+    /// The weighted average of the available components, from 0 to 100
+    pub score: f64,
+    /// Sleep missed over the recent nights, net of nights with more sleep than needed
+    pub sleep_debt: Option<Duration>,
+    pub sleep_debt_score: Option<f64>,
+    /// Resting heart rate of the day minus its baseline, in bpm
+    pub resting_heart_rate_deviation: Option<f64>,
+    pub resting_heart_rate_score: Option<f64>,
+    /// Activity load of the previous day as a multiple of its baseline
+    pub activity_load_ratio: Option<f64>,
+    pub activity_load_score: Option<f64>,
+    /// HRV of the day minus its baseline, as a fraction of the baseline
+    pub hrv_deviation: Option<f64>,
+    pub hrv_score: Option<f64>,
+}
+
+/// Estimates how ready the body is for training on a day
+///
+/// Readiness combines four components:
+///
+/// * the sleep debt of the recent nights
+/// * how far the resting heart rate is above its baseline
+/// * the activity load of the previous day compared to its baseline, where the load of a day
+///   is its fairly active minutes plus twice its very active minutes
+/// * how far HRV is below its baseline, if HRV values are provided; this crate doesn't
+///   fetch HRV
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::ReadinessEstimator;
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+///
+/// let records = (0..29)
+///     .map(|days| cache.fetch_daily_record(today - chrono::Duration::days(days)))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// if let Some(readiness) = ReadinessEstimator::new(&records).estimate(today) {
+///     println!("Readiness: {:.0}", readiness.score);
+/// }
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReadinessEstimator<'a> {
+    records: BTreeMap<NaiveDate, &'a DailyRecord>,
+    hrv: BTreeMap<NaiveDate, f64>,
+    config: ReadinessConfig,
+}
+
+impl<'a> ReadinessEstimator<'a> {
+    /// Creates an estimator from the records of the recent days
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - The records of the day to estimate, and of the days before it that the
+    ///   sleep debt and the baselines are taken over
+    pub fn new(records: impl IntoIterator<Item = &'a DailyRecord>) -> Self {
+        Self {
+            records: records
+                .into_iter()
+                .map(|record| (record.date, record))
+                .collect(),
+            hrv: BTreeMap::new(),
+            config: ReadinessConfig::default(),
+        }
+    }
+
+    /// Sets the daily HRV, e.g. the RMSSD in milliseconds, from another source
+    pub fn with_hrv(mut self, hrv: impl IntoIterator<Item = (NaiveDate, f64)>) -> Self {
+        self.hrv = hrv.into_iter().collect();
+        self
+    }
+
+    /// Sets the weights, sleep need and thresholds
+    pub fn with_config(mut self, config: ReadinessConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Estimates the readiness of a day
+    ///
+    /// # Returns
+    ///
+    /// The readiness, or `None` if none of the components has data or all available
+    /// components have a weight of 0
+    pub fn estimate(&self, date: NaiveDate) -> Option<Readiness> {
+        let config = &self.config;
+        let weights = &config.weights;
+
+        let sleep_debt = self.sleep_debt(date);
+        let sleep_debt_score = sleep_debt
+            .filter(|_| config.max_sleep_debt.num_minutes() > 0)
+            .map(|debt| {
+                score(debt.num_minutes() as f64 / config.max_sleep_debt.num_minutes() as f64)
+            });
+
+        let resting_heart_rate = |date: NaiveDate| resting_heart_rate_of(self.records.get(&date)?);
+        let resting_heart_rate_deviation = resting_heart_rate(date)
+            .zip(self.baseline(date, resting_heart_rate))
+            .map(|(rate, baseline)| rate - baseline);
+        let resting_heart_rate_score = resting_heart_rate_deviation
+            .map(|deviation| score(deviation / config.max_resting_heart_rate_increase));
+
+        let activity_load = |date: NaiveDate| activity_load_of(self.records.get(&date)?);
+        let previous_day = date - Duration::days(1);
+        let activity_load_ratio = activity_load(previous_day)
+            .zip(self.baseline(previous_day, activity_load))
+            .and_then(|(load, baseline)| (baseline > 0.0).then(|| load / baseline));
+        let activity_load_score = activity_load_ratio
+            .map(|ratio| score((ratio - 1.0) / (config.max_activity_load_ratio - 1.0)));
+
+        let hrv = |date: NaiveDate| self.hrv.get(&date).copied();
+        let hrv_deviation = hrv(date)
+            .zip(self.baseline(date, hrv))
+            .and_then(|(hrv, baseline)| (baseline > 0.0).then(|| (hrv - baseline) / baseline));
+        let hrv_score = hrv_deviation.map(|deviation| score(-deviation / config.max_hrv_drop));
+
+        let components = [
+            (sleep_debt_score, weights.sleep_debt),
+            (resting_heart_rate_score, weights.resting_heart_rate),
+            (activity_load_score, weights.activity_load),
+            (hrv_score, weights.hrv),
+        ];
+        let (weighted, total_weight) = components
+            .iter()
+            .filter_map(|(score, weight)| Some((score.as_ref()? * weight, *weight)))
+            .fold((0.0, 0.0), |(sum, total), (weighted, weight)| {
+                (sum + weighted, total + weight)
+            });
+        if total_weight <= 0.0 {
+            return None;
+        }
+
+        Some(Readiness {
+            date,
+            score: weighted / total_weight,
+            sleep_debt,
+            sleep_debt_score,
+            resting_heart_rate_deviation,
+            resting_heart_rate_score,
+            activity_load_ratio,
+            activity_load_score,
+            hrv_deviation,
+            hrv_score,
+        })
+    }
+
+    /// Returns the sleep debt of the nights up to `date`, or `None` if none was recorded
+    fn sleep_debt(&self, date: NaiveDate) -> Option<Duration> {
+        if self.config.sleep_debt_nights == 0 {
+            return None;
+        }
+
+        let first = date - Duration::days(self.config.sleep_debt_nights as i64 - 1);
+        let nights: Vec<i64> = self
+            .records
+            .range(first..=date)
+            .filter_map(|(_, record)| record.sleep.as_ref())
+            .map(|sleep| sleep.summary.total_minutes_asleep as i64)
+            .collect();
+        if nights.is_empty() {
+            return None;
+        }
+
+        let need = self.config.sleep_need.num_minutes();
+        let debt: i64 = nights.iter().map(|asleep| need - asleep).sum();
+        Some(Duration::minutes(debt.max(0)))
+    }
+
+    /// Returns the average of a value over the baseline days before `date`, or `None` if
+    /// too few days have it
+    fn baseline(&self, date: NaiveDate, value: impl Fn(NaiveDate) -> Option<f64>) -> Option<f64> {
+        let values: Vec<f64> = (1..=self.config.baseline_days as i64)
+            .filter_map(|days| value(date - Duration::days(days)))
+            .collect();
+        if values.len() < MIN_BASELINE_DAYS {
+            return None;
+        }
+
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Returns the resting heart rate of a day, preferring the heart rate time series
+fn resting_heart_rate_of(record: &DailyRecord) -> Option<f64> {
+    let from_heart_rate = record
+        .heart_rate
+        .as_ref()
+        .and_then(|heart_rate| heart_rate.resting_heart_rate)
+        .map(|rate| rate as f64);
+
+    from_heart_rate.or_else(|| {
+        let rate = record.activity.as_ref()?.summary.resting_heart_rate?;
+        Some(rate as f64)
+    })
+}
+
+/// Returns the activity load of a day: fairly active minutes plus twice the very active ones
+fn activity_load_of(record: &DailyRecord) -> Option<f64> {
+    let summary = &record.activity.as_ref()?.summary;
+    Some((summary.fairly_active_minutes + 2 * summary.very_active_minutes) as f64)
+}
+
+/// Converts a strain, where 0 means none and 1 the maximum, into a score from 100 to 0
+fn score(strain: f64) -> f64 {
+    100.0 * (1.0 - strain.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::sleep::SleepLevel;
+
+    fn record(
+        day: u32,
+        minutes_asleep: u32,
+        resting_heart_rate: i32,
+        very_active: i32,
+    ) -> DailyRecord {
+        // The night before the day, which may be in the previous month
+        let start = date(day).pred_opt().unwrap().and_hms_opt(23, 0, 0).unwrap();
+        DailyRecord {
+            date: date(day),
+            sleep: Some(
+                SleepDataBuilder::new(start)
+                    .stage(SleepLevel::Light, minutes_asleep)
+                    .build_response(),
+            ),
+            activity: Some(
+                ActivitySummaryBuilder::new()
+                    .resting_heart_rate(resting_heart_rate)
+                    .activity_minutes(600, 200, 0, very_active)
+                    .build(),
+            ),
+            heart_rate: None,
+            breathing_rate: None,
+        }
+    }
+
+    #[test]
+    fn test_rested_day_is_fully_ready() {
+        let records: Vec<DailyRecord> = (1..=5).map(|day| record(day, 480, 60, 30)).collect();
+
+        let readiness = ReadinessEstimator::new(&records).estimate(date(5)).unwrap();
+
+        assert_eq!(readiness.sleep_debt, Some(Duration::zero()));
+        assert_eq!(readiness.resting_heart_rate_deviation, Some(0.0));
+        assert_eq!(readiness.activity_load_ratio, Some(1.0));
+        assert_eq!(readiness.hrv_score, None);
+        assert!((readiness.score - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_strained_day_is_less_ready() {
+        let mut records: Vec<DailyRecord> = (1..=4).map(|day| record(day, 480, 60, 30)).collect();
+        // A hard session after a short night, then an elevated resting heart rate
+        records[3] = record(4, 300, 60, 60);
+        records.push(record(5, 420, 65, 0));
+        let hrv = (1..=5).map(|day| (date(day), if day == 5 { 45.0 } else { 60.0 }));
+
+        let readiness = ReadinessEstimator::new(&records)
+            .with_hrv(hrv)
+            .estimate(date(5))
+            .unwrap();
+
+        assert_eq!(readiness.sleep_debt, Some(Duration::minutes(240)));
+        assert_eq!(readiness.sleep_debt_score, Some(60.0));
+        assert_eq!(readiness.resting_heart_rate_score, Some(50.0));
+        assert_eq!(readiness.activity_load_ratio, Some(2.0));
+        assert_eq!(readiness.hrv_deviation, Some(-0.25));
+        assert_eq!(readiness.hrv_score, Some(0.0));
+        assert!(readiness.score < 50.0);
+    }
+
+    #[test]
+    fn test_no_sleep_debt_nights_leaves_out_sleep() {
+        let records: Vec<DailyRecord> = (1..=5).map(|day| record(day, 300, 60, 30)).collect();
+        let config = ReadinessConfig {
+            sleep_debt_nights: 0,
+            ..ReadinessConfig::default()
+        };
+
+        let readiness = ReadinessEstimator::new(&records)
+            .with_config(config)
+            .estimate(date(5))
+            .unwrap();
+
+        assert_eq!(readiness.sleep_debt, None);
+        assert_eq!(readiness.sleep_debt_score, None);
+        assert!((readiness.score - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zero_max_sleep_debt_leaves_out_sleep() {
+        let records: Vec<DailyRecord> = (1..=5).map(|day| record(day, 480, 60, 30)).collect();
+        let config = ReadinessConfig {
+            max_sleep_debt: Duration::zero(),
+            ..ReadinessConfig::default()
+        };
+
+        let readiness = ReadinessEstimator::new(&records)
+            .with_config(config)
+            .estimate(date(5))
+            .unwrap();
+
+        assert_eq!(readiness.sleep_debt, Some(Duration::zero()));
+        assert_eq!(readiness.sleep_debt_score, None);
+        assert!((readiness.score - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_data_has_no_readiness() {
+        assert_eq!(ReadinessEstimator::new(&[]).estimate(date(5)), None);
+    }
+}
@@ -0,0 +1,239 @@
+//! Personal records across the history of a user.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::activity_time_series::ActiveZoneMinutes;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::response_cache::FitbitResponseCache;
+use crate::sleep::{SleepResponseV1_2, SleepType};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A kind of personal record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RecordKind {
+    /// Most steps in a day
+    MostSteps,
+    /// Most minutes asleep in a main sleep
+    LongestSleep,
+    /// Most minutes of deep sleep in a main sleep
+    MostDeepSleep,
+    /// Most Active Zone Minutes in a day
+    MostActiveZoneMinutes,
+}
+
+/// The best value of a record kind and the day it was set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalRecord {
+    pub kind: RecordKind,
+    pub date: NaiveDate,
+    pub value: u32,
+}
+
+/// A record set by a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewRecord {
+    pub record: PersonalRecord,
+    /// The record before; `None` for the first value of the kind
+    pub previous: Option<PersonalRecord>,
+}
+
+/// Tracks the personal records of a user
+///
+/// A record is only broken by a higher value, so of equal values the first one that was
+/// added keeps the record. The records can be serialized, to keep them between runs
+/// instead of scanning the history again.
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::PersonalRecords;
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+///
+/// cache.get_activity_summary_responses(today - chrono::Duration::days(90), today)?;
+/// let mut records = PersonalRecords::from_cache(&cache);
+///
+/// let tomorrow = today + chrono::Duration::days(1);
+/// let day = cache.get_activity_summary_response(tomorrow)?;
+/// for new in records.add_activity(tomorrow, day) {
+///     println!("New record: {:?} {}", new.record.kind, new.record.value);
+/// }
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersonalRecords {
+    records: BTreeMap<RecordKind, PersonalRecord>,
+}
+
+impl PersonalRecords {
+    /// Creates a tracker without any records
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker with the records of the sleep and activity summary responses in a
+    /// cache
+    ///
+    /// Active Zone Minutes are not cached; add them with
+    /// [`add_active_zone_minutes`](Self::add_active_zone_minutes).
+    pub fn from_cache<C: FitbitClientTrait>(cache: &FitbitResponseCache<C>) -> Self {
+        let mut records = Self::new();
+
+        let mut sleep: Vec<_> = cache.cached_sleep_responses().collect();
+        sleep.sort_by_key(|(date, _)| *date);
+        for (date, response) in sleep {
+            records.add_sleep(date, response);
+        }
+
+        let mut activity: Vec<_> = cache.cached_activity_summary_responses().collect();
+        activity.sort_by_key(|(date, _)| *date);
+        for (date, response) in activity {
+            records.add_activity(date, response);
+        }
+
+        records
+    }
+
+    /// Returns the record of a kind, or `None` if no value of the kind was added
+    pub fn get(&self, kind: RecordKind) -> Option<&PersonalRecord> {
+        self.records.get(&kind)
+    }
+
+    /// Returns the records, ordered by kind
+    pub fn iter(&self) -> impl Iterator<Item = &PersonalRecord> {
+        self.records.values()
+    }
+
+    /// Adds the main sleep of a day
+    ///
+    /// # Returns
+    ///
+    /// The longest sleep and most deep sleep records the night set
+    pub fn add_sleep(&mut self, date: NaiveDate, response: &SleepResponseV1_2) -> Vec<NewRecord> {
+        let Some(main_sleep) = response.main_sleep() else {
+            return Vec::new();
+        };
+
+        let mut new = Vec::new();
+        new.extend(self.add(RecordKind::LongestSleep, date, main_sleep.minutes_asleep));
+        if main_sleep.sleep_type == SleepType::Stages {
+            let deep = main_sleep.levels.summary.deep.minutes;
+            new.extend(self.add(RecordKind::MostDeepSleep, date, deep));
+        }
+
+        new
+    }
+
+    /// Adds the activity summary of a day
+    ///
+    /// # Returns
+    ///
+    /// The most steps record the day set, if any
+    pub fn add_activity(
+        &mut self,
+        date: NaiveDate,
+        response: &ActivitySummaryResponse,
+    ) -> Option<NewRecord> {
+        self.add(RecordKind::MostSteps, date, response.summary.steps)
+    }
+
+    /// Adds the Active Zone Minutes of a day
+    ///
+    /// # Returns
+    ///
+    /// The most Active Zone Minutes record the day set, if any
+    pub fn add_active_zone_minutes(&mut self, azm: &ActiveZoneMinutes) -> Option<NewRecord> {
+        self.add(RecordKind::MostActiveZoneMinutes, azm.date, azm.total)
+    }
+
+    /// Adds a value, replacing the record of its kind if it is higher
+    fn add(&mut self, kind: RecordKind, date: NaiveDate, value: u32) -> Option<NewRecord> {
+        let previous = self.records.get(&kind).copied();
+        if previous.is_some_and(|previous| value <= previous.value) {
+            return None;
+        }
+
+        let record = PersonalRecord { kind, date, value };
+        self.records.insert(kind, record);
+        Some(NewRecord { record, previous })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FitbitError;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::date;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::sleep::SleepLevel;
+    use chrono::Datelike;
+
+    #[test]
+    fn test_new_records() {
+        let mut records = PersonalRecords::new();
+
+        let first =
+            records.add_activity(date(1), &ActivitySummaryBuilder::new().steps(9_000).build());
+        assert_eq!(first.unwrap().previous, None);
+
+        let tie =
+            records.add_activity(date(2), &ActivitySummaryBuilder::new().steps(9_000).build());
+        assert_eq!(tie, None);
+
+        let better = records.add_activity(
+            date(3),
+            &ActivitySummaryBuilder::new().steps(12_000).build(),
+        );
+        assert_eq!(
+            better.unwrap().previous.map(|previous| previous.date),
+            Some(date(1))
+        );
+        assert_eq!(
+            records.get(RecordKind::MostSteps),
+            Some(&PersonalRecord {
+                kind: RecordKind::MostSteps,
+                date: date(3),
+                value: 12_000,
+            })
+        );
+
+        let azm = ActiveZoneMinutes {
+            date: date(3),
+            total: 45,
+            fat_burn: 15,
+            cardio: 15,
+            peak: 0,
+        };
+        assert!(records.add_active_zone_minutes(&azm).is_some());
+        assert_eq!(records.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_records_from_cache() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client.expect_fetch_sleep_data().returning(|day| {
+            let start = day.pred_opt().unwrap().and_hms_opt(23, 0, 0).unwrap();
+            Ok(SleepDataBuilder::new(start)
+                .stage(SleepLevel::Light, 300)
+                .stage(SleepLevel::Deep, 60 + day.day())
+                .build_response())
+        });
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+        cache.get_sleep_responses(date(1), date(3))?;
+        let records = PersonalRecords::from_cache(&cache);
+
+        assert_eq!(
+            records.get(RecordKind::MostDeepSleep).unwrap().date,
+            date(3)
+        );
+        assert_eq!(records.get(RecordKind::LongestSleep).unwrap().date, date(3));
+        assert_eq!(records.get(RecordKind::MostSteps), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,730 @@
+//! Multi-night sleep analysis.
+
+use crate::sleep::{SleepData, SleepResponseV1_2};
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use std::f64::consts::TAU;
+
+/// Number of seconds in a day
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Variability of the sleep schedule across several nights
+///
+/// The standard deviations are circular, so bedtimes on either side of midnight (e.g. 23:30
+/// and 00:30) are treated as one hour apart rather than 23 hours.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SleepConsistency {
+    /// Number of nights with a main sleep that were included
+    pub nights: usize,
+    /// Standard deviation of the time the main sleep started
+    pub bedtime_std_dev: chrono::Duration,
+    /// Standard deviation of the time the main sleep ended
+    pub wake_time_std_dev: chrono::Duration,
+    /// Standard deviation of the midpoint of the main sleep
+    pub midpoint_std_dev: chrono::Duration,
+}
+
+/// Computes how consistent the bedtime and wake time are across several nights
+///
+/// Only the main sleep of each response is considered; responses without one are skipped.
+///
+/// # Arguments
+///
+/// * `responses` - The sleep responses of the nights to compare
+///
+/// # Returns
+///
+/// The schedule variability, or `None` if fewer than two nights have a main sleep
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::sleep_consistency;
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait};
+/// use chrono::NaiveDate;
+///
+/// # fn main() -> Result<(), fitbit_rs::FitbitError> {
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+/// let responses = start
+///     .iter_days()
+///     .take(7)
+///     .map(|date| client.fetch_sleep_data(date))
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// if let Some(consistency) = sleep_consistency(&responses) {
+///     println!("Bedtime varies by {} minutes", consistency.bedtime_std_dev.num_minutes());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn sleep_consistency<'a>(
+    responses: impl IntoIterator<Item = &'a SleepResponseV1_2>,
+) -> Option<SleepConsistency> {
+    let main_sleeps: Vec<_> = responses
+        .into_iter()
+        .filter_map(SleepResponseV1_2::main_sleep)
+        .collect();
+    if main_sleeps.len() < 2 {
+        return None;
+    }
+
+    let bedtimes: Vec<NaiveTime> = main_sleeps.iter().map(|s| s.start_time.time()).collect();
+    let wake_times: Vec<NaiveTime> = main_sleeps.iter().map(|s| s.end_time.time()).collect();
+    let midpoints: Vec<NaiveTime> = main_sleeps
+        .iter()
+        .map(|s| s.sleep_midpoint().time())
+        .collect();
+
+    Some(SleepConsistency {
+        nights: main_sleeps.len(),
+        bedtime_std_dev: circular_std_dev(&bedtimes),
+        wake_time_std_dev: circular_std_dev(&wake_times),
+        midpoint_std_dev: circular_std_dev(&midpoints),
+    })
+}
+
+/// Typical bedtime and wake time across several nights
+///
+/// Means are circular, so bedtimes of 23:30 and 00:30 average to midnight rather than noon.
+/// Likewise, the earliest and latest times are relative to the mean rather than to
+/// midnight, so a 00:30 bedtime is later than a 23:30 one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SleepTiming {
+    /// Number of nights with a main sleep that were included
+    pub nights: usize,
+    pub mean_bedtime: NaiveTime,
+    pub earliest_bedtime: NaiveTime,
+    pub latest_bedtime: NaiveTime,
+    pub mean_wake_time: NaiveTime,
+    pub earliest_wake_time: NaiveTime,
+    pub latest_wake_time: NaiveTime,
+}
+
+/// Computes the average bedtime and wake time across several nights
+///
+/// Only the main sleep of each response is considered; responses without one are skipped.
+///
+/// # Arguments
+///
+/// * `responses` - The sleep responses of the nights to average
+///
+/// # Returns
+///
+/// The average and extreme times, or `None` if no night has a main sleep
+pub fn sleep_timing<'a>(
+    responses: impl IntoIterator<Item = &'a SleepResponseV1_2>,
+) -> Option<SleepTiming> {
+    let main_sleeps: Vec<_> = responses
+        .into_iter()
+        .filter_map(SleepResponseV1_2::main_sleep)
+        .collect();
+    if main_sleeps.is_empty() {
+        return None;
+    }
+
+    let bedtimes: Vec<NaiveTime> = main_sleeps.iter().map(|s| s.start_time.time()).collect();
+    let wake_times: Vec<NaiveTime> = main_sleeps.iter().map(|s| s.end_time.time()).collect();
+    let (mean_bedtime, earliest_bedtime, latest_bedtime) = circular_range(&bedtimes);
+    let (mean_wake_time, earliest_wake_time, latest_wake_time) = circular_range(&wake_times);
+
+    Some(SleepTiming {
+        nights: main_sleeps.len(),
+        mean_bedtime,
+        earliest_bedtime,
+        latest_bedtime,
+        mean_wake_time,
+        earliest_wake_time,
+        latest_wake_time,
+    })
+}
+
+/// Parameters for [`detect_schedule_shifts`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleShiftConfig {
+    /// Minimum deviation from the trailing average that is reported (exclusive)
+    pub threshold: chrono::Duration,
+    /// Number of preceding nights the trailing average is taken over
+    pub trailing_nights: usize,
+}
+
+impl Default for ScheduleShiftConfig {
+    fn default() -> Self {
+        Self {
+            threshold: chrono::Duration::hours(1),
+            trailing_nights: 7,
+        }
+    }
+}
+
+/// Which end of the night shifted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftedTime {
+    Bedtime,
+    WakeTime,
+}
+
+/// A night whose bedtime or wake time deviates from the preceding nights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleShift {
+    /// `date_of_sleep` of the night
+    pub date: NaiveDate,
+    pub shifted: ShiftedTime,
+    /// The bedtime or wake time of the night
+    pub time: NaiveTime,
+    /// The circular mean of the same time over the trailing nights
+    pub trailing_mean: NaiveTime,
+    /// `time - trailing_mean`, within half a day; positive if later than usual
+    pub shift: chrono::Duration,
+}
+
+/// Finds nights where the bedtime or wake time shifted compared to the preceding nights
+///
+/// This flags jet lag, DST transitions or changes in shift work. Only the main sleep of each
+/// response is considered, in chronological order. The first `config.trailing_nights`
+/// nights only serve as the baseline and are never flagged themselves.
+///
+/// # Arguments
+///
+/// * `responses` - The sleep responses to scan
+/// * `config` - The shift threshold and trailing window
+///
+/// # Returns
+///
+/// One event per shifted bedtime or wake time, in chronological order
+pub fn detect_schedule_shifts<'a>(
+    responses: impl IntoIterator<Item = &'a SleepResponseV1_2>,
+    config: &ScheduleShiftConfig,
+) -> Vec<ScheduleShift> {
+    let mut main_sleeps: Vec<_> = responses
+        .into_iter()
+        .filter_map(SleepResponseV1_2::main_sleep)
+        .collect();
+    main_sleeps.sort_by_key(|sleep| sleep.start_time);
+
+    let bedtimes: Vec<NaiveTime> = main_sleeps.iter().map(|s| s.start_time.time()).collect();
+    let wake_times: Vec<NaiveTime> = main_sleeps.iter().map(|s| s.end_time.time()).collect();
+
+    let window = config.trailing_nights.max(1);
+    let mut shifts = Vec::new();
+    for index in window..main_sleeps.len() {
+        let trailing = index - window..index;
+        for (shifted, times) in [
+            (ShiftedTime::Bedtime, &bedtimes),
+            (ShiftedTime::WakeTime, &wake_times),
+        ] {
+            let trailing_mean = circular_mean(&times[trailing.clone()]);
+            let shift = chrono::Duration::seconds(circular_offset(times[index], trailing_mean));
+            if shift.abs() > config.threshold {
+                shifts.push(ScheduleShift {
+                    date: main_sleeps[index].date_of_sleep,
+                    shifted,
+                    time: times[index],
+                    trailing_mean,
+                    shift,
+                });
+            }
+        }
+    }
+
+    shifts
+}
+
+/// Chronotype estimate based on the Munich ChronoType Questionnaire
+///
+/// The chronotype is the mid-sleep on free days, corrected for the extra sleep taken on free
+/// days to make up for the sleep debt of work days (MSFsc). Later times indicate a later
+/// chronotype.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chronotype {
+    /// Number of nights before free days that were included
+    pub free_nights: usize,
+    /// Number of nights before work days that were included
+    pub work_nights: usize,
+    /// Average sleep duration on free days
+    pub free_day_sleep_duration: chrono::Duration,
+    /// Average sleep duration on work days
+    pub work_day_sleep_duration: chrono::Duration,
+    /// Mid-sleep on free days (MSF)
+    pub mid_sleep_free_days: NaiveTime,
+    /// Mid-sleep on free days, corrected for sleep debt (MSFsc)
+    pub mid_sleep_free_days_corrected: NaiveTime,
+}
+
+/// Estimates the chronotype from several weeks of sleep data
+///
+/// Only the main sleep of each response is considered. A night counts as a free night if
+/// the date it ends on (`date_of_sleep`) is a free day, so Friday night counts as free and
+/// Sunday night as a work night when weekends are free. The sleep debt correction uses the
+/// observed ratio of work to free nights as the weekly schedule.
+///
+/// # Arguments
+///
+/// * `responses` - The sleep responses to use; a few weeks give a stable estimate
+/// * `is_free_day` - Returns `true` for days without work or other fixed wake-up times
+///
+/// # Returns
+///
+/// The chronotype, or `None` unless there is at least one free and one work night
+///
+/// # Example
+///
+/// ```no_run
+/// use chrono::{Datelike, Weekday};
+/// use fitbit_rs::analysis::estimate_chronotype;
+/// # use fitbit_rs::SleepResponseV1_2;
+/// # let responses: Vec<SleepResponseV1_2> = Vec::new();
+///
+/// let weekend = |date: chrono::NaiveDate| matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+/// if let Some(chronotype) = estimate_chronotype(&responses, weekend) {
+///     println!("Chronotype (MSFsc): {}", chronotype.mid_sleep_free_days_corrected);
+/// }
+/// ```
+pub fn estimate_chronotype<'a>(
+    responses: impl IntoIterator<Item = &'a SleepResponseV1_2>,
+    is_free_day: impl Fn(NaiveDate) -> bool,
+) -> Option<Chronotype> {
+    let (free, work): (Vec<_>, Vec<_>) = responses
+        .into_iter()
+        .filter_map(SleepResponseV1_2::main_sleep)
+        .partition(|sleep| is_free_day(sleep.date_of_sleep));
+    if free.is_empty() || work.is_empty() {
+        return None;
+    }
+
+    let average_duration = |nights: &[&SleepData]| {
+        let total: chrono::Duration = nights.iter().map(|s| s.end_time - s.start_time).sum();
+        total / nights.len() as i32
+    };
+    let free_day_sleep_duration = average_duration(&free);
+    let work_day_sleep_duration = average_duration(&work);
+
+    let midpoints: Vec<NaiveTime> = free.iter().map(|s| s.sleep_midpoint().time()).collect();
+    let mid_sleep_free_days = circular_mean(&midpoints);
+
+    // Only oversleeping on free days is corrected for, sleeping less needs no correction
+    let mid_sleep_free_days_corrected = if free_day_sleep_duration > work_day_sleep_duration {
+        let weekly_sleep_duration = (free_day_sleep_duration * free.len() as i32
+            + work_day_sleep_duration * work.len() as i32)
+            / (free.len() + work.len()) as i32;
+        let oversleep = free_day_sleep_duration - weekly_sleep_duration;
+        mid_sleep_free_days
+            - chrono::Duration::seconds(
+                (oversleep.num_milliseconds() as f64 / 2000.0).round() as i64
+            )
+    } else {
+        mid_sleep_free_days
+    };
+
+    Some(Chronotype {
+        free_nights: free.len(),
+        work_nights: work.len(),
+        free_day_sleep_duration,
+        work_day_sleep_duration,
+        mid_sleep_free_days,
+        mid_sleep_free_days_corrected,
+    })
+}
+
+/// Totals and nightly averages of sleep across several nights
+///
+/// A night counts as recorded if its response contains a main sleep. Totals include naps;
+/// averages are taken over the recorded nights and are zero if there are none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SleepRangeSummary {
+    /// Number of responses that were aggregated
+    pub nights: usize,
+    /// Number of responses without a main sleep
+    pub missing_nights: usize,
+    /// Minutes asleep across the recorded nights, including naps
+    pub total_minutes_asleep: u32,
+    /// Minutes in bed across the recorded nights, including naps
+    pub total_time_in_bed: u32,
+    pub average_minutes_asleep: f64,
+    pub average_time_in_bed: f64,
+    /// Average minutes per stage; nights recorded in the classic format count as zero
+    pub average_stage_minutes: StageAverages,
+    /// Average efficiency of the main sleep, as reported by Fitbit, over the recorded nights
+    /// that report one
+    pub average_efficiency: f64,
+}
+
+/// Average minutes spent in each sleep stage per night
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StageAverages {
+    pub deep: f64,
+    pub light: f64,
+    pub rem: f64,
+    pub wake: f64,
+}
+
+/// Aggregates the sleep of several nights into totals and nightly averages
+///
+/// # Arguments
+///
+/// * `responses` - The sleep responses of the nights to aggregate, e.g. one per day of a week
+///
+/// # Returns
+///
+/// The totals and averages, with nights lacking a main sleep counted as missing
+pub fn aggregate_sleep<'a>(
+    responses: impl IntoIterator<Item = &'a SleepResponseV1_2>,
+) -> SleepRangeSummary {
+    let mut nights = 0;
+    let mut recorded = Vec::new();
+    for response in responses {
+        nights += 1;
+        if let Some(main_sleep) = response.main_sleep() {
+            recorded.push((response, main_sleep));
+        }
+    }
+
+    let total_minutes_asleep = recorded
+        .iter()
+        .map(|(response, _)| response.summary.total_minutes_asleep)
+        .sum();
+    let total_time_in_bed = recorded
+        .iter()
+        .map(|(response, _)| response.summary.total_time_in_bed)
+        .sum();
+    let average = |value: &dyn Fn(&SleepResponseV1_2, &SleepData) -> u32| {
+        if recorded.is_empty() {
+            return 0.0;
+        }
+        let sum: u32 = recorded
+            .iter()
+            .map(|(response, main_sleep)| value(response, main_sleep))
+            .sum();
+        sum as f64 / recorded.len() as f64
+    };
+
+    SleepRangeSummary {
+        nights,
+        missing_nights: nights - recorded.len(),
+        total_minutes_asleep,
+        total_time_in_bed,
+        average_minutes_asleep: average(&|response, _| response.summary.total_minutes_asleep),
+        average_time_in_bed: average(&|response, _| response.summary.total_time_in_bed),
+        average_stage_minutes: StageAverages {
+            deep: average(&|response, _| response.summary.stages.deep),
+            light: average(&|response, _| response.summary.stages.light),
+            rem: average(&|response, _| response.summary.stages.rem),
+            wake: average(&|response, _| response.summary.stages.wake),
+        },
+        average_efficiency: average_efficiency(recorded.iter().map(|(_, main_sleep)| *main_sleep)),
+    }
+}
+
+/// Returns the average reported efficiency of the records, or zero if none reports one
+fn average_efficiency<'a>(records: impl Iterator<Item = &'a SleepData>) -> f64 {
+    let efficiencies: Vec<u8> = records.filter_map(|sleep| sleep.efficiency).collect();
+    if efficiencies.is_empty() {
+        return 0.0;
+    }
+
+    efficiencies
+        .iter()
+        .map(|&efficiency| efficiency as f64)
+        .sum::<f64>()
+        / efficiencies.len() as f64
+}
+
+/// Maps a time of day onto the unit circle, with midnight at angle zero
+fn time_of_day_angle(time: NaiveTime) -> f64 {
+    time.num_seconds_from_midnight() as f64 / SECONDS_PER_DAY * TAU
+}
+
+/// Returns the sums of the sines and cosines of the given times of day
+fn angle_sums(times: &[NaiveTime]) -> (f64, f64) {
+    times
+        .iter()
+        .map(|&time| time_of_day_angle(time))
+        .fold((0.0, 0.0), |(sin_sum, cos_sum), angle| {
+            (sin_sum + angle.sin(), cos_sum + angle.cos())
+        })
+}
+
+/// Returns the circular mean of the given times of day
+///
+/// If the times are spread evenly around the clock, the mean is arbitrary.
+fn circular_mean(times: &[NaiveTime]) -> NaiveTime {
+    let (sin_sum, cos_sum) = angle_sums(times);
+    let seconds = (sin_sum.atan2(cos_sum) / TAU * SECONDS_PER_DAY).round() as i64;
+
+    NaiveTime::from_num_seconds_from_midnight_opt(
+        seconds.rem_euclid(SECONDS_PER_DAY as i64) as u32,
+        0,
+    )
+    .unwrap_or(NaiveTime::MIN)
+}
+
+/// Returns the signed offset of `time` from `reference` in seconds, within half a day
+fn circular_offset(time: NaiveTime, reference: NaiveTime) -> i64 {
+    let half_day = SECONDS_PER_DAY as i64 / 2;
+    let seconds =
+        time.num_seconds_from_midnight() as i64 - reference.num_seconds_from_midnight() as i64;
+
+    (seconds + half_day).rem_euclid(2 * half_day) - half_day
+}
+
+/// Returns the circular mean of a non-empty set of times, and the earliest and latest time
+/// relative to it
+fn circular_range(times: &[NaiveTime]) -> (NaiveTime, NaiveTime, NaiveTime) {
+    let mean = circular_mean(times);
+    let offset = |time: &&NaiveTime| circular_offset(**time, mean);
+    let earliest = times.iter().min_by_key(offset).copied().unwrap_or(mean);
+    let latest = times.iter().max_by_key(offset).copied().unwrap_or(mean);
+
+    (mean, earliest, latest)
+}
+
+/// Returns the mean resultant length of the given times of day
+///
+/// This is 1 if all times are equal and approaches 0 as they spread around the clock.
+fn mean_resultant_length(times: &[NaiveTime]) -> f64 {
+    let n = times.len() as f64;
+    let (sin_sum, cos_sum) = angle_sums(times);
+
+    ((sin_sum / n).powi(2) + (cos_sum / n).powi(2)).sqrt()
+}
+
+/// Returns the circular standard deviation of the given times of day
+fn circular_std_dev(times: &[NaiveTime]) -> chrono::Duration {
+    let r = mean_resultant_length(times).min(1.0);
+    if r <= 0.0 {
+        // Times are spread evenly around the clock, there is no meaningful deviation
+        return chrono::Duration::hours(24);
+    }
+
+    let std_dev_radians = (-2.0 * r.ln()).sqrt();
+    chrono::Duration::seconds((std_dev_radians / TAU * SECONDS_PER_DAY).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sleep::{SleepSummary, StagesSummary};
+    use chrono::{Datelike, Weekday};
+
+    fn night(date: NaiveDate, bedtime: (u32, u32), wake_time: (u32, u32)) -> SleepResponseV1_2 {
+        let start_time = if bedtime.0 >= 12 {
+            date.pred_opt().unwrap()
+        } else {
+            date
+        }
+        .and_hms_opt(bedtime.0, bedtime.1, 0)
+        .unwrap();
+
+        SleepResponseV1_2 {
+            sleep: vec![SleepData {
+                date_of_sleep: date,
+                is_main_sleep: true,
+                start_time,
+                end_time: date.and_hms_opt(wake_time.0, wake_time.1, 0).unwrap(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sleep_consistency_across_midnight() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let responses = vec![
+            night(date, (23, 0), (7, 0)),
+            night(date.succ_opt().unwrap(), (23, 30), (7, 0)),
+            night(date.succ_opt().unwrap().succ_opt().unwrap(), (0, 0), (7, 0)),
+        ];
+
+        let consistency = sleep_consistency(&responses).unwrap();
+
+        assert_eq!(consistency.nights, 3);
+        // Population standard deviation of -60, -30 and 0 minutes is ~24.5 minutes
+        let bedtime_minutes = consistency.bedtime_std_dev.num_seconds() as f64 / 60.0;
+        assert!(
+            (24.0..25.0).contains(&bedtime_minutes),
+            "Expected around 24.5 minutes, got {} minutes",
+            bedtime_minutes
+        );
+        assert_eq!(consistency.wake_time_std_dev, chrono::Duration::zero());
+    }
+
+    #[test]
+    fn test_sleep_timing_across_midnight() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let responses = vec![
+            night(date, (23, 0), (7, 0)),
+            night(date.succ_opt().unwrap(), (23, 30), (6, 30)),
+            SleepResponseV1_2::default(),
+            night(
+                date.succ_opt().unwrap().succ_opt().unwrap(),
+                (0, 30),
+                (7, 30),
+            ),
+        ];
+
+        let timing = sleep_timing(&responses).unwrap();
+
+        assert_eq!(timing.nights, 3);
+        assert_eq!(
+            timing.mean_bedtime,
+            NaiveTime::from_hms_opt(23, 39, 56).unwrap()
+        );
+        assert_eq!(timing.earliest_bedtime, time(23, 0));
+        assert_eq!(timing.latest_bedtime, time(0, 30));
+        assert_eq!(timing.mean_wake_time, time(7, 0));
+        assert_eq!(timing.earliest_wake_time, time(6, 30));
+        assert_eq!(timing.latest_wake_time, time(7, 30));
+
+        assert_eq!(sleep_timing(&[SleepResponseV1_2::default()]), None);
+    }
+
+    #[test]
+    fn test_estimate_chronotype() {
+        // Monday to Sunday: 23:00 to 06:30 before work days, 00:30 to 09:30 before weekends
+        let monday = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let weekend = |date: NaiveDate| matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let responses: Vec<_> = monday
+            .iter_days()
+            .take(7)
+            .map(|date| {
+                if weekend(date) {
+                    night(date, (0, 30), (9, 30))
+                } else {
+                    night(date, (23, 0), (6, 30))
+                }
+            })
+            .collect();
+
+        let chronotype = estimate_chronotype(&responses, weekend).unwrap();
+
+        assert_eq!(chronotype.free_nights, 2);
+        assert_eq!(chronotype.work_nights, 5);
+        assert_eq!(
+            chronotype.free_day_sleep_duration,
+            chrono::Duration::hours(9)
+        );
+        assert_eq!(
+            chronotype.work_day_sleep_duration,
+            chrono::Duration::minutes(450)
+        );
+        assert_eq!(
+            chronotype.mid_sleep_free_days,
+            NaiveTime::from_hms_opt(5, 0, 0).unwrap()
+        );
+        // Weekly average is 7h 55m 43s, so half of the 64m 17s oversleep is subtracted
+        assert_eq!(
+            chronotype.mid_sleep_free_days_corrected,
+            NaiveTime::from_hms_opt(4, 27, 51).unwrap()
+        );
+
+        assert_eq!(estimate_chronotype(&responses, |_| false), None);
+    }
+
+    #[test]
+    fn test_detect_schedule_shifts() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let schedule = [
+            ((23, 0), (7, 0)),
+            ((23, 0), (7, 0)),
+            ((23, 0), (7, 0)),
+            ((0, 30), (8, 0)),
+            ((23, 0), (7, 0)),
+        ];
+        let responses: Vec<_> = date
+            .iter_days()
+            .zip(schedule)
+            .map(|(date, (bedtime, wake_time))| night(date, bedtime, wake_time))
+            .collect();
+        let config = ScheduleShiftConfig {
+            threshold: chrono::Duration::hours(1),
+            trailing_nights: 3,
+        };
+
+        let shifts = detect_schedule_shifts(&responses, &config);
+
+        // The wake time shifted by exactly the threshold, and the return to the usual
+        // bedtime is only half an hour from the new trailing average
+        assert_eq!(
+            shifts,
+            [ScheduleShift {
+                date: NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+                shifted: ShiftedTime::Bedtime,
+                time: NaiveTime::from_hms_opt(0, 30, 0).unwrap(),
+                trailing_mean: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+                shift: chrono::Duration::minutes(90),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_sleep() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mut first = night(date, (23, 0), (7, 0));
+        first.sleep[0].efficiency = Some(90);
+        first.summary = SleepSummary {
+            stages: StagesSummary {
+                deep: 60,
+                light: 250,
+                rem: 90,
+                wake: 50,
+            },
+            total_minutes_asleep: 400,
+            total_sleep_records: 1,
+            total_time_in_bed: 480,
+        };
+        let mut second = night(date.succ_opt().unwrap(), (0, 0), (7, 0));
+        second.sleep[0].efficiency = Some(85);
+        second.summary = SleepSummary {
+            stages: StagesSummary {
+                deep: 40,
+                light: 230,
+                rem: 80,
+                wake: 70,
+            },
+            total_minutes_asleep: 350,
+            total_sleep_records: 1,
+            total_time_in_bed: 420,
+        };
+        let responses = vec![first, SleepResponseV1_2::default(), second];
+
+        let summary = aggregate_sleep(&responses);
+
+        assert_eq!(summary.nights, 3);
+        assert_eq!(summary.missing_nights, 1);
+        assert_eq!(summary.total_minutes_asleep, 750);
+        assert_eq!(summary.total_time_in_bed, 900);
+        assert_eq!(summary.average_minutes_asleep, 375.0);
+        assert_eq!(summary.average_time_in_bed, 450.0);
+        assert_eq!(
+            summary.average_stage_minutes,
+            StageAverages {
+                deep: 50.0,
+                light: 240.0,
+                rem: 85.0,
+                wake: 60.0,
+            }
+        );
+        assert_eq!(summary.average_efficiency, 87.5);
+    }
+
+    #[test]
+    fn test_aggregate_sleep_without_recorded_nights() {
+        let responses = vec![SleepResponseV1_2::default()];
+
+        let summary = aggregate_sleep(&responses);
+
+        assert_eq!(summary.nights, 1);
+        assert_eq!(summary.missing_nights, 1);
+        assert_eq!(summary.average_minutes_asleep, 0.0);
+        assert_eq!(summary.average_stage_minutes, StageAverages::default());
+    }
+
+    #[test]
+    fn test_sleep_consistency_needs_two_nights() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let responses = vec![night(date, (23, 0), (7, 0)), SleepResponseV1_2::default()];
+
+        assert_eq!(sleep_consistency(&responses), None);
+    }
+}
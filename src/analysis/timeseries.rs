@@ -0,0 +1,418 @@
+//! Resampling, gap filling and alignment of time series.
+//!
+//! Intraday heart rate, intraday activity and sleep stages all come at different
+//! resolutions. These functions bring them onto a common grid, so that e.g. the heart rate
+//! can be compared with the steps or the sleep stage of each 5 minute interval.
+//!
+//! Series are `(timestamp, value)` pairs in chronological order. Use
+//! [`IntradaySamples::at_date`](crate::intraday::IntradaySamples::at_date) to turn the
+//! samples of a day into such pairs.
+
+use crate::error::FitbitError;
+use crate::sleep::{SleepLevel, StageInterval};
+use chrono::{Duration, NaiveDateTime, NaiveTime, Timelike};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// How the values within an interval are combined by [`resample`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Mean,
+    /// E.g. to turn 1 minute steps into 15 minute steps
+    Sum,
+    Min,
+    Max,
+    First,
+    Last,
+}
+
+impl Aggregation {
+    /// Adds the `count`th value of an interval to the aggregate of the previous values
+    fn combine(self, aggregate: f64, value: f64, count: usize) -> f64 {
+        match self {
+            Aggregation::Mean => aggregate + (value - aggregate) / count as f64,
+            Aggregation::Sum => aggregate + value,
+            Aggregation::Min => aggregate.min(value),
+            Aggregation::Max => aggregate.max(value),
+            Aggregation::First => aggregate,
+            Aggregation::Last => value,
+        }
+    }
+}
+
+/// How [`fill_gaps`] treats intervals without a value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GapFill {
+    /// Leaves the interval out
+    Drop,
+    /// Uses a fixed value, e.g. 0 steps
+    Constant(f64),
+    /// Repeats the last value before the gap
+    Previous,
+    /// Interpolates linearly between the values around the gap
+    Linear,
+}
+
+/// Combines the values of a series into fixed intervals
+///
+/// Intervals are aligned to midnight, so `interval` must divide a day evenly, e.g. 1, 5 or
+/// 15 minutes.
+///
+/// # Arguments
+///
+/// * `series` - The values to resample, in chronological order
+/// * `interval` - The length of each interval
+/// * `aggregation` - How the values within an interval are combined
+///
+/// # Returns
+///
+/// The start of every interval from the first to the last value, with the aggregate of its
+/// values or `None` if it has none, or `FitbitError::InvalidArgument` if the interval
+/// doesn't divide a day or the series isn't chronological
+pub fn resample<V: Into<f64>>(
+    series: impl IntoIterator<Item = (NaiveDateTime, V)>,
+    interval: Duration,
+    aggregation: Aggregation,
+) -> Result<Vec<(NaiveDateTime, Option<f64>)>, FitbitError> {
+    let intervals = fold_intervals(
+        series,
+        interval,
+        |value| (value.into(), 1),
+        |(aggregate, count), value| {
+            *count += 1;
+            *aggregate = aggregation.combine(*aggregate, value.into(), *count);
+        },
+    )?;
+
+    Ok(intervals
+        .into_iter()
+        .map(|(start, state)| (start, state.map(|(aggregate, _)| aggregate)))
+        .collect())
+}
+
+/// Folds the values of a series into midnight-aligned intervals
+///
+/// `first` starts the state of an interval from its first value and `add` adds each
+/// further value to it. This is the bucketing behind [`resample`], for aggregates that
+/// need more than one number, such as
+/// [`IntradayHeartRate::resample`](crate::heart_rate::IntradayHeartRate::resample).
+///
+/// # Returns
+///
+/// The start of every interval from the first to the last value, with its state or `None`
+/// if it has no values, or `FitbitError::InvalidArgument` if the interval doesn't divide a
+/// day or the series isn't chronological
+pub(crate) fn fold_intervals<V, S>(
+    series: impl IntoIterator<Item = (NaiveDateTime, V)>,
+    interval: Duration,
+    mut first: impl FnMut(V) -> S,
+    mut add: impl FnMut(&mut S, V),
+) -> Result<Vec<(NaiveDateTime, Option<S>)>, FitbitError> {
+    let interval_seconds = interval_seconds(interval)?;
+    let mut intervals: Vec<(NaiveDateTime, Option<S>)> = Vec::new();
+
+    for (time, value) in series {
+        let start = interval_start(time, interval_seconds);
+        match intervals.last_mut() {
+            Some((last_start, Some(state))) if *last_start == start => add(state, value),
+            Some((last_start, _)) if *last_start > start => {
+                return Err(FitbitError::InvalidArgument(format!(
+                    "series must be in chronological order, got {} after {}",
+                    time, last_start
+                )));
+            }
+            last => {
+                if let Some(&mut (last_start, _)) = last {
+                    let mut gap = last_start + interval;
+                    while gap < start {
+                        intervals.push((gap, None));
+                        gap += interval;
+                    }
+                }
+                intervals.push((start, Some(first(value))));
+            }
+        }
+    }
+
+    Ok(intervals)
+}
+
+/// Fills the intervals that [`resample`] found no values for
+///
+/// Gaps that can't be filled are left out: leading gaps with [`GapFill::Previous`], and
+/// leading and trailing gaps with [`GapFill::Linear`].
+///
+/// # Arguments
+///
+/// * `series` - The values, `None` for intervals without a value, in chronological order
+/// * `fill` - How gaps are filled
+///
+/// # Returns
+///
+/// The values with their gaps filled
+pub fn fill_gaps(
+    series: &[(NaiveDateTime, Option<f64>)],
+    fill: GapFill,
+) -> Vec<(NaiveDateTime, f64)> {
+    let mut filled = Vec::with_capacity(series.len());
+    let mut previous: Option<(NaiveDateTime, f64)> = None;
+    let mut pending: Vec<NaiveDateTime> = Vec::new();
+
+    for &(time, value) in series {
+        match value {
+            Some(value) => {
+                if let Some((start, from)) = previous {
+                    let span = (time - start).num_milliseconds() as f64;
+                    for gap in pending.drain(..) {
+                        let progress = (gap - start).num_milliseconds() as f64 / span;
+                        filled.push((gap, from + (value - from) * progress));
+                    }
+                }
+                filled.push((time, value));
+                previous = Some((time, value));
+            }
+            None => match fill {
+                GapFill::Drop => {}
+                GapFill::Constant(value) => filled.push((time, value)),
+                GapFill::Previous => {
+                    if let Some((_, value)) = previous {
+                        filled.push((time, value));
+                    }
+                }
+                GapFill::Linear => {
+                    if previous.is_some() {
+                        pending.push(time);
+                    }
+                }
+            },
+        }
+    }
+
+    filled
+}
+
+/// Pairs up the values of two series that share a timestamp
+///
+/// Resample both series to the same interval first, so that their timestamps line up.
+///
+/// # Arguments
+///
+/// * `left` - The first series, in chronological order
+/// * `right` - The second series, in chronological order
+///
+/// # Returns
+///
+/// The timestamps found in both series, with the value of each series
+pub fn align<A, B>(
+    left: impl IntoIterator<Item = (NaiveDateTime, A)>,
+    right: impl IntoIterator<Item = (NaiveDateTime, B)>,
+) -> Vec<(NaiveDateTime, A, B)> {
+    let mut right = right.into_iter().peekable();
+    let mut aligned = Vec::new();
+
+    for (time, left_value) in left {
+        while right.next_if(|(other, _)| *other < time).is_some() {}
+        if let Some((_, right_value)) = right.next_if(|(other, _)| *other == time) {
+            aligned.push((time, left_value, right_value));
+        }
+    }
+
+    aligned
+}
+
+/// Samples a hypnogram at fixed intervals, e.g. to align sleep stages with heart rate
+///
+/// Intervals are aligned to midnight, so `interval` must divide a day evenly.
+///
+/// # Arguments
+///
+/// * `hypnogram` - The sleep stages, as returned by
+///   [`SleepData::hypnogram`](crate::sleep::SleepData::hypnogram)
+/// * `interval` - The time between samples
+///
+/// # Returns
+///
+/// The sleep level at the start of every interval that falls within a stage, or
+/// `FitbitError::InvalidArgument` if the interval doesn't divide a day
+pub fn sample_stages(
+    hypnogram: &[StageInterval],
+    interval: Duration,
+) -> Result<Vec<(NaiveDateTime, SleepLevel)>, FitbitError> {
+    let interval_seconds = interval_seconds(interval)?;
+    let (Some(first), Some(last)) = (hypnogram.first(), hypnogram.last()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut time = interval_start(first.start, interval_seconds);
+    if time < first.start {
+        time += interval;
+    }
+
+    let mut stages = hypnogram.iter().peekable();
+    let mut samples = Vec::new();
+    while time < last.end {
+        while stages.next_if(|stage| stage.end <= time).is_some() {}
+        if let Some(stage) = stages.peek().filter(|stage| stage.contains(time)) {
+            samples.push((time, stage.level.clone()));
+        }
+        time += interval;
+    }
+
+    Ok(samples)
+}
+
+/// Returns the interval in seconds, checking that it divides a day evenly
+fn interval_seconds(interval: Duration) -> Result<i64, FitbitError> {
+    let seconds = interval.num_seconds();
+    if seconds <= 0 || Duration::seconds(seconds) != interval || SECONDS_PER_DAY % seconds != 0 {
+        return Err(FitbitError::InvalidArgument(format!(
+            "interval must be a whole number of seconds that divides a day, got {} ms",
+            interval.num_milliseconds()
+        )));
+    }
+
+    Ok(seconds)
+}
+
+/// Returns the start of the midnight-aligned interval containing `time`
+fn interval_start(time: NaiveDateTime, interval_seconds: i64) -> NaiveDateTime {
+    let seconds = time.num_seconds_from_midnight() as i64;
+
+    time.date().and_time(NaiveTime::MIN)
+        + Duration::seconds(seconds / interval_seconds * interval_seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn time(hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 1)
+            .unwrap()
+            .and_hms_opt(hour, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resample() {
+        let steps = [
+            (time(8, 0, 0), 10u16),
+            (time(8, 1, 0), 20),
+            (time(8, 4, 0), 30),
+            // No steps between 08:05 and 08:15
+            (time(8, 15, 0), 40),
+        ];
+
+        assert_eq!(
+            resample(steps, Duration::minutes(5), Aggregation::Sum).unwrap(),
+            vec![
+                (time(8, 0, 0), Some(60.0)),
+                (time(8, 5, 0), None),
+                (time(8, 10, 0), None),
+                (time(8, 15, 0), Some(40.0)),
+            ]
+        );
+        assert_eq!(
+            resample(steps, Duration::minutes(15), Aggregation::Mean).unwrap(),
+            vec![(time(8, 0, 0), Some(20.0)), (time(8, 15, 0), Some(40.0))]
+        );
+        assert!(resample(steps, Duration::minutes(7), Aggregation::Sum).is_err());
+        assert!(
+            resample(
+                [(time(8, 5, 0), 1.0), (time(8, 0, 0), 2.0)],
+                Duration::minutes(1),
+                Aggregation::Sum
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let series = [
+            (time(8, 0, 0), None),
+            (time(8, 1, 0), Some(60.0)),
+            (time(8, 2, 0), None),
+            (time(8, 3, 0), Some(66.0)),
+            (time(8, 4, 0), None),
+        ];
+
+        assert_eq!(
+            fill_gaps(&series, GapFill::Drop),
+            vec![(time(8, 1, 0), 60.0), (time(8, 3, 0), 66.0)]
+        );
+        assert_eq!(fill_gaps(&series, GapFill::Constant(0.0)).len(), 5);
+        assert_eq!(
+            fill_gaps(&series, GapFill::Previous),
+            vec![
+                (time(8, 1, 0), 60.0),
+                (time(8, 2, 0), 60.0),
+                (time(8, 3, 0), 66.0),
+                (time(8, 4, 0), 66.0),
+            ]
+        );
+        assert_eq!(
+            fill_gaps(&series, GapFill::Linear),
+            vec![
+                (time(8, 1, 0), 60.0),
+                (time(8, 2, 0), 63.0),
+                (time(8, 3, 0), 66.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_align() {
+        let heart_rate = [
+            (time(8, 0, 0), 60),
+            (time(8, 1, 0), 62),
+            (time(8, 3, 0), 70),
+        ];
+        let steps = [
+            (time(8, 1, 0), 12.0),
+            (time(8, 2, 0), 30.0),
+            (time(8, 3, 0), 80.0),
+        ];
+
+        assert_eq!(
+            align(heart_rate, steps),
+            vec![(time(8, 1, 0), 62, 12.0), (time(8, 3, 0), 70, 80.0)]
+        );
+    }
+
+    #[test]
+    fn test_sample_stages() {
+        let hypnogram = [
+            StageInterval {
+                level: SleepLevel::Light,
+                start: time(23, 58, 30),
+                end: time(23, 59, 30),
+            },
+            // Not covered between 23:59:30 and 23:59:45
+            StageInterval {
+                level: SleepLevel::Deep,
+                start: time(23, 59, 45),
+                end: time(23, 59, 59),
+            },
+        ];
+
+        assert_eq!(
+            sample_stages(&hypnogram, Duration::seconds(30)).unwrap(),
+            vec![
+                (time(23, 58, 30), SleepLevel::Light),
+                (time(23, 59, 0), SleepLevel::Light),
+            ]
+        );
+        assert_eq!(
+            sample_stages(&hypnogram, Duration::seconds(15)).unwrap(),
+            vec![
+                (time(23, 58, 30), SleepLevel::Light),
+                (time(23, 58, 45), SleepLevel::Light),
+                (time(23, 59, 0), SleepLevel::Light),
+                (time(23, 59, 15), SleepLevel::Light),
+                (time(23, 59, 45), SleepLevel::Deep),
+            ]
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Training load from heart rate, and how recent load compares to the longer term.
+
+use crate::activity_summary::{HeartRateZone, HeartRateZoneName};
+use crate::heart_rate::{HeartRateDay, IntradayHeartRate};
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Longest gap between two intraday samples that the earlier sample is assumed to last
+const MAX_SAMPLE_GAP_SECONDS: u32 = 5 * 60;
+
+/// Load per minute in each heart rate zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneWeights {
+    pub out_of_range: f64,
+    pub fat_burn: f64,
+    pub cardio: f64,
+    pub peak: f64,
+}
+
+impl Default for ZoneWeights {
+    fn default() -> Self {
+        Self {
+            out_of_range: 0.0,
+            fat_burn: 1.0,
+            cardio: 2.0,
+            peak: 3.0,
+        }
+    }
+}
+
+impl ZoneWeights {
+    /// Returns the weight of a zone; zones not known to this crate weigh nothing
+    fn weight(&self, zone: &HeartRateZoneName) -> f64 {
+        match zone {
+            HeartRateZoneName::OutOfRange => self.out_of_range,
+            HeartRateZoneName::FatBurn => self.fat_burn,
+            HeartRateZoneName::Cardio => self.cardio,
+            HeartRateZoneName::Peak => self.peak,
+            HeartRateZoneName::Other(_) => 0.0,
+        }
+    }
+}
+
+/// Parameters for [`training_load`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingLoadConfig {
+    pub zone_weights: ZoneWeights,
+    /// The maximum heart rate; intraday heart rate is only used if it is set
+    pub max_heart_rate: Option<u16>,
+    /// Factor of Banister's TRIMP; 0.64 for men and 0.86 for women
+    pub trimp_factor: f64,
+    /// Exponent of Banister's TRIMP; 1.92 for men and 1.67 for women
+    pub trimp_exponent: f64,
+}
+
+impl Default for TrainingLoadConfig {
+    fn default() -> Self {
+        Self {
+            zone_weights: ZoneWeights::default(),
+            max_heart_rate: None,
+            trimp_factor: 0.64,
+            trimp_exponent: 1.92,
+        }
+    }
+}
+
+/// What a training load was computed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrainingLoadSource {
+    /// Weighted minutes in the heart rate zones
+    Zones,
+    /// Banister's TRIMP over the intraday heart rate
+    Intraday,
+}
+
+/// The training load of a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingLoad {
+    pub date: NaiveDate,
+    pub load: f64,
+    pub source: TrainingLoadSource,
+}
+
+/// Computes a TRIMP-style training load of a day
+///
+/// With intraday heart rate, a maximum heart rate and a resting heart rate, the load is
+/// Banister's TRIMP: every minute adds `r * factor * e^(exponent * r)`, where `r` is the
+/// heart rate reserve in use, `(hr - resting) / (max - resting)`. Each sample lasts until
+/// the next one, up to 5 minutes. Otherwise the load is the minutes in each heart rate zone
+/// times the weight of the zone.
+///
+/// The two sources give loads on different scales, so compare days computed from the same
+/// source.
+///
+/// # Arguments
+///
+/// * `day` - The heart rate zones and resting heart rate of the day
+/// * `intraday` - The intraday heart rate of the day, if fetched
+/// * `config` - The zone weights, maximum heart rate and TRIMP coefficients
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::analysis::{TrainingLoadConfig, training_load};
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait};
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let today = chrono::Local::now().date_naive();
+///
+/// let day = client.fetch_heart_rate(today)?;
+/// let load = training_load(&day, None, &TrainingLoadConfig::default());
+/// println!("Training load: {:.0}", load.load);
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn training_load(
+    day: &HeartRateDay,
+    intraday: Option<&IntradayHeartRate>,
+    config: &TrainingLoadConfig,
+) -> TrainingLoad {
+    let trimp = intraday
+        .zip(config.max_heart_rate)
+        .and_then(|(intraday, max)| {
+            banister_trimp(intraday, day.resting_heart_rate? as f64, max as f64, config)
+        });
+
+    match trimp {
+        Some(load) => TrainingLoad {
+            date: day.date,
+            load,
+            source: TrainingLoadSource::Intraday,
+        },
+        None => TrainingLoad {
+            date: day.date,
+            load: zone_load(&day.heart_rate_zones, &config.zone_weights),
+            source: TrainingLoadSource::Zones,
+        },
+    }
+}
+
+/// Returns the weighted minutes in the heart rate zones
+fn zone_load(zones: &[HeartRateZone], weights: &ZoneWeights) -> f64 {
+    zones
+        .iter()
+        .map(|zone| zone.minutes as f64 * weights.weight(&zone.name))
+        .sum()
+}
+
+/// Returns Banister's TRIMP, or `None` if the maximum heart rate isn't above the resting
+/// heart rate
+fn banister_trimp(
+    intraday: &IntradayHeartRate,
+    resting: f64,
+    max: f64,
+    config: &TrainingLoadConfig,
+) -> Option<f64> {
+    if max <= resting {
+        return None;
+    }
+
+    let samples = intraday.samples.as_slice();
+    let offsets = samples.offsets();
+    let trimp = samples
+        .values()
+        .iter()
+        .zip(offsets.iter().zip(offsets.iter().skip(1)))
+        .map(|(&heart_rate, (&offset, &next))| {
+            let minutes = (next - offset).min(MAX_SAMPLE_GAP_SECONDS) as f64 / 60.0;
+            let reserve = ((heart_rate as f64 - resting) / (max - resting)).clamp(0.0, 1.0);
+            minutes * reserve * config.trimp_factor * (config.trimp_exponent * reserve).exp()
+        })
+        .sum();
+
+    Some(trimp)
+}
+
+/// Parameters for [`workload_ratios`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkloadRatioConfig {
+    /// Number of days up to a day that its acute load is averaged over
+    pub acute_days: u32,
+    /// Number of days up to a day that its chronic load is averaged over
+    pub chronic_days: u32,
+}
+
+impl Default for WorkloadRatioConfig {
+    fn default() -> Self {
+        Self {
+            acute_days: 7,
+            chronic_days: 28,
+        }
+    }
+}
+
+/// The acute:chronic workload ratio of a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkloadRatio {
+    pub date: NaiveDate,
+    /// Average daily load over the acute window
+    pub acute: f64,
+    /// Average daily load over the chronic window
+    pub chronic: f64,
+    /// `acute / chronic`, or `None` if the chronic load is 0; ratios above about 1.5 mean
+    /// load increased faster than the body is used to
+    pub ratio: Option<f64>,
+}
+
+/// Computes the acute:chronic workload ratio of every day whose chronic window is covered
+///
+/// Days missing from `loads` within a window count as rest days with a load of 0.
+///
+/// # Arguments
+///
+/// * `loads` - The training loads of consecutive days, e.g. from [`training_load`]
+/// * `config` - The lengths of the acute and chronic windows
+///
+/// # Returns
+///
+/// The ratios in chronological order, starting at the first day that has `chronic_days`
+/// days of history including itself; empty if either window is 0 days long
+pub fn workload_ratios(loads: &[TrainingLoad], config: &WorkloadRatioConfig) -> Vec<WorkloadRatio> {
+    if config.acute_days == 0 || config.chronic_days == 0 {
+        return Vec::new();
+    }
+
+    let by_date: BTreeMap<NaiveDate, f64> = loads.iter().map(|day| (day.date, day.load)).collect();
+    let (Some(&first), Some(&last)) = (by_date.keys().next(), by_date.keys().next_back()) else {
+        return Vec::new();
+    };
+    let average = |date: NaiveDate, days: u32| {
+        let start = date - Duration::days(days as i64 - 1);
+        by_date
+            .range(start..=date)
+            .map(|(_, load)| load)
+            .sum::<f64>()
+            / days as f64
+    };
+
+    let mut ratios = Vec::new();
+    let mut date = first + Duration::days(config.chronic_days as i64 - 1);
+    while date <= last {
+        let acute = average(date, config.acute_days);
+        let chronic = average(date, config.chronic_days);
+        ratios.push(WorkloadRatio {
+            date,
+            acute,
+            chronic,
+            ratio: (chronic > 0.0).then(|| acute / chronic),
+        });
+        date += Duration::days(1);
+    }
+
+    ratios
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+    use chrono::NaiveTime;
+
+    fn zone(name: HeartRateZoneName, minutes: i32) -> HeartRateZone {
+        HeartRateZone {
+            minutes,
+            calories_out: 0.0,
+            display_name: name.as_str().to_string(),
+            name,
+            min: 0,
+            max: 0,
+        }
+    }
+
+    fn heart_rate_day() -> HeartRateDay {
+        HeartRateDay {
+            date: date(1),
+            resting_heart_rate: Some(60),
+            heart_rate_zones: vec![
+                zone(HeartRateZoneName::OutOfRange, 1200),
+                zone(HeartRateZoneName::FatBurn, 40),
+                zone(HeartRateZoneName::Cardio, 20),
+                zone(HeartRateZoneName::Peak, 5),
+            ],
+            custom_heart_rate_zones: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_zone_load() {
+        let load = training_load(&heart_rate_day(), None, &TrainingLoadConfig::default());
+
+        assert_eq!(load.load, 95.0);
+        assert_eq!(load.source, TrainingLoadSource::Zones);
+    }
+
+    #[test]
+    fn test_intraday_trimp() {
+        let time = |minute| NaiveTime::from_hms_opt(10, minute, 0).unwrap();
+        let intraday = IntradayHeartRate {
+            date: date(1),
+            dataset_interval: 1,
+            dataset_type: "minute".to_string(),
+            samples: [(time(0), 120), (time(30), 60), (time(31), 60)]
+                .into_iter()
+                .collect(),
+        };
+        let config = TrainingLoadConfig {
+            max_heart_rate: Some(180),
+            ..Default::default()
+        };
+
+        let load = training_load(&heart_rate_day(), Some(&intraday), &config);
+
+        // The first sample counts for 5 minutes at half the heart rate reserve
+        let expected = 5.0 * 0.5 * 0.64 * (1.92_f64 * 0.5).exp();
+        assert!((load.load - expected).abs() < 1e-9);
+        assert_eq!(load.source, TrainingLoadSource::Intraday);
+
+        // Without a maximum heart rate, the zones are used
+        let load = training_load(&heart_rate_day(), Some(&intraday), &Default::default());
+        assert_eq!(load.source, TrainingLoadSource::Zones);
+    }
+
+    #[test]
+    fn test_workload_ratios() {
+        let loads: Vec<TrainingLoad> = (1..=10)
+            .map(|day| TrainingLoad {
+                date: date(day),
+                load: if day > 8 { 200.0 } else { 100.0 },
+                source: TrainingLoadSource::Zones,
+            })
+            .collect();
+        let config = WorkloadRatioConfig {
+            acute_days: 2,
+            chronic_days: 8,
+        };
+
+        let ratios = workload_ratios(&loads, &config);
+
+        assert_eq!(ratios.len(), 3);
+        assert_eq!(ratios[0].date, date(8));
+        assert_eq!(ratios[0].ratio, Some(1.0));
+        assert_eq!(ratios[2].acute, 200.0);
+        assert_eq!(ratios[2].chronic, 125.0);
+        assert_eq!(ratios[2].ratio, Some(1.6));
+    }
+
+    #[test]
+    fn test_workload_ratios_without_window() {
+        let loads: Vec<TrainingLoad> = (1..=10)
+            .map(|day| TrainingLoad {
+                date: date(day),
+                load: 100.0,
+                source: TrainingLoadSource::Zones,
+            })
+            .collect();
+
+        for (acute_days, chronic_days) in [(0, 8), (2, 0)] {
+            let config = WorkloadRatioConfig {
+                acute_days,
+                chronic_days,
+            };
+            assert!(workload_ratios(&loads, &config).is_empty());
+        }
+    }
+}
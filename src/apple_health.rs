@@ -0,0 +1,473 @@
+//! Export of sleep, steps and heart rate in the format of an Apple Health export.
+//!
+//! Health's "Export All Health Data" writes an `export.xml` of `<Record>` elements, which
+//! import tools for the Apple ecosystem read. [`AppleHealthExport`] writes the same records
+//! from this crate's models, as XML or as CSV with one record per row.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::activity_time_series::{ActivityResource, IntradayActivity};
+use crate::error::FitbitError;
+use crate::export::{escape_xml, interval_length};
+use crate::heart_rate::{HeartRateDay, IntradayHeartRate};
+use crate::sleep::{SleepData, SleepLevel, with_offset};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime};
+use std::fmt::{self, Write};
+
+/// Date format of Apple Health exports, e.g. `2025-03-01 23:00:00 +0100`
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+/// The type of an Apple Health record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthRecordType {
+    StepCount,
+    HeartRate,
+    RestingHeartRate,
+    SleepAnalysis,
+}
+
+impl HealthRecordType {
+    /// Returns the HealthKit type identifier, e.g. `HKQuantityTypeIdentifierStepCount`
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            HealthRecordType::StepCount => "HKQuantityTypeIdentifierStepCount",
+            HealthRecordType::HeartRate => "HKQuantityTypeIdentifierHeartRate",
+            HealthRecordType::RestingHeartRate => "HKQuantityTypeIdentifierRestingHeartRate",
+            HealthRecordType::SleepAnalysis => "HKCategoryTypeIdentifierSleepAnalysis",
+        }
+    }
+
+    /// Returns the unit of the values, or `None` for category types
+    pub fn unit(&self) -> Option<&'static str> {
+        match self {
+            HealthRecordType::StepCount => Some("count"),
+            HealthRecordType::HeartRate | HealthRecordType::RestingHeartRate => Some("count/min"),
+            HealthRecordType::SleepAnalysis => None,
+        }
+    }
+}
+
+/// The value of a sleep analysis record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SleepAnalysisValue {
+    InBed,
+    Awake,
+    /// Light sleep; Apple calls it core sleep
+    AsleepCore,
+    AsleepDeep,
+    AsleepRem,
+    /// Asleep without a known stage, as in classic sleep records
+    AsleepUnspecified,
+}
+
+impl SleepAnalysisValue {
+    /// Returns the HealthKit category value, e.g. `HKCategoryValueSleepAnalysisAsleepDeep`
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            SleepAnalysisValue::InBed => "HKCategoryValueSleepAnalysisInBed",
+            SleepAnalysisValue::Awake => "HKCategoryValueSleepAnalysisAwake",
+            SleepAnalysisValue::AsleepCore => "HKCategoryValueSleepAnalysisAsleepCore",
+            SleepAnalysisValue::AsleepDeep => "HKCategoryValueSleepAnalysisAsleepDeep",
+            SleepAnalysisValue::AsleepRem => "HKCategoryValueSleepAnalysisAsleepREM",
+            SleepAnalysisValue::AsleepUnspecified => {
+                "HKCategoryValueSleepAnalysisAsleepUnspecified"
+            }
+        }
+    }
+
+    /// Returns the value of a sleep level, or `None` for unknown levels
+    pub fn from_level(level: &SleepLevel) -> Option<Self> {
+        match level {
+            SleepLevel::Deep => Some(SleepAnalysisValue::AsleepDeep),
+            SleepLevel::Light => Some(SleepAnalysisValue::AsleepCore),
+            SleepLevel::Rem => Some(SleepAnalysisValue::AsleepRem),
+            SleepLevel::Asleep => Some(SleepAnalysisValue::AsleepUnspecified),
+            SleepLevel::Wake | SleepLevel::Awake | SleepLevel::Restless => {
+                Some(SleepAnalysisValue::Awake)
+            }
+            SleepLevel::Unknown | SleepLevel::Other(_) => None,
+        }
+    }
+}
+
+/// The value of an Apple Health record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HealthValue {
+    Quantity(f64),
+    Sleep(SleepAnalysisValue),
+}
+
+impl fmt::Display for HealthValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthValue::Quantity(value) => write!(f, "{}", value),
+            HealthValue::Sleep(value) => f.write_str(value.identifier()),
+        }
+    }
+}
+
+/// A record of an Apple Health export
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthRecord {
+    pub record_type: HealthRecordType,
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    pub value: HealthValue,
+}
+
+/// Builds Apple Health records from sleep, activity and heart rate data
+///
+/// Fitbit reports times in the user's local time, so the export needs the user's UTC offset;
+/// see [`UserProfile::utc_offset`](crate::UserProfile::utc_offset).
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::apple_health::AppleHealthExport;
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait};
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let offset = client.fetch_profile()?.user.utc_offset().unwrap();
+/// let today = chrono::Local::now().date_naive();
+///
+/// let mut export = AppleHealthExport::new(offset);
+/// for sleep in &client.fetch_sleep_data(today)?.sleep {
+///     export.add_sleep(sleep);
+/// }
+/// export.add_daily_steps(today, &client.fetch_activity_summary(today)?);
+/// std::fs::write("export.xml", export.to_xml()).unwrap();
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppleHealthExport {
+    offset: FixedOffset,
+    source_name: String,
+    export_date: Option<DateTime<FixedOffset>>,
+    records: Vec<HealthRecord>,
+}
+
+impl AppleHealthExport {
+    /// Creates an export without records
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The UTC offset of the user's local time
+    pub fn new(offset: FixedOffset) -> Self {
+        Self {
+            offset,
+            source_name: "Fitbit".to_string(),
+            export_date: None,
+            records: Vec::new(),
+        }
+    }
+
+    /// Sets the source name of the records; `Fitbit` by default
+    pub fn with_source_name(mut self, source_name: impl Into<String>) -> Self {
+        self.source_name = source_name.into();
+        self
+    }
+
+    /// Sets the `ExportDate` written to the XML; left out by default
+    pub fn with_export_date(mut self, export_date: DateTime<FixedOffset>) -> Self {
+        self.export_date = Some(export_date);
+        self
+    }
+
+    /// Returns the records in the order they were added
+    pub fn records(&self) -> &[HealthRecord] {
+        &self.records
+    }
+
+    /// Adds a sleep record, as an in-bed record over the whole sleep and a record for each
+    /// stage
+    ///
+    /// Short wakes of stages records become awake records, splitting the stage they
+    /// interrupt, like in [`SleepData::hypnogram`].
+    pub fn add_sleep(&mut self, sleep: &SleepData) {
+        self.push(
+            HealthRecordType::SleepAnalysis,
+            sleep.start_time,
+            sleep.end_time,
+            HealthValue::Sleep(SleepAnalysisValue::InBed),
+        );
+        for interval in sleep.hypnogram() {
+            if let Some(value) = SleepAnalysisValue::from_level(&interval.level) {
+                self.push(
+                    HealthRecordType::SleepAnalysis,
+                    interval.start,
+                    interval.end,
+                    HealthValue::Sleep(value),
+                );
+            }
+        }
+    }
+
+    /// Adds the steps of a day as a single record spanning the day
+    pub fn add_daily_steps(&mut self, date: NaiveDate, response: &ActivitySummaryResponse) {
+        let start = date.and_time(Default::default());
+        self.push(
+            HealthRecordType::StepCount,
+            start,
+            start + Duration::days(1),
+            HealthValue::Quantity(response.summary.steps as f64),
+        );
+    }
+
+    /// Adds intraday steps as a record per interval, leaving out intervals without steps
+    ///
+    /// # Returns
+    ///
+    /// `FitbitError::InvalidArgument` if the data isn't steps
+    pub fn add_step_samples(&mut self, intraday: &IntradayActivity) -> Result<(), FitbitError> {
+        if intraday.resource != ActivityResource::Steps {
+            return Err(FitbitError::InvalidArgument(format!(
+                "expected intraday steps, got {:?}",
+                intraday.resource
+            )));
+        }
+
+        let interval = interval_length(intraday.dataset_interval, &intraday.dataset_type);
+        for (start, steps) in intraday.dataset.at_date(intraday.date) {
+            if steps > 0.0 {
+                self.push(
+                    HealthRecordType::StepCount,
+                    start,
+                    start + interval,
+                    HealthValue::Quantity(steps),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds intraday heart rate as a record per sample
+    pub fn add_heart_rate(&mut self, intraday: &IntradayHeartRate) {
+        for (time, heart_rate) in intraday.samples.at_date(intraday.date) {
+            self.push(
+                HealthRecordType::HeartRate,
+                time,
+                time,
+                HealthValue::Quantity(heart_rate as f64),
+            );
+        }
+    }
+
+    /// Adds the resting heart rate of a day, if Fitbit reported one, timestamped at the
+    /// start of the day
+    pub fn add_resting_heart_rate(&mut self, day: &HeartRateDay) {
+        if let Some(resting_heart_rate) = day.resting_heart_rate {
+            let start = day.date.and_time(Default::default());
+            self.push(
+                HealthRecordType::RestingHeartRate,
+                start,
+                start,
+                HealthValue::Quantity(resting_heart_rate as f64),
+            );
+        }
+    }
+
+    /// Exports the records as the `export.xml` of an Apple Health export
+    ///
+    /// # Returns
+    ///
+    /// The XML document
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<HealthData locale=\"en_US\">\n",
+        ));
+
+        // Writing to a String can't fail
+        if let Some(export_date) = self.export_date {
+            let _ = writeln!(
+                xml,
+                " <ExportDate value=\"{}\"/>",
+                export_date.format(DATE_FORMAT)
+            );
+        }
+        let source_name = escape_xml(&self.source_name);
+        for record in &self.records {
+            let _ = write!(
+                xml,
+                " <Record type=\"{}\" sourceName=\"{}\"",
+                record.record_type.identifier(),
+                source_name
+            );
+            if let Some(unit) = record.record_type.unit() {
+                let _ = write!(xml, " unit=\"{}\"", unit);
+            }
+            let _ = writeln!(
+                xml,
+                " startDate=\"{}\" endDate=\"{}\" value=\"{}\"/>",
+                record.start.format(DATE_FORMAT),
+                record.end.format(DATE_FORMAT),
+                record.value
+            );
+        }
+        xml.push_str("</HealthData>\n");
+
+        xml
+    }
+
+    /// Exports the records as CSV, with the columns `type`, `sourceName`, `unit`,
+    /// `startDate`, `endDate` and `value`
+    ///
+    /// # Returns
+    ///
+    /// The CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("type,sourceName,unit,startDate,endDate,value\n");
+
+        // Writing to a String can't fail
+        let source_name = escape_csv(&self.source_name);
+        for record in &self.records {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                record.record_type.identifier(),
+                source_name,
+                record.record_type.unit().unwrap_or_default(),
+                record.start.format(DATE_FORMAT),
+                record.end.format(DATE_FORMAT),
+                record.value
+            );
+        }
+
+        csv
+    }
+
+    /// Adds a record between two local times
+    fn push(
+        &mut self,
+        record_type: HealthRecordType,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        value: HealthValue,
+    ) {
+        self.records.push(HealthRecord {
+            record_type,
+            start: with_offset(start, self.offset),
+            end: with_offset(end, self.offset),
+            value,
+        });
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::intraday::IntradaySamples;
+    use chrono::NaiveTime;
+
+    fn offset() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_sleep_records() {
+        let start = date().and_hms_opt(23, 0, 0).unwrap();
+        let sleep = SleepDataBuilder::new(start)
+            .stage(SleepLevel::Light, 60)
+            .stage(SleepLevel::Deep, 30)
+            .stage(SleepLevel::Rem, 20)
+            .build();
+
+        let mut export = AppleHealthExport::new(offset());
+        export.add_sleep(&sleep);
+
+        let values: Vec<HealthValue> = export.records().iter().map(|r| r.value).collect();
+        assert_eq!(
+            values,
+            [
+                HealthValue::Sleep(SleepAnalysisValue::InBed),
+                HealthValue::Sleep(SleepAnalysisValue::AsleepCore),
+                HealthValue::Sleep(SleepAnalysisValue::AsleepDeep),
+                HealthValue::Sleep(SleepAnalysisValue::AsleepRem),
+            ]
+        );
+        assert_eq!(
+            export.records()[0].start.to_string(),
+            "2025-03-01 23:00:00 +01:00"
+        );
+    }
+
+    #[test]
+    fn test_to_xml() {
+        let mut export = AppleHealthExport::new(offset()).with_source_name("Fitbit \"Charge\"");
+        export.add_daily_steps(date(), &ActivitySummaryBuilder::new().steps(8_000).build());
+        export.add_heart_rate(&IntradayHeartRate {
+            date: date(),
+            dataset_interval: 1,
+            dataset_type: "second".to_string(),
+            samples: [(NaiveTime::from_hms_opt(8, 0, 0).unwrap(), 72)]
+                .into_iter()
+                .collect(),
+        });
+
+        let xml = export.to_xml();
+
+        assert!(xml.contains(concat!(
+            " <Record type=\"HKQuantityTypeIdentifierStepCount\" ",
+            "sourceName=\"Fitbit &quot;Charge&quot;\" unit=\"count\" ",
+            "startDate=\"2025-03-01 00:00:00 +0100\" endDate=\"2025-03-02 00:00:00 +0100\" ",
+            "value=\"8000\"/>\n"
+        )));
+        assert!(xml.contains(concat!(
+            "unit=\"count/min\" startDate=\"2025-03-01 08:00:00 +0100\" ",
+            "endDate=\"2025-03-01 08:00:00 +0100\" value=\"72\"/>"
+        )));
+        assert!(xml.ends_with("</HealthData>\n"));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut export = AppleHealthExport::new(offset()).with_source_name("Fitbit, Inc.");
+        export.add_resting_heart_rate(&HeartRateDay {
+            date: date(),
+            resting_heart_rate: Some(58),
+            heart_rate_zones: Vec::new(),
+            custom_heart_rate_zones: Vec::new(),
+        });
+
+        assert_eq!(
+            export.to_csv(),
+            concat!(
+                "type,sourceName,unit,startDate,endDate,value\n",
+                "HKQuantityTypeIdentifierRestingHeartRate,\"Fitbit, Inc.\",count/min,",
+                "2025-03-01 00:00:00 +0100,2025-03-01 00:00:00 +0100,58\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_step_samples_require_steps() {
+        let intraday = IntradayActivity {
+            resource: ActivityResource::Calories,
+            date: date(),
+            total: 0.0,
+            dataset_interval: 15,
+            dataset_type: "minute".to_string(),
+            dataset: IntradaySamples::new(),
+        };
+
+        let mut export = AppleHealthExport::new(offset());
+        assert!(matches!(
+            export.add_step_samples(&intraday),
+            Err(FitbitError::InvalidArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,784 @@
+//! A local SQLite archive of a user's data, kept up to date from the API.
+//!
+//! An [`Archive`] stores daily activity summaries, sleep sessions and intraday samples in a
+//! SQLite database, so the history of an account outlives the API's limits and can be
+//! queried offline. Every record is upserted: sleep sessions by their log ID, summaries by
+//! date and intraday samples by their time, so syncing the same day twice is harmless.
+//! [`Archive::sync`] fetches the days since the last sync of each kind of data.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::activity_time_series::{ActivityResource, DetailLevel, IntradayActivity};
+use crate::daily_record::unless_unavailable;
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::heart_rate::{HeartRateDetailLevel, IntradayHeartRate};
+use crate::import::sleep_response;
+use crate::intraday::IntradaySamples;
+use crate::sleep::{SleepData, SleepResponseV1_2};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Resource name of intraday heart rate in the intraday tables
+const HEART_RATE_RESOURCE: &str = "heart";
+
+/// Schema migrations, in order; the database's `user_version` is the number applied
+const MIGRATIONS: &[&str] = &["CREATE TABLE activity_summaries (
+        date TEXT PRIMARY KEY,
+        steps INTEGER NOT NULL,
+        resting_heart_rate INTEGER,
+        json TEXT NOT NULL
+    );
+    CREATE TABLE sleep_sessions (
+        log_id INTEGER PRIMARY KEY,
+        date_of_sleep TEXT NOT NULL,
+        start_time TEXT NOT NULL,
+        end_time TEXT NOT NULL,
+        is_main_sleep INTEGER NOT NULL,
+        minutes_asleep INTEGER NOT NULL,
+        json TEXT NOT NULL
+    );
+    CREATE INDEX sleep_sessions_date ON sleep_sessions (date_of_sleep);
+    CREATE TABLE intraday_days (
+        resource TEXT NOT NULL,
+        date TEXT NOT NULL,
+        total REAL, -- NULL for heart rate, which has no daily total
+        dataset_interval INTEGER NOT NULL,
+        dataset_type TEXT NOT NULL,
+        PRIMARY KEY (resource, date)
+    );
+    CREATE TABLE intraday_samples (
+        resource TEXT NOT NULL,
+        date TEXT NOT NULL,
+        time TEXT NOT NULL,
+        value REAL NOT NULL,
+        PRIMARY KEY (resource, date, time)
+    );
+    CREATE TABLE sync_state (
+        data TEXT PRIMARY KEY,
+        last_date TEXT NOT NULL
+    );"];
+
+/// A kind of data the archive syncs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArchiveData {
+    ActivitySummaries,
+    Sleep,
+    IntradaySteps,
+    IntradayHeartRate,
+}
+
+impl ArchiveData {
+    /// Returns the key of the data in the sync state table
+    fn as_str(&self) -> &'static str {
+        match self {
+            ArchiveData::ActivitySummaries => "activity_summaries",
+            ArchiveData::Sleep => "sleep",
+            ArchiveData::IntradaySteps => "intraday_steps",
+            ArchiveData::IntradayHeartRate => "intraday_heart_rate",
+        }
+    }
+}
+
+/// Parameters for [`Archive::sync`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveSyncConfig {
+    /// Number of days before today that data never synced before starts at
+    pub history_days: u32,
+    /// The detail level to sync intraday steps at; not synced if `None`
+    pub intraday_steps: Option<DetailLevel>,
+    /// The detail level to sync intraday heart rate at; not synced if `None`
+    pub intraday_heart_rate: Option<HeartRateDetailLevel>,
+}
+
+impl Default for ArchiveSyncConfig {
+    fn default() -> Self {
+        Self {
+            history_days: 30,
+            intraday_steps: None,
+            intraday_heart_rate: None,
+        }
+    }
+}
+
+/// The number of days [`Archive::sync`] stored of each kind of data
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveSyncReport {
+    pub activity_summaries: u32,
+    pub sleep: u32,
+    pub intraday_steps: u32,
+    pub intraday_heart_rate: u32,
+}
+
+/// A SQLite database of a user's activity, sleep and intraday data
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::archive::{Archive, ArchiveSyncConfig};
+/// use fitbit_rs::FitbitClient;
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let mut archive = Archive::open("fitbit.sqlite")?;
+/// let today = chrono::Local::now().date_naive();
+///
+/// let report = archive.sync(&client, today, &ArchiveSyncConfig::default())?;
+/// println!("Synced {} nights", report.sleep);
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub struct Archive {
+    connection: Connection,
+}
+
+impl Archive {
+    /// Opens or creates the archive at a path, migrating its schema to the latest version
+    ///
+    /// # Returns
+    ///
+    /// The archive, or `FitbitError::DatabaseError` if the database can't be opened or
+    /// migrated
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FitbitError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Creates an archive in memory, e.g. for tests
+    pub fn open_in_memory() -> Result<Self, FitbitError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, FitbitError> {
+        let mut archive = Self { connection };
+        archive.migrate()?;
+        Ok(archive)
+    }
+
+    /// Returns the number of schema migrations applied to the database
+    pub fn schema_version(&self) -> Result<usize, FitbitError> {
+        let version: i64 = self
+            .connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    /// Applies the migrations the database doesn't have yet, each in a transaction
+    fn migrate(&mut self) -> Result<(), FitbitError> {
+        for (version, migration) in MIGRATIONS.iter().enumerate().skip(self.schema_version()?) {
+            let transaction = self.connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", version as i64 + 1)?;
+            transaction.commit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores the activity summary of a day, replacing the stored one
+    pub fn store_activity_summary(
+        &mut self,
+        date: NaiveDate,
+        response: &ActivitySummaryResponse,
+    ) -> Result<(), FitbitError> {
+        self.connection.execute(
+            "INSERT INTO activity_summaries (date, steps, resting_heart_rate, json)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (date) DO UPDATE SET
+                steps = excluded.steps,
+                resting_heart_rate = excluded.resting_heart_rate,
+                json = excluded.json",
+            params![
+                date.to_string(),
+                response.summary.steps,
+                response.summary.resting_heart_rate,
+                to_json(response)?,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Returns the stored activity summary of a day
+    pub fn activity_summary(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<ActivitySummaryResponse>, FitbitError> {
+        let json: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT json FROM activity_summaries WHERE date = ?1",
+                params![date.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        json.map(|json| from_json(&json)).transpose()
+    }
+
+    /// Stores a sleep session, replacing the stored session with the same log ID
+    pub fn store_sleep(&mut self, sleep: &SleepData) -> Result<(), FitbitError> {
+        insert_sleep(&self.connection, sleep)
+    }
+
+    /// Stores the sleep sessions of a night, removing stored sessions of the night that the
+    /// response doesn't have anymore, e.g. because the user deleted them
+    pub fn store_sleep_response(
+        &mut self,
+        date: NaiveDate,
+        response: &SleepResponseV1_2,
+    ) -> Result<(), FitbitError> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM sleep_sessions WHERE date_of_sleep = ?1",
+            params![date.to_string()],
+        )?;
+        for sleep in &response.sleep {
+            insert_sleep(&transaction, sleep)?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Returns the stored sleep sessions of a night, in chronological order
+    pub fn sleep_sessions(&self, date: NaiveDate) -> Result<Vec<SleepData>, FitbitError> {
+        let mut statement = self.connection.prepare(
+            "SELECT json FROM sleep_sessions WHERE date_of_sleep = ?1 ORDER BY start_time",
+        )?;
+        let rows = statement.query_map(params![date.to_string()], |row| row.get::<_, String>(0))?;
+
+        let mut sessions = Vec::new();
+        for json in rows {
+            sessions.push(from_json(&json?)?);
+        }
+        Ok(sessions)
+    }
+
+    /// Returns the stored sleep of a night as the API's daily sleep response, or `None` if
+    /// no session of the night is stored
+    pub fn sleep_response(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<SleepResponseV1_2>, FitbitError> {
+        let sessions = self.sleep_sessions(date)?;
+        Ok((!sessions.is_empty()).then(|| sleep_response(sessions)))
+    }
+
+    /// Stores the intraday values of an activity resource for a day, replacing stored
+    /// values at the same times
+    pub fn store_intraday_activity(
+        &mut self,
+        intraday: &IntradayActivity,
+    ) -> Result<(), FitbitError> {
+        self.store_intraday(
+            intraday.resource.as_str(),
+            intraday.date,
+            Some(intraday.total),
+            intraday.dataset_interval,
+            &intraday.dataset_type,
+            intraday.dataset.iter(),
+        )
+    }
+
+    /// Returns the stored intraday values of an activity resource for a day
+    pub fn intraday_activity(
+        &self,
+        resource: ActivityResource,
+        date: NaiveDate,
+    ) -> Result<Option<IntradayActivity>, FitbitError> {
+        let Some((total, dataset_interval, dataset_type)) =
+            self.intraday_day(resource.as_str(), date)?
+        else {
+            return Ok(None);
+        };
+
+        let dataset = self.intraday_samples(resource.as_str(), date)?;
+        Ok(Some(IntradayActivity {
+            resource,
+            date,
+            total: total.unwrap_or_else(|| dataset.values().iter().sum()),
+            dataset_interval,
+            dataset_type,
+            dataset,
+        }))
+    }
+
+    /// Stores the intraday heart rate of a day, replacing stored samples at the same times
+    pub fn store_intraday_heart_rate(
+        &mut self,
+        intraday: &IntradayHeartRate,
+    ) -> Result<(), FitbitError> {
+        self.store_intraday(
+            HEART_RATE_RESOURCE,
+            intraday.date,
+            None,
+            intraday.dataset_interval,
+            &intraday.dataset_type,
+            intraday
+                .samples
+                .iter()
+                .map(|(time, bpm)| (time, bpm as f64)),
+        )
+    }
+
+    /// Returns the stored intraday heart rate of a day
+    pub fn intraday_heart_rate(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<IntradayHeartRate>, FitbitError> {
+        let Some((_, dataset_interval, dataset_type)) =
+            self.intraday_day(HEART_RATE_RESOURCE, date)?
+        else {
+            return Ok(None);
+        };
+        let samples = self
+            .intraday_samples(HEART_RATE_RESOURCE, date)?
+            .iter()
+            .map(|(time, bpm)| (time, bpm as u16))
+            .collect();
+
+        Ok(Some(IntradayHeartRate {
+            date,
+            dataset_interval,
+            dataset_type,
+            samples,
+        }))
+    }
+
+    /// Returns the last day a kind of data was synced, or `None` if it never was
+    pub fn last_synced(&self, data: ArchiveData) -> Result<Option<NaiveDate>, FitbitError> {
+        let date: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT last_date FROM sync_state WHERE data = ?1",
+                params![data.as_str()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        date.map(|date| parse_date(&date)).transpose()
+    }
+
+    /// Fetches and stores the days since the last sync of each kind of data
+    ///
+    /// Each kind of data resumes at the day it was last synced, which is fetched again
+    /// because the tracker may have synced more of it since, and continues up to `today`.
+    /// Data never synced before starts `history_days` before `today`. Days Fitbit has no
+    /// data for, or that the access token lacks the scope to read, are skipped.
+    ///
+    /// Every day is stored as soon as it's fetched, so after an error, e.g. because the
+    /// rate limit was hit, the next sync continues where this one stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to fetch the data with
+    /// * `today` - The last day to sync, in the user's time zone
+    /// * `config` - How far back to start and which intraday data to sync
+    ///
+    /// # Returns
+    ///
+    /// The number of days stored of each kind of data, or the first error that doesn't mean
+    /// a day is unavailable
+    pub fn sync<C: FitbitClientTrait>(
+        &mut self,
+        client: &C,
+        today: NaiveDate,
+        config: &ArchiveSyncConfig,
+    ) -> Result<ArchiveSyncReport, FitbitError> {
+        let mut report = ArchiveSyncReport {
+            activity_summaries: self.sync_days(
+                ArchiveData::ActivitySummaries,
+                today,
+                config,
+                |date| client.fetch_activity_summary(date),
+                Self::store_activity_summary,
+            )?,
+            sleep: self.sync_days(
+                ArchiveData::Sleep,
+                today,
+                config,
+                |date| client.fetch_sleep_data(date),
+                Self::store_sleep_response,
+            )?,
+            ..Default::default()
+        };
+        if let Some(detail_level) = config.intraday_steps {
+            report.intraday_steps = self.sync_days(
+                ArchiveData::IntradaySteps,
+                today,
+                config,
+                |date| client.fetch_activity_intraday(ActivityResource::Steps, date, detail_level),
+                |archive, _, intraday| archive.store_intraday_activity(intraday),
+            )?;
+        }
+        if let Some(detail_level) = config.intraday_heart_rate {
+            report.intraday_heart_rate = self.sync_days(
+                ArchiveData::IntradayHeartRate,
+                today,
+                config,
+                |date| {
+                    client.fetch_heart_rate_intraday(
+                        date,
+                        detail_level,
+                        NaiveTime::MIN,
+                        NaiveTime::from_hms_opt(23, 59, 59).expect("23:59:59 is a valid time"),
+                    )
+                },
+                |archive, _, intraday| archive.store_intraday_heart_rate(intraday),
+            )?;
+        }
+
+        Ok(report)
+    }
+
+    /// Fetches and stores the days of a kind of data from its last sync up to `today`
+    ///
+    /// # Returns
+    ///
+    /// The number of days stored
+    fn sync_days<T>(
+        &mut self,
+        data: ArchiveData,
+        today: NaiveDate,
+        config: &ArchiveSyncConfig,
+        fetch: impl Fn(NaiveDate) -> Result<T, FitbitError>,
+        store: impl Fn(&mut Self, NaiveDate, &T) -> Result<(), FitbitError>,
+    ) -> Result<u32, FitbitError> {
+        let mut date = match self.last_synced(data)? {
+            Some(last) => last,
+            None => today - Duration::days(config.history_days as i64),
+        };
+
+        let mut stored = 0;
+        while date <= today {
+            if let Some(value) = unless_unavailable(fetch(date))? {
+                store(self, date, &value)?;
+                stored += 1;
+            }
+            self.connection.execute(
+                "INSERT INTO sync_state (data, last_date) VALUES (?1, ?2)
+                 ON CONFLICT (data) DO UPDATE SET last_date = excluded.last_date",
+                params![data.as_str(), date.to_string()],
+            )?;
+            date += Duration::days(1);
+        }
+
+        Ok(stored)
+    }
+
+    /// Upserts the intraday values of a resource for a day in a transaction
+    fn store_intraday(
+        &mut self,
+        resource: &str,
+        date: NaiveDate,
+        total: Option<f64>,
+        dataset_interval: u32,
+        dataset_type: &str,
+        samples: impl Iterator<Item = (NaiveTime, f64)>,
+    ) -> Result<(), FitbitError> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO intraday_days (resource, date, total, dataset_interval, dataset_type)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (resource, date) DO UPDATE SET
+                total = excluded.total,
+                dataset_interval = excluded.dataset_interval,
+                dataset_type = excluded.dataset_type",
+            params![
+                resource,
+                date.to_string(),
+                total,
+                dataset_interval,
+                dataset_type
+            ],
+        )?;
+        {
+            let mut statement = transaction.prepare(
+                "INSERT INTO intraday_samples (resource, date, time, value)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (resource, date, time) DO UPDATE SET value = excluded.value",
+            )?;
+            for (time, value) in samples {
+                statement.execute(params![resource, date.to_string(), time.to_string(), value])?;
+            }
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Returns the total, interval and interval unit of a resource's stored day; heart rate
+    /// has no total
+    fn intraday_day(
+        &self,
+        resource: &str,
+        date: NaiveDate,
+    ) -> Result<Option<(Option<f64>, u32, String)>, FitbitError> {
+        let day = self
+            .connection
+            .query_row(
+                "SELECT total, dataset_interval, dataset_type FROM intraday_days
+                 WHERE resource = ?1 AND date = ?2",
+                params![resource, date.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        Ok(day)
+    }
+
+    /// Returns the stored samples of a resource for a day
+    fn intraday_samples(
+        &self,
+        resource: &str,
+        date: NaiveDate,
+    ) -> Result<IntradaySamples<f64>, FitbitError> {
+        let mut statement = self.connection.prepare(
+            "SELECT time, value FROM intraday_samples WHERE resource = ?1 AND date = ?2",
+        )?;
+        let rows = statement.query_map(params![resource, date.to_string()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            let (time, value) = row?;
+            let time = time.parse::<NaiveTime>().map_err(|error| {
+                FitbitError::json_error(format!("invalid time `{}` in archive: {}", time, error))
+            })?;
+            samples.push((time, value));
+        }
+        Ok(samples.into_iter().collect())
+    }
+}
+
+/// Upserts a sleep session
+fn insert_sleep(connection: &Connection, sleep: &SleepData) -> Result<(), FitbitError> {
+    connection.execute(
+        "INSERT INTO sleep_sessions
+            (log_id, date_of_sleep, start_time, end_time, is_main_sleep, minutes_asleep, json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT (log_id) DO UPDATE SET
+            date_of_sleep = excluded.date_of_sleep,
+            start_time = excluded.start_time,
+            end_time = excluded.end_time,
+            is_main_sleep = excluded.is_main_sleep,
+            minutes_asleep = excluded.minutes_asleep,
+            json = excluded.json",
+        params![
+            sleep.log_id as i64,
+            sleep.date_of_sleep.to_string(),
+            sleep.start_time.to_string(),
+            sleep.end_time.to_string(),
+            sleep.is_main_sleep,
+            sleep.minutes_asleep,
+            to_json(sleep)?,
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, FitbitError> {
+    serde_json::to_string(value).map_err(|error| FitbitError::json_error(error.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, FitbitError> {
+    serde_json::from_str(json).map_err(|error| FitbitError::json_error(error.to_string()))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, FitbitError> {
+    date.parse().map_err(|error| {
+        FitbitError::json_error(format!("invalid date `{}` in archive: {}", date, error))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::date;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::sleep::SleepLevel;
+    use mockall::predicate::eq;
+
+    fn sleep(log_id: u64, minutes: u32) -> SleepData {
+        let start = date(1).and_hms_opt(23, 0, 0).unwrap();
+        // Sessions of any length belong to the same night
+        SleepData {
+            log_id,
+            date_of_sleep: date(2),
+            ..SleepDataBuilder::new(start)
+                .stage(SleepLevel::Light, minutes)
+                .build()
+        }
+    }
+
+    #[test]
+    fn test_migrations_are_applied_once() -> Result<(), FitbitError> {
+        let mut archive = Archive::open_in_memory()?;
+        assert_eq!(archive.schema_version()?, MIGRATIONS.len());
+
+        archive.migrate()?;
+        assert_eq!(archive.schema_version()?, MIGRATIONS.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sleep_upsert_by_log_id() -> Result<(), FitbitError> {
+        let mut archive = Archive::open_in_memory()?;
+        let night = sleep(1, 400).date_of_sleep;
+
+        archive.store_sleep(&sleep(1, 400))?;
+        archive.store_sleep(&sleep(1, 420))?;
+        archive.store_sleep(&sleep(2, 30))?;
+
+        let sessions = archive.sleep_sessions(night)?;
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(
+            sessions
+                .iter()
+                .find(|s| s.log_id == 1)
+                .unwrap()
+                .minutes_asleep,
+            420
+        );
+
+        // A response without session 2 removes it
+        let response = SleepResponseV1_2 {
+            sleep: vec![sleep(1, 420)],
+            ..Default::default()
+        };
+        archive.store_sleep_response(night, &response)?;
+        assert_eq!(archive.sleep_response(night)?.unwrap().sleep.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_intraday_round_trip() -> Result<(), FitbitError> {
+        let mut archive = Archive::open_in_memory()?;
+        let time = |minute| NaiveTime::from_hms_opt(8, minute, 0).unwrap();
+        let intraday = IntradayActivity {
+            resource: ActivityResource::Steps,
+            date: date(1),
+            total: 150.0,
+            dataset_interval: 1,
+            dataset_type: "minute".to_string(),
+            dataset: [(time(0), 100.0), (time(1), 50.0)].into_iter().collect(),
+        };
+
+        archive.store_intraday_activity(&intraday)?;
+        archive.store_intraday_activity(&intraday)?;
+
+        assert_eq!(
+            archive.intraday_activity(ActivityResource::Steps, date(1))?,
+            Some(intraday)
+        );
+        assert_eq!(archive.intraday_heart_rate(date(1))?, None);
+
+        let heart_rate = IntradayHeartRate {
+            date: date(1),
+            dataset_interval: 1,
+            dataset_type: "minute".to_string(),
+            samples: [(time(0), 72)].into_iter().collect(),
+        };
+        archive.store_intraday_heart_rate(&heart_rate)?;
+
+        assert_eq!(archive.intraday_heart_rate(date(1))?, Some(heart_rate));
+        let total: Option<f64> = archive.connection.query_row(
+            "SELECT total FROM intraday_days WHERE resource = ?1",
+            params![HEART_RATE_RESOURCE],
+            |row| row.get(0),
+        )?;
+        assert_eq!(total, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_sync() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_activity_summary()
+            .times(3 + 2)
+            .returning(|_| Ok(ActivitySummaryBuilder::new().steps(8_000).build()));
+        mock_client.expect_fetch_sleep_data().returning(|_| {
+            Err(FitbitError::NotFound {
+                message: "No data".to_string(),
+            })
+        });
+
+        let mut archive = Archive::open_in_memory()?;
+        let config = ArchiveSyncConfig {
+            history_days: 2,
+            ..Default::default()
+        };
+
+        let report = archive.sync(&mock_client, date(3), &config)?;
+        assert_eq!(report.activity_summaries, 3);
+        assert_eq!(report.sleep, 0);
+        assert_eq!(archive.last_synced(ArchiveData::Sleep)?, Some(date(3)));
+
+        // The next sync fetches the last synced day again
+        let report = archive.sync(&mock_client, date(4), &config)?;
+        assert_eq!(report.activity_summaries, 2);
+        assert_eq!(
+            archive.activity_summary(date(4))?.unwrap().summary.steps,
+            8_000
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_heart_rate_for_the_whole_day() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_activity_summary()
+            .returning(|_| Ok(ActivitySummaryBuilder::new().build()));
+        mock_client
+            .expect_fetch_sleep_data()
+            .returning(|_| Ok(SleepResponseV1_2::default()));
+        mock_client
+            .expect_fetch_heart_rate_intraday()
+            .with(
+                eq(date(1)),
+                eq(HeartRateDetailLevel::OneMinute),
+                eq(NaiveTime::MIN),
+                eq(NaiveTime::from_hms_opt(23, 59, 59).unwrap()),
+            )
+            .times(1)
+            .returning(|date, _, _, _| {
+                Ok(IntradayHeartRate {
+                    date,
+                    dataset_interval: 1,
+                    dataset_type: "minute".to_string(),
+                    samples: [(NaiveTime::from_hms_opt(23, 59, 0).unwrap(), 61)]
+                        .into_iter()
+                        .collect(),
+                })
+            });
+
+        let mut archive = Archive::open_in_memory()?;
+        let config = ArchiveSyncConfig {
+            history_days: 0,
+            intraday_heart_rate: Some(HeartRateDetailLevel::OneMinute),
+            ..Default::default()
+        };
+
+        let report = archive.sync(&mock_client, date(1), &config)?;
+        assert_eq!(report.intraday_heart_rate, 1);
+        assert_eq!(
+            archive
+                .intraday_heart_rate(date(1))?
+                .unwrap()
+                .samples
+                .values(),
+            &[61]
+        );
+
+        Ok(())
+    }
+}
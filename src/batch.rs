@@ -0,0 +1,154 @@
+//! Partial results of fetching data for many days.
+//!
+//! Fetching a long date range one day at a time should not be aborted by a single bad day.
+//! [`BatchResult`] keeps the responses of the days that succeeded next to the errors of the
+//! days that failed, so callers can store what they got and retry only the failed days.
+
+use crate::error::FitbitError;
+use chrono::NaiveDate;
+
+/// Per-day results of a batch of requests
+///
+/// Both lists are sorted by date.
+#[derive(Debug)]
+pub struct BatchResult<T> {
+    /// The days that were fetched, with their responses
+    pub successes: Vec<(NaiveDate, T)>,
+    /// The days that could not be fetched, with their errors
+    pub failures: Vec<(NaiveDate, FitbitError)>,
+}
+
+impl<T> BatchResult<T> {
+    /// Checks if every day of the batch was fetched
+    ///
+    /// # Returns
+    ///
+    /// `true` if no day failed, `false` otherwise
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns the days that could not be fetched, e.g. to retry them
+    pub fn failed_dates(&self) -> Vec<NaiveDate> {
+        self.failures.iter().map(|(date, _)| *date).collect()
+    }
+
+    /// Converts the batch into its responses, failing if any day failed
+    ///
+    /// # Returns
+    ///
+    /// The responses of all days, or the error of the earliest day that failed
+    pub fn into_result(self) -> Result<Vec<(NaiveDate, T)>, FitbitError> {
+        match self.failures.into_iter().next() {
+            Some((_, error)) => Err(error),
+            None => Ok(self.successes),
+        }
+    }
+
+    /// Applies `f` to the response of every successful day
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> BatchResult<U> {
+        BatchResult {
+            successes: self
+                .successes
+                .into_iter()
+                .map(|(date, value)| (date, f(value)))
+                .collect(),
+            failures: self.failures,
+        }
+    }
+}
+
+impl<T> Default for BatchResult<T> {
+    fn default() -> Self {
+        Self {
+            successes: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(NaiveDate, Result<T, FitbitError>)> for BatchResult<T> {
+    fn from_iter<I: IntoIterator<Item = (NaiveDate, Result<T, FitbitError>)>>(iter: I) -> Self {
+        let mut batch = Self::default();
+        for (date, result) in iter {
+            match result {
+                Ok(value) => batch.successes.push((date, value)),
+                Err(error) => batch.failures.push((date, error)),
+            }
+        }
+        batch.successes.sort_by_key(|(date, _)| *date);
+        batch.failures.sort_by_key(|(date, _)| *date);
+
+        batch
+    }
+}
+
+/// Returns every date from `start` to `end` (inclusive)
+///
+/// # Returns
+///
+/// The dates, or `FitbitError::InvalidArgument` if the range ends before it starts
+pub(crate) fn date_range(start: NaiveDate, end: NaiveDate) -> Result<Vec<NaiveDate>, FitbitError> {
+    if end < start {
+        return Err(FitbitError::InvalidArgument(format!(
+            "date range must not end before it starts, got {} to {}",
+            start, end
+        )));
+    }
+
+    Ok(start.iter_days().take_while(|date| *date <= end).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+
+    #[test]
+    fn test_batch_result_from_results() {
+        let batch: BatchResult<u32> = vec![
+            (date(3), Ok(3)),
+            (date(2), Err(FitbitError::api_error(500, "Oops"))),
+            (date(1), Ok(1)),
+            (
+                date(4),
+                Err(FitbitError::InvalidArgument("bad".to_string())),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        assert!(!batch.is_complete());
+        assert_eq!(batch.successes, vec![(date(1), 1), (date(3), 3)]);
+        assert_eq!(batch.failed_dates(), vec![date(2), date(4)]);
+        assert!(matches!(
+            batch.into_result(),
+            Err(FitbitError::ApiError {
+                status_code: 500,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_complete_batch_result() {
+        let batch: BatchResult<u32> = vec![(date(1), Ok(1)), (date(2), Ok(2))]
+            .into_iter()
+            .collect();
+
+        assert!(batch.is_complete());
+        assert_eq!(
+            batch.map(|value| value * 10).into_result().unwrap(),
+            vec![(date(1), 10), (date(2), 20)]
+        );
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(
+            date_range(date(1), date(3)).unwrap(),
+            vec![date(1), date(2), date(3)]
+        );
+        assert!(date_range(date(3), date(1)).is_err());
+    }
+}
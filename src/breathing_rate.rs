@@ -0,0 +1,185 @@
+//! Breathing rate during sleep.
+//!
+//! Fitbit estimates the average breathing rate of the main sleep of each night, and
+//! separately for each sleep stage. Nights without enough data have no breathing rate.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Average breathing rate of a night's main sleep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreathingRate {
+    /// The date the sleep ended on
+    pub date: NaiveDate,
+    /// Breaths per minute
+    pub breathing_rate: f64,
+}
+
+/// Average breathing rate of a night's main sleep, overall and per sleep stage
+///
+/// Each rate is in breaths per minute and `None` if Fitbit couldn't estimate it, e.g.
+/// when there was no REM sleep.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreathingRateByStage {
+    /// The date the sleep ended on
+    pub date: NaiveDate,
+    pub full_sleep: Option<f64>,
+    pub deep_sleep: Option<f64>,
+    pub light_sleep: Option<f64>,
+    pub rem_sleep: Option<f64>,
+}
+
+/// Raw breathing rate summary response
+#[derive(Debug, Deserialize)]
+pub(crate) struct BreathingRateResponse {
+    br: Vec<BreathingRateEntry<BreathingRateValue>>,
+}
+
+/// Raw breathing rate response with the per-stage breakdown
+#[derive(Debug, Deserialize)]
+pub(crate) struct BreathingRateIntradayResponse {
+    br: Vec<BreathingRateEntry<BreathingRateStages>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BreathingRateEntry<T> {
+    date_time: NaiveDate,
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BreathingRateValue {
+    breathing_rate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BreathingRateStages {
+    full_sleep_summary: Option<BreathingRateValue>,
+    deep_sleep_summary: Option<BreathingRateValue>,
+    light_sleep_summary: Option<BreathingRateValue>,
+    rem_sleep_summary: Option<BreathingRateValue>,
+}
+
+/// Returns the breathing rate of a stage summary, which the API reports as -1 if it is
+/// not available
+fn stage_rate(summary: Option<BreathingRateValue>) -> Option<f64> {
+    summary
+        .map(|summary| summary.breathing_rate)
+        .filter(|&rate| rate >= 0.0)
+}
+
+impl BreathingRateResponse {
+    /// Returns the breathing rate of the requested night, if there is one
+    pub(crate) fn into_breathing_rate(self) -> Option<BreathingRate> {
+        self.br.into_iter().next().and_then(|entry| {
+            (entry.value.breathing_rate >= 0.0).then_some(BreathingRate {
+                date: entry.date_time,
+                breathing_rate: entry.value.breathing_rate,
+            })
+        })
+    }
+}
+
+impl BreathingRateIntradayResponse {
+    /// Returns the per-stage breathing rates of the requested night, if there are any
+    pub(crate) fn into_breathing_rate_by_stage(self) -> Option<BreathingRateByStage> {
+        self.br
+            .into_iter()
+            .next()
+            .map(|entry| BreathingRateByStage {
+                date: entry.date_time,
+                full_sleep: stage_rate(entry.value.full_sleep_summary),
+                deep_sleep: stage_rate(entry.value.deep_sleep_summary),
+                light_sleep: stage_rate(entry.value.light_sleep_summary),
+                rem_sleep: stage_rate(entry.value.rem_sleep_summary),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_breathing_rate() {
+        let json_str = r#"{"br": [{"value": {"breathingRate": 17.8}, "dateTime": "2021-10-25"}]}"#;
+
+        let response: BreathingRateResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(
+            response.into_breathing_rate(),
+            Some(BreathingRate {
+                date: NaiveDate::from_ymd_opt(2021, 10, 25).unwrap(),
+                breathing_rate: 17.8,
+            })
+        );
+
+        // Nights without sleep data have no entry
+        let response: BreathingRateResponse =
+            serde_json::from_str(r#"{"br": []}"#).expect("Failed to parse JSON");
+        assert_eq!(response.into_breathing_rate(), None);
+    }
+
+    #[test]
+    fn test_parse_breathing_rate_by_stage() {
+        let json_str = r#"{
+            "br": [
+                {
+                    "value": {
+                        "deepSleepSummary": {"breathingRate": 16.8},
+                        "remSleepSummary": {"breathingRate": -1},
+                        "fullSleepSummary": {"breathingRate": 17.8},
+                        "lightSleepSummary": {"breathingRate": 16.8}
+                    },
+                    "dateTime": "2021-10-25"
+                }
+            ]
+        }"#;
+
+        let response: BreathingRateIntradayResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let stages = response
+            .into_breathing_rate_by_stage()
+            .expect("Missing breathing rate");
+
+        assert_eq!(stages.full_sleep, Some(17.8));
+        assert_eq!(stages.deep_sleep, Some(16.8));
+        assert_eq!(stages.light_sleep, Some(16.8));
+        assert_eq!(stages.rem_sleep, None);
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let date = NaiveDate::from_ymd_opt(2021, 10, 25).unwrap();
+
+        let rate = BreathingRate {
+            date,
+            breathing_rate: 17.8,
+        };
+        let serialized = serde_json::to_value(&rate).expect("Failed to serialize");
+        assert_eq!(serialized["breathingRate"], 17.8);
+        let round_tripped: BreathingRate =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, rate);
+
+        let stages = BreathingRateByStage {
+            date,
+            full_sleep: Some(17.8),
+            deep_sleep: Some(16.8),
+            light_sleep: Some(16.8),
+            rem_sleep: None,
+        };
+        let serialized = serde_json::to_value(&stages).expect("Failed to serialize");
+        assert_eq!(serialized["fullSleep"], 17.8);
+        assert!(serialized["remSleep"].is_null());
+        let round_tripped: BreathingRateByStage =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, stages);
+    }
+}
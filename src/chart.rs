@@ -0,0 +1,579 @@
+//! Rendering of sleep, activity and heart rate as SVG charts.
+//!
+//! The charts are self-contained SVG documents without scripts or external stylesheets, so
+//! they can be written to a file, embedded in HTML or converted to PNG by any SVG renderer.
+//! Every bar and interval has a `<title>`, which browsers show as a tooltip.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fitbit_rs::chart::{ChartConfig, render_hypnogram};
+//! use fitbit_rs::{FitbitClient, FitbitClientTrait};
+//!
+//! let client = FitbitClient::new("your_access_token".to_string());
+//! let today = chrono::Local::now().date_naive();
+//!
+//! if let Some(sleep) = client.fetch_sleep_data(today)?.main_sleep() {
+//!     let svg = render_hypnogram(&sleep.hypnogram(), &ChartConfig::default());
+//!     std::fs::write("hypnogram.svg", svg)?;
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::activity_time_series::IntradayActivity;
+use crate::export::escape_xml;
+use crate::heart_rate::{HeartRateDetailLevel, IntradayHeartRate};
+use crate::sleep::{SleepLevel, StageInterval};
+use chrono::{Duration, NaiveDateTime, Timelike};
+use std::fmt::Write;
+
+/// Space left of the plot area, for the axis labels
+const MARGIN_LEFT: f64 = 56.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 12.0;
+/// Space below the plot area, for the time labels
+const MARGIN_BOTTOM: f64 = 28.0;
+
+const AXIS_COLOR: &str = "#888888";
+const GRID_COLOR: &str = "#e0e0e0";
+
+/// Heart rate samples further apart than this are not connected by the line
+const HEART_RATE_GAP_MINUTES: i64 = 15;
+
+/// The size and colors of a chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartConfig {
+    /// Width of the chart in pixels
+    pub width: u32,
+    /// Height of the chart in pixels
+    pub height: u32,
+    /// Color of the bars and lines, as a CSS color
+    pub color: String,
+    /// Background color, as a CSS color; transparent if `None`
+    pub background: Option<String>,
+    pub font_family: String,
+}
+
+impl Default for ChartConfig {
+    fn default() -> Self {
+        Self {
+            width: 720,
+            height: 240,
+            color: "#e4572e".to_string(),
+            background: None,
+            font_family: "sans-serif".to_string(),
+        }
+    }
+}
+
+/// Renders a hypnogram, with a row per sleep stage
+///
+/// Records in the stages format get rows for wake, REM, light and deep sleep, records in
+/// the classic format rows for awake, restless and asleep. Intervals of levels this crate
+/// doesn't know are left out.
+///
+/// # Arguments
+///
+/// * `intervals` - The sleep stages in chronological order, usually from
+///   [`SleepData::hypnogram`](crate::sleep::SleepData::hypnogram)
+/// * `config` - The size and colors of the chart; stages have colors of their own
+///
+/// # Returns
+///
+/// The SVG document, with a "No data" note if there are no intervals
+pub fn render_hypnogram(intervals: &[StageInterval], config: &ChartConfig) -> String {
+    let mut svg = Svg::new(config);
+    let (Some(first), Some(last)) = (intervals.first(), intervals.last()) else {
+        svg.no_data();
+        return svg.finish();
+    };
+
+    let classic = intervals.iter().any(|interval| {
+        matches!(
+            interval.level,
+            SleepLevel::Asleep | SleepLevel::Restless | SleepLevel::Awake
+        )
+    });
+    let lanes: &[SleepLevel] = if classic {
+        &[SleepLevel::Awake, SleepLevel::Restless, SleepLevel::Asleep]
+    } else {
+        &[
+            SleepLevel::Wake,
+            SleepLevel::Rem,
+            SleepLevel::Light,
+            SleepLevel::Deep,
+        ]
+    };
+    let lane_height = svg.plot_height() / lanes.len() as f64;
+    let lane_center = |lane: usize| MARGIN_TOP + (lane as f64 + 0.5) * lane_height;
+
+    let start = first.start;
+    let span = (last.end - start).num_seconds().max(1) as f64;
+    let plot_width = svg.plot_width();
+    let x =
+        |time: NaiveDateTime| MARGIN_LEFT + (time - start).num_seconds() as f64 / span * plot_width;
+
+    for (lane, level) in lanes.iter().enumerate() {
+        svg.label(
+            MARGIN_LEFT - 8.0,
+            lane_center(lane),
+            "end",
+            stage_name(level),
+        );
+    }
+    svg.time_axis(start, last.end);
+
+    // Connect each interval to the next one, as the line of a hypnogram does
+    let mut previous: Option<(f64, usize)> = None;
+    for interval in intervals {
+        let Some(lane) = lanes.iter().position(|level| *level == interval.level) else {
+            previous = None;
+            continue;
+        };
+        if let Some((previous_end, previous_lane)) = previous {
+            let x_start = x(interval.start);
+            if (previous_end - x_start).abs() < 0.5 && previous_lane != lane {
+                svg.line(
+                    x_start,
+                    lane_center(previous_lane),
+                    x_start,
+                    lane_center(lane),
+                    AXIS_COLOR,
+                );
+            }
+        }
+        previous = Some((x(interval.end), lane));
+    }
+
+    for interval in intervals {
+        let Some(lane) = lanes.iter().position(|level| *level == interval.level) else {
+            continue;
+        };
+        let title = format!(
+            "{} {}–{}",
+            stage_name(&interval.level),
+            interval.start.format("%H:%M"),
+            interval.end.format("%H:%M")
+        );
+        svg.rect(
+            x(interval.start),
+            lane_center(lane) - lane_height * 0.35,
+            x(interval.end) - x(interval.start),
+            lane_height * 0.7,
+            stage_color(&interval.level),
+            &title,
+        );
+    }
+
+    svg.finish()
+}
+
+/// Renders the intraday activity of a day as a bar per hour, e.g. steps per hour
+///
+/// # Arguments
+///
+/// * `intraday` - The intraday data of any activity resource; the values within each hour
+///   are summed
+/// * `config` - The size and colors of the chart
+///
+/// # Returns
+///
+/// The SVG document, with a "No data" note if there are no samples
+pub fn render_hourly_activity(intraday: &IntradayActivity, config: &ChartConfig) -> String {
+    let mut svg = Svg::new(config);
+    if intraday.dataset.is_empty() {
+        svg.no_data();
+        return svg.finish();
+    }
+
+    let mut hours = [0.0; 24];
+    for (time, value) in intraday.dataset.iter() {
+        hours[time.hour() as usize] += value;
+    }
+    let max = nice_maximum(hours.iter().copied().fold(0.0, f64::max));
+    svg.value_axis(0.0, max, max / 2.0);
+
+    let day_start = intraday.date.and_time(Default::default());
+    svg.time_axis(day_start, day_start + Duration::days(1));
+
+    let slot_width = svg.plot_width() / 24.0;
+    let plot_height = svg.plot_height();
+    let y = |value: f64| y_position(value, 0.0, max, plot_height);
+    for (hour, value) in hours.iter().enumerate() {
+        if *value <= 0.0 {
+            continue;
+        }
+        let title = format!("{:02}:00 {}", hour, value.round());
+        svg.rect(
+            MARGIN_LEFT + (hour as f64 + 0.1) * slot_width,
+            y(*value),
+            slot_width * 0.8,
+            y(0.0) - y(*value),
+            &config.color,
+            &title,
+        );
+    }
+
+    svg.finish()
+}
+
+/// Renders the heart rate of a day as a line
+///
+/// Samples are averaged per minute first, which keeps the document small for 1 second
+/// data. The line is interrupted where there are no samples for more than 15 minutes,
+/// e.g. while the tracker wasn't worn.
+///
+/// # Arguments
+///
+/// * `heart_rate` - The intraday heart rate of a day
+/// * `config` - The size and colors of the chart
+///
+/// # Returns
+///
+/// The SVG document, with a "No data" note if there are no samples
+pub fn render_heart_rate(heart_rate: &IntradayHeartRate, config: &ChartConfig) -> String {
+    let mut svg = Svg::new(config);
+    let buckets = heart_rate.resample(HeartRateDetailLevel::OneMinute);
+    if buckets.is_empty() {
+        svg.no_data();
+        return svg.finish();
+    }
+
+    let (min, max) = buckets
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), bucket| {
+            (min.min(bucket.average_bpm), max.max(bucket.average_bpm))
+        });
+    let low = ((min - 5.0) / 10.0).floor().max(0.0) * 10.0;
+    let high = ((max + 5.0) / 10.0).ceil() * 10.0;
+    svg.value_axis(low, high, 20.0);
+
+    let day_start = heart_rate.date.and_time(Default::default());
+    svg.time_axis(day_start, day_start + Duration::days(1));
+
+    let mut path = String::new();
+    let mut previous = None;
+    for bucket in &buckets {
+        let x = MARGIN_LEFT
+            + bucket.start.num_seconds_from_midnight() as f64 / 86_400.0 * svg.plot_width();
+        let command = match previous {
+            Some(previous)
+                if bucket.start - previous <= Duration::minutes(HEART_RATE_GAP_MINUTES) =>
+            {
+                'L'
+            }
+            _ => 'M',
+        };
+        // Writing to a String can't fail
+        let _ = write!(
+            path,
+            "{}{:.1} {:.1} ",
+            command,
+            x,
+            svg.y(bucket.average_bpm, low, high)
+        );
+        previous = Some(bucket.start);
+    }
+    let _ = writeln!(
+        svg.content,
+        r#"<path d="{}" fill="none" stroke="{}" stroke-width="1.5" stroke-linejoin="round"/>"#,
+        path.trim_end(),
+        escape_xml(&config.color)
+    );
+
+    svg.finish()
+}
+
+/// Returns the row label of a sleep level
+fn stage_name(level: &SleepLevel) -> &'static str {
+    match level {
+        SleepLevel::Deep => "Deep",
+        SleepLevel::Light => "Light",
+        SleepLevel::Rem => "REM",
+        SleepLevel::Wake => "Awake",
+        SleepLevel::Asleep => "Asleep",
+        SleepLevel::Restless => "Restless",
+        SleepLevel::Awake => "Awake",
+        _ => "Unknown",
+    }
+}
+
+/// Returns the color of a sleep level, similar to the Fitbit app
+fn stage_color(level: &SleepLevel) -> &'static str {
+    match level {
+        SleepLevel::Deep => "#1f3c88",
+        SleepLevel::Light | SleepLevel::Asleep => "#4f8fe6",
+        SleepLevel::Rem => "#7ec8e3",
+        SleepLevel::Restless => "#a0a8e0",
+        _ => "#f28c6b",
+    }
+}
+
+/// Rounds the maximum of an axis up to 1, 2 or 5 times a power of ten
+fn nice_maximum(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|nice| *nice >= value)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Returns the vertical position of a value on an axis from `low` to `high`
+fn y_position(value: f64, low: f64, high: f64, plot_height: f64) -> f64 {
+    MARGIN_TOP + (1.0 - (value - low) / (high - low)) * plot_height
+}
+
+/// An SVG document being written
+struct Svg<'a> {
+    config: &'a ChartConfig,
+    content: String,
+}
+
+impl<'a> Svg<'a> {
+    fn new(config: &'a ChartConfig) -> Self {
+        let (width, height) = (config.width, config.height);
+        let mut content = String::new();
+        // Writing to a String can't fail
+        let _ = writeln!(
+            content,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="{}" font-size="11">"#,
+            width,
+            height,
+            width,
+            height,
+            escape_xml(&config.font_family)
+        );
+        if let Some(background) = &config.background {
+            let _ = writeln!(
+                content,
+                r#"<rect width="100%" height="100%" fill="{}"/>"#,
+                escape_xml(background)
+            );
+        }
+
+        Self { config, content }
+    }
+
+    fn plot_width(&self) -> f64 {
+        (self.config.width as f64 - MARGIN_LEFT - MARGIN_RIGHT).max(1.0)
+    }
+
+    fn plot_height(&self) -> f64 {
+        (self.config.height as f64 - MARGIN_TOP - MARGIN_BOTTOM).max(1.0)
+    }
+
+    /// Returns the vertical position of a value on an axis from `low` to `high`
+    fn y(&self, value: f64, low: f64, high: f64) -> f64 {
+        y_position(value, low, high, self.plot_height())
+    }
+
+    fn no_data(&mut self) {
+        let (x, y) = (
+            self.config.width as f64 / 2.0,
+            self.config.height as f64 / 2.0,
+        );
+        self.label(x, y, "middle", "No data");
+    }
+
+    /// Draws a horizontal grid line and label from `low` to `high` at every `step`
+    fn value_axis(&mut self, low: f64, high: f64, step: f64) {
+        let right = MARGIN_LEFT + self.plot_width();
+        let mut value = low;
+        while value <= high + step / 1000.0 {
+            let y = self.y(value, low, high);
+            self.line(MARGIN_LEFT, y, right, y, GRID_COLOR);
+            self.label(MARGIN_LEFT - 8.0, y, "end", &value.round().to_string());
+            value += step;
+        }
+    }
+
+    /// Draws the bottom axis with a tick on every full hour from `start` to `end`, labeled
+    /// every hour for up to 12 hours, and every few hours beyond
+    fn time_axis(&mut self, start: NaiveDateTime, end: NaiveDateTime) {
+        let bottom = MARGIN_TOP + self.plot_height();
+        let right = MARGIN_LEFT + self.plot_width();
+        self.line(MARGIN_LEFT, bottom, right, bottom, AXIS_COLOR);
+
+        let span = (end - start).num_seconds().max(1) as f64;
+        let label_every = ((end - start).num_hours() / 12 + 1) as u32;
+        let mut tick = start
+            .with_minute(0)
+            .and_then(|time| time.with_second(0))
+            .and_then(|time| time.with_nanosecond(0))
+            .unwrap_or(start);
+        if tick < start {
+            tick += Duration::hours(1);
+        }
+        while tick <= end {
+            let x = MARGIN_LEFT + (tick - start).num_seconds() as f64 / span * self.plot_width();
+            self.line(x, bottom, x, bottom + 4.0, AXIS_COLOR);
+            if tick.hour().is_multiple_of(label_every) {
+                let label = tick.format("%H:%M").to_string();
+                self.label(x, bottom + 16.0, "middle", &label);
+            }
+            tick += Duration::hours(1);
+        }
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: &str) {
+        // Writing to a String can't fail
+        let _ = writeln!(
+            self.content,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"/>"#,
+            x1, y1, x2, y2, color
+        );
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str, title: &str) {
+        // Writing to a String can't fail
+        let _ = writeln!(
+            self.content,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}</title></rect>"#,
+            x,
+            y,
+            width.max(0.0),
+            height.max(0.0),
+            escape_xml(color),
+            escape_xml(title)
+        );
+    }
+
+    /// Draws text vertically centered on `y`, anchored at `start`, `middle` or `end`
+    fn label(&mut self, x: f64, y: f64, anchor: &str, text: &str) {
+        // Writing to a String can't fail
+        let _ = writeln!(
+            self.content,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="{}" dominant-baseline="middle" fill="{}">{}</text>"#,
+            x,
+            y,
+            anchor,
+            AXIS_COLOR,
+            escape_xml(text)
+        );
+    }
+
+    fn finish(mut self) -> String {
+        self.content.push_str("</svg>\n");
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity_time_series::ActivityResource;
+    use chrono::{NaiveDate, NaiveTime};
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn interval(level: SleepLevel, start: (u32, u32), end: (u32, u32)) -> StageInterval {
+        StageInterval {
+            level,
+            start: date().and_time(time(start.0, start.1)),
+            end: date().and_time(time(end.0, end.1)),
+        }
+    }
+
+    #[test]
+    fn test_hypnogram() {
+        let intervals = vec![
+            interval(SleepLevel::Light, (1, 0), (1, 30)),
+            interval(SleepLevel::Deep, (1, 30), (2, 0)),
+            interval(SleepLevel::Wake, (2, 0), (2, 5)),
+        ];
+
+        let svg = render_hypnogram(&intervals, &ChartConfig::default());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"720\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), 3);
+        assert!(svg.contains("<title>Deep 01:30–02:00</title>"));
+        assert!(svg.contains(">REM</text>"));
+        assert!(svg.contains(">02:00</text>"));
+        // The axis, a tick at 01:00 and 02:00, and a connector per stage change
+        assert_eq!(svg.matches("<line ").count(), 5);
+    }
+
+    #[test]
+    fn test_classic_hypnogram() {
+        let intervals = vec![
+            interval(SleepLevel::Asleep, (23, 0), (23, 50)),
+            interval(SleepLevel::Restless, (23, 50), (23, 55)),
+        ];
+
+        let svg = render_hypnogram(&intervals, &ChartConfig::default());
+
+        assert!(svg.contains(">Restless</text>"));
+        assert!(!svg.contains(">Deep</text>"));
+    }
+
+    #[test]
+    fn test_hourly_activity() {
+        let intraday = IntradayActivity {
+            resource: ActivityResource::Steps,
+            date: date(),
+            total: 1_700.0,
+            dataset_interval: 15,
+            dataset_type: "minute".to_string(),
+            dataset: [
+                (time(8, 0), 500.0),
+                (time(8, 15), 700.0),
+                (time(18, 30), 500.0),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let svg = render_hourly_activity(&intraday, &ChartConfig::default());
+
+        assert_eq!(svg.matches("<rect ").count(), 2);
+        assert!(svg.contains("<title>08:00 1200</title>"));
+        assert!(svg.contains(">2000</text>"));
+    }
+
+    #[test]
+    fn test_heart_rate_line_has_gaps() {
+        let heart_rate = IntradayHeartRate {
+            date: date(),
+            dataset_interval: 1,
+            dataset_type: "minute".to_string(),
+            samples: [(time(10, 0), 60), (time(10, 1), 62), (time(11, 0), 70)]
+                .into_iter()
+                .collect(),
+        };
+
+        let svg = render_heart_rate(&heart_rate, &ChartConfig::default());
+
+        let path = svg.lines().find(|line| line.starts_with("<path")).unwrap();
+        assert_eq!(path.matches('M').count(), 2);
+        assert_eq!(path.matches('L').count(), 1);
+    }
+
+    #[test]
+    fn test_empty_chart() {
+        let svg = render_hypnogram(&[], &ChartConfig::default());
+
+        assert!(svg.contains(">No data</text>"));
+        assert!(!svg.contains("<rect "));
+    }
+
+    #[test]
+    fn test_nice_maximum() {
+        assert_eq!(nice_maximum(0.0), 1.0);
+        assert_eq!(nice_maximum(1_200.0), 2_000.0);
+        assert_eq!(nice_maximum(4_800.0), 5_000.0);
+        assert_eq!(nice_maximum(5_000.0), 5_000.0);
+        assert_eq!(nice_maximum(7_300.0), 10_000.0);
+    }
+}
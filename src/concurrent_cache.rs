@@ -0,0 +1,410 @@
+//! Thread-safe cache for Fitbit API responses.
+//!
+//! This module provides a concurrent alternative to [`FitbitResponseCache`](crate::FitbitResponseCache)
+//! for collectors that fetch data from several threads at once. Responses are stored in
+//! sharded maps, so lookups for different dates do not contend on a single lock, and
+//! concurrent requests for the same date are coalesced into a single API call.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::batch::{BatchResult, date_range};
+use crate::daily_record::{DailyRecord, unless_unavailable};
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::sleep::SleepResponseV1_2;
+use chrono::NaiveDate;
+use dashmap::DashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A thread-safe cache for Fitbit API responses.
+///
+/// Unlike [`FitbitResponseCache`](crate::FitbitResponseCache), all methods take `&self`, so
+/// the cache can be shared between threads (e.g. behind an `Arc`). Cached responses are
+/// handed out as `Arc`s, since a reference into the map cannot outlive its shard lock.
+///
+/// If several threads request the same uncached date at the same time, only one of them
+/// calls the API; the others wait for that request to finish and reuse its response. If the
+/// request fails, the waiters get a copy of its error, and the next request for the date
+/// retries.
+pub struct ConcurrentFitbitResponseCache<C: FitbitClientTrait> {
+    fitbit_client: C,
+    sleep_responses: DashMap<NaiveDate, Arc<SleepResponseV1_2>>,
+    activity_summary_responses: DashMap<NaiveDate, Arc<ActivitySummaryResponse>>,
+    sleep_in_flight: DashMap<NaiveDate, InFlight>,
+    activity_summary_in_flight: DashMap<NaiveDate, InFlight>,
+}
+
+impl<C: FitbitClientTrait> ConcurrentFitbitResponseCache<C> {
+    /// Creates a new concurrent cache with the given Fitbit client.
+    ///
+    /// # Arguments
+    ///
+    /// * `fitbit_client` - The Fitbit client to use for making API calls
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fitbit_rs::{ConcurrentFitbitResponseCache, FitbitClient};
+    /// use std::sync::Arc;
+    ///
+    /// let client = FitbitClient::new("your_access_token".to_string());
+    /// let cache = Arc::new(ConcurrentFitbitResponseCache::new(client));
+    /// ```
+    pub fn new(fitbit_client: C) -> Self {
+        Self {
+            fitbit_client,
+            sleep_responses: DashMap::new(),
+            activity_summary_responses: DashMap::new(),
+            sleep_in_flight: DashMap::new(),
+            activity_summary_in_flight: DashMap::new(),
+        }
+    }
+
+    /// Gets a sleep response for the given date.
+    ///
+    /// If the response is not in the cache, it will be fetched from the API and cached.
+    /// Concurrent calls for the same date share a single API request.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to get sleep data
+    ///
+    /// # Returns
+    ///
+    /// A shared handle to the cached sleep response or an error if the request failed
+    pub fn get_sleep_response(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Arc<SleepResponseV1_2>, FitbitError> {
+        get_or_fetch(&self.sleep_responses, &self.sleep_in_flight, date, || {
+            self.fitbit_client.fetch_sleep_data(date)
+        })
+    }
+
+    /// Gets an activity summary response for the given date.
+    ///
+    /// If the response is not in the cache, it will be fetched from the API and cached.
+    /// Concurrent calls for the same date share a single API request.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to get activity data
+    ///
+    /// # Returns
+    ///
+    /// A shared handle to the cached activity summary response or an error if the request failed
+    pub fn get_activity_summary_response(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Arc<ActivitySummaryResponse>, FitbitError> {
+        get_or_fetch(
+            &self.activity_summary_responses,
+            &self.activity_summary_in_flight,
+            date,
+            || self.fitbit_client.fetch_activity_summary(date),
+        )
+    }
+
+    /// Gets the sleep responses for every date from `start` to `end` (inclusive).
+    ///
+    /// Dates that are not in the cache are fetched from the API and cached. A date that
+    /// fails does not stop the others from being fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive)
+    ///
+    /// # Returns
+    ///
+    /// Shared handles to the cached responses and the errors of the dates that could not be
+    /// fetched, or an error if the range is invalid
+    pub fn get_sleep_responses(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BatchResult<Arc<SleepResponseV1_2>>, FitbitError> {
+        Ok(date_range(start, end)?
+            .into_iter()
+            .map(|date| (date, self.get_sleep_response(date)))
+            .collect())
+    }
+
+    /// Gets the activity summary responses for every date from `start` to `end` (inclusive).
+    ///
+    /// Dates that are not in the cache are fetched from the API and cached. A date that
+    /// fails does not stop the others from being fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive)
+    ///
+    /// # Returns
+    ///
+    /// Shared handles to the cached responses and the errors of the dates that could not be
+    /// fetched, or an error if the range is invalid
+    pub fn get_activity_summary_responses(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BatchResult<Arc<ActivitySummaryResponse>>, FitbitError> {
+        Ok(date_range(start, end)?
+            .into_iter()
+            .map(|date| (date, self.get_activity_summary_response(date)))
+            .collect())
+    }
+
+    /// Gets the sleep, activity and heart rate data of the given date.
+    ///
+    /// Sleep and activity come from the cache, and are fetched and cached if missing.
+    /// Heart rate and breathing rate are fetched from the API on every call. Parts that
+    /// Fitbit has no data for, or that the access token lacks the scope for, are left empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to get the data
+    ///
+    /// # Returns
+    ///
+    /// The data of the day, or the first error that doesn't mean a part is unavailable
+    pub fn fetch_daily_record(&self, date: NaiveDate) -> Result<DailyRecord, FitbitError> {
+        let sleep = unless_unavailable(self.get_sleep_response(date).map(Arc::unwrap_or_clone))?
+            .filter(|response| !response.sleep.is_empty());
+        let activity = unless_unavailable(
+            self.get_activity_summary_response(date)
+                .map(Arc::unwrap_or_clone),
+        )?;
+        let heart_rate = unless_unavailable(self.fitbit_client.fetch_heart_rate(date))?;
+        let breathing_rate =
+            unless_unavailable(self.fitbit_client.fetch_breathing_rate(date))?.flatten();
+
+        Ok(DailyRecord {
+            date,
+            sleep,
+            activity,
+            heart_rate,
+            breathing_rate,
+        })
+    }
+
+    /// Clears all cached responses.
+    ///
+    /// Requests that are currently in flight are not cancelled and will still populate the
+    /// cache when they complete.
+    pub fn clear_cache(&self) {
+        self.sleep_responses.clear();
+        self.activity_summary_responses.clear();
+    }
+
+    /// Removes a specific date from the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date to remove from the cache
+    pub fn remove_from_cache(&self, date: NaiveDate) {
+        self.sleep_responses.remove(&date);
+        self.activity_summary_responses.remove(&date);
+    }
+
+    /// Gets a reference to the underlying Fitbit client.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the Fitbit client
+    pub fn client(&self) -> &C {
+        &self.fitbit_client
+    }
+}
+
+/// A per-date lock of a fetch in progress, holding the error if the fetch failed
+type InFlight = Arc<Mutex<Option<Arc<FitbitError>>>>;
+
+/// Looks up `date` in `responses`, fetching and inserting it if missing.
+///
+/// Fetches for the same date are serialized through a per-date lock in `in_flight`, so only
+/// the first caller hits the API and the rest find the response in the cache, or the error of
+/// the failed fetch in the lock, once they acquire it. Fetches for different dates never wait
+/// on each other.
+fn get_or_fetch<T>(
+    responses: &DashMap<NaiveDate, Arc<T>>,
+    in_flight: &DashMap<NaiveDate, InFlight>,
+    date: NaiveDate,
+    fetch: impl FnOnce() -> Result<T, FitbitError>,
+) -> Result<Arc<T>, FitbitError> {
+    if let Some(response) = responses.get(&date) {
+        return Ok(Arc::clone(response.value()));
+    }
+
+    // Clone the lock out of the map so the shard is not held while we wait on it
+    let slot = Arc::clone(in_flight.entry(date).or_default().value());
+    let mut failure = slot.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // Another caller may have completed the fetch while we were waiting
+    if let Some(response) = responses.get(&date) {
+        return Ok(Arc::clone(response.value()));
+    }
+    if let Some(error) = failure.as_ref() {
+        return Err(FitbitError::Shared(Arc::clone(error)));
+    }
+
+    let result = match fetch() {
+        Ok(response) => {
+            let response = Arc::new(response);
+            responses.insert(date, Arc::clone(&response));
+            Ok(response)
+        }
+        // Callers already waiting on the slot share the failure; later callers retry
+        Err(error) => {
+            let error = Arc::new(error);
+            *failure = Some(Arc::clone(&error));
+            Err(FitbitError::Shared(error))
+        }
+    };
+    in_flight.remove(&date);
+
+    result
+}
+
+#[cfg(test)]
+mod concurrent_cache_tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use mockall::predicate::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+
+    #[test]
+    fn test_concurrent_requests_are_coalesced() {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        // Only one of the threads should reach the API
+        mock_client
+            .expect_fetch_sleep_data()
+            .with(eq(date))
+            .times(1)
+            .returning(|_| {
+                thread::sleep(std::time::Duration::from_millis(50));
+                Ok(SleepResponseV1_2::default())
+            });
+
+        let cache = ConcurrentFitbitResponseCache::new(mock_client);
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| cache.get_sleep_response(date).unwrap());
+            }
+        });
+    }
+
+    #[test]
+    fn test_failed_fetch_is_not_cached() {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        // The first call fails, so the second one should hit the API again
+        let failed = AtomicBool::new(false);
+        mock_client
+            .expect_fetch_sleep_data()
+            .with(eq(date))
+            .times(2)
+            .returning(move |_| {
+                if failed.swap(true, Ordering::SeqCst) {
+                    Ok(SleepResponseV1_2::default())
+                } else {
+                    Err(FitbitError::api_error(500, "Internal Server Error"))
+                }
+            });
+
+        let cache = ConcurrentFitbitResponseCache::new(mock_client);
+
+        assert!(cache.get_sleep_response(date).is_err());
+        assert!(cache.get_sleep_response(date).is_ok());
+    }
+
+    #[test]
+    fn test_failed_fetch_is_shared_with_waiters() {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        // The waiters should get the error instead of retrying one after another
+        mock_client
+            .expect_fetch_sleep_data()
+            .with(eq(date))
+            .times(1)
+            .returning(|_| {
+                thread::sleep(std::time::Duration::from_millis(50));
+                Err(FitbitError::api_error(500, "Internal Server Error"))
+            });
+
+        let cache = ConcurrentFitbitResponseCache::new(mock_client);
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| cache.get_sleep_response(date)))
+                .collect();
+            for handle in handles {
+                let error = handle.join().unwrap().unwrap_err();
+                assert!(matches!(
+                    error.inner(),
+                    FitbitError::ApiError {
+                        status_code: 500,
+                        ..
+                    }
+                ));
+                assert!(error.is_retryable());
+            }
+        });
+    }
+
+    #[test]
+    fn test_failed_request_keeps_its_kind_when_shared() {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        mock_client
+            .expect_fetch_sleep_data()
+            .with(eq(date))
+            .times(1)
+            .returning(|_| {
+                thread::sleep(std::time::Duration::from_millis(50));
+                Err(FitbitError::RequestError(ureq::Error::StatusCode(404)))
+            });
+
+        let cache = ConcurrentFitbitResponseCache::new(mock_client);
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| cache.get_sleep_response(date)))
+                .collect();
+            for handle in handles {
+                let error = handle.join().unwrap().unwrap_err();
+                assert!(matches!(
+                    error.inner(),
+                    FitbitError::RequestError(ureq::Error::StatusCode(404))
+                ));
+                assert!(!error.is_retryable());
+            }
+        });
+    }
+
+    #[test]
+    fn test_remove_from_cache() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        mock_client
+            .expect_fetch_sleep_data()
+            .with(eq(date))
+            .times(2)
+            .returning(|_| Ok(SleepResponseV1_2::default()));
+
+        let cache = ConcurrentFitbitResponseCache::new(mock_client);
+
+        let _response1 = cache.get_sleep_response(date)?;
+        cache.remove_from_cache(date);
+        let _response2 = cache.get_sleep_response(date)?;
+
+        Ok(())
+    }
+}
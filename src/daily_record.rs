@@ -0,0 +1,87 @@
+//! Everything recorded on one day.
+//!
+//! Dashboards usually show a day's sleep, activity and heart rate side by side.
+//! [`DailyRecord`] bundles them, leaving out the parts that the user has no data for or that
+//! the access token may not read.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::breathing_rate::BreathingRate;
+use crate::error::{ApiErrorType, FitbitError};
+use crate::heart_rate::HeartRateDay;
+use crate::sleep::SleepResponseV1_2;
+use chrono::NaiveDate;
+
+/// The sleep, activity and heart rate data of one day
+///
+/// A part is `None` if Fitbit has no data for the day, or if the access token lacks the
+/// scope to read it. HRV and SpO2 are not included, since this crate doesn't support their
+/// endpoints yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyRecord {
+    pub date: NaiveDate,
+    /// The sleep logs of the day; `None` if no sleep was logged
+    pub sleep: Option<SleepResponseV1_2>,
+    pub activity: Option<ActivitySummaryResponse>,
+    pub heart_rate: Option<HeartRateDay>,
+    /// The breathing rate of the main sleep that ended on the day
+    pub breathing_rate: Option<BreathingRate>,
+}
+
+impl DailyRecord {
+    /// Checks if no data is available for the day
+    ///
+    /// # Returns
+    ///
+    /// `true` if every part of the record is missing, `false` otherwise
+    pub fn is_empty(&self) -> bool {
+        self.sleep.is_none()
+            && self.activity.is_none()
+            && self.heart_rate.is_none()
+            && self.breathing_rate.is_none()
+    }
+}
+
+/// Converts the errors that mean a part of a daily record is unavailable into `None`
+///
+/// # Returns
+///
+/// The value, `None` if the data doesn't exist or the access token lacks the scope to read
+/// it, or any other error
+pub(crate) fn unless_unavailable<T>(
+    result: Result<T, FitbitError>,
+) -> Result<Option<T>, FitbitError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(error)
+            if error.is_not_found()
+                || error.api_error_type() == Some(&ApiErrorType::InsufficientPermissions) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unless_unavailable() {
+        let insufficient_scope = FitbitError::AuthenticationError {
+            message: "Missing heartrate scope".to_string(),
+            error_type: Some(ApiErrorType::InsufficientPermissions),
+        };
+        let not_found = FitbitError::NotFound {
+            message: "No data".to_string(),
+        };
+
+        assert_eq!(unless_unavailable(Ok(1)).unwrap(), Some(1));
+        assert_eq!(
+            unless_unavailable::<u32>(Err(insufficient_scope)).unwrap(),
+            None
+        );
+        assert_eq!(unless_unavailable::<u32>(Err(not_found)).unwrap(), None);
+        assert!(unless_unavailable::<u32>(Err(FitbitError::api_error(500, "Oops"))).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Devices paired with an account.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A tracker or scale paired with the account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Device {
+    /// The id of the device, e.g. to manage the alarms of a tracker
+    pub id: String,
+    /// The product name, e.g. `Charge 6`
+    pub device_version: String,
+    #[serde(rename = "type")]
+    pub device_type: DeviceType,
+    pub battery: BatteryState,
+    /// Battery level in percent; not reported by all devices
+    #[serde(default)]
+    pub battery_level: Option<u8>,
+    /// When the device last synced, in the user's local time
+    pub last_sync_time: NaiveDateTime,
+    #[serde(default)]
+    pub mac: Option<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+/// The kind of a device
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum DeviceType {
+    Tracker,
+    Scale,
+    /// A kind this crate doesn't know yet
+    Other(String),
+}
+
+impl DeviceType {
+    /// Returns the type value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            DeviceType::Tracker => "TRACKER",
+            DeviceType::Scale => "SCALE",
+            DeviceType::Other(value) => value,
+        }
+    }
+}
+
+impl From<DeviceType> for String {
+    fn from(value: DeviceType) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for DeviceType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "TRACKER" => DeviceType::Tracker,
+            "SCALE" => DeviceType::Scale,
+            _ => DeviceType::Other(value),
+        }
+    }
+}
+
+/// The battery state of a device, as shown in the Fitbit app
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum BatteryState {
+    High,
+    Medium,
+    Low,
+    Empty,
+    /// A state this crate doesn't know yet
+    Other(String),
+}
+
+impl BatteryState {
+    /// Returns the battery value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            BatteryState::High => "High",
+            BatteryState::Medium => "Medium",
+            BatteryState::Low => "Low",
+            BatteryState::Empty => "Empty",
+            BatteryState::Other(value) => value,
+        }
+    }
+}
+
+impl From<BatteryState> for String {
+    fn from(value: BatteryState) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for BatteryState {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "High" => BatteryState::High,
+            "Medium" => BatteryState::Medium,
+            "Low" => BatteryState::Low,
+            "Empty" => BatteryState::Empty,
+            _ => BatteryState::Other(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devices() {
+        let json = r#"[
+            {
+                "battery": "High",
+                "batteryLevel": 85,
+                "deviceVersion": "Charge 6",
+                "features": [],
+                "id": "2570612980",
+                "lastSyncTime": "2025-03-01T07:15:22.000",
+                "mac": "A1B2C3D4E5F6",
+                "type": "TRACKER"
+            },
+            {
+                "battery": "Full",
+                "deviceVersion": "Aria Air",
+                "id": "1234",
+                "lastSyncTime": "2025-02-27T06:40:00.000",
+                "type": "SCALE"
+            }
+        ]"#;
+
+        let devices: Vec<Device> = serde_json::from_str(json).expect("Failed to parse devices");
+
+        assert_eq!(devices[0].device_type, DeviceType::Tracker);
+        assert_eq!(devices[0].battery, BatteryState::High);
+        assert_eq!(devices[0].battery_level, Some(85));
+        assert_eq!(devices[1].device_type, DeviceType::Scale);
+        assert_eq!(devices[1].battery, BatteryState::Other("Full".to_string()));
+        assert_eq!(devices[1].battery_level, None);
+    }
+
+    #[test]
+    fn test_serialize_unknown_type_and_battery() {
+        let json = serde_json::json!({
+            "id": "2570612980",
+            "deviceVersion": "Pixel Watch",
+            "type": "WATCH",
+            "battery": "Full",
+            "batteryLevel": 100,
+            "lastSyncTime": "2025-03-01T07:15:22",
+            "mac": "A1B2C3D4E5F6",
+            "features": []
+        });
+
+        let device: Device = serde_json::from_value(json.clone()).expect("Failed to parse JSON");
+
+        assert_eq!(device.device_type, DeviceType::Other("WATCH".to_string()));
+        assert_eq!(
+            serde_json::to_value(&device).expect("Failed to serialize"),
+            json
+        );
+    }
+}
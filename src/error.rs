@@ -3,6 +3,11 @@
 //! This module defines the various error types that can occur when interacting with
 //! the Fitbit API.
 
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Errors that can occur when interacting with the Fitbit API
@@ -13,16 +18,42 @@ pub enum FitbitError {
     RequestError(#[from] ureq::Error),
 
     /// Error parsing JSON response
-    #[error("JSON parsing failed: {0}")]
-    JsonError(String),
+    #[error(
+        "JSON parsing failed{}: {message}",
+        .path.as_ref().map(|path| format!(" at `{}`", path)).unwrap_or_default()
+    )]
+    JsonError {
+        /// What went wrong, e.g. `invalid type: string "12", expected u32`
+        message: String,
+        /// Path of the offending value in the JSON document, e.g. `sleep[0].levels.data[3].level`
+        path: Option<String>,
+        /// The start of the response body, for debugging new or changed API fields
+        body: Option<String>,
+    },
+
+    /// A response violates the API contract, e.g. a sleep efficiency above 100
+    #[error(
+        "Response failed validation: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    ContractViolation(Vec<crate::validation::ValidationIssue>),
+
+    /// Error parsing a TCX document
+    #[error("TCX parsing failed: {0}")]
+    TcxError(String),
 
     /// API rate limit exceeded
     #[error("Rate limit exceeded - retry after {0} seconds")]
     RateLimitExceeded(u64),
 
-    /// Authentication error
-    #[error("Authentication failed: {0}")]
-    AuthenticationError(String),
+    /// Authentication error, e.g. the API rejected the access token (401) or its scopes (403)
+    #[error("Authentication failed: {message}")]
+    AuthenticationError {
+        /// Error message
+        message: String,
+        /// Category of the error, if the API reported one
+        error_type: Option<ApiErrorType>,
+    },
 
     /// API responded with an error
     #[error("API error: {status_code} - {message}")]
@@ -31,15 +62,265 @@ pub enum FitbitError {
         status_code: u16,
         /// Error message from the API
         message: String,
+        /// Category of the error, if the API reported one
+        error_type: Option<ApiErrorType>,
+    },
+
+    /// The requested resource does not exist (404), e.g. data from before the account was
+    /// created or a log that was deleted
+    #[error("Not found: {message}")]
+    NotFound {
+        /// Error message
+        message: String,
+    },
+
+    /// The write conflicts with existing data (409), e.g. a duplicate log entry
+    #[error("Conflict: {message}")]
+    Conflict {
+        /// Error message
+        message: String,
+    },
+
+    /// The API rejected a request parameter (400)
+    #[error("Validation failed: {message}")]
+    ValidationError {
+        /// Error message
+        message: String,
+        /// Name of the rejected parameter, if the API reported it
+        field_name: Option<String>,
     },
 
     /// Client configuration error
     #[error("Client configuration error: {0}")]
     ConfigurationError(String),
 
+    /// A request argument was rejected before being sent to the API
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    /// Error reading or writing the local archive
+    #[cfg(feature = "sqlite")]
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+
+    /// Error queueing a message for the MQTT broker
+    #[cfg(feature = "mqtt")]
+    #[error("MQTT error: {0}")]
+    MqttError(#[from] rumqttc::ClientError),
+
     /// Error retrieving or using access token
     #[error("Access token error: {0}")]
     AccessTokenError(#[from] crate::access_token::AccessTokenError),
+
+    /// An error of an API request, with the request it occurred in
+    #[error("{context}: {source}")]
+    WithContext {
+        context: RequestContext,
+        #[source]
+        source: Box<FitbitError>,
+    },
+
+    /// An error reported to several callers, e.g. the failed fetch of a date that several
+    /// threads requested from the concurrent cache at once
+    #[cfg(feature = "concurrent-cache")]
+    #[error(transparent)]
+    Shared(std::sync::Arc<FitbitError>),
+}
+
+/// The API request an error occurred in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// HTTP method, e.g. `GET`
+    pub method: &'static str,
+    /// URL path without the query, e.g. `/1.2/user/-/sleep/date/2025-03-01.json`
+    pub path: String,
+    /// The dates in the path, e.g. the start and end of a range, in order
+    pub dates: Vec<NaiveDate>,
+}
+
+impl RequestContext {
+    /// Creates the context of a request
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method
+    /// * `url` - The full request URL
+    pub fn new(method: &'static str, url: &str) -> Self {
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let path = without_scheme
+            .find('/')
+            .map_or("/", |start| &without_scheme[start..]);
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let dates = path
+            .split('/')
+            .filter_map(|segment| NaiveDate::parse_from_str(Self::stem(segment), "%Y-%m-%d").ok())
+            .collect();
+
+        Self {
+            method,
+            path: path.to_string(),
+            dates,
+        }
+    }
+
+    /// Returns the kind of endpoint, i.e. the path with dates replaced by `{date}` and
+    /// numeric ids by `{id}`, e.g. `/1.2/user/-/sleep/date/{date}.json`
+    ///
+    /// The leading API version segment, e.g. `1` or `1.2`, is kept as it is.
+    ///
+    /// Requests of the same endpoint have the same kind, so it can be used to group errors.
+    pub fn endpoint(&self) -> String {
+        self.path
+            .split('/')
+            .enumerate()
+            .map(|(index, segment)| {
+                // The path starts with a slash, so the version is the second segment
+                if index <= 1 {
+                    return segment.to_string();
+                }
+                let stem = Self::stem(segment);
+                let extension = &segment[stem.len()..];
+                if NaiveDate::parse_from_str(stem, "%Y-%m-%d").is_ok() {
+                    format!("{{date}}{}", extension)
+                } else if !stem.is_empty() && stem.bytes().all(|byte| byte.is_ascii_digit()) {
+                    format!("{{id}}{}", extension)
+                } else {
+                    segment.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Returns a path segment without its file extension, e.g. `2025-03-01` for
+    /// `2025-03-01.json`
+    fn stem(segment: &str) -> &str {
+        segment
+            .rsplit_once('.')
+            .filter(|(_, extension)| extension.bytes().all(|byte| byte.is_ascii_alphabetic()))
+            .map_or(segment, |(stem, _)| stem)
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)
+    }
+}
+
+/// Category of an error reported by the API in the `errorType` field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ApiErrorType {
+    /// The access token expired and must be refreshed
+    ExpiredToken,
+    /// The access token is invalid or was revoked
+    InvalidToken,
+    /// The access token lacks the scope the endpoint requires
+    InsufficientPermissions,
+    /// A request parameter has an invalid value
+    Validation,
+    /// The request is malformed
+    Request,
+    /// The API failed to handle the request
+    System,
+    /// A category this crate doesn't know yet
+    Other(String),
+}
+
+impl ApiErrorType {
+    /// Returns the `errorType` value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            ApiErrorType::ExpiredToken => "expired_token",
+            ApiErrorType::InvalidToken => "invalid_token",
+            ApiErrorType::InsufficientPermissions => "insufficient_permissions",
+            ApiErrorType::Validation => "validation",
+            ApiErrorType::Request => "request",
+            ApiErrorType::System => "system",
+            ApiErrorType::Other(error_type) => error_type,
+        }
+    }
+}
+
+impl From<&str> for ApiErrorType {
+    fn from(value: &str) -> Self {
+        match value {
+            "expired_token" => ApiErrorType::ExpiredToken,
+            "invalid_token" => ApiErrorType::InvalidToken,
+            "insufficient_permissions" | "insufficient_scope" => {
+                ApiErrorType::InsufficientPermissions
+            }
+            "validation" => ApiErrorType::Validation,
+            "request" => ApiErrorType::Request,
+            "system" => ApiErrorType::System,
+            other => ApiErrorType::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Raw error response of the API
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    #[serde(default)]
+    errors: Vec<ApiErrorEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiErrorEntry {
+    error_type: Option<String>,
+    field_name: Option<String>,
+    #[serde(default)]
+    message: String,
+}
+
+/// Number of bytes of the response body kept in JSON errors
+const JSON_ERROR_BODY_LIMIT: usize = 512;
+
+/// Deserializes a JSON response body, reporting the path of the offending value on failure
+///
+/// # Arguments
+///
+/// * `body` - The response body
+///
+/// # Returns
+///
+/// The deserialized value, or a `FitbitError::JsonError` with the path and the start of
+/// the body
+pub(crate) fn parse_json<T: DeserializeOwned>(body: &[u8]) -> Result<T, FitbitError> {
+    let json_error = |message: String, path: Option<String>| {
+        let excerpt = String::from_utf8_lossy(&body[..body.len().min(JSON_ERROR_BODY_LIMIT)]);
+        FitbitError::JsonError {
+            message,
+            path,
+            body: Some(if body.len() > JSON_ERROR_BODY_LIMIT {
+                format!("{}...", excerpt)
+            } else {
+                excerpt.into_owned()
+            }),
+        }
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+        let path = error.path().to_string();
+        json_error(
+            error.into_inner().to_string(),
+            (path != ".").then_some(path),
+        )
+    })?;
+    deserializer
+        .end()
+        .map_err(|error| json_error(error.to_string(), None))?;
+
+    Ok(value)
 }
 
 /// Helper functions for working with Fitbit errors
@@ -58,6 +339,127 @@ impl FitbitError {
         FitbitError::ApiError {
             status_code,
             message: message.into(),
+            error_type: None,
+        }
+    }
+
+    /// Creates a JSON error for a response that could be parsed but misses expected data
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Error message
+    ///
+    /// # Returns
+    ///
+    /// A new `FitbitError::JsonError` without path and body
+    pub fn json_error(message: impl Into<String>) -> Self {
+        FitbitError::JsonError {
+            message: message.into(),
+            path: None,
+            body: None,
+        }
+    }
+
+    /// Creates an API error from the status code and body of an error response
+    ///
+    /// The message and error type are taken from the first error in the body; if the body
+    /// is not an API error response, the body itself is the message. Unauthorized (401) and
+    /// forbidden (403) responses become authentication errors, so callers can re-authorize;
+    /// not found (404), conflict (409) and validation (400) responses get their own variants.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_code` - HTTP status code
+    /// * `body` - The response body
+    ///
+    /// # Returns
+    ///
+    /// A new `FitbitError` matching the status code
+    pub(crate) fn from_response(status_code: u16, body: &str) -> Self {
+        let (message, error_type, field_name) = match serde_json::from_str::<ApiErrorResponse>(body)
+            .ok()
+            .and_then(|response| response.errors.into_iter().next())
+        {
+            Some(error) => (
+                error.message,
+                error.error_type.as_deref().map(ApiErrorType::from),
+                error.field_name,
+            ),
+            None => (body.trim().to_string(), None, None),
+        };
+
+        match status_code {
+            401 | 403 => FitbitError::AuthenticationError {
+                message,
+                error_type,
+            },
+            404 => FitbitError::NotFound { message },
+            409 => FitbitError::Conflict { message },
+            400 if error_type == Some(ApiErrorType::Validation) || field_name.is_some() => {
+                FitbitError::ValidationError {
+                    message,
+                    field_name,
+                }
+            }
+            _ => FitbitError::ApiError {
+                status_code,
+                message,
+                error_type,
+            },
+        }
+    }
+
+    /// Wraps the error with the context of the request it occurred in
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method of the request
+    /// * `url` - The full request URL
+    ///
+    /// # Returns
+    ///
+    /// A new `FitbitError::WithContext`, or the error itself if it already has a context
+    pub(crate) fn with_request_context(self, method: &'static str, url: &str) -> Self {
+        if self.context().is_some() {
+            return self;
+        }
+
+        FitbitError::WithContext {
+            context: RequestContext::new(method, url),
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the request the error occurred in, if known
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            FitbitError::WithContext { context, .. } => Some(context),
+            #[cfg(feature = "concurrent-cache")]
+            FitbitError::Shared(error) => error.context(),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its request context
+    ///
+    /// Match on this instead of on the error itself to handle errors of API requests, and
+    /// errors shared by several callers, by their cause.
+    pub fn inner(&self) -> &FitbitError {
+        match self {
+            FitbitError::WithContext { source, .. } => source.inner(),
+            #[cfg(feature = "concurrent-cache")]
+            FitbitError::Shared(error) => error.inner(),
+            error => error,
+        }
+    }
+
+    /// Returns the category of an API or authentication error, or `None` for other errors
+    /// and errors without a category
+    pub fn api_error_type(&self) -> Option<&ApiErrorType> {
+        match self.inner() {
+            FitbitError::ApiError { error_type, .. }
+            | FitbitError::AuthenticationError { error_type, .. } => error_type.as_ref(),
+            _ => None,
         }
     }
 
@@ -71,7 +473,10 @@ impl FitbitError {
     ///
     /// A new `FitbitError::AuthenticationError`
     pub fn authentication_error(message: impl Into<String>) -> Self {
-        FitbitError::AuthenticationError(message.into())
+        FitbitError::AuthenticationError {
+            message: message.into(),
+            error_type: None,
+        }
     }
 
     /// Checks if the error is a rate limit error
@@ -80,7 +485,43 @@ impl FitbitError {
     ///
     /// `true` if the error is a rate limit error, `false` otherwise
     pub fn is_rate_limit(&self) -> bool {
-        matches!(self, FitbitError::RateLimitExceeded(_))
+        matches!(self.inner(), FitbitError::RateLimitExceeded(_))
+    }
+
+    /// Checks if a request that failed with this error may succeed when retried
+    ///
+    /// Timeouts, connection failures, rate limiting and server errors (5xx) are retryable.
+    /// Client errors such as authentication and validation failures, and errors raised
+    /// before a request was sent, are not.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the request should be retried, `false` otherwise
+    pub fn is_retryable(&self) -> bool {
+        match self.inner() {
+            FitbitError::RateLimitExceeded(_) => true,
+            FitbitError::ApiError { status_code, .. } => *status_code == 429 || *status_code >= 500,
+            FitbitError::RequestError(error) => matches!(
+                error,
+                ureq::Error::Timeout(_)
+                    | ureq::Error::Io(_)
+                    | ureq::Error::ConnectionFailed
+                    | ureq::Error::StatusCode(429 | 500..)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Returns how long the API asked to wait before retrying, if it did
+    ///
+    /// # Returns
+    ///
+    /// The time until the rate limit resets, or `None` for other errors
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.inner() {
+            FitbitError::RateLimitExceeded(seconds) => Some(Duration::from_secs(*seconds)),
+            _ => None,
+        }
     }
 
     /// Checks if the error is an authentication error
@@ -89,7 +530,45 @@ impl FitbitError {
     ///
     /// `true` if the error is an authentication error, `false` otherwise
     pub fn is_authentication_error(&self) -> bool {
-        matches!(self, FitbitError::AuthenticationError(_))
+        matches!(self.inner(), FitbitError::AuthenticationError { .. })
+    }
+
+    /// Checks if the error was caused by an expired access token
+    ///
+    /// An expired token can be replaced by refreshing it, without involving the user.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the API rejected the access token as expired, `false` otherwise
+    pub fn is_token_expired(&self) -> bool {
+        matches!(
+            self.inner(),
+            FitbitError::AuthenticationError {
+                error_type: Some(ApiErrorType::ExpiredToken),
+                ..
+            }
+        )
+    }
+
+    /// Checks if the user has to authorize the app again
+    ///
+    /// This is the case for authentication errors other than an expired token, e.g. when
+    /// the token was revoked, is malformed or lacks a required scope.
+    ///
+    /// # Returns
+    ///
+    /// `true` if refreshing the access token will not help, `false` otherwise
+    pub fn requires_reauthorization(&self) -> bool {
+        self.is_authentication_error() && !self.is_token_expired()
+    }
+
+    /// Checks if the error is a not found error
+    ///
+    /// # Returns
+    ///
+    /// `true` if the requested resource does not exist, `false` otherwise
+    pub fn is_not_found(&self) -> bool {
+        matches!(self.inner(), FitbitError::NotFound { .. })
     }
 
     /// Checks if the error is a client configuration error
@@ -98,6 +577,223 @@ impl FitbitError {
     ///
     /// `true` if the error is a client configuration error, `false` otherwise
     pub fn is_configuration_error(&self) -> bool {
-        matches!(self, FitbitError::ConfigurationError(_))
+        matches!(self.inner(), FitbitError::ConfigurationError(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_error_from_response() {
+        let error = FitbitError::from_response(
+            401,
+            r#"{"errors": [{"errorType": "expired_token",
+                "message": "Access token expired: eyJhbGciOiJIUzI1NiJ9"}], "success": false}"#,
+        );
+
+        assert!(matches!(
+            &error,
+            FitbitError::AuthenticationError { message, .. }
+                if message == "Access token expired: eyJhbGciOiJIUzI1NiJ9"
+        ));
+        assert!(error.is_authentication_error());
+        assert!(error.is_token_expired());
+        assert!(!error.requires_reauthorization());
+        assert_eq!(error.api_error_type(), Some(&ApiErrorType::ExpiredToken));
+
+        let error = FitbitError::from_response(
+            401,
+            r#"{"errors": [{"errorType": "invalid_token", "message": "Access token invalid"}]}"#,
+        );
+        assert!(!error.is_token_expired());
+        assert!(error.requires_reauthorization());
+
+        let error = FitbitError::from_response(
+            403,
+            r#"{"errors": [{"errorType": "insufficient_permissions", "message": "No scope"}]}"#,
+        );
+        assert!(error.is_authentication_error());
+        assert_eq!(
+            error.api_error_type(),
+            Some(&ApiErrorType::InsufficientPermissions)
+        );
+
+        assert!(error.requires_reauthorization());
+        assert!(!error.is_token_expired());
+
+        let error = FitbitError::from_response(401, "Unauthorized");
+        assert!(matches!(
+            &error,
+            FitbitError::AuthenticationError { message, error_type: None } if message == "Unauthorized"
+        ));
+
+        let error = FitbitError::from_response(
+            400,
+            r#"{"errors": [{"errorType": "new_category", "message": "Unknown"}]}"#,
+        );
+        assert_eq!(
+            error.api_error_type(),
+            Some(&ApiErrorType::Other("new_category".to_string()))
+        );
+        assert_eq!(error.api_error_type().unwrap().as_str(), "new_category");
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(FitbitError::RateLimitExceeded(30).is_retryable());
+        assert!(FitbitError::api_error(503, "Service Unavailable").is_retryable());
+        assert!(FitbitError::RequestError(ureq::Error::ConnectionFailed).is_retryable());
+        assert!(FitbitError::RequestError(ureq::Error::StatusCode(502)).is_retryable());
+
+        assert!(!FitbitError::api_error(400, "Invalid date").is_retryable());
+        assert!(!FitbitError::authentication_error("Expired").is_retryable());
+        assert!(!FitbitError::InvalidArgument("bad range".to_string()).is_retryable());
+        assert!(!FitbitError::RequestError(ureq::Error::StatusCode(404)).is_retryable());
+
+        assert_eq!(
+            FitbitError::RateLimitExceeded(30).retry_after(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(FitbitError::api_error(503, "").retry_after(), None);
+    }
+
+    #[test]
+    fn test_request_context() {
+        let context = RequestContext::new(
+            "GET",
+            "https://api.fitbit.com/1.2/user/-/sleep/date/2025-03-01/2025-03-05.json?x=1",
+        );
+
+        assert_eq!(
+            context.path,
+            "/1.2/user/-/sleep/date/2025-03-01/2025-03-05.json"
+        );
+        assert_eq!(
+            context.dates,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 3, 5).unwrap(),
+            ]
+        );
+        assert_eq!(
+            context.endpoint(),
+            "/1.2/user/-/sleep/date/{date}/{date}.json"
+        );
+        assert_eq!(
+            context.to_string(),
+            "GET /1.2/user/-/sleep/date/2025-03-01/2025-03-05.json"
+        );
+
+        let context =
+            RequestContext::new("GET", "https://api.fitbit.com/1/user/-/activities/123.tcx");
+        assert_eq!(context.endpoint(), "/1/user/-/activities/{id}.tcx");
+        assert!(context.dates.is_empty());
+    }
+
+    #[test]
+    fn test_error_with_context() {
+        let error = FitbitError::RateLimitExceeded(30)
+            .with_request_context("GET", "https://api.fitbit.com/1/user/-/profile.json")
+            .with_request_context("GET", "https://api.fitbit.com/other.json");
+
+        assert_eq!(error.context().unwrap().path, "/1/user/-/profile.json");
+        assert!(matches!(error.inner(), FitbitError::RateLimitExceeded(30)));
+        assert!(error.is_rate_limit());
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(30)));
+        assert!(FitbitError::RateLimitExceeded(30).context().is_none());
+    }
+
+    #[test]
+    fn test_error_from_status() {
+        let error = FitbitError::from_response(
+            400,
+            r#"{"errors": [{"errorType": "validation", "fieldName": "date",
+                "message": "Invalid date:2025-13-01"}], "success": false}"#,
+        );
+        assert!(matches!(
+            &error,
+            FitbitError::ValidationError { message, field_name: Some(field_name) }
+                if message == "Invalid date:2025-13-01" && field_name == "date"
+        ));
+
+        let error = FitbitError::from_response(
+            400,
+            r#"{"errors": [{"errorType": "request", "message": "Bad request"}]}"#,
+        );
+        assert!(matches!(
+            error,
+            FitbitError::ApiError {
+                status_code: 400,
+                ..
+            }
+        ));
+
+        let error = FitbitError::from_response(
+            404,
+            r#"{"errors": [{"errorType": "not_found", "message": "Log not found"}]}"#,
+        );
+        assert!(error.is_not_found());
+        assert!(!error.is_retryable());
+
+        let error = FitbitError::from_response(409, "Duplicate log");
+        assert!(matches!(&error, FitbitError::Conflict { message } if message == "Duplicate log"));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Day {
+        #[allow(dead_code)]
+        steps: u32,
+    }
+
+    #[test]
+    fn test_parse_json_reports_path_and_body() {
+        let body = br#"{"steps": 1200}"#;
+        assert_eq!(parse_json::<Day>(body).unwrap().steps, 1200);
+
+        let body = br#"{"days": [{"steps": 1200}, {"steps": "many"}]}"#;
+        let error = parse_json::<HashMap<String, Vec<Day>>>(body).unwrap_err();
+        let FitbitError::JsonError {
+            message,
+            path,
+            body: excerpt,
+        } = &error
+        else {
+            panic!("expected a JSON error, got {:?}", error);
+        };
+        assert!(message.starts_with("invalid type: string \"many\""));
+        assert_eq!(path.as_deref(), Some("days[1].steps"));
+        assert_eq!(
+            excerpt.as_deref(),
+            Some(r#"{"days": [{"steps": 1200}, {"steps": "many"}]}"#)
+        );
+        assert!(
+            error
+                .to_string()
+                .starts_with("JSON parsing failed at `days[1].steps`: ")
+        );
+
+        let body = format!(r#"{{"steps": {}}}"#, "9".repeat(1000));
+        let FitbitError::JsonError { body: excerpt, .. } =
+            parse_json::<Day>(body.as_bytes()).unwrap_err()
+        else {
+            panic!("expected a JSON error");
+        };
+        assert_eq!(excerpt.unwrap().len(), JSON_ERROR_BODY_LIMIT + 3);
+    }
+
+    #[test]
+    fn test_error_from_non_json_response() {
+        let error = FitbitError::from_response(502, "Bad Gateway\n");
+
+        assert!(matches!(
+            &error,
+            FitbitError::ApiError { status_code: 502, message, error_type: None }
+                if message == "Bad Gateway"
+        ));
+        assert_eq!(FitbitError::api_error(500, "Oops").api_error_type(), None);
     }
 }
@@ -0,0 +1,28 @@
+//! Helpers shared by the exports and renderings of this crate's models.
+
+use chrono::Duration;
+
+/// Returns the length of an intraday interval, e.g. 15 `minute`s
+pub(crate) fn interval_length(interval: u32, unit: &str) -> Duration {
+    match unit {
+        "second" => Duration::seconds(interval as i64),
+        "hour" => Duration::hours(interval as i64),
+        _ => Duration::minutes(interval as i64),
+    }
+}
+
+/// Escapes text for use in an XML attribute or text
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
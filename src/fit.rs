@@ -0,0 +1,720 @@
+//! Export of exercises as FIT activity files.
+//!
+//! FIT is the binary format of Garmin devices, and the format training platforms such as
+//! Strava, TrainingPeaks or intervals.icu import most reliably. [`FitExport`] writes an
+//! activity file with a single session and lap, and a record per GPS track point or heart
+//! rate sample.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fitbit_rs::fit::FitExport;
+//! use fitbit_rs::{DateFilter, FitbitClient, FitbitClientTrait, SortOrder, TcxTrack};
+//!
+//! let client = FitbitClient::new("your_access_token".to_string());
+//! let today = chrono::Local::now().date_naive();
+//!
+//! let logs = client.fetch_activity_logs(DateFilter::Before(today), SortOrder::Descending, 10)?;
+//! for activity in &logs.activities {
+//!     let fit = if activity.has_gps {
+//!         let track = TcxTrack::parse(&client.fetch_activity_tcx(activity.log_id)?)?;
+//!         FitExport::new(activity).with_track(&track).to_bytes()
+//!     } else {
+//!         FitExport::new(activity).to_bytes()
+//!     };
+//!     std::fs::write(format!("{}.fit", activity.log_id), fit)?;
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::activity_log::ActivityLog;
+use crate::heart_rate::IntradayHeartRate;
+use crate::tcx::TcxTrack;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Timelike};
+use std::collections::HashMap;
+
+/// Protocol version 1.0, which every FIT reader supports
+const PROTOCOL_VERSION: u8 = 0x10;
+/// Profile version 21.32
+const PROFILE_VERSION: u16 = 2132;
+/// Seconds from the Unix epoch to the FIT epoch, 1989-12-31T00:00:00Z
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+/// `manufacturer` value for software that isn't a registered manufacturer
+const MANUFACTURER_DEVELOPMENT: u16 = 255;
+
+/// Global message numbers
+const MESG_FILE_ID: u16 = 0;
+const MESG_SESSION: u16 = 18;
+const MESG_LAP: u16 = 19;
+const MESG_RECORD: u16 = 20;
+const MESG_EVENT: u16 = 21;
+const MESG_ACTIVITY: u16 = 34;
+
+/// Field number of the timestamp, shared by all messages that have one
+const FIELD_TIMESTAMP: u8 = 253;
+
+/// Heart rate samples further from a track point than this are not assigned to it
+const MAX_HEART_RATE_LOOKUP_SECONDS: u32 = 60;
+
+/// The sport of a FIT session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FitSport {
+    Generic,
+    Running,
+    Cycling,
+    /// Elliptical, stair climber and other machines
+    FitnessEquipment,
+    Swimming,
+    /// Strength training, yoga and other workouts without distance
+    Training,
+    Walking,
+    Hiking,
+}
+
+impl FitSport {
+    /// Guesses the sport from the name of a Fitbit activity, e.g. `Outdoor Bike`
+    ///
+    /// # Returns
+    ///
+    /// The sport, or `FitSport::Generic` for names that don't match a known sport
+    pub fn from_activity_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let matches = |keywords: &[&str]| keywords.iter().any(|keyword| name.contains(keyword));
+
+        if matches(&["run", "treadmill"]) {
+            FitSport::Running
+        } else if matches(&["bike", "cycl", "spinning"]) {
+            FitSport::Cycling
+        } else if matches(&["swim"]) {
+            FitSport::Swimming
+        } else if matches(&["hike", "hiking"]) {
+            FitSport::Hiking
+        } else if matches(&["walk"]) {
+            FitSport::Walking
+        } else if matches(&["elliptical", "stair", "rowing"]) {
+            FitSport::FitnessEquipment
+        } else if matches(&[
+            "weights", "workout", "yoga", "pilates", "strength", "aerobic",
+        ]) {
+            FitSport::Training
+        } else {
+            FitSport::Generic
+        }
+    }
+
+    /// Returns the value of the `sport` enum of the FIT profile
+    pub fn value(&self) -> u8 {
+        match self {
+            FitSport::Generic => 0,
+            FitSport::Running => 1,
+            FitSport::Cycling => 2,
+            FitSport::FitnessEquipment => 4,
+            FitSport::Swimming => 5,
+            FitSport::Training => 10,
+            FitSport::Walking => 11,
+            FitSport::Hiking => 17,
+        }
+    }
+}
+
+/// Builds a FIT activity file from a logged exercise
+///
+/// Without a track or heart rate, the file only has the totals of the exercise, which most
+/// platforms accept as a manual activity.
+#[derive(Debug, Clone)]
+pub struct FitExport<'a> {
+    activity: &'a ActivityLog,
+    sport: FitSport,
+    track: Option<&'a TcxTrack>,
+    heart_rate: Option<&'a IntradayHeartRate>,
+}
+
+/// A sample of the exercise, written as a `record` message
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Sample {
+    /// Seconds since the FIT epoch
+    timestamp: u32,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    altitude_meters: Option<f64>,
+    distance_meters: Option<f64>,
+    heart_rate_bpm: Option<u32>,
+}
+
+impl<'a> FitExport<'a> {
+    /// Creates an export of an exercise, with the sport guessed from its name
+    pub fn new(activity: &'a ActivityLog) -> Self {
+        Self {
+            activity,
+            sport: FitSport::from_activity_name(&activity.activity_name),
+            track: None,
+            heart_rate: None,
+        }
+    }
+
+    /// Sets the sport, for activities whose name doesn't tell it
+    pub fn with_sport(mut self, sport: FitSport) -> Self {
+        self.sport = sport;
+        self
+    }
+
+    /// Adds the GPS track of the exercise, e.g. parsed from
+    /// [`fetch_activity_tcx`](crate::FitbitClientTrait::fetch_activity_tcx)
+    pub fn with_track(mut self, track: &'a TcxTrack) -> Self {
+        self.track = Some(track);
+        self
+    }
+
+    /// Adds the intraday heart rate of the day of the exercise
+    ///
+    /// With a track, the heart rate fills in track points without one. Without a track,
+    /// every sample during the exercise becomes a record. Samples of other days than the
+    /// one the exercise started on are not used.
+    pub fn with_heart_rate(mut self, heart_rate: &'a IntradayHeartRate) -> Self {
+        self.heart_rate = Some(heart_rate);
+        self
+    }
+
+    /// Returns the sport the session is written with
+    pub fn sport(&self) -> FitSport {
+        self.sport
+    }
+
+    /// Encodes the FIT activity file
+    ///
+    /// # Returns
+    ///
+    /// The bytes of the file, including the header and the trailing CRC
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let activity = self.activity;
+        let start = fit_timestamp(activity.start_time);
+        let end = fit_timestamp(activity.start_time + activity.get_duration());
+        let samples = self.samples();
+
+        let heart_rates: Vec<u32> = samples
+            .iter()
+            .filter_map(|sample| sample.heart_rate_bpm)
+            .collect();
+        let average_heart_rate = activity.average_heart_rate.or_else(|| {
+            (!heart_rates.is_empty())
+                .then(|| heart_rates.iter().sum::<u32>() / heart_rates.len() as u32)
+        });
+        let max_heart_rate = heart_rates.iter().copied().max();
+        let distance_meters = activity
+            .distance_value()
+            .map(|distance| distance.kilometers() * 1000.0)
+            .or_else(|| {
+                samples
+                    .iter()
+                    .rev()
+                    .find_map(|sample| sample.distance_meters)
+            });
+
+        let elapsed = Value::UInt32(clamp_u32(activity.duration));
+        let timer = Value::UInt32(clamp_u32(activity.active_duration));
+        let distance = Value::UInt32(scaled(distance_meters, 100.0));
+        let calories = Value::UInt16(activity.calories.min(u16::MAX as u32 - 1) as u16);
+        let average_heart_rate = Value::UInt8(heart_rate(average_heart_rate));
+        let max_heart_rate = Value::UInt8(heart_rate(max_heart_rate));
+        let sport = Value::Enum(self.sport.value());
+
+        let mut writer = FitWriter::default();
+        writer.message(
+            MESG_FILE_ID,
+            &[
+                (0, Value::Enum(4)), // type: activity
+                (1, Value::UInt16(MANUFACTURER_DEVELOPMENT)),
+                (2, Value::UInt16(0)),                       // product
+                (3, Value::UInt32z(activity.log_id as u32)), // serial_number
+                (4, Value::UInt32(start)),                   // time_created
+            ],
+        );
+        writer.message(
+            MESG_EVENT,
+            &[
+                (FIELD_TIMESTAMP, Value::UInt32(start)),
+                (0, Value::Enum(0)), // event: timer
+                (1, Value::Enum(0)), // event_type: start
+            ],
+        );
+        for sample in &samples {
+            writer.message(
+                MESG_RECORD,
+                &[
+                    (FIELD_TIMESTAMP, Value::UInt32(sample.timestamp)),
+                    (0, Value::SInt32(semicircles(sample.latitude))),
+                    (1, Value::SInt32(semicircles(sample.longitude))),
+                    (2, Value::UInt16(altitude(sample.altitude_meters))),
+                    (3, Value::UInt8(heart_rate(sample.heart_rate_bpm))),
+                    (5, Value::UInt32(scaled(sample.distance_meters, 100.0))),
+                ],
+            );
+        }
+        writer.message(
+            MESG_EVENT,
+            &[
+                (FIELD_TIMESTAMP, Value::UInt32(end)),
+                (0, Value::Enum(0)), // event: timer
+                (1, Value::Enum(4)), // event_type: stop_all
+            ],
+        );
+        writer.message(
+            MESG_LAP,
+            &[
+                (FIELD_TIMESTAMP, Value::UInt32(end)),
+                (0, Value::Enum(9)), // event: lap
+                (1, Value::Enum(1)), // event_type: stop
+                (2, Value::UInt32(start)),
+                (7, elapsed),
+                (8, timer),
+                (9, distance),
+                (11, calories),
+                (15, average_heart_rate),
+                (16, max_heart_rate),
+                (25, sport),
+            ],
+        );
+        writer.message(
+            MESG_SESSION,
+            &[
+                (FIELD_TIMESTAMP, Value::UInt32(end)),
+                (0, Value::Enum(8)), // event: session
+                (1, Value::Enum(1)), // event_type: stop
+                (2, Value::UInt32(start)),
+                (5, sport),
+                (6, Value::Enum(0)), // sub_sport: generic
+                (7, elapsed),
+                (8, timer),
+                (9, distance),
+                (11, calories),
+                (16, average_heart_rate),
+                (17, max_heart_rate),
+                (25, Value::UInt16(0)), // first_lap_index
+                (26, Value::UInt16(1)), // num_laps
+            ],
+        );
+        let utc_offset = activity.start_time.offset().local_minus_utc() as i64;
+        writer.message(
+            MESG_ACTIVITY,
+            &[
+                (FIELD_TIMESTAMP, Value::UInt32(end)),
+                (0, timer),
+                (1, Value::UInt16(1)), // num_sessions
+                (2, Value::Enum(0)),   // type: manual
+                (3, Value::Enum(26)),  // event: activity
+                (4, Value::Enum(1)),   // event_type: stop
+                (5, Value::UInt32((end as i64 + utc_offset).max(0) as u32)),
+            ],
+        );
+
+        writer.finish()
+    }
+
+    /// Returns the samples of the exercise: the track points if there is a track, the
+    /// heart rate samples during the exercise otherwise
+    fn samples(&self) -> Vec<Sample> {
+        if let Some(track) = self.track.filter(|track| !track.points.is_empty()) {
+            return track
+                .points
+                .iter()
+                .map(|point| Sample {
+                    timestamp: fit_timestamp(point.time),
+                    latitude: point.latitude,
+                    longitude: point.longitude,
+                    altitude_meters: point.altitude_meters,
+                    distance_meters: point.distance_meters,
+                    heart_rate_bpm: point
+                        .heart_rate_bpm
+                        .or_else(|| self.heart_rate_at(point.time)),
+                })
+                .collect();
+        }
+
+        let Some(heart_rate) = self.heart_rate else {
+            return Vec::new();
+        };
+        let start = self.activity.start_time;
+        let offset = *start.offset();
+        let (start, end) = (
+            start.naive_local(),
+            start.naive_local() + self.activity.get_duration(),
+        );
+        heart_rate
+            .samples
+            .at_date(heart_rate.date)
+            .filter(|(time, _)| start <= *time && *time < end)
+            .map(|(time, bpm)| Sample {
+                timestamp: fit_timestamp_local(time, offset),
+                heart_rate_bpm: Some(bpm as u32),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Returns the heart rate sample nearest before a time, if it's within a minute
+    fn heart_rate_at(&self, time: DateTime<FixedOffset>) -> Option<u32> {
+        let heart_rate = self.heart_rate?;
+        let local = time.with_timezone(self.activity.start_time.offset());
+        if local.date_naive() != heart_rate.date {
+            return None;
+        }
+
+        let seconds = local.num_seconds_from_midnight();
+        let offsets = heart_rate.samples.offsets();
+        let index = offsets
+            .partition_point(|offset| *offset <= seconds)
+            .checked_sub(1)?;
+        (seconds - offsets[index] <= MAX_HEART_RATE_LOOKUP_SECONDS)
+            .then(|| heart_rate.samples.values()[index] as u32)
+    }
+}
+
+/// A field value, with the base type it is encoded as
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Enum(u8),
+    UInt8(u8),
+    UInt16(u16),
+    UInt32(u32),
+    SInt32(i32),
+    UInt32z(u32),
+}
+
+impl Value {
+    /// Returns the base type number and the size in bytes
+    fn base_type(&self) -> (u8, u8) {
+        match self {
+            Value::Enum(_) => (0x00, 1),
+            Value::UInt8(_) => (0x02, 1),
+            Value::UInt16(_) => (0x84, 2),
+            Value::UInt32(_) => (0x86, 4),
+            Value::SInt32(_) => (0x85, 4),
+            Value::UInt32z(_) => (0x8C, 4),
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match *self {
+            Value::Enum(value) | Value::UInt8(value) => out.push(value),
+            Value::UInt16(value) => out.extend_from_slice(&value.to_le_bytes()),
+            Value::UInt32(value) | Value::UInt32z(value) => {
+                out.extend_from_slice(&value.to_le_bytes())
+            }
+            Value::SInt32(value) => out.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+}
+
+/// The number, size and base type of each field of a message
+type Layout = Vec<(u8, u8, u8)>;
+
+/// Writes the messages of a FIT file, defining each message layout before its first use
+#[derive(Debug, Default)]
+struct FitWriter {
+    data: Vec<u8>,
+    /// The global message number and field layout of each local message number in use
+    definitions: HashMap<u16, (u8, Layout)>,
+}
+
+impl FitWriter {
+    fn message(&mut self, global: u16, fields: &[(u8, Value)]) {
+        let layout: Layout = fields
+            .iter()
+            .map(|(number, value)| {
+                let (base_type, size) = value.base_type();
+                (*number, size, base_type)
+            })
+            .collect();
+
+        // Local message numbers are assigned per global message, in order of first use
+        let next_local = self.definitions.len() as u8;
+        let (local, defined) = self
+            .definitions
+            .entry(global)
+            .or_insert_with(|| (next_local, Vec::new()));
+        let local = *local;
+        if *defined != layout {
+            self.data.push(0x40 | local);
+            self.data.push(0); // reserved
+            self.data.push(0); // architecture: little endian
+            self.data.extend_from_slice(&global.to_le_bytes());
+            self.data.push(layout.len() as u8);
+            for (number, size, base_type) in &layout {
+                self.data.extend_from_slice(&[*number, *size, *base_type]);
+            }
+            *defined = layout;
+        }
+
+        self.data.push(local);
+        for (_, value) in fields {
+            value.write(&mut self.data);
+        }
+    }
+
+    /// Returns the file: the header, the messages and the CRC of both
+    fn finish(self) -> Vec<u8> {
+        let mut file = Vec::with_capacity(14 + self.data.len() + 2);
+        file.push(14); // header size
+        file.push(PROTOCOL_VERSION);
+        file.extend_from_slice(&PROFILE_VERSION.to_le_bytes());
+        file.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        file.extend_from_slice(b".FIT");
+        let header_crc = crc(&file);
+        file.extend_from_slice(&header_crc.to_le_bytes());
+
+        file.extend_from_slice(&self.data);
+        let file_crc = crc(&file);
+        file.extend_from_slice(&file_crc.to_le_bytes());
+        file
+    }
+}
+
+/// Computes the CRC-16 that FIT files are checked with
+fn crc(bytes: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+        0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+
+    bytes.iter().fold(0, |crc, &byte| {
+        let crc = (crc >> 4) ^ TABLE[(crc & 0xF) as usize] ^ TABLE[(byte & 0xF) as usize];
+        (crc >> 4) ^ TABLE[(crc & 0xF) as usize] ^ TABLE[(byte >> 4) as usize]
+    })
+}
+
+/// Converts a time to seconds since the FIT epoch
+fn fit_timestamp(time: DateTime<FixedOffset>) -> u32 {
+    (time.timestamp() - FIT_EPOCH_OFFSET).clamp(0, u32::MAX as i64 - 1) as u32
+}
+
+/// Converts a local time with its UTC offset to seconds since the FIT epoch
+fn fit_timestamp_local(time: NaiveDateTime, offset: FixedOffset) -> u32 {
+    let utc = time - Duration::seconds(offset.local_minus_utc() as i64);
+    (utc.and_utc().timestamp() - FIT_EPOCH_OFFSET).clamp(0, u32::MAX as i64 - 1) as u32
+}
+
+/// Converts degrees to semicircles, or the invalid value if there is no position
+fn semicircles(degrees: Option<f64>) -> i32 {
+    degrees.map_or(i32::MAX, |degrees| {
+        (degrees * (2f64.powi(31) / 180.0)).round() as i32
+    })
+}
+
+/// Encodes an altitude with the scale 5 and offset 500 of the `record` message
+fn altitude(meters: Option<f64>) -> u16 {
+    meters.map_or(u16::MAX, |meters| {
+        ((meters + 500.0) * 5.0)
+            .round()
+            .clamp(0.0, u16::MAX as f64 - 1.0) as u16
+    })
+}
+
+/// Encodes a value with a scale, or the invalid value if there is none
+fn scaled(value: Option<f64>, scale: f64) -> u32 {
+    value.map_or(u32::MAX, |value| {
+        (value * scale).round().clamp(0.0, u32::MAX as f64 - 1.0) as u32
+    })
+}
+
+fn heart_rate(bpm: Option<u32>) -> u8 {
+    bpm.map_or(u8::MAX, |bpm| bpm.min(u8::MAX as u32 - 1) as u8)
+}
+
+fn clamp_u32(value: u64) -> u32 {
+    value.min(u32::MAX as u64 - 1) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tcx::TrackPoint;
+    use chrono::NaiveTime;
+
+    fn walk() -> ActivityLog {
+        serde_json::from_str(
+            r#"{
+                "activityName": "Walk",
+                "activityTypeId": 90013,
+                "averageHeartRate": 86,
+                "calories": 204,
+                "distance": 1.2,
+                "distanceUnit": "Kilometer",
+                "duration": 1536000,
+                "activeDuration": 1536000,
+                "lastModified": "2019-01-04T19:31:15.000Z",
+                "logId": 19018673358,
+                "logType": "auto_detected",
+                "startTime": "2019-01-03T12:08:00.000-08:00"
+            }"#,
+        )
+        .expect("Failed to parse activity")
+    }
+
+    /// Returns the global message number of each data message in a file
+    fn data_messages(file: &[u8]) -> Vec<u16> {
+        let mut layouts: HashMap<u8, (u16, usize)> = HashMap::new();
+        let mut messages = Vec::new();
+        let mut position = file[0] as usize;
+        while position < file.len() - 2 {
+            let header = file[position];
+            let local = header & 0x0F;
+            position += 1;
+            if header & 0x40 != 0 {
+                let global = u16::from_le_bytes([file[position + 2], file[position + 3]]);
+                let fields = file[position + 4] as usize;
+                let size = (0..fields)
+                    .map(|field| file[position + 5 + field * 3 + 1] as usize)
+                    .sum();
+                layouts.insert(local, (global, size));
+                position += 5 + fields * 3;
+            } else {
+                let (global, size) = layouts[&local];
+                messages.push(global);
+                position += size;
+            }
+        }
+
+        messages
+    }
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(crc(b"123456789"), 0xBB3D);
+    }
+
+    #[test]
+    fn test_writer_defines_each_layout_once() {
+        let mut writer = FitWriter::default();
+        for timestamp in [1, 2] {
+            writer.message(MESG_RECORD, &[(FIELD_TIMESTAMP, Value::UInt32(timestamp))]);
+        }
+        writer.message(MESG_EVENT, &[(0, Value::Enum(0))]);
+
+        let file = writer.finish();
+
+        // A definition and two data messages for records, one of each for the event
+        assert_eq!(file.len(), 14 + (6 + 3 + 5 + 5) + (6 + 3 + 2) + 2);
+        assert_eq!(crc(&file), 0);
+        assert_eq!(
+            data_messages(&file),
+            vec![MESG_RECORD, MESG_RECORD, MESG_EVENT]
+        );
+    }
+
+    #[test]
+    fn test_file_without_samples() {
+        let file = FitExport::new(&walk()).to_bytes();
+
+        assert_eq!(&file[8..12], b".FIT");
+        assert_eq!(
+            u32::from_le_bytes([file[4], file[5], file[6], file[7]]) as usize,
+            file.len() - 16
+        );
+        assert_eq!(crc(&file[..14]), 0);
+        assert_eq!(crc(&file), 0);
+        assert_eq!(
+            data_messages(&file),
+            vec![
+                MESG_FILE_ID,
+                MESG_EVENT,
+                MESG_EVENT,
+                MESG_LAP,
+                MESG_SESSION,
+                MESG_ACTIVITY
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records_from_track_and_heart_rate() {
+        let activity = walk();
+        let time = activity.start_time;
+        let point = |seconds: i64, heart_rate_bpm: Option<u32>| TrackPoint {
+            time: time + Duration::seconds(seconds),
+            latitude: Some(37.7749),
+            longitude: Some(-122.4194),
+            altitude_meters: Some(16.2),
+            distance_meters: Some(seconds as f64),
+            heart_rate_bpm,
+        };
+        let track = TcxTrack {
+            points: vec![point(0, Some(92)), point(5, None)],
+        };
+        let heart_rate = IntradayHeartRate {
+            date: time.date_naive(),
+            dataset_interval: 1,
+            dataset_type: "second".to_string(),
+            samples: [(NaiveTime::from_hms_opt(12, 8, 3).unwrap(), 97)]
+                .into_iter()
+                .collect(),
+        };
+
+        let export = FitExport::new(&activity)
+            .with_track(&track)
+            .with_heart_rate(&heart_rate);
+        let samples = export.samples();
+
+        assert_eq!(export.sport(), FitSport::Walking);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].heart_rate_bpm, Some(92));
+        assert_eq!(samples[1].heart_rate_bpm, Some(97));
+        assert_eq!(samples[1].timestamp - samples[0].timestamp, 5);
+
+        let file = export.to_bytes();
+        assert_eq!(crc(&file), 0);
+        assert_eq!(
+            data_messages(&file)
+                .iter()
+                .filter(|global| **global == MESG_RECORD)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_records_from_heart_rate_only() {
+        let activity = walk();
+        let heart_rate = IntradayHeartRate {
+            date: activity.start_time.date_naive(),
+            dataset_interval: 1,
+            dataset_type: "minute".to_string(),
+            samples: [(12, 0, 80), (12, 8, 85), (12, 30, 90), (12, 40, 70)]
+                .into_iter()
+                .map(|(hour, minute, bpm)| (NaiveTime::from_hms_opt(hour, minute, 0).unwrap(), bpm))
+                .collect(),
+        };
+
+        let samples = FitExport::new(&activity)
+            .with_heart_rate(&heart_rate)
+            .samples();
+
+        let heart_rates: Vec<_> = samples
+            .iter()
+            .filter_map(|sample| sample.heart_rate_bpm)
+            .collect();
+        assert_eq!(heart_rates, vec![85, 90]);
+        assert_eq!(samples[0].timestamp, fit_timestamp(activity.start_time));
+    }
+
+    #[test]
+    fn test_sport_from_activity_name() {
+        assert_eq!(FitSport::from_activity_name("Run"), FitSport::Running);
+        assert_eq!(
+            FitSport::from_activity_name("Outdoor Bike"),
+            FitSport::Cycling
+        );
+        assert_eq!(FitSport::from_activity_name("Hike"), FitSport::Hiking);
+        assert_eq!(FitSport::from_activity_name("Weights"), FitSport::Training);
+        assert_eq!(FitSport::from_activity_name("Tennis"), FitSport::Generic);
+    }
+
+    #[test]
+    fn test_encoding() {
+        assert_eq!(semicircles(Some(90.0)), 1 << 30);
+        assert_eq!(semicircles(Some(-90.0)), -(1 << 30));
+        assert_eq!(semicircles(None), i32::MAX);
+        assert_eq!(altitude(Some(16.2)), 2581);
+        assert_eq!(scaled(Some(1200.0), 100.0), 120_000);
+        assert_eq!(heart_rate(None), u8::MAX);
+    }
+}
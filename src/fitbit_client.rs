@@ -3,22 +3,99 @@
 //! This module provides the `FitbitClient` which handles communication with the Fitbit API,
 //! including authentication, request formation, and response parsing.
 
+use crate::activity_catalog::{ActivityCatalog, ActivityDefinition, ActivityDefinitionResponse};
+use crate::activity_goals::{ActivityGoals, ActivityGoalsResponse, GoalPeriod};
+use crate::activity_log::{ActivityLogListResponse, CreatedActivityLog, LogActivityRequest};
+use crate::activity_summary::Activity;
 use crate::activity_summary::ActivitySummaryResponse;
-use crate::error::FitbitError;
-use crate::sleep::SleepResponseV1_2;
-use chrono::NaiveDate;
+use crate::activity_time_series::{
+    ActiveZoneMinutes, ActivityIntradayResponse, ActivityResource, ActivityTimeSeries,
+    ActivityTimeSeriesResponse, AzmTimeSeriesResponse, DetailLevel, IntradayActivity,
+};
+use crate::alarm::{Alarm, AlarmListResponse, AlarmRequest, AlarmResponse};
+use crate::breathing_rate::{
+    BreathingRate, BreathingRateByStage, BreathingRateIntradayResponse, BreathingRateResponse,
+};
+use crate::device::Device;
+use crate::error::{FitbitError, parse_json};
+use crate::food::{
+    CreatedFoodLog, FoodGoalResponse, FoodGoalUpdate, FoodLog, FoodLogResponse, MealType,
+};
+use crate::friends::{FriendInvitation, FriendInvitationResponse, Invitee};
+use crate::heart_rate::{
+    HeartRateDay, HeartRateDetailLevel, HeartRateIntradayResponse, HeartRateTimeSeriesResponse,
+    IntradayHeartRate,
+};
+use crate::meal::{Meal, MealListResponse, MealRequest, MealResponse};
+use crate::pagination::{DateFilter, Pagination, SortOrder};
+use crate::profile::ProfileResponse;
+use crate::route::Route;
+use crate::sleep::{
+    CreatedSleepLog, SleepData, SleepLogListResponse, SleepRangeResponse, SleepResponseV1,
+    SleepResponseV1_2, log_sleep_form,
+};
+use crate::tcx::TcxTrack;
+use crate::units::{UnitSystem, VolumeUnit, VolumeValue};
+use crate::validation::Validate;
+use crate::water::{
+    CreatedWaterLog, WaterGoal, WaterGoalResponse, WaterLog, WaterLogResponse, WaterLogs,
+};
+use crate::weight::{CreatedWeightLog, WeightLog, WeightLogResponse, WeightTimeSeriesResponse};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use std::sync::Arc;
 use ureq::Agent;
 
 /// Base URL for the Fitbit API
 const API_BASE_URL: &str = "https://api.fitbit.com";
 
+/// Seconds to wait after hitting the rate limit if the response doesn't say when it resets
+const DEFAULT_RATE_LIMIT_RESET_SECONDS: u64 = 60;
+
 /// API version for sleep endpoints
 const SLEEP_API_VERSION: &str = "1.2";
 
+/// API version for the classic sleep endpoints
+const SLEEP_CLASSIC_API_VERSION: &str = "1";
+
 /// API version for activity endpoints
 const ACTIVITY_API_VERSION: &str = "1";
 
+/// Maximum number of days the daily activity time series endpoints accept in one request
+const MAX_ACTIVITY_TIME_SERIES_DAYS: i64 = 1095;
+
+/// API version for the profile endpoint
+const PROFILE_API_VERSION: &str = "1";
+
+/// API version for the breathing rate endpoints
+const BREATHING_RATE_API_VERSION: &str = "1";
+
+/// API version for the body endpoints
+const BODY_API_VERSION: &str = "1";
+
+/// API version for the food and water endpoints
+const FOOD_API_VERSION: &str = "1";
+
+/// API version for the device endpoints
+const DEVICES_API_VERSION: &str = "1";
+
+/// API version for the friends endpoints
+const FRIENDS_API_VERSION: &str = "1.1";
+
+/// Maximum number of days the weight log date range endpoint accepts in one request
+const MAX_WEIGHT_LOG_RANGE_DAYS: i64 = 31;
+
+/// Maximum number of days the weight time series endpoint accepts in one request
+const MAX_WEIGHT_TIME_SERIES_DAYS: i64 = 1095;
+
+/// Maximum number of days the heart rate time series endpoint accepts in one request
+const MAX_HEART_RATE_RANGE_DAYS: i64 = 366;
+
+/// Maximum number of days accepted by the sleep date range endpoint
+const MAX_SLEEP_RANGE_DAYS: i64 = 100;
+
+/// Maximum page size accepted by the list endpoints
+const MAX_LIST_LIMIT: u32 = 100;
+
 /// Trait defining the operations available on a Fitbit client
 ///
 /// This trait abstracts the Fitbit API operations, making it easier to test
@@ -36,6 +113,51 @@ pub trait FitbitClientTrait {
     /// Sleep data response or an error if the request failed
     fn fetch_sleep_data(&self, date: NaiveDate) -> Result<SleepResponseV1_2, FitbitError>;
 
+    /// Fetches sleep data for a specific date in the classic v1 format
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to fetch sleep data
+    ///
+    /// # Returns
+    ///
+    /// Classic sleep data response or an error if the request failed
+    fn fetch_sleep_data_v1(&self, date: NaiveDate) -> Result<SleepResponseV1, FitbitError>;
+
+    /// Fetches all sleep records for a range of dates in a single request
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive, at most 100 days after `start`)
+    ///
+    /// # Returns
+    ///
+    /// Sleep records for the range or an error if the request failed
+    fn fetch_sleep_data_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<SleepRangeResponse, FitbitError>;
+
+    /// Creates a manual sleep log
+    ///
+    /// This can be used to record naps or sleep for nights the tracker wasn't worn.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_time` - The time the sleep started (minute precision)
+    /// * `duration` - The duration of the sleep
+    ///
+    /// # Returns
+    ///
+    /// The created sleep record or an error if the request failed
+    fn log_sleep(
+        &self,
+        start_time: NaiveDateTime,
+        duration: chrono::Duration,
+    ) -> Result<SleepData, FitbitError>;
+
     /// Fetches activity summary for a specific date
     ///
     /// # Arguments
@@ -49,121 +171,1947 @@ pub trait FitbitClientTrait {
         &self,
         date: NaiveDate,
     ) -> Result<ActivitySummaryResponse, FitbitError>;
-}
 
-/// Client for interacting with the Fitbit API
-///
-/// This client handles authentication, request formation, and response parsing
-/// for Fitbit API endpoints.
-#[derive(Clone)]
-pub struct FitbitClient {
-    access_token: Arc<String>,
-    agent: ureq::Agent,
-}
+    /// Fetches the daily values of an activity resource for a range of dates
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The activity resource to fetch, e.g. steps
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive, at most 1095 days after `start`)
+    ///
+    /// # Returns
+    ///
+    /// One value per day or an error if the request failed
+    fn fetch_activity_time_series(
+        &self,
+        resource: ActivityResource,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<ActivityTimeSeries, FitbitError>;
 
-impl FitbitClient {
-    /// Creates a new Fitbit client with the given access token
+    /// Fetches the values of an activity resource throughout a single day
+    ///
+    /// Intraday data is only available for steps, calories, distance, floors and elevation,
+    /// and requires an app registered as a personal app or with intraday access approved.
     ///
     /// # Arguments
     ///
-    /// * `access_token` - The OAuth2 access token for authenticating with the Fitbit API
+    /// * `resource` - The activity resource to fetch, e.g. steps
+    /// * `date` - The date for which to fetch intraday data
+    /// * `detail_level` - The interval length of the dataset
     ///
-    /// # Example
+    /// # Returns
     ///
-    /// ```
-    /// use fitbit_rs::FitbitClient;
+    /// The daily total and per-interval values or an error if the request failed
+    fn fetch_activity_intraday(
+        &self,
+        resource: ActivityResource,
+        date: NaiveDate,
+        detail_level: DetailLevel,
+    ) -> Result<IntradayActivity, FitbitError>;
+
+    /// Fetches the daily Active Zone Minutes for a range of dates
     ///
-    /// let client = FitbitClient::new("your_access_token".to_string());
-    /// ```
-    pub fn new(access_token: String) -> Self {
-        let agent: Agent = Agent::config_builder()
-            .timeout_global(Some(std::time::Duration::from_secs(30)))
-            .build()
-            .into();
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive, at most 1095 days after `start`)
+    ///
+    /// # Returns
+    ///
+    /// The Active Zone Minutes per day in chronological order or an error if the request
+    /// failed. Days without Active Zone Minutes are omitted.
+    fn fetch_azm_time_series(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<ActiveZoneMinutes>, FitbitError>;
 
-        Self {
-            access_token: Arc::new(access_token),
-            agent,
-        }
-    }
+    /// Fetches the first page of the activity log list
+    ///
+    /// Unlike the daily activity summary, the log list includes per-exercise details such
+    /// as heart rate zones, pace and whether GPS was recorded. Use
+    /// [`fetch_next_activity_logs`](FitbitClientTrait::fetch_next_activity_logs) to follow
+    /// the pagination cursor to subsequent pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Only return activity logs before or after this date
+    /// * `sort` - Sort order; must be ascending for `After` and descending for `Before`
+    /// * `limit` - Maximum number of activity logs per page (at most 100)
+    ///
+    /// # Returns
+    ///
+    /// The first page of activity logs or an error if the request failed
+    fn fetch_activity_logs(
+        &self,
+        filter: DateFilter,
+        sort: SortOrder,
+        limit: u32,
+    ) -> Result<ActivityLogListResponse, FitbitError>;
 
-    /// Creates a new Fitbit client with a custom agent configuration
+    /// Fetches the page of the activity log list following the given one
     ///
-    /// This allows more control over the HTTP client configuration, such as timeout values,
-    /// proxy settings, etc.
+    /// # Arguments
+    ///
+    /// * `pagination` - The pagination information of the current page
+    ///
+    /// # Returns
+    ///
+    /// The next page of activity logs, `None` if the current page was the last one, or an
+    /// error if the request failed
+    fn fetch_next_activity_logs(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<Option<ActivityLogListResponse>, FitbitError>;
+
+    /// Logs an exercise
     ///
     /// # Arguments
     ///
-    /// * `access_token` - The OAuth2 access token for authenticating with the Fitbit API
-    /// * `agent` - Custom configured ureq agent
+    /// * `request` - The exercise to log
     ///
-    /// # Example
+    /// # Returns
     ///
-    /// ```
-    /// use fitbit_rs::FitbitClient;
-    /// use std::time::Duration;
-    /// use ureq::Agent;
+    /// The created activity or an error if the request failed
+    fn log_activity(&self, request: &LogActivityRequest) -> Result<Activity, FitbitError>;
+
+    /// Fetches the TCX document of a logged exercise
     ///
-    /// let agent: Agent = Agent::config_builder()
-    ///             .timeout_global(Some(std::time::Duration::from_secs(30)))
-    ///             .into();
+    /// The document contains the GPS route and heart rate samples of exercises recorded
+    /// with GPS; parse it with [`TcxTrack::parse`](crate::TcxTrack::parse) to get the track
+    /// points.
     ///
-    /// let client = FitbitClient::with_agent("your_access_token".to_string(), agent);
-    /// ```
-    pub fn with_agent(access_token: String, agent: ureq::Agent) -> Self {
-        Self {
-            access_token: Arc::new(access_token),
-            agent,
-        }
-    }
+    /// # Arguments
+    ///
+    /// * `log_id` - The `log_id` of the exercise, e.g. from the activity log list
+    ///
+    /// # Returns
+    ///
+    /// The raw TCX document or an error if the request failed
+    fn fetch_activity_tcx(&self, log_id: u64) -> Result<String, FitbitError>;
 
-    /// Makes an API request to the given URL and deserializes the JSON response
+    /// Fetches the GPS route of a logged exercise
     ///
     /// # Arguments
     ///
-    /// * `url` - The full API URL to request
+    /// * `log_id` - The `log_id` of the exercise, e.g. from the activity log list
     ///
     /// # Returns
     ///
-    /// The deserialized response or an error if the request or deserialization failed
-    fn make_api_request<T>(&self, url: &str) -> Result<T, FitbitError>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        self.agent
-            .get(url)
-            .header("Authorization", &format!("Bearer {}", self.access_token))
-            .call()
-            .map_err(FitbitError::RequestError)?
-            .body_mut()
-            .read_json()
-            .map_err(|e| FitbitError::JsonError(e.to_string()))
-    }
-}
+    /// The route, which is empty if the exercise was recorded without GPS, or an error if
+    /// the request or parsing the TCX document failed
+    fn fetch_activity_route(&self, log_id: u64) -> Result<Route, FitbitError>;
 
-impl FitbitClientTrait for FitbitClient {
-    fn fetch_sleep_data(&self, date: NaiveDate) -> Result<SleepResponseV1_2, FitbitError> {
-        let url = format!(
-            "{}/{}/user/-/sleep/date/{}.json",
-            API_BASE_URL,
-            SLEEP_API_VERSION,
-            date.format("%Y-%m-%d")
-        );
+    /// Fetches the user's activity goals
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - Whether to fetch the daily or weekly goals
+    ///
+    /// # Returns
+    ///
+    /// The activity goals or an error if the request failed
+    fn fetch_activity_goals(&self, period: GoalPeriod) -> Result<ActivityGoals, FitbitError>;
 
-        self.make_api_request(&url)
-    }
+    /// Updates the user's activity goals
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - Whether to update the daily or weekly goals
+    /// * `goals` - The goals to change; goals that are `None` are left unchanged
+    ///
+    /// # Returns
+    ///
+    /// All goals of the period after the update or an error if the request failed
+    fn update_activity_goals(
+        &self,
+        period: GoalPeriod,
+        goals: &ActivityGoals,
+    ) -> Result<ActivityGoals, FitbitError>;
 
-    fn fetch_activity_summary(
+    /// Fetches the catalog of activity types
+    ///
+    /// # Returns
+    ///
+    /// All activity types grouped by category or an error if the request failed
+    fn browse_activity_types(&self) -> Result<ActivityCatalog, FitbitError>;
+
+    /// Fetches a single activity type
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the activity type
+    ///
+    /// # Returns
+    ///
+    /// The activity type with its levels and MET values or an error if the request failed
+    fn get_activity_type(&self, id: u64) -> Result<ActivityDefinition, FitbitError>;
+
+    /// Fetches the first page of the sleep log list
+    ///
+    /// Use [`fetch_next_sleep_log_list`](FitbitClientTrait::fetch_next_sleep_log_list) to
+    /// follow the pagination cursor to subsequent pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Only return sleep logs before or after this date
+    /// * `sort` - Sort order; must be ascending for `After` and descending for `Before`
+    /// * `limit` - Maximum number of sleep logs per page (at most 100)
+    ///
+    /// # Returns
+    ///
+    /// The first page of sleep logs or an error if the request failed
+    fn fetch_sleep_log_list(
+        &self,
+        filter: DateFilter,
+        sort: SortOrder,
+        limit: u32,
+    ) -> Result<SleepLogListResponse, FitbitError>;
+
+    /// Fetches the page of the sleep log list following the given one
+    ///
+    /// # Arguments
+    ///
+    /// * `pagination` - The pagination information of the current page
+    ///
+    /// # Returns
+    ///
+    /// The next page of sleep logs, `None` if the current page was the last one, or an
+    /// error if the request failed
+    fn fetch_next_sleep_log_list(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<Option<SleepLogListResponse>, FitbitError>;
+
+    /// Fetches the profile of the authenticated user
+    ///
+    /// The profile contains the user's timezone, which is needed to convert the local
+    /// timestamps of other responses to absolute times.
+    ///
+    /// # Returns
+    ///
+    /// Profile response or an error if the request failed
+    fn fetch_profile(&self) -> Result<ProfileResponse, FitbitError>;
+
+    /// Fetches the resting heart rate and time in heart rate zones for a single date
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to fetch heart rate data
+    ///
+    /// # Returns
+    ///
+    /// The heart rate data of the day or an error if the request failed
+    fn fetch_heart_rate(&self, date: NaiveDate) -> Result<HeartRateDay, FitbitError>;
+
+    /// Fetches the resting heart rate and time in heart rate zones for a range of dates
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive, at most one year after `start`)
+    ///
+    /// # Returns
+    ///
+    /// The heart rate data per day in chronological order or an error if the request failed
+    fn fetch_heart_rate_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<HeartRateDay>, FitbitError>;
+
+    /// Fetches the heart rate samples of a single day within a time window
+    ///
+    /// Intraday data requires an app registered as a personal app or with intraday access
+    /// approved.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to fetch heart rate samples
+    /// * `detail_level` - The interval length of the samples
+    /// * `start_time` - The start of the time window (minute precision)
+    /// * `end_time` - The end of the time window (minute precision, inclusive); use
+    ///   00:00 to 23:59 for the whole day
+    ///
+    /// # Returns
+    ///
+    /// The heart rate samples or an error if the request failed
+    fn fetch_heart_rate_intraday(
         &self,
         date: NaiveDate,
-    ) -> Result<ActivitySummaryResponse, FitbitError> {
-        let url = format!(
-            "{}/{}/user/-/activities/date/{}.json",
-            API_BASE_URL,
-            ACTIVITY_API_VERSION,
-            date.format("%Y-%m-%d")
-        );
+        detail_level: HeartRateDetailLevel,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<IntradayHeartRate, FitbitError>;
 
-        self.make_api_request(&url)
+    /// Fetches the average breathing rate of the main sleep ending on a date
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date the sleep ended on
+    ///
+    /// # Returns
+    ///
+    /// The breathing rate, `None` if there is no estimate for the night, or an error if the
+    /// request failed
+    fn fetch_breathing_rate(&self, date: NaiveDate) -> Result<Option<BreathingRate>, FitbitError>;
+
+    /// Fetches the average breathing rate of the main sleep ending on a date, overall and
+    /// per sleep stage
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date the sleep ended on
+    ///
+    /// # Returns
+    ///
+    /// The breathing rates, `None` if there is no estimate for the night, or an error if
+    /// the request failed
+    fn fetch_breathing_rate_intraday(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<BreathingRateByStage>, FitbitError>;
+
+    /// Fetches the weight logs of a single date
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to fetch weight logs
+    ///
+    /// # Returns
+    ///
+    /// The weight logs of the day or an error if the request failed
+    fn fetch_weight_logs(&self, date: NaiveDate) -> Result<Vec<WeightLog>, FitbitError>;
+
+    /// Fetches the weight logs of a range of dates
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive, at most 30 days after `start`)
+    ///
+    /// # Returns
+    ///
+    /// The weight logs of the range or an error if the request failed
+    fn fetch_weight_logs_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<WeightLog>, FitbitError>;
+
+    /// Fetches the daily weight for a range of dates
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive, at most 1095 days after `start`)
+    ///
+    /// # Returns
+    ///
+    /// The weight per day in chronological order or an error if the request failed
+    fn fetch_weight_time_series(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, f64)>, FitbitError>;
+
+    /// Creates a weight log, e.g. for a measurement of a third-party scale
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - The weight in the weight unit of the client's unit system
+    /// * `date` - The date of the measurement
+    /// * `time` - The time of the measurement (second precision)
+    ///
+    /// # Returns
+    ///
+    /// The created weight log, including the BMI calculated by Fitbit, or an error if the
+    /// request failed
+    fn log_weight(
+        &self,
+        weight: f64,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<WeightLog, FitbitError>;
+
+    /// Deletes a weight log
+    ///
+    /// # Arguments
+    ///
+    /// * `log_id` - The `log_id` of the weight log
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the log was deleted or an error if the request failed
+    fn delete_weight_log(&self, log_id: u64) -> Result<(), FitbitError>;
+
+    /// Fetches the foods logged on a date with the day's nutrition totals
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to fetch food logs
+    ///
+    /// # Returns
+    ///
+    /// Food log response or an error if the request failed
+    fn fetch_food_logs(&self, date: NaiveDate) -> Result<FoodLogResponse, FitbitError>;
+
+    /// Fetches the water logs of a date
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to fetch water logs
+    ///
+    /// # Returns
+    ///
+    /// The water logs and the day's total in the water unit of the client's unit system, or
+    /// an error if the request failed
+    fn fetch_water_logs(&self, date: NaiveDate) -> Result<WaterLogs, FitbitError>;
+
+    /// Creates a water log
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount of water in `unit`
+    /// * `unit` - The unit of `amount`
+    /// * `date` - The date the water was drunk
+    ///
+    /// # Returns
+    ///
+    /// The created water log or an error if the request failed
+    fn log_water(
+        &self,
+        amount: f64,
+        unit: VolumeUnit,
+        date: NaiveDate,
+    ) -> Result<WaterLog, FitbitError>;
+
+    /// Deletes a water log
+    ///
+    /// # Arguments
+    ///
+    /// * `log_id` - The `log_id` of the water log
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the log was deleted or an error if the request failed
+    fn delete_water_log(&self, log_id: u64) -> Result<(), FitbitError>;
+
+    /// Fetches the daily calorie intake goal and the food plan it was derived from
+    ///
+    /// # Returns
+    ///
+    /// The calorie goal and food plan or an error if the request failed
+    fn fetch_food_goals(&self) -> Result<FoodGoalResponse, FitbitError>;
+
+    /// Updates the daily calorie intake goal
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The calorie goal or the food plan to derive it from
+    ///
+    /// # Returns
+    ///
+    /// The updated calorie goal and food plan or an error if the request failed
+    fn update_food_goals(&self, update: FoodGoalUpdate) -> Result<FoodGoalResponse, FitbitError>;
+
+    /// Fetches the daily water goal
+    ///
+    /// # Returns
+    ///
+    /// The water goal in the water unit of the client's unit system or an error if the
+    /// request failed
+    fn fetch_water_goal(&self) -> Result<WaterGoal, FitbitError>;
+
+    /// Updates the daily water goal
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The goal in the water unit of the client's unit system
+    ///
+    /// # Returns
+    ///
+    /// The updated water goal or an error if the request failed
+    fn update_water_goal(&self, target: f64) -> Result<WaterGoal, FitbitError>;
+
+    /// Fetches the user's saved meals
+    ///
+    /// # Returns
+    ///
+    /// The saved meals or an error if the request failed
+    fn fetch_meals(&self) -> Result<Vec<Meal>, FitbitError>;
+
+    /// Fetches a saved meal
+    ///
+    /// # Arguments
+    ///
+    /// * `meal_id` - The id of the meal
+    ///
+    /// # Returns
+    ///
+    /// The meal or an error if the request failed
+    fn fetch_meal(&self, meal_id: u64) -> Result<Meal, FitbitError>;
+
+    /// Creates a saved meal
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The name, description and foods of the meal
+    ///
+    /// # Returns
+    ///
+    /// The created meal or an error if the request failed
+    fn create_meal(&self, request: &MealRequest) -> Result<Meal, FitbitError>;
+
+    /// Replaces the name, description and foods of a saved meal
+    ///
+    /// # Arguments
+    ///
+    /// * `meal_id` - The id of the meal
+    /// * `request` - The new name, description and foods of the meal
+    ///
+    /// # Returns
+    ///
+    /// The updated meal or an error if the request failed
+    fn update_meal(&self, meal_id: u64, request: &MealRequest) -> Result<Meal, FitbitError>;
+
+    /// Deletes a saved meal
+    ///
+    /// # Arguments
+    ///
+    /// * `meal_id` - The id of the meal
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the meal was deleted or an error if the request failed
+    fn delete_meal(&self, meal_id: u64) -> Result<(), FitbitError>;
+
+    /// Logs the foods of a saved meal
+    ///
+    /// Each food is logged with a separate request. If a request fails, the foods logged
+    /// before it remain logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `meal` - The meal to log
+    /// * `meal_type` - The meal type to log the foods for
+    /// * `date` - The date the meal was eaten
+    ///
+    /// # Returns
+    ///
+    /// The created food logs in the order of the meal's foods or an error if a request
+    /// failed
+    fn log_meal(
+        &self,
+        meal: &Meal,
+        meal_type: MealType,
+        date: NaiveDate,
+    ) -> Result<Vec<FoodLog>, FitbitError>;
+
+    /// Fetches the trackers and scales paired with the account
+    ///
+    /// # Returns
+    ///
+    /// The devices, with their battery state and last sync time, or an error if the request
+    /// failed
+    fn fetch_devices(&self) -> Result<Vec<Device>, FitbitError>;
+
+    /// Fetches the alarms of a tracker
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker_id` - The id of the tracker
+    ///
+    /// # Returns
+    ///
+    /// The tracker's alarms or an error if the request failed
+    fn fetch_alarms(&self, tracker_id: &str) -> Result<Vec<Alarm>, FitbitError>;
+
+    /// Adds an alarm to a tracker
+    ///
+    /// The alarm is set on the tracker with its next sync.
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker_id` - The id of the tracker
+    /// * `request` - The time and recurrence of the alarm
+    ///
+    /// # Returns
+    ///
+    /// The added alarm or an error if the request failed
+    fn add_alarm(&self, tracker_id: &str, request: &AlarmRequest) -> Result<Alarm, FitbitError>;
+
+    /// Replaces the settings of an alarm
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker_id` - The id of the tracker
+    /// * `alarm_id` - The id of the alarm
+    /// * `request` - The new settings of the alarm
+    ///
+    /// # Returns
+    ///
+    /// The updated alarm or an error if the request failed
+    fn update_alarm(
+        &self,
+        tracker_id: &str,
+        alarm_id: u64,
+        request: &AlarmRequest,
+    ) -> Result<Alarm, FitbitError>;
+
+    /// Deletes an alarm
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker_id` - The id of the tracker
+    /// * `alarm_id` - The id of the alarm
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the alarm was deleted or an error if the request failed
+    fn delete_alarm(&self, tracker_id: &str, alarm_id: u64) -> Result<(), FitbitError>;
+
+    /// Fetches the pending invitations from other users to become friends
+    ///
+    /// # Returns
+    ///
+    /// The invitations or an error if the request failed
+    fn fetch_friend_invitations(&self) -> Result<Vec<FriendInvitation>, FitbitError>;
+
+    /// Invites a user to become friends
+    ///
+    /// # Arguments
+    ///
+    /// * `invitee` - The email address or id of the user to invite
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the invitation was sent or an error if the request failed
+    fn send_friend_invitation(&self, invitee: &Invitee) -> Result<(), FitbitError>;
+
+    /// Accepts or declines an invitation to become friends
+    ///
+    /// # Arguments
+    ///
+    /// * `from_user_id` - The encoded id of the inviting user
+    /// * `accept` - `true` to accept the invitation, `false` to decline it
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the invitation was answered or an error if the request failed
+    fn respond_to_friend_invitation(
+        &self,
+        from_user_id: &str,
+        accept: bool,
+    ) -> Result<(), FitbitError>;
+}
+
+/// Client for interacting with the Fitbit API
+///
+/// This client handles authentication, request formation, and response parsing
+/// for Fitbit API endpoints.
+#[derive(Clone)]
+pub struct FitbitClient {
+    access_token: Arc<String>,
+    agent: ureq::Agent,
+    unit_system: UnitSystem,
+}
+
+impl FitbitClient {
+    /// Creates a new Fitbit client with the given access token
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The OAuth2 access token for authenticating with the Fitbit API
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fitbit_rs::FitbitClient;
+    ///
+    /// let client = FitbitClient::new("your_access_token".to_string());
+    /// ```
+    pub fn new(access_token: String) -> Self {
+        let agent: Agent = Agent::config_builder()
+            .timeout_global(Some(std::time::Duration::from_secs(30)))
+            .build()
+            .into();
+
+        Self {
+            access_token: Arc::new(access_token),
+            agent,
+            unit_system: UnitSystem::default(),
+        }
+    }
+
+    /// Creates a new Fitbit client with a custom agent configuration
+    ///
+    /// This allows more control over the HTTP client configuration, such as timeout values,
+    /// proxy settings, etc.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The OAuth2 access token for authenticating with the Fitbit API
+    /// * `agent` - Custom configured ureq agent
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fitbit_rs::FitbitClient;
+    /// use std::time::Duration;
+    /// use ureq::Agent;
+    ///
+    /// let agent: Agent = Agent::config_builder()
+    ///             .timeout_global(Some(std::time::Duration::from_secs(30)))
+    ///             .into();
+    ///
+    /// let client = FitbitClient::with_agent("your_access_token".to_string(), agent);
+    /// ```
+    pub fn with_agent(access_token: String, agent: ureq::Agent) -> Self {
+        Self {
+            access_token: Arc::new(access_token),
+            agent,
+            unit_system: UnitSystem::default(),
+        }
+    }
+
+    /// Sets the unit system measurements are requested in
+    ///
+    /// The default is metric. The unit system is sent as the `Accept-Language` header
+    /// and attached to responses with distances and weights, so their values can be
+    /// converted.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit_system` - The unit system to request measurements in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fitbit_rs::{FitbitClient, UnitSystem};
+    ///
+    /// let client = FitbitClient::new("your_access_token".to_string())
+    ///     .with_unit_system(UnitSystem::Us);
+    /// ```
+    pub fn with_unit_system(mut self, unit_system: UnitSystem) -> Self {
+        self.unit_system = unit_system;
+        self
+    }
+
+    /// Returns the unit system measurements are requested in
+    pub fn unit_system(&self) -> UnitSystem {
+        self.unit_system
+    }
+
+    /// Adds the authorization and unit system headers to a request
+    ///
+    /// Error statuses are not turned into transport errors, so that [`Self::check_status`]
+    /// can read the error details from the body.
+    fn with_headers<B>(&self, request: ureq::RequestBuilder<B>) -> ureq::RequestBuilder<B> {
+        let request = request
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .header("Authorization", &format!("Bearer {}", self.access_token));
+        match self.unit_system.accept_language() {
+            Some(language) => request.header("Accept-Language", language),
+            None => request,
+        }
+    }
+
+    /// Turns error responses into errors
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response to a request
+    ///
+    /// # Returns
+    ///
+    /// The response if its status is successful, `FitbitError::RateLimitExceeded` if the
+    /// rate limit was hit, or `FitbitError::ApiError` with the details from the body
+    fn check_status(
+        mut response: ureq::http::Response<ureq::Body>,
+    ) -> Result<ureq::http::Response<ureq::Body>, FitbitError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        if status.as_u16() == 429 {
+            let headers = response.headers();
+            let reset = headers
+                .get("Fitbit-Rate-Limit-Reset")
+                .or_else(|| headers.get("Retry-After"))
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_RATE_LIMIT_RESET_SECONDS);
+            return Err(FitbitError::RateLimitExceeded(reset));
+        }
+
+        let body = response.body_mut().read_to_string().unwrap_or_default();
+        Err(FitbitError::from_response(status.as_u16(), &body))
+    }
+
+    /// Sets the unit system of the client on weight logs
+    fn with_weight_unit_system(&self, mut logs: Vec<WeightLog>) -> Vec<WeightLog> {
+        for log in &mut logs {
+            log.unit_system = self.unit_system;
+        }
+
+        logs
+    }
+
+    /// Makes an API request to the given URL and deserializes the JSON response
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full API URL to request
+    ///
+    /// # Returns
+    ///
+    /// The deserialized response or an error if the request or deserialization failed. Errors
+    /// carry the [`RequestContext`](crate::error::RequestContext) of the request, as do the
+    /// errors of the other request helpers.
+    fn make_api_request<T>(&self, url: &str) -> Result<T, FitbitError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.with_headers(self.agent.get(url))
+            .call()
+            .map_err(FitbitError::RequestError)
+            .and_then(Self::check_status)
+            .and_then(|mut response| {
+                response
+                    .body_mut()
+                    .read_to_vec()
+                    .map_err(FitbitError::RequestError)
+            })
+            .and_then(|body| parse_json(&body))
+            .map_err(|error| error.with_request_context("GET", url))
+    }
+
+    /// Checks a response against the API contract if the `strict` feature is enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The deserialized response
+    /// * `url` - The URL the response was requested from
+    ///
+    /// # Returns
+    ///
+    /// The response, or `FitbitError::ContractViolation` if strict validation failed
+    #[cfg_attr(not(feature = "strict"), allow(unused_variables))]
+    fn checked<T: Validate>(response: T, url: &str) -> Result<T, FitbitError> {
+        #[cfg(feature = "strict")]
+        response
+            .validate()
+            .map_err(|error| error.with_request_context("GET", url))?;
+
+        Ok(response)
+    }
+
+    /// Makes a GET request to the given URL and returns the response body as text
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full API URL to request
+    ///
+    /// # Returns
+    ///
+    /// The response body or an error if the request failed
+    fn make_raw_api_request(&self, url: &str) -> Result<String, FitbitError> {
+        self.with_headers(self.agent.get(url))
+            .call()
+            .map_err(FitbitError::RequestError)
+            .and_then(Self::check_status)
+            .and_then(|mut response| {
+                response
+                    .body_mut()
+                    .read_to_string()
+                    .map_err(FitbitError::RequestError)
+            })
+            .map_err(|error| error.with_request_context("GET", url))
+    }
+
+    /// Makes a form-encoded POST request to the given URL and deserializes the JSON response
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full API URL to request
+    /// * `form` - The form parameters to send
+    ///
+    /// # Returns
+    ///
+    /// The deserialized response or an error if the request or deserialization failed
+    fn make_api_post_request<T>(&self, url: &str, form: &[(&str, String)]) -> Result<T, FitbitError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.with_headers(self.agent.post(url))
+            .send_form(form.iter().map(|(key, value)| (*key, value.as_str())))
+            .map_err(FitbitError::RequestError)
+            .and_then(Self::check_status)
+            .and_then(|mut response| {
+                response
+                    .body_mut()
+                    .read_to_vec()
+                    .map_err(FitbitError::RequestError)
+            })
+            .and_then(|body| parse_json(&body))
+            .map_err(|error| error.with_request_context("POST", url))
+    }
+
+    /// Makes a POST request with form parameters to the given URL, ignoring the response
+    /// body
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full API URL to request
+    /// * `form` - The form parameters to send
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the request succeeded or an error if it failed
+    fn make_api_post_request_without_response(
+        &self,
+        url: &str,
+        form: &[(&str, String)],
+    ) -> Result<(), FitbitError> {
+        self.with_headers(self.agent.post(url))
+            .send_form(form.iter().map(|(key, value)| (*key, value.as_str())))
+            .map_err(FitbitError::RequestError)
+            .and_then(Self::check_status)
+            .map(|_| ())
+            .map_err(|error| error.with_request_context("POST", url))
+    }
+
+    /// Makes a POST request with a JSON body to the given URL and deserializes the JSON
+    /// response
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full API URL to request
+    /// * `body` - The request body
+    ///
+    /// # Returns
+    ///
+    /// The deserialized response or an error if the request or deserialization failed
+    fn make_api_json_post_request<T, B>(&self, url: &str, body: &B) -> Result<T, FitbitError>
+    where
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize,
+    {
+        self.with_headers(self.agent.post(url))
+            .send_json(body)
+            .map_err(FitbitError::RequestError)
+            .and_then(Self::check_status)
+            .and_then(|mut response| {
+                response
+                    .body_mut()
+                    .read_to_vec()
+                    .map_err(FitbitError::RequestError)
+            })
+            .and_then(|body| parse_json(&body))
+            .map_err(|error| error.with_request_context("POST", url))
+    }
+
+    /// Makes a DELETE request to the given URL
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full API URL to request
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the resource was deleted or an error if the request failed
+    fn make_api_delete_request(&self, url: &str) -> Result<(), FitbitError> {
+        self.with_headers(self.agent.delete(url))
+            .call()
+            .map_err(FitbitError::RequestError)
+            .and_then(Self::check_status)
+            .map(|_| ())
+            .map_err(|error| error.with_request_context("DELETE", url))
+    }
+
+    /// Builds the URL of the first page of a list endpoint
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The endpoint path after the API base URL, e.g. `1.2/user/-/sleep/list.json`
+    /// * `filter` - The date filter of the request
+    /// * `sort` - The sort order of the request
+    /// * `limit` - The page size of the request
+    ///
+    /// # Returns
+    ///
+    /// The full request URL, or an error if the arguments are not accepted by the API
+    fn list_url(
+        path: &str,
+        filter: DateFilter,
+        sort: SortOrder,
+        limit: u32,
+    ) -> Result<String, FitbitError> {
+        if limit == 0 || limit > MAX_LIST_LIMIT {
+            return Err(FitbitError::InvalidArgument(format!(
+                "limit must be between 1 and {}, got {}",
+                MAX_LIST_LIMIT, limit
+            )));
+        }
+
+        match (filter, sort) {
+            (DateFilter::After(_), SortOrder::Ascending)
+            | (DateFilter::Before(_), SortOrder::Descending) => {}
+            _ => {
+                return Err(FitbitError::InvalidArgument(
+                    "sort must be ascending with an after date and descending with a before date"
+                        .to_string(),
+                ));
+            }
+        }
+
+        Ok(format!(
+            "{}/{}?{}&sort={}&offset=0&limit={}",
+            API_BASE_URL,
+            path,
+            filter.to_query(),
+            sort.as_str(),
+            limit
+        ))
+    }
+
+    /// Builds the URL of a tracker's alarms, or of a single alarm
+    ///
+    /// The tracker id is percent-encoded, so that it always stays a single path segment.
+    /// Empty ids and the dot-segments `.` and `..` are rejected, as they would resolve to
+    /// another endpoint.
+    fn alarms_url(tracker_id: &str, alarm_id: Option<u64>) -> Result<String, FitbitError> {
+        if matches!(tracker_id, "" | "." | "..") {
+            return Err(FitbitError::InvalidArgument(format!(
+                "invalid tracker id {:?}",
+                tracker_id
+            )));
+        }
+
+        let alarms = match alarm_id {
+            Some(alarm_id) => format!("alarms/{}.json", alarm_id),
+            None => "alarms.json".to_string(),
+        };
+
+        Ok(format!(
+            "{}/{}/user/-/devices/tracker/{}/{}",
+            API_BASE_URL,
+            DEVICES_API_VERSION,
+            encode_path_segment(tracker_id),
+            alarms
+        ))
+    }
+
+    /// Builds the URL of the invitation from the given user
+    ///
+    /// The user id is percent-encoded, so that it always stays a single path segment.
+    /// Empty ids are rejected, as they would name no invitation.
+    fn friend_invitation_url(from_user_id: &str) -> Result<String, FitbitError> {
+        if from_user_id.is_empty() {
+            return Err(FitbitError::InvalidArgument(format!(
+                "invalid user id {:?}",
+                from_user_id
+            )));
+        }
+
+        Ok(format!(
+            "{}/{}/user/-/friends/invitations/{}.json",
+            API_BASE_URL,
+            FRIENDS_API_VERSION,
+            encode_path_segment(from_user_id)
+        ))
+    }
+
+    /// Follows the `next` cursor of a list endpoint page
+    ///
+    /// The cursor is only followed if it points to the Fitbit API, so that the access token
+    /// is never sent to another host.
+    ///
+    /// # Arguments
+    ///
+    /// * `pagination` - The pagination information of the current page
+    ///
+    /// # Returns
+    ///
+    /// The deserialized next page, `None` if there is no next page, or an error if the
+    /// request or deserialization failed
+    fn fetch_next_page<T>(&self, pagination: &Pagination) -> Result<Option<T>, FitbitError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match pagination.next_url() {
+            None => Ok(None),
+            Some(url) if url.starts_with(&format!("{}/", API_BASE_URL)) => {
+                self.make_api_request(url).map(Some)
+            }
+            Some(url) => Err(FitbitError::InvalidArgument(format!(
+                "refusing to follow pagination cursor to {}",
+                url
+            ))),
+        }
+    }
+}
+
+impl FitbitClientTrait for FitbitClient {
+    fn fetch_sleep_data(&self, date: NaiveDate) -> Result<SleepResponseV1_2, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/sleep/date/{}.json",
+            API_BASE_URL,
+            SLEEP_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        Self::checked(self.make_api_request(&url)?, &url)
+    }
+
+    fn fetch_sleep_data_v1(&self, date: NaiveDate) -> Result<SleepResponseV1, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/sleep/date/{}.json",
+            API_BASE_URL,
+            SLEEP_CLASSIC_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        Self::checked(self.make_api_request(&url)?, &url)
+    }
+
+    fn fetch_sleep_data_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<SleepRangeResponse, FitbitError> {
+        let days = (end - start).num_days() + 1;
+        if !(1..=MAX_SLEEP_RANGE_DAYS).contains(&days) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "sleep date range must span 1 to {} days, got {} to {}",
+                MAX_SLEEP_RANGE_DAYS, start, end
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/sleep/date/{}/{}.json",
+            API_BASE_URL,
+            SLEEP_API_VERSION,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        Self::checked(self.make_api_request(&url)?, &url)
+    }
+
+    fn log_sleep(
+        &self,
+        start_time: NaiveDateTime,
+        duration: chrono::Duration,
+    ) -> Result<SleepData, FitbitError> {
+        if duration <= chrono::Duration::zero() {
+            return Err(FitbitError::InvalidArgument(
+                "sleep duration must be positive".to_string(),
+            ));
+        }
+
+        let url = format!("{}/{}/user/-/sleep.json", API_BASE_URL, SLEEP_API_VERSION);
+        let form = log_sleep_form(start_time, duration);
+
+        self.make_api_post_request::<CreatedSleepLog>(&url, &form)
+            .map(|created| created.sleep)
+    }
+
+    fn fetch_activity_summary(
+        &self,
+        date: NaiveDate,
+    ) -> Result<ActivitySummaryResponse, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/activities/date/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        let mut response: ActivitySummaryResponse = self.make_api_request(&url)?;
+        response.unit_system = self.unit_system;
+
+        Self::checked(response, &url)
+    }
+
+    fn fetch_activity_time_series(
+        &self,
+        resource: ActivityResource,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<ActivityTimeSeries, FitbitError> {
+        let days = (end - start).num_days() + 1;
+        if !(1..=MAX_ACTIVITY_TIME_SERIES_DAYS).contains(&days) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "activity time series range must span 1 to {} days, got {} to {}",
+                MAX_ACTIVITY_TIME_SERIES_DAYS, start, end
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities/{}/date/{}/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            resource.as_str(),
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<ActivityTimeSeriesResponse>(&url)?
+            .into_series(resource)
+    }
+
+    fn fetch_azm_time_series(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<ActiveZoneMinutes>, FitbitError> {
+        let days = (end - start).num_days() + 1;
+        if !(1..=MAX_ACTIVITY_TIME_SERIES_DAYS).contains(&days) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "active zone minutes range must span 1 to {} days, got {} to {}",
+                MAX_ACTIVITY_TIME_SERIES_DAYS, start, end
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities/active-zone-minutes/date/{}/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<AzmTimeSeriesResponse>(&url)
+            .map(AzmTimeSeriesResponse::into_days)
+    }
+
+    fn fetch_activity_intraday(
+        &self,
+        resource: ActivityResource,
+        date: NaiveDate,
+        detail_level: DetailLevel,
+    ) -> Result<IntradayActivity, FitbitError> {
+        if !resource.supports_intraday() {
+            return Err(FitbitError::InvalidArgument(format!(
+                "intraday data is not available for {}",
+                resource.as_str()
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities/{}/date/{}/1d/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            resource.as_str(),
+            date.format("%Y-%m-%d"),
+            detail_level.as_str()
+        );
+
+        self.make_api_request::<ActivityIntradayResponse>(&url)?
+            .into_intraday(resource)
+    }
+
+    fn fetch_activity_logs(
+        &self,
+        filter: DateFilter,
+        sort: SortOrder,
+        limit: u32,
+    ) -> Result<ActivityLogListResponse, FitbitError> {
+        let path = format!("{}/user/-/activities/list.json", ACTIVITY_API_VERSION);
+        let url = Self::list_url(&path, filter, sort, limit)?;
+
+        self.make_api_request(&url)
+    }
+
+    fn fetch_next_activity_logs(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<Option<ActivityLogListResponse>, FitbitError> {
+        self.fetch_next_page(pagination)
+    }
+
+    fn log_activity(&self, request: &LogActivityRequest) -> Result<Activity, FitbitError> {
+        if request.duration() <= chrono::Duration::zero() {
+            return Err(FitbitError::InvalidArgument(
+                "activity duration must be positive".to_string(),
+            ));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities.json",
+            API_BASE_URL, ACTIVITY_API_VERSION
+        );
+
+        self.make_api_post_request::<CreatedActivityLog>(&url, &request.to_form())
+            .map(|created| created.activity_log)
+    }
+
+    fn fetch_activity_tcx(&self, log_id: u64) -> Result<String, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/activities/{}.tcx",
+            API_BASE_URL, ACTIVITY_API_VERSION, log_id
+        );
+
+        self.make_raw_api_request(&url)
+    }
+
+    fn fetch_activity_route(&self, log_id: u64) -> Result<Route, FitbitError> {
+        let tcx = self.fetch_activity_tcx(log_id)?;
+
+        TcxTrack::parse(&tcx).map(|track| Route::from(&track))
+    }
+
+    fn fetch_activity_goals(&self, period: GoalPeriod) -> Result<ActivityGoals, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/activities/goals/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            period.as_str()
+        );
+
+        self.make_api_request::<ActivityGoalsResponse>(&url)
+            .map(|response| response.goals)
+    }
+
+    fn update_activity_goals(
+        &self,
+        period: GoalPeriod,
+        goals: &ActivityGoals,
+    ) -> Result<ActivityGoals, FitbitError> {
+        if period == GoalPeriod::Weekly
+            && (goals.calories_out.is_some() || goals.active_minutes.is_some())
+        {
+            return Err(FitbitError::InvalidArgument(
+                "weekly goals don't support calories out or active minutes".to_string(),
+            ));
+        }
+        let form = goals.to_form();
+        if form.is_empty() {
+            return Err(FitbitError::InvalidArgument(
+                "at least one goal must be set".to_string(),
+            ));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities/goals/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            period.as_str()
+        );
+
+        self.make_api_post_request::<ActivityGoalsResponse>(&url, &form)
+            .map(|response| response.goals)
+    }
+
+    fn browse_activity_types(&self) -> Result<ActivityCatalog, FitbitError> {
+        let url = format!("{}/{}/activities.json", API_BASE_URL, ACTIVITY_API_VERSION);
+
+        self.make_api_request(&url)
+    }
+
+    fn get_activity_type(&self, id: u64) -> Result<ActivityDefinition, FitbitError> {
+        let url = format!(
+            "{}/{}/activities/{}.json",
+            API_BASE_URL, ACTIVITY_API_VERSION, id
+        );
+
+        self.make_api_request::<ActivityDefinitionResponse>(&url)
+            .map(|response| response.activity)
+    }
+
+    fn fetch_sleep_log_list(
+        &self,
+        filter: DateFilter,
+        sort: SortOrder,
+        limit: u32,
+    ) -> Result<SleepLogListResponse, FitbitError> {
+        let path = format!("{}/user/-/sleep/list.json", SLEEP_API_VERSION);
+        let url = Self::list_url(&path, filter, sort, limit)?;
+
+        Self::checked(self.make_api_request(&url)?, &url)
+    }
+
+    fn fetch_next_sleep_log_list(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<Option<SleepLogListResponse>, FitbitError> {
+        self.fetch_next_page(pagination)?
+            .map(|page| Self::checked(page, pagination.next_url().unwrap_or_default()))
+            .transpose()
+    }
+
+    fn fetch_profile(&self) -> Result<ProfileResponse, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/profile.json",
+            API_BASE_URL, PROFILE_API_VERSION
+        );
+
+        self.make_api_request(&url)
+    }
+
+    fn fetch_heart_rate(&self, date: NaiveDate) -> Result<HeartRateDay, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/activities/heart/date/{}/1d.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        let day = self
+            .make_api_request::<HeartRateTimeSeriesResponse>(&url)?
+            .into_day()?;
+
+        Self::checked(day, &url)
+    }
+
+    fn fetch_heart_rate_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<HeartRateDay>, FitbitError> {
+        let days = (end - start).num_days() + 1;
+        if !(1..=MAX_HEART_RATE_RANGE_DAYS).contains(&days) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "heart rate range must span 1 to {} days, got {} to {}",
+                MAX_HEART_RATE_RANGE_DAYS, start, end
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities/heart/date/{}/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        let days = self
+            .make_api_request::<HeartRateTimeSeriesResponse>(&url)?
+            .into_days();
+
+        Self::checked(days, &url)
+    }
+
+    fn fetch_heart_rate_intraday(
+        &self,
+        date: NaiveDate,
+        detail_level: HeartRateDetailLevel,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<IntradayHeartRate, FitbitError> {
+        if end_time < start_time {
+            return Err(FitbitError::InvalidArgument(format!(
+                "heart rate time window must not end before it starts, got {} to {}",
+                start_time, end_time
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/activities/heart/date/{}/1d/{}/time/{}/{}.json",
+            API_BASE_URL,
+            ACTIVITY_API_VERSION,
+            date.format("%Y-%m-%d"),
+            detail_level.as_str(),
+            start_time.format("%H:%M"),
+            end_time.format("%H:%M")
+        );
+
+        self.make_api_request::<HeartRateIntradayResponse>(&url)
+            .map(|response| response.into_intraday(date))
+    }
+
+    fn fetch_breathing_rate(&self, date: NaiveDate) -> Result<Option<BreathingRate>, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/br/date/{}.json",
+            API_BASE_URL,
+            BREATHING_RATE_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<BreathingRateResponse>(&url)
+            .map(BreathingRateResponse::into_breathing_rate)
+    }
+
+    fn fetch_breathing_rate_intraday(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<BreathingRateByStage>, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/br/date/{}/all.json",
+            API_BASE_URL,
+            BREATHING_RATE_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<BreathingRateIntradayResponse>(&url)
+            .map(BreathingRateIntradayResponse::into_breathing_rate_by_stage)
+    }
+
+    fn fetch_weight_logs(&self, date: NaiveDate) -> Result<Vec<WeightLog>, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/body/log/weight/date/{}.json",
+            API_BASE_URL,
+            BODY_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<WeightLogResponse>(&url)
+            .map(|response| self.with_weight_unit_system(response.weight))
+    }
+
+    fn fetch_weight_logs_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<WeightLog>, FitbitError> {
+        let days = (end - start).num_days() + 1;
+        if !(1..=MAX_WEIGHT_LOG_RANGE_DAYS).contains(&days) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "weight log range must span 1 to {} days, got {} to {}",
+                MAX_WEIGHT_LOG_RANGE_DAYS, start, end
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/body/log/weight/date/{}/{}.json",
+            API_BASE_URL,
+            BODY_API_VERSION,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<WeightLogResponse>(&url)
+            .map(|response| self.with_weight_unit_system(response.weight))
+    }
+
+    fn fetch_weight_time_series(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, f64)>, FitbitError> {
+        let days = (end - start).num_days() + 1;
+        if !(1..=MAX_WEIGHT_TIME_SERIES_DAYS).contains(&days) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "weight time series range must span 1 to {} days, got {} to {}",
+                MAX_WEIGHT_TIME_SERIES_DAYS, start, end
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/body/weight/date/{}/{}.json",
+            API_BASE_URL,
+            BODY_API_VERSION,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<WeightTimeSeriesResponse>(&url)
+            .map(WeightTimeSeriesResponse::into_values)
+    }
+
+    fn log_weight(
+        &self,
+        weight: f64,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<WeightLog, FitbitError> {
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "weight must be positive, got {}",
+                weight
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/body/log/weight.json",
+            API_BASE_URL, BODY_API_VERSION
+        );
+        let form = [
+            ("weight", weight.to_string()),
+            ("date", date.format("%Y-%m-%d").to_string()),
+            ("time", time.format("%H:%M:%S").to_string()),
+        ];
+
+        self.make_api_post_request::<CreatedWeightLog>(&url, &form)
+            .map(|created| {
+                let mut log = created.weight_log;
+                log.unit_system = self.unit_system;
+                log
+            })
+    }
+
+    fn delete_weight_log(&self, log_id: u64) -> Result<(), FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/body/log/weight/{}.json",
+            API_BASE_URL, BODY_API_VERSION, log_id
+        );
+
+        self.make_api_delete_request(&url)
+    }
+
+    fn fetch_food_logs(&self, date: NaiveDate) -> Result<FoodLogResponse, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log/date/{}.json",
+            API_BASE_URL,
+            FOOD_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        let mut response: FoodLogResponse = self.make_api_request(&url)?;
+        response.unit_system = self.unit_system;
+
+        Ok(response)
+    }
+
+    fn fetch_water_logs(&self, date: NaiveDate) -> Result<WaterLogs, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log/water/date/{}.json",
+            API_BASE_URL,
+            FOOD_API_VERSION,
+            date.format("%Y-%m-%d")
+        );
+
+        self.make_api_request::<WaterLogResponse>(&url)
+            .map(|response| response.into_water_logs(date, self.unit_system.water_unit()))
+    }
+
+    fn log_water(
+        &self,
+        amount: f64,
+        unit: VolumeUnit,
+        date: NaiveDate,
+    ) -> Result<WaterLog, FitbitError> {
+        if !(amount.is_finite() && amount > 0.0) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "water amount must be positive, got {}",
+                amount
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/foods/log/water.json",
+            API_BASE_URL, FOOD_API_VERSION
+        );
+        let form = [
+            ("amount", amount.to_string()),
+            ("date", date.format("%Y-%m-%d").to_string()),
+            ("unit", unit.symbol().to_string()),
+        ];
+
+        // The amount is echoed back in the locale's unit; report it in the logged unit
+        self.make_api_post_request::<CreatedWaterLog>(&url, &form)
+            .map(|created| WaterLog {
+                log_id: created.water_log.log_id,
+                amount: VolumeValue::new(amount, unit),
+            })
+    }
+
+    fn delete_water_log(&self, log_id: u64) -> Result<(), FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log/water/{}.json",
+            API_BASE_URL, FOOD_API_VERSION, log_id
+        );
+
+        self.make_api_delete_request(&url)
+    }
+
+    fn fetch_food_goals(&self) -> Result<FoodGoalResponse, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log/goal.json",
+            API_BASE_URL, FOOD_API_VERSION
+        );
+
+        self.make_api_request(&url)
+    }
+
+    fn update_food_goals(&self, update: FoodGoalUpdate) -> Result<FoodGoalResponse, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log/goal.json",
+            API_BASE_URL, FOOD_API_VERSION
+        );
+
+        self.make_api_post_request(&url, &update.to_form())
+    }
+
+    fn fetch_water_goal(&self) -> Result<WaterGoal, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log/water/goal.json",
+            API_BASE_URL, FOOD_API_VERSION
+        );
+
+        self.make_api_request::<WaterGoalResponse>(&url)
+            .map(|response| response.into_water_goal(self.unit_system.water_unit()))
+    }
+
+    fn update_water_goal(&self, target: f64) -> Result<WaterGoal, FitbitError> {
+        if !(target.is_finite() && target > 0.0) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "water goal must be positive, got {}",
+                target
+            )));
+        }
+
+        let url = format!(
+            "{}/{}/user/-/foods/log/water/goal.json",
+            API_BASE_URL, FOOD_API_VERSION
+        );
+        let form = [("target", target.to_string())];
+
+        self.make_api_post_request::<WaterGoalResponse>(&url, &form)
+            .map(|response| response.into_water_goal(self.unit_system.water_unit()))
+    }
+
+    fn fetch_meals(&self) -> Result<Vec<Meal>, FitbitError> {
+        let url = format!("{}/{}/user/-/meals.json", API_BASE_URL, FOOD_API_VERSION);
+
+        self.make_api_request::<MealListResponse>(&url)
+            .map(|response| response.meals)
+    }
+
+    fn fetch_meal(&self, meal_id: u64) -> Result<Meal, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/meals/{}.json",
+            API_BASE_URL, FOOD_API_VERSION, meal_id
+        );
+
+        self.make_api_request::<MealResponse>(&url)
+            .map(|response| response.meal)
+    }
+
+    fn create_meal(&self, request: &MealRequest) -> Result<Meal, FitbitError> {
+        request.validate().map_err(FitbitError::InvalidArgument)?;
+
+        let url = format!("{}/{}/user/-/meals.json", API_BASE_URL, FOOD_API_VERSION);
+
+        self.make_api_json_post_request::<MealResponse, _>(&url, request)
+            .map(|response| response.meal)
+    }
+
+    fn update_meal(&self, meal_id: u64, request: &MealRequest) -> Result<Meal, FitbitError> {
+        request.validate().map_err(FitbitError::InvalidArgument)?;
+
+        let url = format!(
+            "{}/{}/user/-/meals/{}.json",
+            API_BASE_URL, FOOD_API_VERSION, meal_id
+        );
+
+        self.make_api_json_post_request::<MealResponse, _>(&url, request)
+            .map(|response| response.meal)
+    }
+
+    fn delete_meal(&self, meal_id: u64) -> Result<(), FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/meals/{}.json",
+            API_BASE_URL, FOOD_API_VERSION, meal_id
+        );
+
+        self.make_api_delete_request(&url)
+    }
+
+    fn log_meal(
+        &self,
+        meal: &Meal,
+        meal_type: MealType,
+        date: NaiveDate,
+    ) -> Result<Vec<FoodLog>, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/foods/log.json",
+            API_BASE_URL, FOOD_API_VERSION
+        );
+
+        meal.meal_foods
+            .iter()
+            .map(|food| {
+                let form = [
+                    ("foodId", food.food_id.to_string()),
+                    ("mealTypeId", meal_type.id().to_string()),
+                    ("unitId", food.unit.id.to_string()),
+                    ("amount", food.amount.to_string()),
+                    ("date", date.format("%Y-%m-%d").to_string()),
+                ];
+
+                self.make_api_post_request::<CreatedFoodLog>(&url, &form)
+                    .map(|created| created.food_log)
+            })
+            .collect()
+    }
+
+    fn fetch_devices(&self) -> Result<Vec<Device>, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/devices.json",
+            API_BASE_URL, DEVICES_API_VERSION
+        );
+
+        self.make_api_request::<Vec<Device>>(&url)
+    }
+
+    fn fetch_alarms(&self, tracker_id: &str) -> Result<Vec<Alarm>, FitbitError> {
+        let url = Self::alarms_url(tracker_id, None)?;
+
+        self.make_api_request::<AlarmListResponse>(&url)
+            .map(|response| response.tracker_alarms)
+    }
+
+    fn add_alarm(&self, tracker_id: &str, request: &AlarmRequest) -> Result<Alarm, FitbitError> {
+        request.validate().map_err(FitbitError::InvalidArgument)?;
+
+        let url = Self::alarms_url(tracker_id, None)?;
+
+        self.make_api_post_request::<AlarmResponse>(&url, &request.to_add_form())
+            .map(|response| response.tracker_alarm)
+    }
+
+    fn update_alarm(
+        &self,
+        tracker_id: &str,
+        alarm_id: u64,
+        request: &AlarmRequest,
+    ) -> Result<Alarm, FitbitError> {
+        request.validate().map_err(FitbitError::InvalidArgument)?;
+
+        let url = Self::alarms_url(tracker_id, Some(alarm_id))?;
+
+        self.make_api_post_request::<AlarmResponse>(&url, &request.to_update_form())
+            .map(|response| response.tracker_alarm)
+    }
+
+    fn delete_alarm(&self, tracker_id: &str, alarm_id: u64) -> Result<(), FitbitError> {
+        let url = Self::alarms_url(tracker_id, Some(alarm_id))?;
+
+        self.make_api_delete_request(&url)
+    }
+
+    fn fetch_friend_invitations(&self) -> Result<Vec<FriendInvitation>, FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/friends/invitations.json",
+            API_BASE_URL, FRIENDS_API_VERSION
+        );
+
+        self.make_api_request::<FriendInvitationResponse>(&url)
+            .map(FriendInvitationResponse::into_invitations)
+    }
+
+    fn send_friend_invitation(&self, invitee: &Invitee) -> Result<(), FitbitError> {
+        let url = format!(
+            "{}/{}/user/-/friends/invitations.json",
+            API_BASE_URL, FRIENDS_API_VERSION
+        );
+
+        self.make_api_post_request_without_response(&url, &invitee.to_form())
+    }
+
+    fn respond_to_friend_invitation(
+        &self,
+        from_user_id: &str,
+        accept: bool,
+    ) -> Result<(), FitbitError> {
+        let url = Self::friend_invitation_url(from_user_id)?;
+        let form = [("accept", accept.to_string())];
+
+        self.make_api_post_request_without_response(&url, &form)
+    }
+}
+
+/// Percent-encodes every byte of a path segment except the unreserved characters of RFC 3986
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_url() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        let url = FitbitClient::list_url(
+            "1.2/user/-/sleep/list.json",
+            DateFilter::Before(date),
+            SortOrder::Descending,
+            10,
+        )
+        .unwrap();
+        assert_eq!(
+            url,
+            "https://api.fitbit.com/1.2/user/-/sleep/list.json?beforeDate=2024-01-01&sort=desc&offset=0&limit=10"
+        );
+    }
+
+    #[test]
+    fn test_list_url_rejects_invalid_arguments() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let path = "1.2/user/-/sleep/list.json";
+
+        assert!(
+            FitbitClient::list_url(path, DateFilter::After(date), SortOrder::Descending, 10)
+                .is_err()
+        );
+        assert!(
+            FitbitClient::list_url(path, DateFilter::After(date), SortOrder::Ascending, 0).is_err()
+        );
+        assert!(
+            FitbitClient::list_url(path, DateFilter::After(date), SortOrder::Ascending, 101)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_alarms_url_encodes_tracker_id() {
+        assert_eq!(
+            FitbitClient::alarms_url("123456789", None).unwrap(),
+            "https://api.fitbit.com/1/user/-/devices/tracker/123456789/alarms.json"
+        );
+        assert_eq!(
+            FitbitClient::alarms_url("../../profile.json?x=1", Some(42)).unwrap(),
+            "https://api.fitbit.com/1/user/-/devices/tracker/..%2F..%2Fprofile.json%3Fx%3D1/alarms/42.json"
+        );
+        assert_eq!(encode_path_segment("Charge 6 ü"), "Charge%206%20%C3%BC");
+    }
+
+    #[test]
+    fn test_alarms_url_rejects_dot_segments() {
+        for tracker_id in ["", ".", ".."] {
+            assert!(matches!(
+                FitbitClient::alarms_url(tracker_id, None),
+                Err(FitbitError::InvalidArgument(_))
+            ));
+        }
+        assert!(FitbitClient::alarms_url("...", Some(42)).is_ok());
+    }
+
+    #[test]
+    fn test_friend_invitation_url_encodes_user_id() {
+        assert_eq!(
+            FitbitClient::friend_invitation_url("257V3V").unwrap(),
+            "https://api.fitbit.com/1.1/user/-/friends/invitations/257V3V.json"
+        );
+        assert_eq!(
+            FitbitClient::friend_invitation_url("../profile.json?x=1#y").unwrap(),
+            "https://api.fitbit.com/1.1/user/-/friends/invitations/..%2Fprofile.json%3Fx%3D1%23y.json"
+        );
+    }
+
+    #[test]
+    fn test_friend_invitation_url_rejects_empty_user_id() {
+        assert!(matches!(
+            FitbitClient::friend_invitation_url(""),
+            Err(FitbitError::InvalidArgument(_))
+        ));
     }
 }
@@ -0,0 +1,461 @@
+//! Builders for constructing responses in tests.
+//!
+//! The response types mirror the API's JSON and have many fields, which makes fake data
+//! tedious to write out by hand. These builders fill in consistent defaults, so tests only
+//! need to set the values they care about.
+
+use crate::activity_summary::{
+    Activity, ActivitySummaryResponse, ActivityType, Distance, Goals, HeartRateZone,
+    HeartRateZoneName, Summary,
+};
+use crate::sleep::{
+    LevelData, LevelSummary, LevelsSummary, SleepData, SleepLevel, SleepLevels, SleepLogType,
+    SleepResponseV1_2, SleepSummary, SleepType, StagesSummary,
+};
+use crate::units::UnitSystem;
+use chrono::NaiveDateTime;
+
+/// Builds a sleep record from consecutive sleep stages
+///
+/// The durations, minutes asleep and awake, efficiency and level summaries are derived
+/// from the stages, so they are always consistent with each other.
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use fitbit_rs::{SleepDataBuilder, SleepLevel};
+///
+/// let start = NaiveDate::from_ymd_opt(2025, 3, 1)
+///     .unwrap()
+///     .and_hms_opt(23, 0, 0)
+///     .unwrap();
+/// let sleep = SleepDataBuilder::new(start)
+///     .stage(SleepLevel::Wake, 10)
+///     .stage(SleepLevel::Light, 240)
+///     .stage(SleepLevel::Deep, 90)
+///     .stage(SleepLevel::Rem, 100)
+///     .build();
+///
+/// assert_eq!(sleep.minutes_asleep, 430);
+/// assert_eq!(sleep.time_in_bed, 440);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SleepDataBuilder {
+    start_time: NaiveDateTime,
+    stages: Vec<(SleepLevel, u32)>,
+    log_id: u64,
+    is_main_sleep: bool,
+    log_type: SleepLogType,
+    sleep_type: SleepType,
+    efficiency: Option<u8>,
+    minutes_to_fall_asleep: u32,
+}
+
+impl SleepDataBuilder {
+    /// Creates a builder for an auto-detected main sleep in stages format
+    ///
+    /// # Arguments
+    ///
+    /// * `start_time` - When the record starts, in the user's local time
+    pub fn new(start_time: NaiveDateTime) -> Self {
+        Self {
+            start_time,
+            stages: Vec::new(),
+            log_id: 1,
+            is_main_sleep: true,
+            log_type: SleepLogType::AutoDetected,
+            sleep_type: SleepType::Stages,
+            efficiency: None,
+            minutes_to_fall_asleep: 0,
+        }
+    }
+
+    /// Appends a stage of the given length after the previous one
+    pub fn stage(mut self, level: SleepLevel, minutes: u32) -> Self {
+        self.stages.push((level, minutes));
+        self
+    }
+
+    pub fn log_id(mut self, log_id: u64) -> Self {
+        self.log_id = log_id;
+        self
+    }
+
+    /// Marks the record as a nap instead of the main sleep
+    pub fn nap(mut self) -> Self {
+        self.is_main_sleep = false;
+        self
+    }
+
+    pub fn log_type(mut self, log_type: SleepLogType) -> Self {
+        self.log_type = log_type;
+        self
+    }
+
+    /// Sets the level format; use the classic levels (asleep, restless, awake) for
+    /// `SleepType::Classic`
+    pub fn sleep_type(mut self, sleep_type: SleepType) -> Self {
+        self.sleep_type = sleep_type;
+        self
+    }
+
+    /// Overrides the efficiency, which defaults to the share of time in bed spent asleep
+    pub fn efficiency(mut self, efficiency: u8) -> Self {
+        self.efficiency = Some(efficiency);
+        self
+    }
+
+    pub fn minutes_to_fall_asleep(mut self, minutes: u32) -> Self {
+        self.minutes_to_fall_asleep = minutes;
+        self
+    }
+
+    /// Builds the sleep record
+    pub fn build(self) -> SleepData {
+        let mut data = Vec::with_capacity(self.stages.len());
+        let mut summary = LevelsSummary::default();
+        let mut date_time = self.start_time;
+        for (level, minutes) in self.stages {
+            let level_summary = match level {
+                SleepLevel::Deep => Some(&mut summary.deep),
+                SleepLevel::Light => Some(&mut summary.light),
+                SleepLevel::Rem => Some(&mut summary.rem),
+                SleepLevel::Wake => Some(&mut summary.wake),
+                SleepLevel::Asleep => Some(&mut summary.asleep),
+                SleepLevel::Restless => Some(&mut summary.restless),
+                SleepLevel::Awake => Some(&mut summary.awake),
+                SleepLevel::Unknown | SleepLevel::Other(_) => None,
+            };
+            if let Some(level_summary) = level_summary {
+                level_summary.count += 1;
+                level_summary.minutes += minutes;
+            }
+
+            data.push(LevelData {
+                date_time,
+                level,
+                seconds: minutes * 60,
+            });
+            date_time += chrono::Duration::minutes(minutes as i64);
+        }
+
+        let time_in_bed: u32 = data.iter().map(|level| level.seconds / 60).sum();
+        let minutes_asleep: u32 = data
+            .iter()
+            .filter(|level| level.is_sleep())
+            .map(|level| level.seconds / 60)
+            .sum();
+        let efficiency = self
+            .efficiency
+            .unwrap_or_else(|| (minutes_asleep * 100).checked_div(time_in_bed).unwrap_or(0) as u8);
+
+        SleepData {
+            date_of_sleep: date_time.date(),
+            duration: time_in_bed as u64 * 60_000,
+            efficiency: Some(efficiency),
+            end_time: date_time,
+            info_code: 0,
+            is_main_sleep: self.is_main_sleep,
+            levels: SleepLevels {
+                data,
+                short_data: Vec::new(),
+                summary,
+            },
+            log_id: self.log_id,
+            log_type: self.log_type,
+            minutes_after_wakeup: 0,
+            minutes_asleep,
+            minutes_awake: time_in_bed - minutes_asleep,
+            minutes_to_fall_asleep: self.minutes_to_fall_asleep,
+            start_time: self.start_time,
+            time_in_bed,
+            sleep_type: self.sleep_type,
+        }
+    }
+
+    /// Builds a daily sleep response containing only this record
+    pub fn build_response(self) -> SleepResponseV1_2 {
+        let sleep = self.build();
+        let minutes = |summary: &LevelSummary| summary.minutes;
+        let stages = match sleep.sleep_type {
+            SleepType::Stages => StagesSummary {
+                deep: minutes(&sleep.levels.summary.deep),
+                light: minutes(&sleep.levels.summary.light),
+                rem: minutes(&sleep.levels.summary.rem),
+                wake: minutes(&sleep.levels.summary.wake),
+            },
+            _ => StagesSummary::default(),
+        };
+
+        SleepResponseV1_2 {
+            summary: SleepSummary {
+                stages,
+                total_minutes_asleep: sleep.minutes_asleep,
+                total_sleep_records: 1,
+                total_time_in_bed: sleep.time_in_bed,
+            },
+            sleep: vec![sleep],
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+/// Builds a daily activity summary
+///
+/// Starts from a day without any activity and the default Fitbit goals.
+///
+/// # Example
+///
+/// ```
+/// use fitbit_rs::{ActivitySummaryBuilder, HeartRateZoneName};
+///
+/// let response = ActivitySummaryBuilder::new()
+///     .steps(12_000)
+///     .distance(9.1)
+///     .heart_rate_zone(HeartRateZoneName::Cardio, 25, 142, 176)
+///     .build();
+///
+/// assert_eq!(response.get_steps(), 12_000);
+/// ```
+#[derive(Debug)]
+pub struct ActivitySummaryBuilder {
+    response: ActivitySummaryResponse,
+}
+
+impl ActivitySummaryBuilder {
+    /// Creates a builder for a day without any activity
+    pub fn new() -> Self {
+        Self {
+            response: ActivitySummaryResponse {
+                activities: Vec::new(),
+                summary: Summary {
+                    calories_out: 0,
+                    activity_calories: 0,
+                    calories_bmr: 0,
+                    active_score: None,
+                    steps: 0,
+                    floors: Some(0),
+                    elevation: Some(0.0),
+                    sedentary_minutes: 0,
+                    lightly_active_minutes: 0,
+                    fairly_active_minutes: 0,
+                    very_active_minutes: 0,
+                    distances: vec![Distance {
+                        activity: ActivityType::Total,
+                        distance: 0.0,
+                    }],
+                    marginal_calories: 0,
+                    resting_heart_rate: None,
+                    heart_rate_zones: Vec::new(),
+                    custom_heart_rate_zones: Vec::new(),
+                    use_estimation: false,
+                    calories_out_unestimated: None,
+                    calories_estimation_mu: None,
+                },
+                goals: Goals {
+                    calories_out: 2500,
+                    steps: 10_000,
+                    distance: 8.05,
+                    floors: Some(10),
+                    active_minutes: 30,
+                },
+                unit_system: UnitSystem::default(),
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            },
+        }
+    }
+
+    pub fn steps(mut self, steps: u32) -> Self {
+        self.response.summary.steps = steps;
+        self
+    }
+
+    /// Sets the calories burned, including the basal metabolic rate
+    pub fn calories_out(mut self, calories: i32) -> Self {
+        self.response.summary.calories_out = calories;
+        self
+    }
+
+    /// Sets the total distance, in the distance unit of the unit system
+    pub fn distance(mut self, distance: f64) -> Self {
+        let distances = &mut self.response.summary.distances;
+        match distances
+            .iter_mut()
+            .find(|distance| distance.activity == ActivityType::Total)
+        {
+            Some(total) => total.distance = distance,
+            None => distances.push(Distance {
+                activity: ActivityType::Total,
+                distance,
+            }),
+        }
+        self
+    }
+
+    /// Sets the floors climbed, and the elevation to 3.048 meters per floor; `None` for
+    /// devices without an altimeter
+    pub fn floors(mut self, floors: Option<i32>) -> Self {
+        self.response.summary.floors = floors;
+        self.response.summary.elevation = floors.map(|floors| floors as f64 * 3.048);
+        self
+    }
+
+    pub fn resting_heart_rate(mut self, resting_heart_rate: i32) -> Self {
+        self.response.summary.resting_heart_rate = Some(resting_heart_rate);
+        self
+    }
+
+    /// Sets the minutes spent at each activity level
+    pub fn activity_minutes(
+        mut self,
+        sedentary: i32,
+        lightly: i32,
+        fairly: i32,
+        very: i32,
+    ) -> Self {
+        let summary = &mut self.response.summary;
+        summary.sedentary_minutes = sedentary;
+        summary.lightly_active_minutes = lightly;
+        summary.fairly_active_minutes = fairly;
+        summary.very_active_minutes = very;
+        self
+    }
+
+    /// Adds the minutes spent in a heart rate zone, replacing earlier minutes of the zone
+    pub fn heart_rate_zone(
+        mut self,
+        name: HeartRateZoneName,
+        minutes: i32,
+        min: i32,
+        max: i32,
+    ) -> Self {
+        let zones = &mut self.response.summary.heart_rate_zones;
+        zones.retain(|zone| zone.name != name);
+        zones.push(HeartRateZone {
+            minutes,
+            calories_out: 0.0,
+            display_name: name.as_str().to_string(),
+            name,
+            min,
+            max,
+        });
+        zones.sort_by(|a, b| a.name.cmp(&b.name));
+        self
+    }
+
+    /// Adds a logged activity
+    pub fn activity(mut self, activity: Activity) -> Self {
+        self.response.activities.push(activity);
+        self
+    }
+
+    pub fn goals(mut self, goals: Goals) -> Self {
+        self.response.goals = goals;
+        self
+    }
+
+    /// Sets the unit system the distances are in
+    pub fn unit_system(mut self, unit_system: UnitSystem) -> Self {
+        self.response.unit_system = unit_system;
+        self
+    }
+
+    /// Builds the activity summary
+    pub fn build(self) -> ActivitySummaryResponse {
+        self.response
+    }
+}
+
+impl Default for ActivitySummaryBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a day of March 2025, the month most tests are set in; March 3 is a Monday
+#[cfg(test)]
+pub(crate) fn date(day: u32) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sleep::{SleepRecord, SleepResponse};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_sleep_data_builder() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1)
+            .unwrap()
+            .and_hms_opt(23, 30, 0)
+            .unwrap();
+        let response = SleepDataBuilder::new(start)
+            .stage(SleepLevel::Wake, 10)
+            .stage(SleepLevel::Light, 200)
+            .stage(SleepLevel::Deep, 60)
+            .stage(SleepLevel::Wake, 5)
+            .stage(SleepLevel::Rem, 85)
+            .build_response();
+
+        let sleep = &response.sleep[0];
+        assert_eq!(
+            sleep.date_of_sleep,
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+        );
+        assert_eq!(
+            sleep.end_time,
+            NaiveDate::from_ymd_opt(2025, 3, 2)
+                .unwrap()
+                .and_hms_opt(5, 30, 0)
+                .unwrap()
+        );
+        assert_eq!(sleep.time_in_bed, 360);
+        assert_eq!(sleep.minutes_asleep, 345);
+        assert_eq!(sleep.minutes_awake, 15);
+        assert_eq!(sleep.efficiency, Some(95));
+        assert_eq!(sleep.levels.summary.wake.count, 2);
+        assert_eq!(sleep.levels.summary.wake.minutes, 15);
+        assert_eq!(
+            sleep.get_time_fell_asleep(),
+            Some(start + chrono::Duration::minutes(10))
+        );
+
+        assert_eq!(response.summary.stages.deep, 60);
+        assert_eq!(response.summary.total_minutes_asleep, 345);
+        assert_eq!(
+            response.get_total_duration_asleep(),
+            chrono::Duration::minutes(345)
+        );
+    }
+
+    #[test]
+    fn test_activity_summary_builder() {
+        let response = ActivitySummaryBuilder::new()
+            .steps(8_000)
+            .distance(5.6)
+            .floors(None)
+            .heart_rate_zone(HeartRateZoneName::Peak, 5, 177, 220)
+            .heart_rate_zone(HeartRateZoneName::Cardio, 20, 142, 176)
+            .heart_rate_zone(HeartRateZoneName::Cardio, 25, 142, 176)
+            .build();
+
+        assert_eq!(response.get_steps(), 8_000);
+        assert_eq!(response.summary.distances.len(), 1);
+        assert_eq!(response.summary.distances[0].distance, 5.6);
+        assert_eq!(response.summary.elevation, None);
+        assert_eq!(
+            response
+                .summary
+                .minutes_in_or_above(HeartRateZoneName::Cardio),
+            30
+        );
+        assert_eq!(
+            response.summary.heart_rate_zones[0].name,
+            HeartRateZoneName::Cardio
+        );
+    }
+}
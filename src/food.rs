@@ -0,0 +1,559 @@
+//! Food logs and nutrition.
+
+use crate::activity_summary::Percentage;
+use crate::units::{UnitSystem, VolumeValue};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Energy per gram of protein and carbohydrates in kcal
+const CALORIES_PER_GRAM_PROTEIN_OR_CARBS: f64 = 4.0;
+
+/// Energy per gram of fat in kcal
+const CALORIES_PER_GRAM_FAT: f64 = 9.0;
+
+/// The foods logged on a day with the day's nutrition totals
+/// (`/1/user/-/foods/log/date/{date}.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoodLogResponse {
+    pub foods: Vec<FoodLog>,
+    /// Not reported if the user has no calorie goal
+    pub goals: Option<FoodGoals>,
+    pub summary: NutritionSummary,
+    /// The unit system the response was requested in, which determines the unit of
+    /// water; set by the client
+    #[serde(default, rename = "unitSystem")]
+    pub unit_system: UnitSystem,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FoodLogResponse {
+    /// Returns the calories eaten on the day
+    pub fn total_calories(&self) -> f64 {
+        self.summary.calories
+    }
+
+    /// Returns the calories eaten on the day as an energy
+    #[cfg(feature = "uom")]
+    pub fn energy_in(&self) -> uom::si::f64::Energy {
+        crate::units::energy(self.summary.calories)
+    }
+
+    /// Returns the water drunk on the day
+    pub fn water(&self) -> VolumeValue {
+        VolumeValue::new(self.summary.water, self.unit_system.water_unit())
+    }
+
+    /// Returns the calories left until the calorie intake goal is reached, negative if it
+    /// was exceeded, or `None` if no goal is set
+    pub fn remaining_calories(&self) -> Option<f64> {
+        self.goals
+            .as_ref()
+            .map(|goals| goals.calories as f64 - self.summary.calories)
+    }
+
+    /// Returns the share of protein, carbohydrates and fat in the energy of the day's
+    /// macronutrients, or `None` if no macronutrients were logged
+    ///
+    /// The energy is estimated with 4 kcal per gram of protein and carbohydrates and 9 kcal
+    /// per gram of fat, so the shares add up to 100% even if the logged calories don't match.
+    pub fn macro_split(&self) -> Option<MacroSplit> {
+        let protein = self.summary.protein * CALORIES_PER_GRAM_PROTEIN_OR_CARBS;
+        let carbs = self.summary.carbs * CALORIES_PER_GRAM_PROTEIN_OR_CARBS;
+        let fat = self.summary.fat * CALORIES_PER_GRAM_FAT;
+        let total = protein + carbs + fat;
+
+        Some(MacroSplit {
+            protein: Percentage::of(protein, total)?,
+            carbs: Percentage::of(carbs, total)?,
+            fat: Percentage::of(fat, total)?,
+        })
+    }
+
+    /// Returns the nutrition of the logged foods per meal type
+    ///
+    /// Foods without nutrition data only contribute their calories.
+    pub fn meal_subtotals(&self) -> BTreeMap<MealType, NutritionalValues> {
+        let mut subtotals: BTreeMap<MealType, NutritionalValues> = BTreeMap::new();
+        for food in &self.foods {
+            let subtotal = subtotals.entry(food.logged_food.meal_type).or_default();
+            match &food.nutritional_values {
+                Some(values) => subtotal.add(values),
+                None => subtotal.calories += food.logged_food.calories as f64,
+            }
+        }
+
+        subtotals
+    }
+}
+
+/// Share of each macronutrient in the energy of a day's macronutrients
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacroSplit {
+    pub protein: Percentage,
+    pub carbs: Percentage,
+    pub fat: Percentage,
+}
+
+/// A logged food
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoodLog {
+    pub log_id: u64,
+    pub log_date: NaiveDate,
+    pub is_favorite: bool,
+    pub logged_food: LoggedFood,
+    /// Nutrition of the logged amount; not reported for foods without nutrition data
+    pub nutritional_values: Option<NutritionalValues>,
+}
+
+/// The food and amount of a food log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggedFood {
+    pub food_id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub brand: String,
+    /// Amount in `unit`
+    pub amount: f64,
+    pub unit: FoodUnit,
+    pub calories: u32,
+    #[serde(rename = "mealTypeId")]
+    pub meal_type: MealType,
+    /// `PUBLIC` for foods from the database, `PRIVATE` for the user's custom foods
+    pub access_level: String,
+}
+
+/// Response of the create food log endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatedFoodLog {
+    pub food_log: FoodLog,
+}
+
+/// A unit foods can be logged in, e.g. `cup` or `oz`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoodUnit {
+    pub id: u32,
+    pub name: String,
+    pub plural: String,
+}
+
+/// The meal a food was logged for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "u32", into = "u32")]
+pub enum MealType {
+    Breakfast,
+    MorningSnack,
+    Lunch,
+    AfternoonSnack,
+    Dinner,
+    Anytime,
+    /// A meal type id this crate doesn't know yet
+    Other(u32),
+}
+
+impl MealType {
+    /// Returns the meal type id used by the API
+    pub fn id(&self) -> u32 {
+        match self {
+            MealType::Breakfast => 1,
+            MealType::MorningSnack => 2,
+            MealType::Lunch => 3,
+            MealType::AfternoonSnack => 4,
+            MealType::Dinner => 5,
+            MealType::Anytime => 7,
+            MealType::Other(id) => *id,
+        }
+    }
+}
+
+impl From<MealType> for u32 {
+    fn from(meal_type: MealType) -> Self {
+        meal_type.id()
+    }
+}
+
+impl From<u32> for MealType {
+    fn from(id: u32) -> Self {
+        match id {
+            1 => MealType::Breakfast,
+            2 => MealType::MorningSnack,
+            3 => MealType::Lunch,
+            4 => MealType::AfternoonSnack,
+            5 => MealType::Dinner,
+            7 => MealType::Anytime,
+            id => MealType::Other(id),
+        }
+    }
+}
+
+/// Nutrition of a logged food; nutrients without data are zero
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NutritionalValues {
+    pub calories: f64,
+    /// Carbohydrates in grams
+    pub carbs: f64,
+    /// Fat in grams
+    pub fat: f64,
+    /// Fiber in grams
+    pub fiber: f64,
+    /// Protein in grams
+    pub protein: f64,
+    /// Sodium in milligrams
+    pub sodium: f64,
+}
+
+impl NutritionalValues {
+    fn add(&mut self, other: &NutritionalValues) {
+        self.calories += other.calories;
+        self.carbs += other.carbs;
+        self.fat += other.fat;
+        self.fiber += other.fiber;
+        self.protein += other.protein;
+        self.sodium += other.sodium;
+    }
+}
+
+/// Nutrition totals of a day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NutritionSummary {
+    pub calories: f64,
+    /// Carbohydrates in grams
+    pub carbs: f64,
+    /// Fat in grams
+    pub fat: f64,
+    /// Fiber in grams
+    pub fiber: f64,
+    /// Protein in grams
+    pub protein: f64,
+    /// Sodium in milligrams
+    pub sodium: f64,
+    /// Water in the client's water unit; see [`FoodLogResponse::water`]
+    pub water: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoodGoals {
+    /// Daily calorie intake goal
+    pub calories: u32,
+}
+
+/// The calorie intake goal with the food plan it was derived from
+/// (`/1/user/-/foods/log/goal.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoodGoalResponse {
+    pub goals: FoodGoals,
+    /// Not reported if the calorie goal was set directly
+    pub food_plan: Option<FoodPlan>,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A food plan, which derives the calorie intake goal from the user's weight goal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoodPlan {
+    pub intensity: FoodPlanIntensity,
+    /// The date the weight goal is estimated to be reached
+    pub estimated_date: NaiveDate,
+    /// Whether the goal is personalized to the user's activity
+    pub personalized: bool,
+}
+
+/// How ambitious a food plan is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FoodPlanIntensity {
+    /// Keep the current weight
+    Maintenance,
+    Easier,
+    Medium,
+    KindaHard,
+    Harder,
+}
+
+impl FoodPlanIntensity {
+    /// Returns the intensity value used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FoodPlanIntensity::Maintenance => "MAINTENANCE",
+            FoodPlanIntensity::Easier => "EASIER",
+            FoodPlanIntensity::Medium => "MEDIUM",
+            FoodPlanIntensity::KindaHard => "KINDAHARD",
+            FoodPlanIntensity::Harder => "HARDER",
+        }
+    }
+}
+
+/// A change of the calorie intake goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoodGoalUpdate {
+    /// Sets the calorie intake goal directly
+    Calories(u32),
+    /// Derives the calorie intake goal from a food plan
+    Plan {
+        intensity: FoodPlanIntensity,
+        personalized: bool,
+    },
+}
+
+impl FoodGoalUpdate {
+    /// Returns the form parameters for the update
+    pub(crate) fn to_form(self) -> Vec<(&'static str, String)> {
+        match self {
+            FoodGoalUpdate::Calories(calories) => vec![("calories", calories.to_string())],
+            FoodGoalUpdate::Plan {
+                intensity,
+                personalized,
+            } => vec![
+                ("intensity", intensity.as_str().to_string()),
+                ("personalized", personalized.to_string()),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::VolumeUnit;
+
+    fn food_log_response() -> FoodLogResponse {
+        let json_str = r#"{
+            "foods": [
+                {
+                    "isFavorite": false,
+                    "logDate": "2019-03-01",
+                    "logId": 20164447891,
+                    "loggedFood": {
+                        "accessLevel": "PUBLIC",
+                        "amount": 1,
+                        "brand": "",
+                        "calories": 95,
+                        "foodId": 81282,
+                        "locale": "en_US",
+                        "mealTypeId": 1,
+                        "name": "Apple",
+                        "unit": {"id": 226, "name": "medium", "plural": "medium"},
+                        "units": [226, 180, 147]
+                    },
+                    "nutritionalValues": {
+                        "calories": 95,
+                        "carbs": 25.13,
+                        "fat": 0.31,
+                        "fiber": 4.4,
+                        "protein": 0.47,
+                        "sodium": 2
+                    }
+                },
+                {
+                    "isFavorite": true,
+                    "logDate": "2019-03-01",
+                    "logId": 20164447892,
+                    "loggedFood": {
+                        "accessLevel": "PRIVATE",
+                        "amount": 2,
+                        "calories": 300,
+                        "foodId": 533871,
+                        "mealTypeId": 6,
+                        "name": "Homemade granola",
+                        "unit": {"id": 91, "name": "cup", "plural": "cups"}
+                    }
+                }
+            ],
+            "goals": {"calories": 2000},
+            "summary": {
+                "calories": 395,
+                "carbs": 73.13,
+                "fat": 12.31,
+                "fiber": 8.4,
+                "protein": 9.47,
+                "sodium": 52,
+                "water": 500
+            }
+        }"#;
+
+        serde_json::from_str(json_str).expect("Failed to parse JSON")
+    }
+
+    #[test]
+    fn test_parse_food_logs() {
+        let response = food_log_response();
+
+        assert_eq!(response.foods.len(), 2);
+        let apple = &response.foods[0];
+        assert_eq!(apple.logged_food.name, "Apple");
+        assert_eq!(apple.logged_food.meal_type, MealType::Breakfast);
+        assert_eq!(apple.logged_food.unit.name, "medium");
+        assert_eq!(apple.nutritional_values.as_ref().unwrap().carbs, 25.13);
+
+        let granola = &response.foods[1];
+        assert_eq!(granola.logged_food.brand, "");
+        assert_eq!(granola.logged_food.meal_type, MealType::Other(6));
+        assert_eq!(granola.logged_food.unit.plural, "cups");
+        assert!(granola.nutritional_values.is_none());
+
+        assert_eq!(response.goals, Some(FoodGoals { calories: 2000 }));
+        assert_eq!(response.summary.calories, 395.0);
+        assert_eq!(response.summary.water, 500.0);
+        assert_eq!(
+            response.water(),
+            VolumeValue::new(500.0, VolumeUnit::Milliliters)
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut response = food_log_response();
+        response.unit_system = UnitSystem::Us;
+
+        let serialized = serde_json::to_value(&response).expect("Failed to serialize");
+        assert_eq!(serialized["foods"][0]["loggedFood"]["mealTypeId"], 1);
+        assert_eq!(serialized["foods"][1]["loggedFood"]["mealTypeId"], 6);
+        assert!(serialized["foods"][1]["nutritionalValues"].is_null());
+        assert_eq!(serialized["summary"]["water"], 500.0);
+        assert_eq!(serialized["unitSystem"], "en_US");
+
+        let round_tripped: FoodLogResponse =
+            serde_json::from_value(serialized.clone()).expect("Failed to parse serialized JSON");
+        assert_eq!(
+            serde_json::to_value(&round_tripped).expect("Failed to serialize"),
+            serialized
+        );
+        assert_eq!(
+            round_tripped.foods[1].logged_food.meal_type,
+            MealType::Other(6)
+        );
+        assert_eq!(round_tripped.summary, response.summary);
+        assert_eq!(round_tripped.water().unit, VolumeUnit::FluidOunces);
+    }
+
+    #[test]
+    fn test_nutrition_helpers() {
+        let json_str = r#"{
+            "foods": [
+                {
+                    "isFavorite": false,
+                    "logDate": "2019-03-01",
+                    "logId": 1,
+                    "loggedFood": {
+                        "accessLevel": "PUBLIC", "amount": 1, "calories": 95, "foodId": 81282,
+                        "mealTypeId": 1, "name": "Apple",
+                        "unit": {"id": 226, "name": "medium", "plural": "medium"}
+                    },
+                    "nutritionalValues": {"calories": 95, "carbs": 25, "fat": 0, "protein": 0.5}
+                },
+                {
+                    "isFavorite": false,
+                    "logDate": "2019-03-01",
+                    "logId": 2,
+                    "loggedFood": {
+                        "accessLevel": "PUBLIC", "amount": 1, "calories": 150, "foodId": 19561,
+                        "mealTypeId": 1, "name": "Yogurt",
+                        "unit": {"id": 91, "name": "cup", "plural": "cups"}
+                    },
+                    "nutritionalValues": {"calories": 150, "carbs": 15, "fat": 4, "protein": 12}
+                },
+                {
+                    "isFavorite": false,
+                    "logDate": "2019-03-01",
+                    "logId": 3,
+                    "loggedFood": {
+                        "accessLevel": "PRIVATE", "amount": 1, "calories": 455, "foodId": 533871,
+                        "mealTypeId": 5, "name": "Curry",
+                        "unit": {"id": 304, "name": "serving", "plural": "servings"}
+                    }
+                }
+            ],
+            "goals": {"calories": 2000},
+            "summary": {"calories": 700, "carbs": 40, "fat": 4, "protein": 12.5}
+        }"#;
+
+        let response: FoodLogResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.total_calories(), 700.0);
+        assert_eq!(response.remaining_calories(), Some(1300.0));
+
+        // 50 kcal protein, 160 kcal carbs, 36 kcal fat
+        let split = response.macro_split().expect("Missing macro split");
+        assert!((split.protein.value() - 50.0 / 246.0 * 100.0).abs() < 1e-9);
+        assert!((split.carbs.value() - 160.0 / 246.0 * 100.0).abs() < 1e-9);
+        assert!((split.fat.value() - 36.0 / 246.0 * 100.0).abs() < 1e-9);
+
+        let subtotals = response.meal_subtotals();
+        assert_eq!(subtotals.len(), 2);
+        let breakfast = &subtotals[&MealType::Breakfast];
+        assert_eq!(breakfast.calories, 245.0);
+        assert_eq!(breakfast.carbs, 40.0);
+        assert_eq!(breakfast.protein, 12.5);
+        assert_eq!(subtotals[&MealType::Dinner].calories, 455.0);
+    }
+
+    #[test]
+    fn test_nutrition_helpers_without_goal_or_macros() {
+        let response: FoodLogResponse =
+            serde_json::from_str(r#"{"foods": [], "summary": {"calories": 0}}"#)
+                .expect("Failed to parse JSON");
+
+        assert_eq!(response.remaining_calories(), None);
+        assert_eq!(response.macro_split(), None);
+        assert!(response.meal_subtotals().is_empty());
+    }
+
+    #[test]
+    fn test_parse_food_goal() {
+        let json_str = r#"{
+            "foodPlan": {"estimatedDate": "2019-07-14", "intensity": "KINDAHARD", "personalized": false},
+            "goals": {"calories": 1790}
+        }"#;
+
+        let response: FoodGoalResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.goals.calories, 1790);
+        let plan = response.food_plan.expect("Missing food plan");
+        assert_eq!(plan.intensity, FoodPlanIntensity::KindaHard);
+        assert!(!plan.personalized);
+
+        let response: FoodGoalResponse =
+            serde_json::from_str(r#"{"goals": {"calories": 2000}}"#).expect("Failed to parse JSON");
+        assert!(response.food_plan.is_none());
+    }
+
+    #[test]
+    fn test_food_goal_update_form() {
+        assert_eq!(
+            FoodGoalUpdate::Calories(1800).to_form(),
+            vec![("calories", "1800".to_string())]
+        );
+        assert_eq!(
+            FoodGoalUpdate::Plan {
+                intensity: FoodPlanIntensity::Medium,
+                personalized: true,
+            }
+            .to_form(),
+            vec![
+                ("intensity", "MEDIUM".to_string()),
+                ("personalized", "true".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_meal_type_ids() {
+        assert_eq!(MealType::from(5), MealType::Dinner);
+        assert_eq!(MealType::from(7).id(), 7);
+        assert_eq!(MealType::Other(9).id(), 9);
+    }
+}
@@ -0,0 +1,139 @@
+//! Friend invitations.
+
+use serde::{Deserialize, Serialize};
+
+/// A pending invitation from another user to become friends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendInvitation {
+    /// The encoded id of the inviting user
+    pub user_id: String,
+    pub name: String,
+    /// URL of the inviting user's avatar
+    pub avatar: String,
+    /// `true` if the inviting user has a child account
+    pub child: bool,
+}
+
+/// The user to invite
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invitee {
+    /// Invites the user with the email address, who gets an email to sign up if they have
+    /// no Fitbit account
+    Email(String),
+    /// Invites the user with the encoded id
+    UserId(String),
+}
+
+impl Invitee {
+    /// Returns the form parameters for the invitation
+    pub(crate) fn to_form(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Invitee::Email(email) => vec![("invitedUserEmail", email.clone())],
+            Invitee::UserId(user_id) => vec![("invitedUserId", user_id.clone())],
+        }
+    }
+}
+
+/// Raw friend invitation response
+#[derive(Debug, Deserialize)]
+pub(crate) struct FriendInvitationResponse {
+    data: Vec<PersonResource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PersonResource {
+    id: String,
+    attributes: PersonAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+struct PersonAttributes {
+    name: String,
+    #[serde(default)]
+    avatar: String,
+    #[serde(default)]
+    child: bool,
+}
+
+impl FriendInvitationResponse {
+    /// Returns the invitations
+    pub(crate) fn into_invitations(self) -> Vec<FriendInvitation> {
+        self.data
+            .into_iter()
+            .map(|person| FriendInvitation {
+                user_id: person.id,
+                name: person.attributes.name,
+                avatar: person.attributes.avatar,
+                child: person.attributes.child,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_invitations() {
+        let json_str = r#"{
+            "data": [
+                {
+                    "type": "person",
+                    "id": "2ABC34",
+                    "attributes": {
+                        "name": "Alex P.",
+                        "avatar": "https://static0.fitbit.com/images/profile/defaultProfile_100.png",
+                        "child": false,
+                        "friend": false
+                    }
+                }
+            ]
+        }"#;
+
+        let response: FriendInvitationResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let invitations = response.into_invitations();
+
+        assert_eq!(
+            invitations,
+            vec![FriendInvitation {
+                user_id: "2ABC34".to_string(),
+                name: "Alex P.".to_string(),
+                avatar: "https://static0.fitbit.com/images/profile/defaultProfile_100.png"
+                    .to_string(),
+                child: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invitee_form() {
+        assert_eq!(
+            Invitee::Email("alex@example.com".to_string()).to_form(),
+            vec![("invitedUserEmail", "alex@example.com".to_string())]
+        );
+        assert_eq!(
+            Invitee::UserId("2ABC34".to_string()).to_form(),
+            vec![("invitedUserId", "2ABC34".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let invitation = FriendInvitation {
+            user_id: "2ABC34".to_string(),
+            name: "Alex P.".to_string(),
+            avatar: "https://static0.fitbit.com/images/profile/defaultProfile_100.png".to_string(),
+            child: true,
+        };
+
+        let serialized = serde_json::to_value(&invitation).expect("Failed to serialize");
+        assert_eq!(serialized["userId"], "2ABC34");
+        assert_eq!(serialized["child"], true);
+        let round_tripped: FriendInvitation =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, invitation);
+    }
+}
@@ -0,0 +1,492 @@
+//! Export of sleep, steps and heart rate as Health Connect records.
+//!
+//! The records have the shape of Android Health Connect's `SleepSessionRecord`,
+//! `StepsRecord` and `HeartRateRecord`: instants in UTC with the zone offset next to them,
+//! sleep stages as the `STAGE_TYPE_*` constants, and a client record id that makes
+//! re-importing the same data an update rather than a duplicate. Pipelines can insert them
+//! into Health Connect, or Google Fit, field by field.
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::activity_time_series::{ActivityResource, IntradayActivity};
+use crate::error::FitbitError;
+use crate::export::interval_length;
+use crate::heart_rate::IntradayHeartRate;
+use crate::sleep::{SleepData, SleepLevel, with_offset};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike, Utc};
+use serde::{Serialize, Serializer};
+
+/// The stage of a sleep session, as the `SleepSessionRecord.STAGE_TYPE_*` constants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SleepStageType {
+    Unknown,
+    Awake,
+    /// Asleep without a known stage, as in classic sleep records
+    Sleeping,
+    OutOfBed,
+    Light,
+    Deep,
+    Rem,
+    AwakeInBed,
+}
+
+impl SleepStageType {
+    /// Returns the value of the `STAGE_TYPE_*` constant
+    pub fn value(&self) -> u8 {
+        match self {
+            SleepStageType::Unknown => 0,
+            SleepStageType::Awake => 1,
+            SleepStageType::Sleeping => 2,
+            SleepStageType::OutOfBed => 3,
+            SleepStageType::Light => 4,
+            SleepStageType::Deep => 5,
+            SleepStageType::Rem => 6,
+            SleepStageType::AwakeInBed => 7,
+        }
+    }
+
+    /// Returns the stage of a sleep level
+    pub fn from_level(level: &SleepLevel) -> Self {
+        match level {
+            SleepLevel::Deep => SleepStageType::Deep,
+            SleepLevel::Light => SleepStageType::Light,
+            SleepLevel::Rem => SleepStageType::Rem,
+            SleepLevel::Asleep => SleepStageType::Sleeping,
+            SleepLevel::Wake | SleepLevel::Awake | SleepLevel::Restless => SleepStageType::Awake,
+            SleepLevel::Unknown | SleepLevel::Other(_) => SleepStageType::Unknown,
+        }
+    }
+}
+
+impl Serialize for SleepStageType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.value())
+    }
+}
+
+/// The app a record was written by
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataOrigin {
+    pub package_name: String,
+}
+
+/// Where a record comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    /// Id of the record in the Fitbit data, e.g. `fitbit-sleep-26589710670`
+    pub client_record_id: String,
+    pub data_origin: DataOrigin,
+}
+
+/// A stage of a sleep session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepStage {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub stage: SleepStageType,
+}
+
+/// A sleep with its stages, in the shape of `SleepSessionRecord`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepSessionRecord {
+    pub start_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_offset")]
+    pub start_zone_offset: FixedOffset,
+    pub end_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_offset")]
+    pub end_zone_offset: FixedOffset,
+    pub stages: Vec<SleepStage>,
+    pub metadata: Metadata,
+}
+
+/// The steps of an interval, in the shape of `StepsRecord`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepsRecord {
+    pub start_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_offset")]
+    pub start_zone_offset: FixedOffset,
+    pub end_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_offset")]
+    pub end_zone_offset: FixedOffset,
+    pub count: u64,
+    pub metadata: Metadata,
+}
+
+/// A heart rate sample of a [`HeartRateRecord`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartRateSample {
+    pub time: DateTime<Utc>,
+    pub beats_per_minute: u32,
+}
+
+/// A series of heart rate samples, in the shape of `HeartRateRecord`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartRateRecord {
+    pub start_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_offset")]
+    pub start_zone_offset: FixedOffset,
+    pub end_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_offset")]
+    pub end_zone_offset: FixedOffset,
+    /// Samples in chronological order, all within `start_time` up to `end_time`
+    pub samples: Vec<HeartRateSample>,
+    pub metadata: Metadata,
+}
+
+/// A Health Connect record, serialized with its type in `recordType`, e.g. `Steps`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "recordType")]
+pub enum HealthConnectRecord {
+    SleepSession(SleepSessionRecord),
+    Steps(StepsRecord),
+    HeartRate(HeartRateRecord),
+}
+
+/// Builds Health Connect records from sleep, activity and heart rate data
+///
+/// Fitbit reports times in the user's local time, so the export needs the user's UTC offset;
+/// see [`UserProfile::utc_offset`](crate::UserProfile::utc_offset).
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::health_connect::HealthConnectExport;
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait};
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let offset = client.fetch_profile()?.user.utc_offset().unwrap();
+/// let today = chrono::Local::now().date_naive();
+///
+/// let mut export = HealthConnectExport::new(offset);
+/// for sleep in &client.fetch_sleep_data(today)?.sleep {
+///     export.add_sleep(sleep);
+/// }
+/// export.add_daily_steps(today, &client.fetch_activity_summary(today)?);
+/// std::fs::write("records.json", export.to_json()?).unwrap();
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthConnectExport {
+    offset: FixedOffset,
+    data_origin: String,
+    records: Vec<HealthConnectRecord>,
+}
+
+impl HealthConnectExport {
+    /// Creates an export without records
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The UTC offset of the user's local time
+    pub fn new(offset: FixedOffset) -> Self {
+        Self {
+            offset,
+            data_origin: "com.fitbit.FitbitMobile".to_string(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Sets the package name of the data origin; the Fitbit app's by default
+    pub fn with_data_origin(mut self, package_name: impl Into<String>) -> Self {
+        self.data_origin = package_name.into();
+        self
+    }
+
+    /// Returns the records in the order they were added
+    pub fn records(&self) -> &[HealthConnectRecord] {
+        &self.records
+    }
+
+    /// Adds a sleep session with a stage per interval of its hypnogram
+    ///
+    /// Short wakes of stages records become awake stages, splitting the stage they
+    /// interrupt, like in [`SleepData::hypnogram`].
+    pub fn add_sleep(&mut self, sleep: &SleepData) {
+        let stages = sleep
+            .hypnogram()
+            .into_iter()
+            .map(|interval| SleepStage {
+                start_time: self.instant(interval.start),
+                end_time: self.instant(interval.end),
+                stage: SleepStageType::from_level(&interval.level),
+            })
+            .collect();
+
+        self.records
+            .push(HealthConnectRecord::SleepSession(SleepSessionRecord {
+                start_time: self.instant(sleep.start_time),
+                start_zone_offset: self.offset,
+                end_time: self.instant(sleep.end_time),
+                end_zone_offset: self.offset,
+                stages,
+                metadata: self.metadata(format!("fitbit-sleep-{}", sleep.log_id)),
+            }));
+    }
+
+    /// Adds the steps of a day as a single record spanning the day
+    ///
+    /// Health Connect counts overlapping steps records of the same app twice, so don't add
+    /// both the daily steps and the intraday steps of a day.
+    pub fn add_daily_steps(&mut self, date: NaiveDate, response: &ActivitySummaryResponse) {
+        let start = date.and_time(Default::default());
+        self.push_steps(
+            start,
+            start + Duration::days(1),
+            response.summary.steps as u64,
+            format!("fitbit-steps-{}", date),
+        );
+    }
+
+    /// Adds intraday steps as a record per interval, leaving out intervals without steps
+    ///
+    /// # Returns
+    ///
+    /// `FitbitError::InvalidArgument` if the data isn't steps
+    pub fn add_step_samples(&mut self, intraday: &IntradayActivity) -> Result<(), FitbitError> {
+        if intraday.resource != ActivityResource::Steps {
+            return Err(FitbitError::InvalidArgument(format!(
+                "expected intraday steps, got {:?}",
+                intraday.resource
+            )));
+        }
+
+        let interval = interval_length(intraday.dataset_interval, &intraday.dataset_type);
+        for (start, steps) in intraday.dataset.at_date(intraday.date) {
+            if steps > 0.0 {
+                self.push_steps(
+                    start,
+                    start + interval,
+                    steps.round() as u64,
+                    format!("fitbit-steps-{}", start.format("%Y-%m-%dT%H:%M:%S")),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds intraday heart rate as a record per hour with samples
+    pub fn add_heart_rate(&mut self, intraday: &IntradayHeartRate) {
+        let mut hour_start: Option<NaiveDateTime> = None;
+        let mut samples = Vec::new();
+        for (time, heart_rate) in intraday.samples.at_date(intraday.date) {
+            let start = time
+                .with_minute(0)
+                .and_then(|time| time.with_second(0))
+                .unwrap_or(time);
+            if hour_start != Some(start) {
+                if let Some(hour_start) = hour_start {
+                    self.push_heart_rate(hour_start, std::mem::take(&mut samples));
+                }
+                hour_start = Some(start);
+            }
+            samples.push(HeartRateSample {
+                time: self.instant(time),
+                beats_per_minute: heart_rate as u32,
+            });
+        }
+        if let Some(hour_start) = hour_start {
+            self.push_heart_rate(hour_start, samples);
+        }
+    }
+
+    /// Exports the records as a JSON array
+    ///
+    /// # Returns
+    ///
+    /// The JSON, or an error if serializing failed
+    pub fn to_json(&self) -> Result<String, FitbitError> {
+        serde_json::to_string_pretty(&self.records)
+            .map_err(|error| FitbitError::json_error(error.to_string()))
+    }
+
+    /// Converts a local time to an instant
+    fn instant(&self, time: NaiveDateTime) -> DateTime<Utc> {
+        with_offset(time, self.offset).with_timezone(&Utc)
+    }
+
+    fn metadata(&self, client_record_id: String) -> Metadata {
+        Metadata {
+            client_record_id,
+            data_origin: DataOrigin {
+                package_name: self.data_origin.clone(),
+            },
+        }
+    }
+
+    /// Adds a steps record between two local times
+    fn push_steps(
+        &mut self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        count: u64,
+        client_record_id: String,
+    ) {
+        self.records.push(HealthConnectRecord::Steps(StepsRecord {
+            start_time: self.instant(start),
+            start_zone_offset: self.offset,
+            end_time: self.instant(end),
+            end_zone_offset: self.offset,
+            count,
+            metadata: self.metadata(client_record_id),
+        }));
+    }
+
+    /// Adds a heart rate record spanning the hour from a local time
+    fn push_heart_rate(&mut self, hour_start: NaiveDateTime, samples: Vec<HeartRateSample>) {
+        self.records
+            .push(HealthConnectRecord::HeartRate(HeartRateRecord {
+                start_time: self.instant(hour_start),
+                start_zone_offset: self.offset,
+                end_time: self.instant(hour_start + Duration::hours(1)),
+                end_zone_offset: self.offset,
+                samples,
+                metadata: self.metadata(format!(
+                    "fitbit-heart-rate-{}",
+                    hour_start.format("%Y-%m-%dT%H")
+                )),
+            }));
+    }
+}
+
+/// Serializes a zone offset like `java.time.ZoneOffset`, e.g. `+01:00`
+fn serialize_offset<S: Serializer>(offset: &FixedOffset, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::intraday::IntradaySamples;
+    use chrono::NaiveTime;
+    use serde_json::json;
+
+    fn offset() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_sleep_session() {
+        let start = date().and_hms_opt(23, 0, 0).unwrap();
+        let sleep = SleepDataBuilder::new(start)
+            .log_id(26589710670)
+            .stage(SleepLevel::Light, 60)
+            .stage(SleepLevel::Deep, 30)
+            .build();
+
+        let mut export = HealthConnectExport::new(offset());
+        export.add_sleep(&sleep);
+
+        let json = serde_json::to_value(export.records()).expect("Failed to serialize");
+        assert_eq!(
+            json[0],
+            json!({
+                "recordType": "SleepSession",
+                "startTime": "2025-03-01T22:00:00Z",
+                "startZoneOffset": "+01:00",
+                "endTime": "2025-03-01T23:30:00Z",
+                "endZoneOffset": "+01:00",
+                "stages": [
+                    {"startTime": "2025-03-01T22:00:00Z", "endTime": "2025-03-01T23:00:00Z", "stage": 4},
+                    {"startTime": "2025-03-01T23:00:00Z", "endTime": "2025-03-01T23:30:00Z", "stage": 5}
+                ],
+                "metadata": {
+                    "clientRecordId": "fitbit-sleep-26589710670",
+                    "dataOrigin": {"packageName": "com.fitbit.FitbitMobile"}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_steps() -> Result<(), FitbitError> {
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        let intraday = IntradayActivity {
+            resource: ActivityResource::Steps,
+            date: date(),
+            total: 1_200.0,
+            dataset_interval: 15,
+            dataset_type: "minute".to_string(),
+            dataset: [(time(8, 0), 1_200.0), (time(8, 15), 0.0)]
+                .into_iter()
+                .collect(),
+        };
+
+        let mut export = HealthConnectExport::new(offset()).with_data_origin("org.example");
+        export.add_daily_steps(date(), &ActivitySummaryBuilder::new().steps(8_000).build());
+        export.add_step_samples(&intraday)?;
+
+        let json = serde_json::to_value(export.records()).expect("Failed to serialize");
+        assert_eq!(json.as_array().map(Vec::len), Some(2));
+        assert_eq!(json[0]["count"], 8_000);
+        assert_eq!(json[0]["endTime"], "2025-03-01T23:00:00Z");
+        assert_eq!(json[1]["startTime"], "2025-03-01T07:00:00Z");
+        assert_eq!(json[1]["endTime"], "2025-03-01T07:15:00Z");
+        assert_eq!(
+            json[1]["metadata"]["dataOrigin"]["packageName"],
+            "org.example"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_heart_rate_per_hour() {
+        let samples: IntradaySamples<u16> = [(10, 0, 61), (10, 59, 64), (12, 30, 70)]
+            .into_iter()
+            .map(|(hour, minute, bpm)| (NaiveTime::from_hms_opt(hour, minute, 0).unwrap(), bpm))
+            .collect();
+        let intraday = IntradayHeartRate {
+            date: date(),
+            dataset_interval: 1,
+            dataset_type: "minute".to_string(),
+            samples,
+        };
+
+        let mut export = HealthConnectExport::new(offset());
+        export.add_heart_rate(&intraday);
+
+        let sample_counts: Vec<usize> = export
+            .records()
+            .iter()
+            .map(|record| match record {
+                HealthConnectRecord::HeartRate(record) => record.samples.len(),
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(sample_counts, [2, 1]);
+
+        let json = serde_json::to_value(&export.records()[1]).expect("Failed to serialize");
+        assert_eq!(json["recordType"], "HeartRate");
+        assert_eq!(json["startTime"], "2025-03-01T11:00:00Z");
+        assert_eq!(json["endTime"], "2025-03-01T12:00:00Z");
+        assert_eq!(
+            json["samples"][0],
+            json!({"time": "2025-03-01T11:30:00Z", "beatsPerMinute": 70})
+        );
+    }
+
+    #[test]
+    fn test_stage_from_level() {
+        assert_eq!(SleepStageType::from_level(&SleepLevel::Rem).value(), 6);
+        assert_eq!(
+            SleepStageType::from_level(&SleepLevel::Restless),
+            SleepStageType::Awake
+        );
+        assert_eq!(
+            SleepStageType::from_level(&SleepLevel::Other("x".to_string())),
+            SleepStageType::Unknown
+        );
+    }
+}
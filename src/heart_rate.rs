@@ -0,0 +1,471 @@
+//! Daily and intraday heart rate data.
+//!
+//! The heart rate endpoints report the resting heart rate and the time spent in each heart
+//! rate zone per day, independent of the activity summary. The intraday endpoint returns the
+//! heart rate samples of a single day at up to one second resolution.
+
+use crate::activity_summary::{HeartRateZone, deserialize_heart_rate_zones};
+use crate::analysis::timeseries::fold_intervals;
+use crate::error::FitbitError;
+use crate::intraday::{IntradaySamples, deserialize_samples};
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// Resting heart rate and time in zones of one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartRateDay {
+    pub date: NaiveDate,
+    /// Not reported for days without enough heart rate data
+    pub resting_heart_rate: Option<u32>,
+    /// The default zones, from out of range to peak
+    #[serde(deserialize_with = "deserialize_heart_rate_zones")]
+    pub heart_rate_zones: Vec<HeartRateZone>,
+    /// The user's custom zones; empty unless they configured one
+    pub custom_heart_rate_zones: Vec<CustomHeartRateZone>,
+}
+
+/// A heart rate zone configured by the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CustomHeartRateZone {
+    pub name: String,
+    pub min: i32,
+    pub max: i32,
+    #[serde(default)]
+    pub minutes: i32,
+    #[serde(default)]
+    pub calories_out: f64,
+}
+
+/// Interval length of intraday heart rate data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeartRateDetailLevel {
+    OneSecond,
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+}
+
+impl HeartRateDetailLevel {
+    /// Returns the detail level path segment used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeartRateDetailLevel::OneSecond => "1sec",
+            HeartRateDetailLevel::OneMinute => "1min",
+            HeartRateDetailLevel::FiveMinutes => "5min",
+            HeartRateDetailLevel::FifteenMinutes => "15min",
+        }
+    }
+
+    /// Returns the interval length in seconds
+    pub fn seconds(&self) -> u32 {
+        match self {
+            HeartRateDetailLevel::OneSecond => 1,
+            HeartRateDetailLevel::OneMinute => 60,
+            HeartRateDetailLevel::FiveMinutes => 5 * 60,
+            HeartRateDetailLevel::FifteenMinutes => 15 * 60,
+        }
+    }
+}
+
+/// Heart rate samples throughout (part of) one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntradayHeartRate {
+    pub date: NaiveDate,
+    /// Length of each interval in `dataset_type` units, e.g. 1 for 1 second data
+    pub dataset_interval: u32,
+    /// Unit of `dataset_interval`, e.g. `second` or `minute`
+    pub dataset_type: String,
+    /// The heart rate in beats per minute, keyed by the sample time, in chronological
+    /// order. Intervals without a reading are omitted, so 1 second data has gaps.
+    pub samples: IntradaySamples<u16>,
+}
+
+impl IntradayHeartRate {
+    /// Downsamples the samples into fixed intervals, e.g. 1 second data into 5 minute
+    /// averages for charting
+    ///
+    /// Intervals are aligned to midnight, like those of
+    /// [`timeseries::resample`](crate::analysis::timeseries::resample). Intervals without
+    /// samples are omitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The length of each interval
+    ///
+    /// # Returns
+    ///
+    /// The statistics of each interval with samples, in chronological order
+    pub fn resample(&self, interval: HeartRateDetailLevel) -> Vec<HeartRateBucket> {
+        let samples = self
+            .samples
+            .at_date(self.date)
+            .map(|(time, bpm)| (time, u32::from(bpm)));
+        // (sum, samples, min, max)
+        let intervals = fold_intervals(
+            samples,
+            chrono::Duration::seconds(interval.seconds() as i64),
+            |bpm| (bpm as u64, 1, bpm, bpm),
+            |(sum, samples, min, max), bpm| {
+                *sum += bpm as u64;
+                *samples += 1;
+                *min = (*min).min(bpm);
+                *max = (*max).max(bpm);
+            },
+        )
+        .expect("detail levels divide a day and samples are chronological");
+
+        intervals
+            .into_iter()
+            .filter_map(|(start, state)| {
+                let (sum, samples, min_bpm, max_bpm) = state?;
+                Some(HeartRateBucket {
+                    start: start.time(),
+                    samples,
+                    average_bpm: sum as f64 / samples as f64,
+                    min_bpm,
+                    max_bpm,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Statistics of the heart rate samples within one interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartRateBucket {
+    /// The start of the interval
+    pub start: NaiveTime,
+    /// Number of samples within the interval
+    pub samples: usize,
+    pub average_bpm: f64,
+    pub min_bpm: u32,
+    pub max_bpm: u32,
+}
+
+/// Raw intraday heart rate response
+///
+/// The daily summary that accompanies the dataset has a different shape depending on
+/// whether a time range was requested, so only the dataset is parsed.
+#[derive(Debug, Deserialize)]
+pub(crate) struct HeartRateIntradayResponse {
+    #[serde(rename = "activities-heart-intraday")]
+    intraday: IntradayHeartRateDataset,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntradayHeartRateDataset {
+    #[serde(deserialize_with = "deserialize_samples::<_, IntradayHeartRateEntry, _>")]
+    dataset: IntradaySamples<u16>,
+    dataset_interval: u32,
+    dataset_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntradayHeartRateEntry {
+    time: NaiveTime,
+    value: u16,
+}
+
+impl From<IntradayHeartRateEntry> for (NaiveTime, u16) {
+    fn from(entry: IntradayHeartRateEntry) -> Self {
+        (entry.time, entry.value)
+    }
+}
+
+impl HeartRateIntradayResponse {
+    /// Returns the samples of the requested date
+    pub(crate) fn into_intraday(self, date: NaiveDate) -> IntradayHeartRate {
+        IntradayHeartRate {
+            date,
+            dataset_interval: self.intraday.dataset_interval,
+            dataset_type: self.intraday.dataset_type,
+            samples: self.intraday.dataset,
+        }
+    }
+}
+
+/// Raw heart rate time series response
+#[derive(Debug, Deserialize)]
+pub(crate) struct HeartRateTimeSeriesResponse {
+    #[serde(rename = "activities-heart")]
+    days: Vec<HeartRateEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+struct HeartRateEntry {
+    date_time: NaiveDate,
+    value: HeartRateEntryValue,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+struct HeartRateEntryValue {
+    resting_heart_rate: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_heart_rate_zones")]
+    heart_rate_zones: Vec<HeartRateZone>,
+    #[serde(default)]
+    custom_heart_rate_zones: Vec<CustomHeartRateZone>,
+}
+
+impl HeartRateTimeSeriesResponse {
+    /// Returns the days in chronological order
+    pub(crate) fn into_days(self) -> Vec<HeartRateDay> {
+        let mut days: Vec<HeartRateDay> = self
+            .days
+            .into_iter()
+            .map(|entry| HeartRateDay {
+                date: entry.date_time,
+                resting_heart_rate: entry.value.resting_heart_rate,
+                heart_rate_zones: entry.value.heart_rate_zones,
+                custom_heart_rate_zones: entry.value.custom_heart_rate_zones,
+            })
+            .collect();
+        days.sort_by_key(|day| day.date);
+
+        days
+    }
+
+    /// Returns the only day of a single-day response
+    pub(crate) fn into_day(self) -> Result<HeartRateDay, FitbitError> {
+        self.into_days().into_iter().next().ok_or_else(|| {
+            FitbitError::json_error("missing `activities-heart` in heart rate response")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity_summary::HeartRateZoneName;
+
+    #[test]
+    fn test_parse_heart_rate_time_series() {
+        let json_str = r#"{
+            "activities-heart": [
+                {
+                    "dateTime": "2019-05-09",
+                    "value": {
+                        "customHeartRateZones": [
+                            {"caloriesOut": 412.6, "max": 150, "min": 120, "minutes": 34, "name": "Tempo"}
+                        ],
+                        "heartRateZones": [
+                            {"caloriesOut": 1979.7, "max": 96, "min": 30, "minutes": 1224, "name": "Out of Range"},
+                            {"caloriesOut": 510.3, "max": 134, "min": 96, "minutes": 181, "name": "Fat Burn"},
+                            {"caloriesOut": 0, "max": 163, "min": 134, "minutes": 0, "name": "Cardio"},
+                            {"caloriesOut": 0, "max": 220, "min": 163, "minutes": 0, "name": "Peak"}
+                        ],
+                        "restingHeartRate": 76
+                    }
+                },
+                {
+                    "dateTime": "2019-05-08",
+                    "value": {
+                        "customHeartRateZones": [],
+                        "heartRateZones": [
+                            {"caloriesOut": 0, "max": 96, "min": 30, "minutes": 0, "name": "Out of Range"},
+                            {"caloriesOut": 0, "max": 134, "min": 96, "minutes": 0, "name": "Fat Burn"},
+                            {"caloriesOut": 0, "max": 163, "min": 134, "minutes": 0, "name": "Cardio"},
+                            {"caloriesOut": 0, "max": 220, "min": 163, "minutes": 0, "name": "Peak"}
+                        ]
+                    }
+                }
+            ]
+        }"#;
+
+        let response: HeartRateTimeSeriesResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let days = response.into_days();
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2019, 5, 8).unwrap());
+        assert_eq!(days[0].resting_heart_rate, None);
+
+        let day = &days[1];
+        assert_eq!(day.resting_heart_rate, Some(76));
+        assert_eq!(day.heart_rate_zones.len(), 4);
+        assert_eq!(day.heart_rate_zones[1].name, HeartRateZoneName::FatBurn);
+        assert_eq!(day.heart_rate_zones[1].minutes, 181);
+        assert_eq!(day.custom_heart_rate_zones[0].name, "Tempo");
+        assert_eq!(day.custom_heart_rate_zones[0].minutes, 34);
+    }
+
+    #[test]
+    fn test_parse_heart_rate_intraday() {
+        // With a time range, the daily summary reports the average as a string `value`
+        let json_str = r#"{
+            "activities-heart": [
+                {"customHeartRateZones": [], "dateTime": "2019-01-01", "heartRateZones": [], "value": "64.2"}
+            ],
+            "activities-heart-intraday": {
+                "dataset": [
+                    {"time": "08:00:00", "value": 62},
+                    {"time": "08:00:07", "value": 65},
+                    {"time": "08:00:02", "value": 63}
+                ],
+                "datasetInterval": 1,
+                "datasetType": "second"
+            }
+        }"#;
+
+        let response: HeartRateIntradayResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let date = NaiveDate::from_ymd_opt(2019, 1, 1).unwrap();
+        let intraday = response.into_intraday(date);
+
+        let time = |second| NaiveTime::from_hms_opt(8, 0, second).unwrap();
+        assert_eq!(intraday.date, date);
+        assert_eq!(intraday.dataset_interval, 1);
+        assert_eq!(intraday.dataset_type, "second");
+        assert_eq!(
+            intraday.samples.iter().collect::<Vec<_>>(),
+            [(time(0), 62), (time(2), 63), (time(7), 65)]
+        );
+    }
+
+    #[test]
+    fn test_resample_intraday_heart_rate() {
+        let time = |hour, minute, second| NaiveTime::from_hms_opt(hour, minute, second).unwrap();
+        let intraday = IntradayHeartRate {
+            date: NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
+            dataset_interval: 1,
+            dataset_type: "second".to_string(),
+            samples: vec![
+                (time(8, 0, 0), 60),
+                (time(8, 0, 30), 64),
+                (time(8, 0, 59), 68),
+                (time(8, 1, 5), 90),
+                // No samples between 08:02 and 08:05
+                (time(8, 5, 10), 70),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let minutes = intraday.resample(HeartRateDetailLevel::OneMinute);
+        assert_eq!(
+            minutes,
+            [
+                HeartRateBucket {
+                    start: time(8, 0, 0),
+                    samples: 3,
+                    average_bpm: 64.0,
+                    min_bpm: 60,
+                    max_bpm: 68,
+                },
+                HeartRateBucket {
+                    start: time(8, 1, 0),
+                    samples: 1,
+                    average_bpm: 90.0,
+                    min_bpm: 90,
+                    max_bpm: 90,
+                },
+                HeartRateBucket {
+                    start: time(8, 5, 0),
+                    samples: 1,
+                    average_bpm: 70.0,
+                    min_bpm: 70,
+                    max_bpm: 70,
+                },
+            ]
+        );
+
+        let five_minutes = intraday.resample(HeartRateDetailLevel::FiveMinutes);
+        assert_eq!(five_minutes.len(), 2);
+        assert_eq!(five_minutes[0].samples, 4);
+        assert_eq!(five_minutes[0].average_bpm, 70.5);
+        assert_eq!(five_minutes[0].max_bpm, 90);
+        assert_eq!(five_minutes[1].start, time(8, 5, 0));
+
+        // The buckets line up with the intervals of the generic resampling
+        let means = crate::analysis::timeseries::resample(
+            intraday.samples.at_date(intraday.date),
+            chrono::Duration::minutes(1),
+            crate::analysis::timeseries::Aggregation::Mean,
+        )
+        .unwrap();
+        let means: Vec<_> = means
+            .into_iter()
+            .filter_map(|(start, mean)| Some((start.time(), mean?)))
+            .collect();
+        assert_eq!(
+            means,
+            minutes
+                .iter()
+                .map(|bucket| (bucket.start, bucket.average_bpm))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        // German account, whose default zones are only identified by their ranges
+        let json_str = r#"{
+            "activities-heart": [{
+                "dateTime": "2019-05-09",
+                "value": {
+                    "customHeartRateZones": [
+                        {"caloriesOut": 412.6, "max": 150, "min": 120, "minutes": 34, "name": "Tempo"}
+                    ],
+                    "heartRateZones": [
+                        {"caloriesOut": 1979.7, "max": 96, "min": 30, "minutes": 1224, "name": "Außerhalb der Zonen"},
+                        {"caloriesOut": 510.3, "max": 134, "min": 96, "minutes": 181, "name": "Fettverbrennung"},
+                        {"caloriesOut": 0, "max": 163, "min": 134, "minutes": 0, "name": "Cardio"},
+                        {"caloriesOut": 0, "max": 220, "min": 163, "minutes": 0, "name": "Höchstleistung"}
+                    ],
+                    "restingHeartRate": 76
+                }
+            }]
+        }"#;
+        let response: HeartRateTimeSeriesResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let day = response.into_day().expect("Missing day");
+
+        let serialized = serde_json::to_value(&day).expect("Failed to serialize");
+        assert_eq!(serialized["restingHeartRate"], 76);
+        assert_eq!(serialized["heartRateZones"][1]["name"], "Fettverbrennung");
+        let round_tripped: HeartRateDay =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, day);
+        assert_eq!(
+            round_tripped.heart_rate_zones[1].name,
+            HeartRateZoneName::FatBurn
+        );
+
+        let time = |minute, second| NaiveTime::from_hms_opt(8, minute, second).unwrap();
+        let intraday = IntradayHeartRate {
+            date: NaiveDate::from_ymd_opt(2019, 5, 9).unwrap(),
+            dataset_interval: 1,
+            dataset_type: "second".to_string(),
+            samples: vec![(time(0, 0), 62), (time(0, 7), 65), (time(1, 2), 70)]
+                .into_iter()
+                .collect(),
+        };
+
+        let serialized = serde_json::to_value(&intraday).expect("Failed to serialize");
+        assert_eq!(serialized["datasetInterval"], 1);
+        assert_eq!(
+            serialized["samples"][1],
+            serde_json::json!(["08:00:07", 65])
+        );
+        let round_tripped: IntradayHeartRate =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, intraday);
+
+        let buckets = intraday.resample(HeartRateDetailLevel::OneMinute);
+        let serialized = serde_json::to_value(&buckets).expect("Failed to serialize");
+        assert_eq!(serialized[0]["start"], "08:00:00");
+        assert_eq!(serialized[0]["averageBpm"], 63.5);
+        let round_tripped: Vec<HeartRateBucket> =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, buckets);
+    }
+}
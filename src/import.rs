@@ -0,0 +1,450 @@
+//! Import of the "export your data" archive of a Fitbit account.
+//!
+//! The archive holds the full history of an account as JSON files, beyond what the Web API
+//! returns per request. Its files have slightly different shapes than API responses:
+//! sleep records use `mainSleep` instead of `isMainSleep`, and steps and heart rate are
+//! flat lists of UTC timestamps, e.g. `Physical Activity/steps-2025-03-01.json`.
+//! [`FitbitArchive`] collects these files into the types of this crate, so historical data
+//! can be analyzed with the same code as API responses.
+
+use crate::activity_time_series::{ActivityResource, IntradayActivity};
+use crate::error::{FitbitError, parse_json};
+use crate::fitbit_client::FitbitClientTrait;
+use crate::heart_rate::IntradayHeartRate;
+use crate::response_cache::FitbitResponseCache;
+use crate::sleep::{SleepData, SleepResponseV1_2, SleepSummary, SleepType, StagesSummary};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Timestamp format of the steps and heart rate files, e.g. `03/01/25 07:15:00`
+const TIMESTAMP_FORMAT: &str = "%m/%d/%y %H:%M:%S";
+
+/// A kind of archive file this module imports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArchiveFile {
+    /// `sleep-*.json`, sleep records
+    Sleep,
+    /// `steps-*.json`, steps per minute
+    Steps,
+    /// `heart_rate-*.json`, heart rate samples
+    HeartRate,
+}
+
+impl ArchiveFile {
+    /// Returns the kind of a file by its name, or `None` if it isn't imported
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file, with or without its directory
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+        if !name.ends_with(".json") {
+            return None;
+        }
+
+        if name.starts_with("sleep-") {
+            Some(ArchiveFile::Sleep)
+        } else if name.starts_with("steps-") {
+            Some(ArchiveFile::Steps)
+        } else if name.starts_with("heart_rate-") {
+            Some(ArchiveFile::HeartRate)
+        } else {
+            None
+        }
+    }
+}
+
+/// The sleep, steps and heart rate of an account archive
+///
+/// Files can be added in any order, and files that overlap, e.g. because the same day is
+/// split across two monthly files, are merged. The UTC timestamps of the steps and heart
+/// rate files are converted to local time in the time zone `Tz`, e.g. a `chrono_tz::Tz`
+/// for a user who observes daylight saving time.
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::import::FitbitArchive;
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let offset = chrono::FixedOffset::east_opt(3600).unwrap();
+/// let mut archive = FitbitArchive::new(offset);
+/// for entry in std::fs::read_dir("MyFitbitData/Sleep").unwrap() {
+///     let path = entry.unwrap().path();
+///     let contents = std::fs::read(&path).unwrap();
+///     archive.add_file(&path.to_string_lossy(), &contents)?;
+/// }
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// archive.fill_cache(&mut cache);
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitbitArchive<Tz: TimeZone = FixedOffset> {
+    tz: Tz,
+    /// Sleep records by log ID, so records in several files are only kept once
+    sleep: BTreeMap<u64, SleepData>,
+    steps: BTreeMap<NaiveDateTime, f64>,
+    heart_rate: BTreeMap<NaiveDateTime, u16>,
+}
+
+impl<Tz: TimeZone> FitbitArchive<Tz> {
+    /// Creates an empty archive
+    ///
+    /// # Arguments
+    ///
+    /// * `tz` - The user's time zone, which the UTC timestamps of the steps and heart rate
+    ///   files are converted to, so that days line up with the API's
+    pub fn new(tz: Tz) -> Self {
+        Self {
+            tz,
+            sleep: BTreeMap::new(),
+            steps: BTreeMap::new(),
+            heart_rate: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a file of the archive, if its name is one of an imported kind
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file, e.g. `Sleep/sleep-2025-03-01.json`
+    /// * `contents` - The contents of the file
+    ///
+    /// # Returns
+    ///
+    /// The kind of the file, `None` if files of its name aren't imported, or
+    /// `FitbitError::JsonError` if it can't be parsed
+    pub fn add_file(
+        &mut self,
+        file_name: &str,
+        contents: &[u8],
+    ) -> Result<Option<ArchiveFile>, FitbitError> {
+        let Some(kind) = ArchiveFile::from_file_name(file_name) else {
+            return Ok(None);
+        };
+
+        match kind {
+            ArchiveFile::Sleep => self.add_sleep(contents)?,
+            ArchiveFile::Steps => self.add_steps(contents)?,
+            ArchiveFile::HeartRate => self.add_heart_rate(contents)?,
+        }
+
+        Ok(Some(kind))
+    }
+
+    /// Adds the records of a `sleep-*.json` file
+    pub fn add_sleep(&mut self, contents: &[u8]) -> Result<(), FitbitError> {
+        let records: Vec<Map<String, Value>> = parse_json(contents)?;
+        for record in records {
+            let sleep = sleep_data(record)?;
+            self.sleep.insert(sleep.log_id, sleep);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the samples of a `steps-*.json` file
+    pub fn add_steps(&mut self, contents: &[u8]) -> Result<(), FitbitError> {
+        let entries: Vec<ArchiveEntry<String>> = parse_json(contents)?;
+        for entry in entries {
+            let steps = entry.value.parse::<f64>().map_err(|_| {
+                FitbitError::json_error(format!("invalid step count `{}`", entry.value))
+            })?;
+            self.steps.insert(self.local(entry.date_time), steps);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the samples of a `heart_rate-*.json` file
+    pub fn add_heart_rate(&mut self, contents: &[u8]) -> Result<(), FitbitError> {
+        let entries: Vec<ArchiveEntry<ArchiveHeartRate>> = parse_json(contents)?;
+        for entry in entries {
+            self.heart_rate
+                .insert(self.local(entry.date_time), entry.value.bpm);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the sleep of every night as the API's daily sleep response
+    pub fn sleep_responses(&self) -> BTreeMap<NaiveDate, SleepResponseV1_2> {
+        let mut nights: BTreeMap<NaiveDate, Vec<SleepData>> = BTreeMap::new();
+        for sleep in self.sleep.values() {
+            nights
+                .entry(sleep.date_of_sleep)
+                .or_default()
+                .push(sleep.clone());
+        }
+
+        nights
+            .into_iter()
+            .map(|(date, mut sleep)| {
+                sleep.sort_by_key(|sleep| sleep.start_time);
+                (date, sleep_response(sleep))
+            })
+            .collect()
+    }
+
+    /// Returns the steps per minute of every day, in chronological order
+    pub fn intraday_steps(&self) -> Vec<IntradayActivity> {
+        by_day(&self.steps)
+            .into_iter()
+            .map(|(date, samples)| IntradayActivity {
+                resource: ActivityResource::Steps,
+                date,
+                total: samples.iter().map(|(_, steps)| steps).sum(),
+                dataset_interval: 1,
+                dataset_type: "minute".to_string(),
+                dataset: samples.into_iter().collect(),
+            })
+            .collect()
+    }
+
+    /// Returns the heart rate samples of every day, in chronological order
+    pub fn intraday_heart_rate(&self) -> Vec<IntradayHeartRate> {
+        by_day(&self.heart_rate)
+            .into_iter()
+            .map(|(date, samples)| IntradayHeartRate {
+                date,
+                dataset_interval: 1,
+                dataset_type: "second".to_string(),
+                samples: samples.into_iter().collect(),
+            })
+            .collect()
+    }
+
+    /// Inserts the sleep responses into a cache, so that the analyses of
+    /// [`analysis`](crate::analysis) cover the imported nights without fetching them
+    pub fn fill_cache<C: FitbitClientTrait>(&self, cache: &mut FitbitResponseCache<C>) {
+        for (date, response) in self.sleep_responses() {
+            cache.insert_sleep_response(date, response);
+        }
+    }
+
+    /// Converts a UTC timestamp of the archive to local time, with the offset in effect at
+    /// that instant
+    fn local(&self, time: NaiveDateTime) -> NaiveDateTime {
+        self.tz.from_utc_datetime(&time).naive_local()
+    }
+}
+
+/// A sample of the steps and heart rate files
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveEntry<V> {
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    date_time: NaiveDateTime,
+    value: V,
+}
+
+/// The value of a heart rate sample; the confidence of the reading is ignored
+#[derive(Debug, Deserialize)]
+struct ArchiveHeartRate {
+    bpm: u16,
+}
+
+fn deserialize_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<NaiveDateTime, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    NaiveDateTime::parse_from_str(&value, TIMESTAMP_FORMAT).map_err(serde::de::Error::custom)
+}
+
+/// Converts a sleep record of the archive to the API's shape and parses it
+fn sleep_data(mut record: Map<String, Value>) -> Result<SleepData, FitbitError> {
+    if let Some(main_sleep) = record.remove("mainSleep") {
+        record.insert("isMainSleep".to_string(), main_sleep);
+    }
+    // Older archives don't say how a record was created
+    record
+        .entry("logType")
+        .or_insert_with(|| Value::from("auto_detected"));
+
+    serde_json::from_value(Value::Object(record))
+        .map_err(|error| FitbitError::json_error(error.to_string()))
+}
+
+/// Builds the daily sleep response of a night's records, summing them like the API does
+pub(crate) fn sleep_response(sleep: Vec<SleepData>) -> SleepResponseV1_2 {
+    let mut summary = SleepSummary {
+        total_sleep_records: sleep.len() as u32,
+        ..Default::default()
+    };
+    for record in &sleep {
+        summary.total_minutes_asleep += record.minutes_asleep;
+        summary.total_time_in_bed += record.time_in_bed;
+        if record.sleep_type == SleepType::Stages {
+            let levels = &record.levels.summary;
+            summary.stages = StagesSummary {
+                deep: summary.stages.deep + levels.deep.minutes,
+                light: summary.stages.light + levels.light.minutes,
+                rem: summary.stages.rem + levels.rem.minutes,
+                wake: summary.stages.wake + levels.wake.minutes,
+            };
+        }
+    }
+
+    SleepResponseV1_2 {
+        sleep,
+        summary,
+        #[cfg(feature = "extra-fields")]
+        extra: Default::default(),
+    }
+}
+
+/// Splits timestamped samples into days
+fn by_day<V: Copy>(
+    samples: &BTreeMap<NaiveDateTime, V>,
+) -> BTreeMap<NaiveDate, Vec<(NaiveTime, V)>> {
+    let mut days: BTreeMap<NaiveDate, Vec<_>> = BTreeMap::new();
+    for (time, value) in samples {
+        days.entry(time.date())
+            .or_default()
+            .push((time.time(), *value));
+    }
+
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+    use crate::sleep::SleepLevel;
+
+    const SLEEP: &str = r#"[{
+        "logId": 48192,
+        "dateOfSleep": "2025-03-02",
+        "startTime": "2025-03-01T23:00:00.000",
+        "endTime": "2025-03-02T06:00:00.000",
+        "duration": 25200000,
+        "minutesToFallAsleep": 0,
+        "minutesAsleep": 390,
+        "minutesAwake": 30,
+        "minutesAfterWakeup": 0,
+        "timeInBed": 420,
+        "efficiency": 93,
+        "type": "stages",
+        "infoCode": 0,
+        "levels": {
+            "summary": {
+                "deep": { "count": 3, "minutes": 80, "thirtyDayAvgMinutes": 0 },
+                "wake": { "count": 20, "minutes": 30, "thirtyDayAvgMinutes": 0 },
+                "light": { "count": 25, "minutes": 220, "thirtyDayAvgMinutes": 0 },
+                "rem": { "count": 5, "minutes": 90, "thirtyDayAvgMinutes": 0 }
+            },
+            "data": [
+                { "dateTime": "2025-03-01T23:00:00.000", "level": "light", "seconds": 25200 }
+            ],
+            "shortData": []
+        },
+        "mainSleep": true
+    }]"#;
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(
+            ArchiveFile::from_file_name("MyFitbitData/Sleep/sleep-2025-03-01.json"),
+            Some(ArchiveFile::Sleep)
+        );
+        assert_eq!(
+            ArchiveFile::from_file_name("heart_rate-2025-03-01.json"),
+            Some(ArchiveFile::HeartRate)
+        );
+        assert_eq!(ArchiveFile::from_file_name("steps-2025-03-01.csv"), None);
+        assert_eq!(
+            ArchiveFile::from_file_name("sedentary_minutes-2025-03-01.json"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_import_sleep() -> Result<(), FitbitError> {
+        let mut archive = FitbitArchive::new(FixedOffset::east_opt(0).unwrap());
+        archive.add_file("sleep-2025-03-01.json", SLEEP.as_bytes())?;
+        // The same record in another file is only kept once
+        archive.add_file("sleep-2025-03-02.json", SLEEP.as_bytes())?;
+
+        let responses = archive.sleep_responses();
+        let response = &responses[&date(2)];
+        assert_eq!(response.sleep.len(), 1);
+        assert!(response.sleep[0].is_main_sleep);
+        assert_eq!(response.summary.total_minutes_asleep, 390);
+        assert_eq!(response.summary.stages.deep, 80);
+        assert_eq!(response.sleep[0].levels.data[0].level, SleepLevel::Light);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_steps_in_local_time() -> Result<(), FitbitError> {
+        let steps = r#"[
+            { "dateTime": "03/01/25 22:59:00", "value": "12" },
+            { "dateTime": "03/01/25 23:00:00", "value": "30" },
+            { "dateTime": "03/01/25 23:01:00", "value": "0" }
+        ]"#;
+        let mut archive = FitbitArchive::new(FixedOffset::east_opt(3600).unwrap());
+        archive.add_file("steps-2025-03-01.json", steps.as_bytes())?;
+
+        let days = archive.intraday_steps();
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].date, days[0].total), (date(1), 12.0));
+        assert_eq!((days[1].date, days[1].total), (date(2), 30.0));
+        assert_eq!(days[1].dataset.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_steps_across_dst() -> Result<(), FitbitError> {
+        // Berlin switched from UTC+1 to UTC+2 at 01:00 UTC on March 30
+        let steps = r#"[
+            { "dateTime": "03/29/25 22:59:00", "value": "12" },
+            { "dateTime": "03/29/25 23:00:00", "value": "30" },
+            { "dateTime": "03/30/25 21:59:00", "value": "8" },
+            { "dateTime": "03/30/25 22:00:00", "value": "5" }
+        ]"#;
+        let mut archive = FitbitArchive::new(chrono_tz::Europe::Berlin);
+        archive.add_file("steps-2025-03-29.json", steps.as_bytes())?;
+
+        let days = archive.intraday_steps();
+        assert_eq!(days.len(), 3);
+        assert_eq!((days[0].date, days[0].total), (date(29), 12.0));
+        assert_eq!((days[1].date, days[1].total), (date(30), 38.0));
+        assert_eq!((days[2].date, days[2].total), (date(31), 5.0));
+        assert_eq!(
+            days[1].dataset.iter().last(),
+            Some((NaiveTime::from_hms_opt(23, 59, 0).unwrap(), 8.0))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_heart_rate() -> Result<(), FitbitError> {
+        let heart_rate = r#"[
+            { "dateTime": "03/01/25 07:00:05", "value": { "bpm": 64, "confidence": 3 } },
+            { "dateTime": "03/01/25 07:00:10", "value": { "bpm": 66, "confidence": 2 } }
+        ]"#;
+        let mut archive = FitbitArchive::new(FixedOffset::east_opt(0).unwrap());
+        archive.add_file("heart_rate-2025-03-01.json", heart_rate.as_bytes())?;
+
+        let days = archive.intraday_heart_rate();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].samples.values(), &[64, 66]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let mut archive = FitbitArchive::new(FixedOffset::east_opt(0).unwrap());
+        let result = archive.add_file("steps-2025-03-01.json", br#"[{ "value": "12" }]"#);
+
+        assert!(matches!(result, Err(FitbitError::JsonError { .. })));
+    }
+}
@@ -0,0 +1,349 @@
+//! Compact storage of intraday samples.
+//!
+//! Intraday datasets hold up to one sample per second of a day. [`IntradaySamples`] stores
+//! each sample as its offset from midnight in seconds next to its value, in two separate
+//! arrays, so a day of 1 second heart rate data takes about 500 KB.
+//!
+//! The entries of a dataset are parsed one at a time straight into this buffer, without
+//! buffering the dataset first.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Samples of one day, in chronological order
+///
+/// Times are stored as whole seconds from midnight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntradaySamples<V> {
+    offsets: Vec<u32>,
+    values: Vec<V>,
+}
+
+/// A borrowed, chronological run of [`IntradaySamples`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntradaySlice<'a, V> {
+    offsets: &'a [u32],
+    values: &'a [V],
+}
+
+impl<V> IntradaySamples<V> {
+    /// Creates an empty buffer
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty buffer with room for `capacity` samples
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            offsets: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a sample
+    ///
+    /// Samples must be pushed in chronological order; collect an iterator into the buffer to
+    /// add samples in any order.
+    ///
+    /// # Panics
+    ///
+    /// If `time` is before the time of the last sample
+    pub fn push(&mut self, time: NaiveTime, value: V) {
+        let offset = time.num_seconds_from_midnight();
+        assert!(
+            self.offsets.last().is_none_or(|&last| last <= offset),
+            "intraday samples must be pushed in chronological order"
+        );
+        self.offsets.push(offset);
+        self.values.push(value);
+    }
+
+    /// Returns the number of samples
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if there are no samples
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the offset of each sample from midnight, in seconds
+    pub fn offsets(&self) -> &[u32] {
+        &self.offsets
+    }
+
+    /// Returns the value of each sample
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Borrows all samples
+    pub fn as_slice(&self) -> IntradaySlice<'_, V> {
+        IntradaySlice {
+            offsets: &self.offsets,
+            values: &self.values,
+        }
+    }
+
+    /// Borrows the samples within a time window
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the window (inclusive)
+    /// * `end` - The end of the window (exclusive)
+    ///
+    /// # Returns
+    ///
+    /// The samples from `start` up to `end`, empty if `end` isn't after `start`
+    pub fn between(&self, start: NaiveTime, end: NaiveTime) -> IntradaySlice<'_, V> {
+        self.as_slice().between(start, end)
+    }
+}
+
+impl<V: Copy> IntradaySamples<V> {
+    /// Returns the sample at `index`
+    pub fn get(&self, index: usize) -> Option<(NaiveTime, V)> {
+        self.as_slice().get(index)
+    }
+
+    /// Returns the samples as `(time, value)` pairs, in chronological order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (NaiveTime, V)> + ExactSizeIterator {
+        self.as_slice().iter()
+    }
+
+    /// Returns the samples as timestamps on `date`, e.g. to resample them with
+    /// [`analysis::timeseries`](crate::analysis::timeseries)
+    pub fn at_date(
+        &self,
+        date: NaiveDate,
+    ) -> impl DoubleEndedIterator<Item = (NaiveDateTime, V)> + ExactSizeIterator {
+        self.iter()
+            .map(move |(time, value)| (date.and_time(time), value))
+    }
+}
+
+impl<V> Default for IntradaySamples<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> FromIterator<(NaiveTime, V)> for IntradaySamples<V> {
+    /// Collects samples in any order, sorting them by time
+    fn from_iter<I: IntoIterator<Item = (NaiveTime, V)>>(iter: I) -> Self {
+        let mut pairs: Vec<(u32, V)> = iter
+            .into_iter()
+            .map(|(time, value)| (time.num_seconds_from_midnight(), value))
+            .collect();
+        pairs.sort_by_key(|(offset, _)| *offset);
+
+        let (offsets, values) = pairs.into_iter().unzip();
+        Self { offsets, values }
+    }
+}
+
+impl<V: Copy + Serialize> Serialize for IntradaySamples<V> {
+    /// Serializes the samples as `(time, value)` pairs, in chronological order
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for IntradaySamples<V> {
+    /// Deserializes `(time, value)` pairs in any order, as written by `Serialize`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs = Vec::<(NaiveTime, V)>::deserialize(deserializer)?;
+
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+impl<'a, V> IntradaySlice<'a, V> {
+    /// Returns the number of samples
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if there are no samples
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the offset of each sample from midnight, in seconds
+    pub fn offsets(self) -> &'a [u32] {
+        self.offsets
+    }
+
+    /// Returns the value of each sample
+    pub fn values(self) -> &'a [V] {
+        self.values
+    }
+
+    /// Narrows the slice to the samples from `start` (inclusive) up to `end` (exclusive)
+    pub fn between(self, start: NaiveTime, end: NaiveTime) -> IntradaySlice<'a, V> {
+        let from = self
+            .offsets
+            .partition_point(|&offset| offset < start.num_seconds_from_midnight());
+        let to = self
+            .offsets
+            .partition_point(|&offset| offset < end.num_seconds_from_midnight())
+            .max(from);
+
+        IntradaySlice {
+            offsets: &self.offsets[from..to],
+            values: &self.values[from..to],
+        }
+    }
+}
+
+impl<'a, V: Copy> IntradaySlice<'a, V> {
+    /// Returns the sample at `index`
+    pub fn get(self, index: usize) -> Option<(NaiveTime, V)> {
+        Some((time(*self.offsets.get(index)?), self.values[index]))
+    }
+
+    /// Returns the samples as `(time, value)` pairs, in chronological order
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = (NaiveTime, V)> + ExactSizeIterator + 'a {
+        self.offsets
+            .iter()
+            .zip(self.values)
+            .map(|(&offset, &value)| (time(offset), value))
+    }
+}
+
+/// Converts an offset from midnight back into a time
+fn time(offset: u32) -> NaiveTime {
+    NaiveTime::from_num_seconds_from_midnight_opt(offset, 0)
+        .expect("intraday offsets are within the day")
+}
+
+/// Deserializes a `dataset` array of entries into samples
+///
+/// `E` is the shape of an entry, which is converted into its sample as soon as it is parsed.
+/// The samples are sorted by time, which the API usually already does.
+pub(crate) fn deserialize_samples<'de, D, E, V>(
+    deserializer: D,
+) -> Result<IntradaySamples<V>, D::Error>
+where
+    D: Deserializer<'de>,
+    E: Deserialize<'de> + Into<(NaiveTime, V)>,
+{
+    deserializer.deserialize_seq(SamplesVisitor::<E, V>(PhantomData))
+}
+
+struct SamplesVisitor<E, V>(PhantomData<fn() -> (E, V)>);
+
+impl<'de, E, V> Visitor<'de> for SamplesVisitor<E, V>
+where
+    E: Deserialize<'de> + Into<(NaiveTime, V)>,
+{
+    type Value = IntradaySamples<V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an array of intraday entries")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut samples = IntradaySamples::with_capacity(seq.size_hint().unwrap_or(0));
+        let mut sorted = true;
+        while let Some(entry) = seq.next_element::<E>()? {
+            let (time, value) = entry.into();
+            let offset = time.num_seconds_from_midnight();
+            sorted &= samples.offsets.last().is_none_or(|&last| last <= offset);
+            samples.offsets.push(offset);
+            samples.values.push(value);
+        }
+
+        if sorted {
+            Ok(samples)
+        } else {
+            Ok(samples
+                .offsets
+                .into_iter()
+                .map(time)
+                .zip(samples.values)
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Entry {
+        time: NaiveTime,
+        value: u16,
+    }
+
+    impl From<Entry> for (NaiveTime, u16) {
+        fn from(entry: Entry) -> Self {
+            (entry.time, entry.value)
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Dataset {
+        #[serde(deserialize_with = "deserialize_samples::<_, Entry, _>")]
+        dataset: IntradaySamples<u16>,
+    }
+
+    #[test]
+    fn test_deserialize_samples() {
+        let json_str = r#"{"dataset": [
+            {"time": "00:00:01", "value": 61},
+            {"time": "00:00:00", "value": 60},
+            {"time": "00:00:05", "value": 63}
+        ]}"#;
+
+        let dataset: Dataset = serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(dataset.dataset.offsets(), &[0, 1, 5]);
+        assert_eq!(dataset.dataset.values(), &[60, 61, 63]);
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let time = |seconds| NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0).unwrap();
+        let samples: IntradaySamples<u16> = [(time(0), 60), (time(5), 63)].into_iter().collect();
+
+        let serialized = serde_json::to_value(&samples).expect("Failed to serialize");
+        assert_eq!(
+            serialized,
+            serde_json::json!([["00:00:00", 60], ["00:00:05", 63]])
+        );
+
+        let round_tripped: IntradaySamples<u16> =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, samples);
+    }
+
+    #[test]
+    fn test_slice_samples() {
+        let time = |seconds| NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0).unwrap();
+        let mut samples = IntradaySamples::new();
+        for (seconds, bpm) in [(0, 60u16), (60, 62), (120, 64), (180, 66)] {
+            samples.push(time(seconds), bpm);
+        }
+
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples.get(1), Some((time(60), 62)));
+        assert_eq!(samples.get(4), None);
+
+        let window = samples.between(time(60), time(180));
+        assert_eq!(window.values(), &[62, 64]);
+        assert_eq!(
+            window.iter().collect::<Vec<_>>(),
+            vec![(time(60), 62), (time(120), 64)]
+        );
+        assert!(samples.between(time(180), time(60)).is_empty());
+    }
+}
@@ -7,7 +7,80 @@
 //! * Authentication using access tokens
 //! * Fetch sleep data with detailed sleep stages and levels
 //! * Fetch activity summaries including steps, calories, heart rate zones, etc.
-//! * Response caching to minimize API calls
+//! * Fetch daily activity time series (steps, calories, distance, ...) for date ranges, and
+//!   intraday data in 1 or 15 minute intervals
+//! * Fetch daily Active Zone Minutes per heart rate zone
+//! * Fetch daily resting heart rate and time in heart rate zones, and intraday heart rate
+//!   at up to one second resolution, stored compactly as offsets from midnight
+//! * Fetch the breathing rate during sleep, overall and per sleep stage
+//! * Fetch, create and delete weight logs with BMI and body fat, and fetch the daily weight
+//!   time series
+//! * Fetch food logs with meal types and daily nutrition totals, macro split, remaining
+//!   calories and per-meal subtotals
+//! * Fetch, create and delete water logs
+//! * Fetch and update the calorie intake goal, food plan and water goal
+//! * Create, fetch, update, delete and log saved meals
+//! * Fetch paired devices with their battery state, and fetch, add, update and delete tracker
+//!   alarms
+//! * List, send, accept and decline friend invitations
+//! * Convert distances, weights and water volumes into the user's preferred units or SI
+//! * Page through logged exercises with heart rate zones, pace and GPS details, and
+//!   download their TCX routes or export them as GPX
+//! * Export logged exercises with their GPS track and heart rate as FIT activity files,
+//!   for uploading them to other training platforms
+//! * Read and update daily and weekly activity goals
+//! * Request metric, US or UK units, with typed distances that convert between kilometers
+//!   and miles
+//! * Fetch the user profile, e.g. to convert local timestamps to UTC
+//! * Response caching to minimize API calls, with an optional thread-safe cache
+//!   (`concurrent-cache` feature)
+//! * Resample intraday heart rate, steps and sleep stages to fixed intervals, fill their
+//!   gaps and align them on timestamps
+//! * Fetch long date ranges day by day, keeping the days that succeeded when others fail
+//! * Fetch a day's sleep, activity, heart rate and breathing rate in one call through the
+//!   cache
+//! * Aggregate the sleep and activity of a date range through the cache, with goal-hit
+//!   rates and missing days
+//! * Track progress towards daily and weekly goals, with projected weekly totals and the
+//!   pace needed to reach them
+//! * Correlate daily sleep and activity metrics, e.g. active minutes with the following
+//!   night's deep sleep, using Pearson or Spearman correlation
+//! * Estimate daily readiness from sleep debt, resting heart rate, the previous day's
+//!   activity load and optionally HRV, with configurable weights
+//! * Build weekly and monthly reports with sleep averages, activity totals, streaks, best
+//!   days and notable days, ready to be rendered by exporters
+//! * Render reports and daily records as Markdown tables, with changes from the previous
+//!   period, for journals, note vaults or email bodies
+//! * Detect anomalous days, such as resting heart rate spikes, unusually short sleep and
+//!   days the tracker wasn't worn, using z-scores or interquartile ranges
+//! * Compute a TRIMP-style daily training load from heart rate zones or intraday heart
+//!   rate, and the acute:chronic workload ratio over a range
+//! * Track personal records such as most steps, longest sleep, most deep sleep and most
+//!   Active Zone Minutes, and detect days that set new ones
+//! * Compare two date ranges, e.g. this month with last month, by the change of sleep,
+//!   steps, active minutes and resting heart rate
+//! * Export sleep stages, steps and heart rate as Apple Health compatible XML or CSV records,
+//!   for mirroring data into the Apple ecosystem
+//! * Export sleep sessions, steps and heart rate series as Health Connect shaped JSON
+//!   records, for Android-centric pipelines
+//! * Import sleep, steps and heart rate from the "export your data" archive of an account, to
+//!   analyze history beyond API limits with the same code
+//! * Keep a local SQLite archive of activity summaries, sleep sessions and intraday samples,
+//!   synced incrementally from the API (`sqlite` feature)
+//! * Publish steps, last night's sleep, tracker battery and resting heart rate to an MQTT
+//!   broker, with Home Assistant discovery (`mqtt` feature)
+//! * Render hypnograms, hourly activity bars and heart rate lines as SVG charts (`svg`
+//!   feature)
+//! * Answer subscriber verification requests, and parse and verify the signature of
+//!   Subscriptions API notifications (`webhook` feature)
+//! * Fetch only the data that changed according to notifications, as typed change events
+//!   (`webhook` feature)
+//! * Build sleep records and activity summaries for tests (`test-fixtures` feature)
+//! * Keep the response fields the crate doesn't model yet in `extra`, so that they survive
+//!   a round trip through `Serialize` (`extra-fields` feature)
+//! * Convert distances, weights, volumes and energy into `uom` quantities (`uom` feature)
+//! * Validate responses against the API contract, and reject unknown fields and invalid
+//!   values of sleep, activity and heart rate responses (`strict` feature)
 //!
 //! ## Examples
 //!
@@ -33,16 +106,103 @@
 //! ```
 
 pub mod access_token;
+pub mod activity_catalog;
+pub mod activity_goals;
+pub mod activity_log;
 pub mod activity_summary;
+pub mod activity_time_series;
+pub mod alarm;
+pub mod analysis;
+pub mod apple_health;
+#[cfg(feature = "sqlite")]
+pub mod archive;
+pub mod batch;
+pub mod breathing_rate;
+#[cfg(feature = "svg")]
+pub mod chart;
+#[cfg(feature = "concurrent-cache")]
+mod concurrent_cache;
+pub mod daily_record;
+pub mod device;
 pub mod error;
+mod export;
+pub mod fit;
 pub mod fitbit_client;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+pub mod food;
+pub mod friends;
+pub mod health_connect;
+pub mod heart_rate;
+pub mod import;
+pub mod intraday;
+pub mod meal;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod pagination;
+pub mod profile;
+pub mod report;
 mod response_cache;
+pub mod route;
 pub mod sleep;
+#[cfg(feature = "webhook")]
+pub mod sync;
+pub mod tcx;
+pub mod units;
+pub mod validation;
+pub mod water;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+pub mod weight;
 
 // Re-export the most commonly used types
 pub use access_token::{AccessTokenError, get_access_token};
-pub use activity_summary::ActivitySummaryResponse;
-pub use error::FitbitError;
+pub use activity_catalog::{ActivityCatalog, ActivityDefinition};
+pub use activity_goals::{ActivityGoals, GoalPeriod};
+pub use activity_log::{ActivityLog, ActivityLogListResponse, LogActivityRequest};
+pub use activity_summary::{
+    ActivitySummaryResponse, HeartRateZoneBoundary, HeartRateZoneName, Percentage,
+};
+pub use activity_time_series::{
+    ActiveZoneMinutes, ActivityResource, ActivityTimeSeries, DetailLevel, IntradayActivity,
+    IntradayActivityRange, IntradayRangeConfig, fetch_activity_intraday_days,
+    fetch_activity_intraday_range,
+};
+pub use alarm::{Alarm, AlarmRecurrence, AlarmRequest, AlarmVibe};
+pub use batch::BatchResult;
+pub use breathing_rate::{BreathingRate, BreathingRateByStage};
+#[cfg(feature = "concurrent-cache")]
+pub use concurrent_cache::ConcurrentFitbitResponseCache;
+pub use daily_record::DailyRecord;
+pub use device::{BatteryState, Device, DeviceType};
+pub use error::{ApiErrorType, FitbitError, RequestContext};
 pub use fitbit_client::{FitbitClient, FitbitClientTrait};
+#[cfg(any(test, feature = "test-fixtures"))]
+pub use fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+pub use food::{
+    FoodGoalResponse, FoodGoalUpdate, FoodGoals, FoodLog, FoodLogResponse, FoodPlan,
+    FoodPlanIntensity, MacroSplit, MealType, NutritionSummary, NutritionalValues,
+};
+pub use friends::{FriendInvitation, Invitee};
+pub use heart_rate::{
+    CustomHeartRateZone, HeartRateBucket, HeartRateDay, HeartRateDetailLevel, IntradayHeartRate,
+};
+pub use intraday::{IntradaySamples, IntradaySlice};
+pub use meal::{Meal, MealFood, MealRequest};
+pub use pagination::{DateFilter, Pagination, SortOrder};
+pub use profile::{ProfileResponse, UserProfile};
 pub use response_cache::FitbitResponseCache;
-pub use sleep::{SleepLevel, SleepResponse, SleepResponseV1_2};
+pub use route::{Route, RoutePoint};
+pub use sleep::{
+    Sleep, SleepAnalysisConfig, SleepLevel, SleepLogListResponse, SleepLogType, SleepRangeResponse,
+    SleepRecord, SleepResponse, SleepResponseV1, SleepResponseV1_2, SleepType, StageComparison,
+    StageInterval, StageTransitions, TimelineReport,
+};
+pub use tcx::{TcxTrack, TrackPoint};
+pub use units::{
+    DistanceUnit, DistanceValue, UnitSystem, Units, VolumeUnit, VolumeValue, WeightUnit,
+    WeightValue,
+};
+pub use validation::{Validate, ValidationIssue};
+pub use water::{WaterGoal, WaterLog, WaterLogs};
+pub use weight::{WeightLog, WeightLogSource};
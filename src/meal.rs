@@ -0,0 +1,197 @@
+//! Saved meals, reusable groups of foods.
+
+use crate::food::{FoodUnit, MealType};
+use serde::{Deserialize, Serialize};
+
+/// A saved meal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meal {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub meal_foods: Vec<MealFood>,
+}
+
+/// A food of a saved meal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MealFood {
+    pub food_id: u64,
+    pub name: String,
+    /// Amount in `unit`
+    pub amount: f64,
+    pub unit: FoodUnit,
+    pub calories: u32,
+    #[serde(rename = "mealTypeId")]
+    pub meal_type: MealType,
+}
+
+/// Raw meal list response
+#[derive(Debug, Deserialize)]
+pub(crate) struct MealListResponse {
+    pub meals: Vec<Meal>,
+}
+
+/// Raw single meal response, returned when fetching, creating or updating a meal
+#[derive(Debug, Deserialize)]
+pub(crate) struct MealResponse {
+    pub meal: Meal,
+}
+
+/// Request for creating or updating a saved meal
+///
+/// # Example
+///
+/// ```
+/// use fitbit_rs::MealRequest;
+///
+/// // Two medium apples (food 81282, unit 226) and a cup of yogurt (food 19561, unit 91)
+/// let request = MealRequest::new("Snack")
+///     .description("Apples and yogurt")
+///     .food(81282, 226, 2.0)
+///     .food(19561, 91, 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MealRequest {
+    name: String,
+    description: String,
+    meal_foods: Vec<MealFoodRequest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MealFoodRequest {
+    food_id: u64,
+    unit_id: u32,
+    amount: f64,
+}
+
+impl MealRequest {
+    /// Creates a request for a meal without foods
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the meal
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            meal_foods: Vec::new(),
+        }
+    }
+
+    /// Sets the description of the meal
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Adds a food to the meal
+    ///
+    /// # Arguments
+    ///
+    /// * `food_id` - The id of the food
+    /// * `unit_id` - The id of one of the food's units
+    /// * `amount` - The amount in the unit
+    pub fn food(mut self, food_id: u64, unit_id: u32, amount: f64) -> Self {
+        self.meal_foods.push(MealFoodRequest {
+            food_id,
+            unit_id,
+            amount,
+        });
+        self
+    }
+
+    /// Returns an error message if the API would reject the request
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("meal name must not be empty".to_string());
+        }
+        if self.meal_foods.is_empty() {
+            return Err("meal must contain at least one food".to_string());
+        }
+        if let Some(food) = self
+            .meal_foods
+            .iter()
+            .find(|food| !(food.amount.is_finite() && food.amount > 0.0))
+        {
+            return Err(format!(
+                "food amounts must be positive, got {} for food {}",
+                food.amount, food.food_id
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meals() {
+        let json_str = r#"{
+            "meals": [
+                {
+                    "description": "Apples and yogurt",
+                    "id": 1137362,
+                    "mealFoods": [
+                        {
+                            "amount": 2,
+                            "calories": 190,
+                            "foodId": 81282,
+                            "mealTypeId": 7,
+                            "name": "Apple",
+                            "unit": {"id": 226, "name": "medium", "plural": "medium"}
+                        }
+                    ],
+                    "name": "Snack"
+                }
+            ]
+        }"#;
+
+        let response: MealListResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.meals.len(), 1);
+        let meal = &response.meals[0];
+        assert_eq!(meal.id, 1137362);
+        assert_eq!(meal.name, "Snack");
+        assert_eq!(meal.meal_foods[0].amount, 2.0);
+        assert_eq!(meal.meal_foods[0].unit.id, 226);
+        assert_eq!(meal.meal_foods[0].meal_type, MealType::Anytime);
+    }
+
+    #[test]
+    fn test_meal_request_body() {
+        let request = MealRequest::new("Snack")
+            .description("Apples")
+            .food(81282, 226, 2.0);
+
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "name": "Snack",
+                "description": "Apples",
+                "mealFoods": [{"foodId": 81282, "unitId": 226, "amount": 2.0}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_meal_request_validation() {
+        assert!(MealRequest::new("Snack").validate().is_err());
+        assert!(MealRequest::new(" ").food(1, 1, 1.0).validate().is_err());
+        assert!(
+            MealRequest::new("Snack")
+                .food(1, 1, 0.0)
+                .validate()
+                .is_err()
+        );
+    }
+}
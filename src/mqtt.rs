@@ -0,0 +1,505 @@
+//! Publishing of metrics to an MQTT broker, with Home Assistant discovery.
+//!
+//! [`MqttPublisher`] publishes a sensor per metric, so Home Assistant picks up steps, last
+//! night's sleep, the tracker battery and the resting heart rate without any configuration.
+//! The messages are built by [`HomeAssistantConfig`], independent of the MQTT client, e.g.
+//! to publish them with another client.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fitbit_rs::FitbitClient;
+//! use fitbit_rs::mqtt::{HomeAssistantConfig, MetricValues, MqttPublisher};
+//! use rumqttc::{Client, MqttOptions};
+//!
+//! let (mqtt, mut connection) = Client::new(MqttOptions::new("fitbit-rs", "localhost", 1883), 10);
+//! std::thread::spawn(move || for _ in connection.iter() {});
+//!
+//! let publisher = MqttPublisher::new(mqtt, HomeAssistantConfig::default())?;
+//! publisher.publish_discovery()?;
+//!
+//! let client = FitbitClient::new("your_access_token".to_string());
+//! let today = chrono::Local::now().date_naive();
+//! publisher.publish(&MetricValues::fetch(&client, today)?)?;
+//! # Ok::<(), fitbit_rs::FitbitError>(())
+//! ```
+
+use crate::daily_record::unless_unavailable;
+use crate::device::Device;
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use chrono::NaiveDate;
+use rumqttc::{Client, QoS};
+use serde_json::json;
+
+/// A metric published as a Home Assistant sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PublishedMetric {
+    /// Steps of the day so far
+    Steps,
+    /// Minutes asleep of last night's main sleep
+    LastSleepDuration,
+    /// Battery level of the tracker that synced last, in percent
+    Battery,
+    RestingHeartRate,
+}
+
+impl PublishedMetric {
+    /// All metrics, in the order they are published
+    pub const ALL: [PublishedMetric; 4] = [
+        PublishedMetric::Steps,
+        PublishedMetric::LastSleepDuration,
+        PublishedMetric::Battery,
+        PublishedMetric::RestingHeartRate,
+    ];
+
+    /// Returns the id of the sensor within the device, e.g. `resting_heart_rate`
+    pub fn object_id(&self) -> &'static str {
+        match self {
+            PublishedMetric::Steps => "steps",
+            PublishedMetric::LastSleepDuration => "last_sleep_duration",
+            PublishedMetric::Battery => "battery",
+            PublishedMetric::RestingHeartRate => "resting_heart_rate",
+        }
+    }
+
+    /// Returns the name Home Assistant shows for the sensor
+    fn name(&self) -> &'static str {
+        match self {
+            PublishedMetric::Steps => "Steps",
+            PublishedMetric::LastSleepDuration => "Last sleep duration",
+            PublishedMetric::Battery => "Battery",
+            PublishedMetric::RestingHeartRate => "Resting heart rate",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            PublishedMetric::Steps => "steps",
+            PublishedMetric::LastSleepDuration => "min",
+            PublishedMetric::Battery => "%",
+            PublishedMetric::RestingHeartRate => "bpm",
+        }
+    }
+
+    /// Returns the Home Assistant device class, if one fits
+    fn device_class(&self) -> Option<&'static str> {
+        match self {
+            PublishedMetric::LastSleepDuration => Some("duration"),
+            PublishedMetric::Battery => Some("battery"),
+            PublishedMetric::Steps | PublishedMetric::RestingHeartRate => None,
+        }
+    }
+
+    /// Returns the Home Assistant state class; steps count up during the day and reset at
+    /// midnight
+    fn state_class(&self) -> &'static str {
+        match self {
+            PublishedMetric::Steps => "total_increasing",
+            _ => "measurement",
+        }
+    }
+
+    fn icon(&self) -> Option<&'static str> {
+        match self {
+            PublishedMetric::Steps => Some("mdi:walk"),
+            PublishedMetric::LastSleepDuration => Some("mdi:sleep"),
+            PublishedMetric::RestingHeartRate => Some("mdi:heart-pulse"),
+            PublishedMetric::Battery => None,
+        }
+    }
+}
+
+/// The values of the published metrics; metrics without a value aren't published
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricValues {
+    pub steps: Option<u32>,
+    pub last_sleep_minutes: Option<u32>,
+    pub battery_level: Option<u8>,
+    pub resting_heart_rate: Option<i32>,
+}
+
+impl MetricValues {
+    /// Fetches the values of a day
+    ///
+    /// Data Fitbit has no data for, or that the access token lacks the scope to read, has no
+    /// value.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to fetch the values with
+    /// * `date` - The day to fetch steps and resting heart rate of, and whose night's sleep
+    ///   is the last sleep
+    ///
+    /// # Returns
+    ///
+    /// The values, or the first error that doesn't mean data is unavailable
+    pub fn fetch<C: FitbitClientTrait>(client: &C, date: NaiveDate) -> Result<Self, FitbitError> {
+        let activity = unless_unavailable(client.fetch_activity_summary(date))?;
+        let sleep = unless_unavailable(client.fetch_sleep_data(date))?;
+        let devices = unless_unavailable(client.fetch_devices())?.unwrap_or_default();
+
+        Ok(Self {
+            steps: activity.as_ref().map(|activity| activity.summary.steps),
+            last_sleep_minutes: sleep
+                .as_ref()
+                .and_then(|sleep| sleep.main_sleep())
+                .map(|sleep| sleep.minutes_asleep),
+            battery_level: battery_level(&devices),
+            resting_heart_rate: activity.and_then(|activity| activity.summary.resting_heart_rate),
+        })
+    }
+
+    /// Returns the value of a metric as the state payload
+    fn state(&self, metric: PublishedMetric) -> Option<String> {
+        match metric {
+            PublishedMetric::Steps => self.steps.map(|steps| steps.to_string()),
+            PublishedMetric::LastSleepDuration => {
+                self.last_sleep_minutes.map(|minutes| minutes.to_string())
+            }
+            PublishedMetric::Battery => self.battery_level.map(|level| level.to_string()),
+            PublishedMetric::RestingHeartRate => self
+                .resting_heart_rate
+                .map(|heart_rate| heart_rate.to_string()),
+        }
+    }
+}
+
+/// Returns the battery level of the device that synced last among those that report one
+fn battery_level(devices: &[Device]) -> Option<u8> {
+    devices
+        .iter()
+        .filter(|device| device.battery_level.is_some())
+        .max_by_key(|device| device.last_sync_time)
+        .and_then(|device| device.battery_level)
+}
+
+/// A message to publish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: String,
+    /// Whether the broker keeps the message for new subscribers
+    pub retain: bool,
+}
+
+/// The topics and device that metrics are published under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomeAssistantConfig {
+    /// Prefix of the discovery topics, as configured in Home Assistant
+    pub discovery_prefix: String,
+    /// Prefix of the state topics
+    pub base_topic: String,
+    /// Id of the device the sensors belong to; unique per published account. It is also
+    /// the node id of the discovery topics, so it may only contain letters, digits, `_`
+    /// and `-`
+    pub device_id: String,
+    /// Name Home Assistant shows for the device
+    pub device_name: String,
+    pub metrics: Vec<PublishedMetric>,
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        Self {
+            discovery_prefix: "homeassistant".to_string(),
+            base_topic: "fitbit".to_string(),
+            device_id: "fitbit".to_string(),
+            device_name: "Fitbit".to_string(),
+            metrics: PublishedMetric::ALL.to_vec(),
+        }
+    }
+}
+
+impl HomeAssistantConfig {
+    /// Checks that the device id can be used in the state and discovery topics
+    ///
+    /// The device id is a single level of the state topics, so it can't contain the level
+    /// separator `/` or the wildcards `+` and `#`. As the node id of the discovery topics,
+    /// Home Assistant only accepts letters, digits, `_` and `-` in it.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the device id is valid, otherwise `FitbitError::InvalidArgument`
+    pub fn validate(&self) -> Result<(), FitbitError> {
+        if self.device_id.contains(['+', '#', '/']) {
+            return Err(FitbitError::InvalidArgument(format!(
+                "device id {:?} must not contain `+`, `#` or `/`",
+                self.device_id
+            )));
+        }
+        let is_node_id = !self.device_id.is_empty()
+            && self
+                .device_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_node_id {
+            return Err(FitbitError::InvalidArgument(format!(
+                "device id {:?} is not a valid discovery node id, which may only contain \
+                 letters, digits, `_` and `-`",
+                self.device_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the topic the state of a metric is published to, e.g. `fitbit/fitbit/steps`
+    pub fn state_topic(&self, metric: PublishedMetric) -> String {
+        format!(
+            "{}/{}/{}",
+            self.base_topic,
+            self.device_id,
+            metric.object_id()
+        )
+    }
+
+    /// Returns the retained discovery messages that create a sensor per metric
+    ///
+    /// [`validate`](Self::validate) the configuration first when publishing the messages
+    /// with another client.
+    pub fn discovery_messages(&self) -> Vec<MqttMessage> {
+        self.metrics
+            .iter()
+            .map(|&metric| {
+                let mut config = json!({
+                    "name": metric.name(),
+                    "unique_id": format!("{}_{}", self.device_id, metric.object_id()),
+                    "state_topic": self.state_topic(metric),
+                    "unit_of_measurement": metric.unit(),
+                    "state_class": metric.state_class(),
+                    "device": {
+                        "identifiers": [self.device_id],
+                        "name": self.device_name,
+                        "manufacturer": "Fitbit",
+                    },
+                });
+                if let Some(device_class) = metric.device_class() {
+                    config["device_class"] = json!(device_class);
+                }
+                if let Some(icon) = metric.icon() {
+                    config["icon"] = json!(icon);
+                }
+
+                MqttMessage {
+                    topic: format!(
+                        "{}/sensor/{}/{}/config",
+                        self.discovery_prefix,
+                        self.device_id,
+                        metric.object_id()
+                    ),
+                    payload: config.to_string(),
+                    retain: true,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the retained state messages of the metrics that have a value
+    pub fn state_messages(&self, values: &MetricValues) -> Vec<MqttMessage> {
+        self.metrics
+            .iter()
+            .filter_map(|&metric| {
+                Some(MqttMessage {
+                    topic: self.state_topic(metric),
+                    payload: values.state(metric)?,
+                    retain: true,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Publishes metrics to an MQTT broker
+///
+/// The client only queues messages; its connection must be polled, e.g. on another thread,
+/// for them to be sent.
+pub struct MqttPublisher {
+    client: Client,
+    config: HomeAssistantConfig,
+}
+
+impl MqttPublisher {
+    /// Creates a publisher
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The MQTT client to publish with
+    /// * `config` - The topics and device to publish under
+    ///
+    /// # Returns
+    ///
+    /// The publisher, or `FitbitError::InvalidArgument` if the device id can't be used in
+    /// topics; see [`HomeAssistantConfig::validate`]
+    pub fn new(client: Client, config: HomeAssistantConfig) -> Result<Self, FitbitError> {
+        config.validate()?;
+
+        Ok(Self { client, config })
+    }
+
+    /// Publishes the discovery messages, e.g. once after connecting
+    pub fn publish_discovery(&self) -> Result<(), FitbitError> {
+        self.publish_all(self.config.discovery_messages())
+    }
+
+    /// Publishes the values of the metrics
+    pub fn publish(&self, values: &MetricValues) -> Result<(), FitbitError> {
+        self.publish_all(self.config.state_messages(values))
+    }
+
+    fn publish_all(&self, messages: Vec<MqttMessage>) -> Result<(), FitbitError> {
+        for message in messages {
+            self.client.publish(
+                message.topic,
+                QoS::AtLeastOnce,
+                message.retain,
+                message.payload,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{BatteryState, DeviceType};
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::ActivitySummaryBuilder;
+
+    fn device(battery_level: Option<u8>, sync_hour: u32) -> Device {
+        Device {
+            id: "1".to_string(),
+            device_version: "Charge 6".to_string(),
+            device_type: DeviceType::Tracker,
+            battery: BatteryState::High,
+            battery_level,
+            last_sync_time: NaiveDate::from_ymd_opt(2025, 3, 1)
+                .unwrap()
+                .and_hms_opt(sync_hour, 0, 0)
+                .unwrap(),
+            mac: None,
+            features: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_discovery_messages() {
+        let config = HomeAssistantConfig {
+            metrics: vec![PublishedMetric::Battery],
+            ..Default::default()
+        };
+
+        let messages = config.discovery_messages();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0].topic,
+            "homeassistant/sensor/fitbit/battery/config"
+        );
+        assert!(messages[0].retain);
+        let payload: serde_json::Value = serde_json::from_str(&messages[0].payload).unwrap();
+        assert_eq!(payload["state_topic"], "fitbit/fitbit/battery");
+        assert_eq!(payload["device_class"], "battery");
+        assert_eq!(payload["unique_id"], "fitbit_battery");
+        assert_eq!(payload.get("icon"), None);
+    }
+
+    #[test]
+    fn test_validate_device_id() {
+        let config = |device_id: &str| HomeAssistantConfig {
+            device_id: device_id.to_string(),
+            ..Default::default()
+        };
+
+        assert!(HomeAssistantConfig::default().validate().is_ok());
+        assert!(config("fitbit_Alice-2").validate().is_ok());
+        for device_id in ["fit/bit", "fitbit+", "#", "", "fitbit alice", "fitbït"] {
+            assert!(matches!(
+                config(device_id).validate(),
+                Err(FitbitError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_publisher_rejects_invalid_device_id() {
+        let (client, _connection) =
+            Client::new(rumqttc::MqttOptions::new("test", "localhost", 1883), 10);
+        let config = HomeAssistantConfig {
+            device_id: "fitbit/#".to_string(),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            MqttPublisher::new(client, config),
+            Err(FitbitError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_state_messages_skip_missing_values() {
+        let values = MetricValues {
+            steps: Some(8_000),
+            resting_heart_rate: Some(58),
+            ..Default::default()
+        };
+
+        let messages = HomeAssistantConfig::default().state_messages(&values);
+
+        let states: Vec<(&str, &str)> = messages
+            .iter()
+            .map(|message| (message.topic.as_str(), message.payload.as_str()))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("fitbit/fitbit/steps", "8000"),
+                ("fitbit/fitbit/resting_heart_rate", "58")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_battery_of_last_synced_device() {
+        let devices = [device(Some(80), 7), device(Some(40), 9), device(None, 10)];
+
+        assert_eq!(battery_level(&devices), Some(40));
+        assert_eq!(battery_level(&[]), None);
+    }
+
+    #[test]
+    fn test_fetch_values() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client.expect_fetch_activity_summary().returning(|_| {
+            Ok(ActivitySummaryBuilder::new()
+                .steps(8_000)
+                .resting_heart_rate(58)
+                .build())
+        });
+        mock_client.expect_fetch_sleep_data().returning(|_| {
+            Err(FitbitError::NotFound {
+                message: "No data".to_string(),
+            })
+        });
+        mock_client
+            .expect_fetch_devices()
+            .returning(|| Ok(vec![device(Some(80), 7)]));
+
+        let values =
+            MetricValues::fetch(&mock_client, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap())?;
+
+        assert_eq!(
+            values,
+            MetricValues {
+                steps: Some(8_000),
+                last_sleep_minutes: None,
+                battery_level: Some(80),
+                resting_heart_rate: Some(58),
+            }
+        );
+
+        Ok(())
+    }
+}
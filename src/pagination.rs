@@ -0,0 +1,118 @@
+//! Types shared by the paginated list endpoints.
+//!
+//! Fitbit's list endpoints (e.g. the sleep log list) return results one page at a time,
+//! together with a `pagination` object describing the request and a cursor URL for the
+//! next page.
+
+use serde::{Deserialize, Serialize};
+
+/// The date filter of a list request
+///
+/// Fitbit requires exactly one of `beforeDate` or `afterDate` to be given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFilter {
+    /// Only return entries before the given date
+    Before(chrono::NaiveDate),
+    /// Only return entries after the given date
+    After(chrono::NaiveDate),
+}
+
+impl DateFilter {
+    /// Returns the query string fragment for this filter, e.g. `beforeDate=2024-01-01`
+    pub(crate) fn to_query(self) -> String {
+        match self {
+            DateFilter::Before(date) => format!("beforeDate={}", date.format("%Y-%m-%d")),
+            DateFilter::After(date) => format!("afterDate={}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+/// Sort order of a list request
+///
+/// Fitbit only accepts `Ascending` together with [`DateFilter::After`] and `Descending`
+/// together with [`DateFilter::Before`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    /// Oldest entries first
+    #[serde(rename = "asc")]
+    Ascending,
+    /// Newest entries first
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+impl SortOrder {
+    /// Returns the value used for the `sort` query parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "asc",
+            SortOrder::Descending => "desc",
+        }
+    }
+}
+
+/// Pagination information returned by list endpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    /// The `afterDate` filter of the request, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_date: Option<String>,
+    /// The `beforeDate` filter of the request, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_date: Option<String>,
+    /// Maximum number of entries per page
+    pub limit: u32,
+    /// Offset of this page
+    pub offset: u32,
+    /// Sort order of the request
+    pub sort: SortOrder,
+    /// URL of the next page, or an empty string if this is the last page
+    #[serde(default)]
+    pub next: String,
+    /// URL of the previous page, or an empty string if this is the first page
+    #[serde(default)]
+    pub previous: String,
+}
+
+impl Pagination {
+    /// Returns the URL of the next page, if there is one
+    pub fn next_url(&self) -> Option<&str> {
+        Some(self.next.as_str()).filter(|next| !next.is_empty())
+    }
+
+    /// Returns the URL of the previous page, if there is one
+    pub fn previous_url(&self) -> Option<&str> {
+        Some(self.previous.as_str()).filter(|previous| !previous.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pagination() {
+        let json_str = r#"{
+            "beforeDate": "2017-04-01",
+            "limit": 1,
+            "next": "https://api.fitbit.com/1.2/user/-/sleep/list.json?offset=1&limit=1&beforeDate=2017-04-01&sort=desc",
+            "offset": 0,
+            "previous": "",
+            "sort": "desc"
+        }"#;
+
+        let pagination: Pagination = serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(pagination.before_date.as_deref(), Some("2017-04-01"));
+        assert_eq!(pagination.after_date, None);
+        assert_eq!(pagination.sort, SortOrder::Descending);
+        assert_eq!(
+            pagination.next_url(),
+            Some(
+                "https://api.fitbit.com/1.2/user/-/sleep/list.json?offset=1&limit=1&beforeDate=2017-04-01&sort=desc"
+            )
+        );
+        assert_eq!(pagination.previous_url(), None);
+    }
+}
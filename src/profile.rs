@@ -0,0 +1,114 @@
+use crate::units::UnitSystem;
+use chrono::FixedOffset;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileResponse {
+    pub user: UserProfile,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The parts of the user profile needed to interpret other responses
+///
+/// Fields that require scopes other than `profile` are not included.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub encoded_id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub full_name: String,
+    #[serde(default)]
+    pub locale: String,
+    /// IANA name of the user's timezone, e.g. `Europe/Berlin`
+    pub timezone: String,
+    /// The current offset of `timezone` from UTC
+    #[serde(rename = "offsetFromUTCMillis")]
+    pub offset_from_utc_millis: i64,
+    /// The unit system the user prefers for distances
+    #[serde(default)]
+    pub distance_unit: UnitSystem,
+    /// The unit system the user prefers for weights
+    #[serde(default)]
+    pub weight_unit: UnitSystem,
+    /// The unit system the user prefers for water
+    #[serde(default)]
+    pub water_unit: UnitSystem,
+}
+
+impl UserProfile {
+    /// Returns the user's current UTC offset
+    ///
+    /// All timestamps in Fitbit responses are in the user's local time. This offset converts
+    /// them to absolute times; note that it is the offset at the time of the request, so it
+    /// is off by the DST difference for data recorded in the other half of the year.
+    ///
+    /// # Returns
+    ///
+    /// The offset, or `None` if Fitbit reported an offset of a day or more
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        i32::try_from(self.offset_from_utc_millis / 1000)
+            .ok()
+            .and_then(FixedOffset::east_opt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let json_str = r#"{
+            "user": {
+                "age": 34,
+                "displayName": "Jane D.",
+                "encodedId": "ABC123",
+                "fullName": "Jane Doe",
+                "locale": "de_DE",
+                "memberSince": "2019-06-01",
+                "offsetFromUTCMillis": 3600000,
+                "timezone": "Europe/Berlin",
+                "distanceUnit": "METRIC",
+                "weightUnit": "en_US"
+            }
+        }"#;
+
+        let response: ProfileResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.user.encoded_id, "ABC123");
+        assert_eq!(response.user.timezone, "Europe/Berlin");
+        assert_eq!(response.user.utc_offset(), FixedOffset::east_opt(3600));
+        assert_eq!(response.user.distance_unit, UnitSystem::Metric);
+        assert_eq!(response.user.weight_unit, UnitSystem::Us);
+        assert_eq!(response.user.water_unit, UnitSystem::Metric);
+    }
+
+    #[cfg(feature = "extra-fields")]
+    #[test]
+    fn test_keeps_unknown_fields() {
+        let json_str = r#"{
+            "user": {
+                "displayName": "Jane",
+                "encodedId": "ABC123",
+                "offsetFromUTCMillis": 0,
+                "timezone": "UTC"
+            },
+            "features": {"exerciseGoal": true}
+        }"#;
+
+        let response: ProfileResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(
+            response.extra["features"],
+            serde_json::json!({"exerciseGoal": true})
+        );
+        let serialized = serde_json::to_value(&response).unwrap();
+        assert_eq!(serialized["features"]["exerciseGoal"], true);
+    }
+}
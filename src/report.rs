@@ -0,0 +1,296 @@
+//! Weekly and monthly reports of sleep and activity.
+//!
+//! A [`PeriodReport`] collects what a summary of a week or month shows: sleep averages,
+//! activity totals, goal streaks, the best days and the days that stood out. It holds data
+//! only, so that exporters can render it in any format.
+
+pub mod markdown;
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::analysis::activity::{MissingDays, StepGoalStreaks, step_goal_streaks};
+use crate::analysis::anomaly::{Anomaly, AnomalyMethod, detect_anomalies};
+use crate::analysis::correlation::DailyMetric;
+use crate::analysis::range::{RangeAggregate, aggregate};
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::response_cache::FitbitResponseCache;
+use crate::sleep::SleepData;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Distance from the period mean, in standard deviations, from which a day is notable
+const NOTABLE_Z_SCORE: f64 = 2.0;
+
+/// The period a report covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReportPeriod {
+    /// The seven days starting at a date
+    Week(NaiveDate),
+    /// The calendar month of a date
+    Month(NaiveDate),
+}
+
+impl ReportPeriod {
+    /// Returns the first day of the period
+    pub fn start(&self) -> NaiveDate {
+        match *self {
+            ReportPeriod::Week(start) => start,
+            ReportPeriod::Month(date) => date - Duration::days(date.day0() as i64),
+        }
+    }
+
+    /// Returns the last day of the period (inclusive)
+    pub fn end(&self) -> NaiveDate {
+        match *self {
+            ReportPeriod::Week(start) => start + Duration::days(6),
+            ReportPeriod::Month(_) => self
+                .start()
+                .checked_add_months(Months::new(1))
+                .and_then(|next| next.pred_opt())
+                .unwrap_or(NaiveDate::MAX),
+        }
+    }
+}
+
+/// The best value of a metric in a period and the day it was reached
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodBest<T> {
+    pub date: NaiveDate,
+    pub value: T,
+}
+
+/// The best days of a period; the earliest one if several are equally good
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodBests {
+    pub most_steps: Option<PeriodBest<u32>>,
+    /// Fairly and very active minutes
+    pub most_active_minutes: Option<PeriodBest<i32>>,
+    pub lowest_resting_heart_rate: Option<PeriodBest<i32>>,
+    /// Minutes asleep of the main sleep
+    pub longest_sleep: Option<PeriodBest<u32>>,
+    /// Efficiency of the main sleep, as reported by Fitbit
+    pub best_sleep_efficiency: Option<PeriodBest<u8>>,
+}
+
+/// The sleep and activity of a week or month
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodReport {
+    pub period: ReportPeriod,
+    /// Sleep averages, activity totals, goal rates and missing days
+    pub aggregate: RangeAggregate,
+    /// Streaks of days meeting the steps goal within the period
+    pub steps_goal_streaks: StepGoalStreaks,
+    pub bests: PeriodBests,
+    /// Days whose steps, resting heart rate or sleep are at least 2 standard deviations
+    /// from the period mean, and days the tracker wasn't worn, in chronological order
+    pub notable_days: Vec<Anomaly>,
+}
+
+impl PeriodReport {
+    /// Creates the report of a period
+    ///
+    /// Responses come from the cache, and are fetched and cached if missing. Days Fitbit has
+    /// no data for, or that the access token lacks the scope to read, are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - The week or month to report on
+    /// * `cache` - The cache to get the responses from
+    ///
+    /// # Returns
+    ///
+    /// The report, or the first error that doesn't mean a day is unavailable
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fitbit_rs::report::{PeriodReport, ReportPeriod};
+    /// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+    ///
+    /// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+    /// let today = chrono::Local::now().date_naive();
+    ///
+    /// let report = PeriodReport::fetch(ReportPeriod::Month(today), &mut cache)?;
+    /// println!(
+    ///     "{} steps, longest steps goal streak of {} days",
+    ///     report.aggregate.activity.total_steps, report.steps_goal_streaks.longest
+    /// );
+    /// # Ok::<(), fitbit_rs::FitbitError>(())
+    /// ```
+    pub fn fetch<C: FitbitClientTrait>(
+        period: ReportPeriod,
+        cache: &mut FitbitResponseCache<C>,
+    ) -> Result<Self, FitbitError> {
+        let (start, end) = (period.start(), period.end());
+        let aggregate = aggregate(start..=end, cache)?;
+
+        // The aggregate already fetched every available day, so these are cache hits
+        let activity: BTreeMap<NaiveDate, ActivitySummaryResponse> = cache
+            .get_activity_summary_responses(start, end)?
+            .successes
+            .into_iter()
+            .map(|(date, response)| (date, response.clone()))
+            .collect();
+        let sleep: BTreeMap<NaiveDate, SleepData> = cache
+            .get_sleep_responses(start, end)?
+            .successes
+            .into_iter()
+            .filter_map(|(date, response)| Some((date, response.main_sleep()?.clone())))
+            .collect();
+
+        Ok(Self::from_days(period, aggregate, &activity, &sleep))
+    }
+
+    /// Creates the report from the aggregate and the responses of the period
+    fn from_days(
+        period: ReportPeriod,
+        aggregate: RangeAggregate,
+        activity: &BTreeMap<NaiveDate, ActivitySummaryResponse>,
+        sleep: &BTreeMap<NaiveDate, SleepData>,
+    ) -> Self {
+        let bests = PeriodBests {
+            most_steps: best(activity, |response| Some(response.summary.steps), true),
+            most_active_minutes: best(
+                activity,
+                |response| Some(response.total_active_minutes()),
+                true,
+            ),
+            lowest_resting_heart_rate: best(
+                activity,
+                |response| response.summary.resting_heart_rate,
+                false,
+            ),
+            longest_sleep: best(sleep, |sleep| Some(sleep.minutes_asleep), true),
+            best_sleep_efficiency: best(sleep, |sleep| sleep.efficiency, true),
+        };
+
+        let mut notable_days = notable(DailyMetric::Steps, activity, |response| {
+            Some(response.summary.steps as f64)
+        });
+        notable_days.extend(notable(
+            DailyMetric::RestingHeartRate,
+            activity,
+            |response| Some(response.summary.resting_heart_rate? as f64),
+        ));
+        notable_days.extend(notable(DailyMetric::MinutesAsleep, sleep, |sleep| {
+            Some(sleep.minutes_asleep as f64)
+        }));
+        notable_days.sort_by_key(|day| day.date);
+
+        Self {
+            period,
+            aggregate,
+            steps_goal_streaks: step_goal_streaks(activity, MissingDays::BreakStreak),
+            bests,
+            notable_days,
+        }
+    }
+}
+
+/// Returns the day with the highest value, or the lowest if `highest` is false
+fn best<T, V: Ord + Copy>(
+    days: &BTreeMap<NaiveDate, T>,
+    value: impl Fn(&T) -> Option<V>,
+    highest: bool,
+) -> Option<PeriodBest<V>> {
+    let mut best: Option<PeriodBest<V>> = None;
+    for (&date, day) in days {
+        let Some(value) = value(day) else {
+            continue;
+        };
+        let better = best.is_none_or(|best| {
+            if highest {
+                value > best.value
+            } else {
+                value < best.value
+            }
+        });
+        if better {
+            best = Some(PeriodBest { date, value });
+        }
+    }
+
+    best
+}
+
+/// Returns the days whose value of a metric is notable
+fn notable<T>(
+    metric: DailyMetric,
+    days: &BTreeMap<NaiveDate, T>,
+    value: impl Fn(&T) -> Option<f64>,
+) -> Vec<Anomaly> {
+    detect_anomalies(
+        metric,
+        days.iter()
+            .filter_map(|(date, day)| Some((*date, value(day)?))),
+        AnomalyMethod::ZScore {
+            threshold: NOTABLE_Z_SCORE,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::anomaly::AnomalyReason;
+    use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::{ActivitySummaryBuilder, SleepDataBuilder};
+    use crate::sleep::SleepLevel;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        assert_eq!(ReportPeriod::Week(date(3, 4)).end(), date(3, 10));
+        assert_eq!(ReportPeriod::Month(date(2, 14)).start(), date(2, 1));
+        assert_eq!(ReportPeriod::Month(date(2, 14)).end(), date(2, 29));
+        assert_eq!(ReportPeriod::Month(date(12, 31)).end(), date(12, 31));
+    }
+
+    #[test]
+    fn test_weekly_report() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        mock_client
+            .expect_fetch_activity_summary()
+            .times(7)
+            .returning(|day| {
+                let steps = match day.day() {
+                    // The tracker wasn't worn
+                    7 => 0,
+                    day => 10_000 + day * 100,
+                };
+                Ok(ActivitySummaryBuilder::new()
+                    .steps(steps)
+                    .resting_heart_rate(60 - day.day() as i32)
+                    .build())
+            });
+        mock_client
+            .expect_fetch_sleep_data()
+            .times(7)
+            .returning(|day| {
+                let start = day.pred_opt().unwrap().and_hms_opt(23, 0, 0).unwrap();
+                Ok(SleepDataBuilder::new(start)
+                    .stage(SleepLevel::Light, 400 + day.day())
+                    .build_response())
+            });
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+        let report = PeriodReport::fetch(ReportPeriod::Week(date(3, 1)), &mut cache)?;
+
+        assert_eq!(report.aggregate.days(), 7);
+        assert_eq!(report.steps_goal_streaks.longest, 6);
+        assert_eq!(report.steps_goal_streaks.current, 0);
+        let most_steps = report.bests.most_steps.unwrap();
+        assert_eq!((most_steps.date, most_steps.value), (date(3, 6), 10_600));
+        assert_eq!(report.bests.lowest_resting_heart_rate.unwrap().value, 53);
+        assert_eq!(report.bests.longest_sleep.unwrap().date, date(3, 7));
+        assert_eq!(report.notable_days.len(), 1);
+        assert_eq!(report.notable_days[0].date, date(3, 7));
+        assert_eq!(report.notable_days[0].metric, DailyMetric::Steps);
+        assert_eq!(report.notable_days[0].reason, AnomalyReason::NotWorn);
+
+        Ok(())
+    }
+}
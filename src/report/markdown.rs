@@ -0,0 +1,545 @@
+//! Rendering of reports as Markdown.
+//!
+//! The output uses headings, GitHub-flavored tables and lists only, so it reads well as
+//! plain text and renders in journals, Obsidian vaults and email clients alike.
+
+use crate::analysis::anomaly::{Anomaly, AnomalyReason};
+use crate::analysis::correlation::DailyMetric;
+use crate::daily_record::DailyRecord;
+use crate::report::{PeriodBest, PeriodReport, ReportPeriod};
+use chrono::NaiveDate;
+use std::fmt::Write;
+
+/// Placeholder for values a period or day has no data for
+const MISSING: &str = "–";
+
+/// Renders a period report as Markdown
+///
+/// With the report of the previous period, the summary table compares the two, with the
+/// change of each value.
+///
+/// # Arguments
+///
+/// * `report` - The report to render
+/// * `previous` - The report of the period before, to compare against
+///
+/// # Returns
+///
+/// The Markdown document, starting with a level 1 heading
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::report::markdown::render_period_report;
+/// use fitbit_rs::report::{PeriodReport, ReportPeriod};
+/// use fitbit_rs::{FitbitClient, FitbitResponseCache};
+///
+/// let mut cache = FitbitResponseCache::new(FitbitClient::new("your_access_token".to_string()));
+/// let today = chrono::Local::now().date_naive();
+/// let week_start = today - chrono::Duration::days(6);
+///
+/// let report = PeriodReport::fetch(ReportPeriod::Week(week_start), &mut cache)?;
+/// let previous = PeriodReport::fetch(
+///     ReportPeriod::Week(week_start - chrono::Duration::days(7)),
+///     &mut cache,
+/// )?;
+/// println!("{}", render_period_report(&report, Some(&previous)));
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+pub fn render_period_report(report: &PeriodReport, previous: Option<&PeriodReport>) -> String {
+    // Writing to a String can't fail
+    let mut markdown = String::new();
+    let _ = writeln!(markdown, "# {}", title(&report.period));
+    let _ = writeln!(
+        markdown,
+        "\n{} to {}\n",
+        report.period.start(),
+        report.period.end()
+    );
+
+    markdown.push_str("## Summary\n\n");
+    let rows = summary_rows(report);
+    match previous {
+        Some(previous) => {
+            let _ = writeln!(
+                markdown,
+                "| Metric | {} | {} | Change |\n| --- | ---: | ---: | ---: |",
+                period_name(&report.period),
+                previous_period_name(&report.period)
+            );
+            for (row, previous_row) in rows.iter().zip(summary_rows(previous)) {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} |",
+                    row.label,
+                    row.value.format(),
+                    previous_row.value.format(),
+                    row.value.change_from(&previous_row.value)
+                );
+            }
+        }
+        None => {
+            markdown.push_str("| Metric | Value |\n| --- | ---: |\n");
+            for row in &rows {
+                let _ = writeln!(markdown, "| {} | {} |", row.label, row.value.format());
+            }
+        }
+    }
+
+    let streaks = &report.steps_goal_streaks;
+    markdown.push_str("\n## Steps goal streaks\n\n");
+    match streaks.longest_start {
+        Some(start) => {
+            let _ = writeln!(
+                markdown,
+                "- Longest: {} from {}",
+                days(streaks.longest),
+                start
+            );
+        }
+        None => markdown.push_str("- Longest: 0 days\n"),
+    }
+    let _ = writeln!(markdown, "- Current: {}", days(streaks.current));
+
+    let bests = &report.bests;
+    let best_rows = [
+        best_row("Most steps", &bests.most_steps, |steps| {
+            thousands(steps as u64)
+        }),
+        best_row(
+            "Most active minutes",
+            &bests.most_active_minutes,
+            |minutes| format!("{} min", minutes),
+        ),
+        best_row(
+            "Lowest resting heart rate",
+            &bests.lowest_resting_heart_rate,
+            |heart_rate| format!("{} bpm", heart_rate),
+        ),
+        best_row("Longest sleep", &bests.longest_sleep, |minutes| {
+            duration(minutes as f64)
+        }),
+        best_row(
+            "Best sleep efficiency",
+            &bests.best_sleep_efficiency,
+            |efficiency| format!("{}%", efficiency),
+        ),
+    ];
+    if best_rows.iter().any(Option::is_some) {
+        markdown.push_str("\n## Best days\n\n| | Day | Value |\n| --- | --- | ---: |\n");
+        for (label, date, value) in best_rows.into_iter().flatten() {
+            let _ = writeln!(markdown, "| {} | {} | {} |", label, date, value);
+        }
+    }
+
+    if !report.notable_days.is_empty() {
+        markdown.push_str("\n## Notable days\n\n");
+        for anomaly in &report.notable_days {
+            let _ = writeln!(markdown, "- {}", notable_day(anomaly));
+        }
+    }
+
+    let aggregate = &report.aggregate;
+    if !aggregate.missing_sleep_days.is_empty() || !aggregate.missing_activity_days.is_empty() {
+        markdown.push_str("\n## Missing data\n\n");
+        if !aggregate.missing_sleep_days.is_empty() {
+            let _ = writeln!(
+                markdown,
+                "- Sleep: {}",
+                join_dates(&aggregate.missing_sleep_days)
+            );
+        }
+        if !aggregate.missing_activity_days.is_empty() {
+            let _ = writeln!(
+                markdown,
+                "- Activity: {}",
+                join_dates(&aggregate.missing_activity_days)
+            );
+        }
+    }
+
+    markdown
+}
+
+/// Renders daily records as a Markdown table with a row per day
+///
+/// # Arguments
+///
+/// * `records` - The days to render, in the order of the rows
+///
+/// # Returns
+///
+/// The table of sleep, sleep efficiency, steps, active minutes and resting heart rate
+pub fn render_daily_records(records: &[DailyRecord]) -> String {
+    let mut markdown = String::from(concat!(
+        "| Day | Sleep | Efficiency | Steps | Active minutes | Resting HR |\n",
+        "| --- | ---: | ---: | ---: | ---: | ---: |\n",
+    ));
+
+    // Writing to a String can't fail
+    for record in records {
+        let main_sleep = record.sleep.as_ref().and_then(|sleep| sleep.main_sleep());
+        let activity = record.activity.as_ref();
+        let resting_heart_rate = record
+            .heart_rate
+            .as_ref()
+            .and_then(|heart_rate| heart_rate.resting_heart_rate.map(|bpm| bpm as i32))
+            .or_else(|| activity.and_then(|activity| activity.summary.resting_heart_rate));
+
+        let _ = writeln!(
+            markdown,
+            "| {} | {} | {} | {} | {} | {} |",
+            record.date,
+            or_missing(main_sleep.map(|sleep| duration(sleep.minutes_asleep as f64))),
+            or_missing(
+                main_sleep
+                    .and_then(|sleep| sleep.efficiency)
+                    .map(|efficiency| format!("{}%", efficiency))
+            ),
+            or_missing(activity.map(|activity| thousands(activity.summary.steps as u64))),
+            or_missing(activity.map(|activity| activity.total_active_minutes().to_string())),
+            or_missing(resting_heart_rate.map(|bpm| format!("{} bpm", bpm))),
+        );
+    }
+
+    markdown
+}
+
+/// A row of the summary table
+struct SummaryRow {
+    label: &'static str,
+    value: SummaryValue,
+}
+
+/// A value of the summary table, `None` if the period has no data for it
+enum SummaryValue {
+    Minutes(Option<f64>),
+    /// A share from 0 to 100
+    Percent(Option<f64>),
+    Count(Option<f64>),
+}
+
+impl SummaryValue {
+    fn format(&self) -> String {
+        match *self {
+            SummaryValue::Minutes(minutes) => or_missing(minutes.map(duration)),
+            SummaryValue::Percent(percent) => {
+                or_missing(percent.map(|percent| format!("{:.0}%", percent)))
+            }
+            SummaryValue::Count(count) => {
+                or_missing(count.map(|count| thousands(count.round() as u64)))
+            }
+        }
+    }
+
+    /// Formats the change from a previous value: minutes as a duration, shares in
+    /// percentage points and counts in percent
+    fn change_from(&self, previous: &SummaryValue) -> String {
+        let change = match (self, previous) {
+            (SummaryValue::Minutes(Some(value)), SummaryValue::Minutes(Some(previous))) => {
+                let delta = value - previous;
+                let sign = if delta < 0.0 { "-" } else { "+" };
+                Some(format!("{}{}", sign, duration(delta.abs())))
+            }
+            (SummaryValue::Percent(Some(value)), SummaryValue::Percent(Some(previous))) => {
+                Some(format!("{:+.0} pts", value - previous))
+            }
+            (SummaryValue::Count(Some(value)), SummaryValue::Count(Some(previous)))
+                if *previous != 0.0 =>
+            {
+                Some(format!("{:+.1}%", (value - previous) / previous * 100.0))
+            }
+            _ => None,
+        };
+
+        or_missing(change)
+    }
+}
+
+/// Returns the rows of the summary table, the same rows for every report
+fn summary_rows(report: &PeriodReport) -> Vec<SummaryRow> {
+    let sleep = &report.aggregate.sleep;
+    let activity = &report.aggregate.activity;
+    let has_sleep = sleep.nights > sleep.missing_nights;
+    let has_activity = activity.days > 0;
+    let sleep_value = |value: f64| has_sleep.then_some(value);
+    let activity_value = |value: f64| has_activity.then_some(value);
+
+    vec![
+        SummaryRow {
+            label: "Average sleep",
+            value: SummaryValue::Minutes(sleep_value(sleep.average_minutes_asleep)),
+        },
+        SummaryRow {
+            label: "Average time in bed",
+            value: SummaryValue::Minutes(sleep_value(sleep.average_time_in_bed)),
+        },
+        SummaryRow {
+            label: "Average deep sleep",
+            value: SummaryValue::Minutes(sleep_value(sleep.average_stage_minutes.deep)),
+        },
+        SummaryRow {
+            label: "Average REM sleep",
+            value: SummaryValue::Minutes(sleep_value(sleep.average_stage_minutes.rem)),
+        },
+        SummaryRow {
+            label: "Sleep efficiency",
+            value: SummaryValue::Percent(sleep_value(sleep.average_efficiency)),
+        },
+        SummaryRow {
+            label: "Total steps",
+            value: SummaryValue::Count(activity_value(activity.total_steps as f64)),
+        },
+        SummaryRow {
+            label: "Average steps",
+            value: SummaryValue::Count(activity_value(activity.average_steps)),
+        },
+        SummaryRow {
+            label: "Average active minutes",
+            value: SummaryValue::Count(activity_value(activity.average_active_minutes)),
+        },
+        SummaryRow {
+            label: "Steps goal met",
+            value: SummaryValue::Percent(activity_value(report.aggregate.steps_goal_rate * 100.0)),
+        },
+    ]
+}
+
+fn title(period: &ReportPeriod) -> String {
+    match period {
+        ReportPeriod::Week(start) => format!("Week of {}", start),
+        ReportPeriod::Month(date) => date.format("%B %Y").to_string(),
+    }
+}
+
+fn period_name(period: &ReportPeriod) -> &'static str {
+    match period {
+        ReportPeriod::Week(_) => "This week",
+        ReportPeriod::Month(_) => "This month",
+    }
+}
+
+fn previous_period_name(period: &ReportPeriod) -> &'static str {
+    match period {
+        ReportPeriod::Week(_) => "Previous week",
+        ReportPeriod::Month(_) => "Previous month",
+    }
+}
+
+/// Returns the label, day and formatted value of a best day, if the period has one
+fn best_row<T: Copy>(
+    label: &'static str,
+    best: &Option<PeriodBest<T>>,
+    format: impl Fn(T) -> String,
+) -> Option<(&'static str, NaiveDate, String)> {
+    best.as_ref()
+        .map(|best| (label, best.date, format(best.value)))
+}
+
+/// Describes a notable day, e.g. `2025-03-07: resting heart rate of 68 bpm, unusually high`
+fn notable_day(anomaly: &Anomaly) -> String {
+    if anomaly.reason == AnomalyReason::NotWorn {
+        return format!(
+            "{}: no steps, the tracker probably wasn't worn",
+            anomaly.date
+        );
+    }
+
+    let value = match anomaly.metric {
+        DailyMetric::Steps => format!("{} steps", thousands(anomaly.value as u64)),
+        DailyMetric::RestingHeartRate => format!("resting heart rate of {} bpm", anomaly.value),
+        DailyMetric::SleepEfficiency => format!("sleep efficiency of {}%", anomaly.value),
+        DailyMetric::CaloriesOut => format!("{} calories burned", thousands(anomaly.value as u64)),
+        DailyMetric::ActiveMinutes => format!("{} active minutes", anomaly.value),
+        DailyMetric::VeryActiveMinutes => format!("{} very active minutes", anomaly.value),
+        DailyMetric::SedentaryMinutes => {
+            format!("{} sedentary", duration(anomaly.value))
+        }
+        DailyMetric::MinutesAsleep => format!("{} asleep", duration(anomaly.value)),
+        DailyMetric::TimeInBed => format!("{} in bed", duration(anomaly.value)),
+        DailyMetric::DeepSleepMinutes => format!("{} of deep sleep", duration(anomaly.value)),
+        DailyMetric::LightSleepMinutes => format!("{} of light sleep", duration(anomaly.value)),
+        DailyMetric::RemSleepMinutes => format!("{} of REM sleep", duration(anomaly.value)),
+        DailyMetric::WakeMinutes => format!("{} awake", duration(anomaly.value)),
+    };
+    let direction = match anomaly.reason {
+        AnomalyReason::Low => "low",
+        _ => "high",
+    };
+
+    format!("{}: {}, unusually {}", anomaly.date, value, direction)
+}
+
+/// Formats minutes as hours and minutes, e.g. `7h 05m`, or just minutes below an hour
+fn duration(minutes: f64) -> String {
+    let minutes = minutes.round() as i64;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Formats a number with thousands separators, e.g. `12,345`
+fn thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    formatted
+}
+
+fn days(count: u32) -> String {
+    if count == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", count)
+    }
+}
+
+fn join_dates(dates: &[NaiveDate]) -> String {
+    dates
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn or_missing(value: Option<String>) -> String {
+    value.unwrap_or_else(|| MISSING.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::activity::{ActivityRangeSummary, StepGoalStreaks};
+    use crate::analysis::range::RangeAggregate;
+    use crate::analysis::sleep::{SleepRangeSummary, StageAverages};
+    use crate::fixtures::ActivitySummaryBuilder;
+    use crate::fixtures::date;
+    use crate::report::PeriodBests;
+
+    fn report(average_steps: f64, average_minutes_asleep: f64) -> PeriodReport {
+        PeriodReport {
+            period: ReportPeriod::Week(date(3)),
+            aggregate: RangeAggregate {
+                start: date(3),
+                end: date(9),
+                sleep: SleepRangeSummary {
+                    nights: 7,
+                    missing_nights: 0,
+                    total_minutes_asleep: (average_minutes_asleep * 7.0) as u32,
+                    total_time_in_bed: 0,
+                    average_minutes_asleep,
+                    average_time_in_bed: 0.0,
+                    average_stage_minutes: StageAverages::default(),
+                    average_efficiency: 90.0,
+                },
+                activity: ActivityRangeSummary {
+                    days: 7,
+                    total_steps: (average_steps * 7.0) as u64,
+                    total_calories_out: 0,
+                    total_distance_km: 0.0,
+                    total_active_minutes: 0,
+                    average_steps,
+                    average_calories_out: 0.0,
+                    average_distance_km: 0.0,
+                    average_active_minutes: 0.0,
+                    steps_goal_days: 0,
+                    calories_goal_days: 0,
+                    active_minutes_goal_days: 0,
+                    most_active_day: None,
+                    least_active_day: None,
+                },
+                steps_goal_rate: 0.5,
+                calories_goal_rate: 0.0,
+                active_minutes_goal_rate: 0.0,
+                missing_sleep_days: Vec::new(),
+                missing_activity_days: vec![date(9)],
+            },
+            steps_goal_streaks: StepGoalStreaks {
+                current: 1,
+                longest: 3,
+                longest_start: Some(date(4)),
+            },
+            bests: PeriodBests {
+                most_steps: Some(PeriodBest {
+                    date: date(5),
+                    value: 14_200,
+                }),
+                most_active_minutes: None,
+                lowest_resting_heart_rate: None,
+                longest_sleep: None,
+                best_sleep_efficiency: None,
+            },
+            notable_days: vec![Anomaly {
+                date: date(6),
+                metric: DailyMetric::RestingHeartRate,
+                value: 68.0,
+                reason: AnomalyReason::High,
+                score: 2.4,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_with_previous_period() {
+        let markdown = render_period_report(&report(8_800.0, 440.0), Some(&report(8_000.0, 420.0)));
+
+        assert!(markdown.starts_with("# Week of 2025-03-03\n\n2025-03-03 to 2025-03-09\n"));
+        assert!(markdown.contains("| Metric | This week | Previous week | Change |\n"));
+        assert!(markdown.contains("| Average sleep | 7h 20m | 7h 00m | +20m |\n"));
+        assert!(markdown.contains("| Average steps | 8,800 | 8,000 | +10.0% |\n"));
+        assert!(markdown.contains("| Sleep efficiency | 90% | 90% | +0 pts |\n"));
+        assert!(markdown.contains("- Longest: 3 days from 2025-03-04\n- Current: 1 day\n"));
+        assert!(markdown.contains("| Most steps | 2025-03-05 | 14,200 |\n"));
+        assert!(markdown.contains("- 2025-03-06: resting heart rate of 68 bpm, unusually high\n"));
+        assert!(markdown.ends_with("## Missing data\n\n- Activity: 2025-03-09\n"));
+    }
+
+    #[test]
+    fn test_render_without_data() {
+        let mut empty = report(0.0, 0.0);
+        empty.aggregate.sleep.nights = 0;
+        empty.aggregate.activity.days = 0;
+
+        let markdown = render_period_report(&empty, None);
+
+        assert!(markdown.contains("| Metric | Value |\n"));
+        assert!(markdown.contains("| Average sleep | – |\n"));
+        assert!(markdown.contains("| Total steps | – |\n"));
+    }
+
+    #[test]
+    fn test_render_daily_records() {
+        let record = DailyRecord {
+            date: date(3),
+            sleep: None,
+            activity: Some(
+                ActivitySummaryBuilder::new()
+                    .steps(12_345)
+                    .resting_heart_rate(57)
+                    .build(),
+            ),
+            heart_rate: None,
+            breathing_rate: None,
+        };
+
+        let markdown = render_daily_records(&[record]);
+
+        assert!(markdown.ends_with("| 2025-03-03 | – | – | 12,345 | 0 | 57 bpm |\n"));
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(duration(45.0), "45m");
+        assert_eq!(duration(425.4), "7h 05m");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1_234_567), "1,234,567");
+    }
+}
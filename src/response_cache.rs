@@ -4,6 +4,8 @@
 //! of API calls made.
 
 use crate::activity_summary::ActivitySummaryResponse;
+use crate::batch::{BatchResult, date_range};
+use crate::daily_record::{DailyRecord, unless_unavailable};
 use crate::error::FitbitError;
 use crate::fitbit_client::FitbitClientTrait;
 use crate::sleep::SleepResponseV1_2;
@@ -116,6 +118,114 @@ impl<C: FitbitClientTrait> FitbitResponseCache<C> {
         Ok(self.activity_summary_responses.get(&date).unwrap())
     }
 
+    /// Gets the sleep responses for every date from `start` to `end` (inclusive).
+    ///
+    /// Dates that are not in the cache are fetched from the API and cached. A date that
+    /// fails does not stop the others from being fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive)
+    ///
+    /// # Returns
+    ///
+    /// References to the cached responses and the errors of the dates that could not be
+    /// fetched, or an error if the range is invalid
+    pub fn get_sleep_responses(
+        &mut self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BatchResult<&SleepResponseV1_2>, FitbitError> {
+        let client = &self.fitbit_client;
+        fetch_missing(&mut self.sleep_responses, start, end, |date| {
+            client.fetch_sleep_data(date)
+        })
+    }
+
+    /// Gets the activity summary responses for every date from `start` to `end` (inclusive).
+    ///
+    /// Dates that are not in the cache are fetched from the API and cached. A date that
+    /// fails does not stop the others from being fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first date of the range
+    /// * `end` - The last date of the range (inclusive)
+    ///
+    /// # Returns
+    ///
+    /// References to the cached responses and the errors of the dates that could not be
+    /// fetched, or an error if the range is invalid
+    pub fn get_activity_summary_responses(
+        &mut self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BatchResult<&ActivitySummaryResponse>, FitbitError> {
+        let client = &self.fitbit_client;
+        fetch_missing(&mut self.activity_summary_responses, start, end, |date| {
+            client.fetch_activity_summary(date)
+        })
+    }
+
+    /// Gets the sleep, activity and heart rate data of the given date.
+    ///
+    /// Sleep and activity come from the cache, and are fetched and cached if missing.
+    /// Heart rate and breathing rate are fetched from the API on every call. Parts that
+    /// Fitbit has no data for, or that the access token lacks the scope for, are left empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date for which to get the data
+    ///
+    /// # Returns
+    ///
+    /// The data of the day, or the first error that doesn't mean a part is unavailable
+    pub fn fetch_daily_record(&mut self, date: NaiveDate) -> Result<DailyRecord, FitbitError> {
+        let sleep = unless_unavailable(self.get_sleep_response(date).cloned())?
+            .filter(|response| !response.sleep.is_empty());
+        let activity = unless_unavailable(self.get_activity_summary_response(date).cloned())?;
+        let heart_rate = unless_unavailable(self.fitbit_client.fetch_heart_rate(date))?;
+        let breathing_rate =
+            unless_unavailable(self.fitbit_client.fetch_breathing_rate(date))?.flatten();
+
+        Ok(DailyRecord {
+            date,
+            sleep,
+            activity,
+            heart_rate,
+            breathing_rate,
+        })
+    }
+
+    /// Returns the cached sleep responses with their dates, in no particular order.
+    pub fn cached_sleep_responses(&self) -> impl Iterator<Item = (NaiveDate, &SleepResponseV1_2)> {
+        self.sleep_responses
+            .iter()
+            .map(|(date, response)| (*date, response))
+    }
+
+    /// Returns the cached activity summary responses with their dates, in no particular
+    /// order.
+    pub fn cached_activity_summary_responses(
+        &self,
+    ) -> impl Iterator<Item = (NaiveDate, &ActivitySummaryResponse)> {
+        self.activity_summary_responses
+            .iter()
+            .map(|(date, response)| (*date, response))
+    }
+
+    /// Inserts a sleep response, e.g. one imported from an account archive, replacing the
+    /// cached response of the date.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date of the response
+    /// * `response` - The response to cache
+    pub fn insert_sleep_response(&mut self, date: NaiveDate, response: SleepResponseV1_2) {
+        self.sleep_responses.insert(date, response);
+    }
+
     /// Clears all cached responses.
     ///
     /// This can be useful if you want to force a refresh of all data.
@@ -146,10 +256,44 @@ impl<C: FitbitClientTrait> FitbitResponseCache<C> {
     }
 }
 
+/// Fetches the dates from `start` to `end` that are missing in `responses` and inserts them.
+fn fetch_missing<T>(
+    responses: &mut HashMap<NaiveDate, T>,
+    start: NaiveDate,
+    end: NaiveDate,
+    mut fetch: impl FnMut(NaiveDate) -> Result<T, FitbitError>,
+) -> Result<BatchResult<&T>, FitbitError> {
+    let dates = date_range(start, end)?;
+    let mut failures = Vec::new();
+    for &date in &dates {
+        if responses.contains_key(&date) {
+            continue;
+        }
+
+        match fetch(date) {
+            Ok(response) => {
+                responses.insert(date, response);
+            }
+            Err(error) => failures.push((date, error)),
+        }
+    }
+
+    let responses = &*responses;
+    Ok(BatchResult {
+        successes: dates
+            .into_iter()
+            .filter_map(|date| responses.get(&date).map(|response| (date, response)))
+            .collect(),
+        failures,
+    })
+}
+
 #[cfg(test)]
 mod response_cache_tests {
     use super::*;
+    use crate::error::ApiErrorType;
     use crate::fitbit_client::MockFitbitClientTrait;
+    use crate::fixtures::ActivitySummaryBuilder;
     use chrono::NaiveDate;
     use mockall::predicate::*;
 
@@ -227,6 +371,98 @@ mod response_cache_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_sleep_responses_keeps_successful_days() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let bad_day = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+
+        // Every day is fetched once, and the failed day again on the second call
+        mock_client
+            .expect_fetch_sleep_data()
+            .times(4)
+            .returning(move |date| {
+                if date == bad_day {
+                    Err(FitbitError::api_error(500, "Internal Server Error"))
+                } else {
+                    Ok(create_mock_sleep_response())
+                }
+            });
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+
+        let batch = cache.get_sleep_responses(start, end)?;
+        assert_eq!(batch.successes.len(), 2);
+        assert_eq!(batch.failed_dates(), vec![bad_day]);
+
+        let batch = cache.get_sleep_responses(start, end)?;
+        assert_eq!(batch.successes.len(), 2);
+        assert!(cache.get_sleep_responses(end, start).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_daily_record() -> Result<(), FitbitError> {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        mock_client
+            .expect_fetch_sleep_data()
+            .with(eq(date))
+            .times(1)
+            .returning(|_| Ok(create_mock_sleep_response()));
+        mock_client
+            .expect_fetch_activity_summary()
+            .with(eq(date))
+            .times(1)
+            .returning(|_| Ok(ActivitySummaryBuilder::new().steps(8000).build()));
+        // The token lacks the heartrate scope, and the night has no breathing rate
+        mock_client
+            .expect_fetch_heart_rate()
+            .times(2)
+            .returning(|_| {
+                Err(FitbitError::AuthenticationError {
+                    message: "Missing scope".to_string(),
+                    error_type: Some(ApiErrorType::InsufficientPermissions),
+                })
+            });
+        mock_client
+            .expect_fetch_breathing_rate()
+            .times(2)
+            .returning(|_| Ok(None));
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+
+        let record = cache.fetch_daily_record(date)?;
+        assert_eq!(record.date, date);
+        assert_eq!(record.sleep, None);
+        assert_eq!(
+            record.activity.map(|activity| activity.get_steps()),
+            Some(8000)
+        );
+        assert_eq!(record.heart_rate, None);
+        assert_eq!(record.breathing_rate, None);
+        assert!(!cache.fetch_daily_record(date)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_daily_record_propagates_other_errors() {
+        let mut mock_client = MockFitbitClientTrait::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        mock_client
+            .expect_fetch_sleep_data()
+            .returning(|_| Err(FitbitError::api_error(500, "Internal Server Error")));
+
+        let mut cache = FitbitResponseCache::new(mock_client);
+
+        assert!(cache.fetch_daily_record(date).is_err());
+    }
+
     fn create_mock_sleep_response() -> SleepResponseV1_2 {
         SleepResponseV1_2::default()
     }
@@ -0,0 +1,183 @@
+//! GPS routes of exercises and their export to GPX.
+
+use crate::tcx::TcxTrack;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// The GPS route of an exercise
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    /// Points in chronological order
+    pub points: Vec<RoutePoint>,
+}
+
+/// A timestamped position of a route
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePoint {
+    pub time: DateTime<FixedOffset>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_meters: Option<f64>,
+    pub heart_rate_bpm: Option<u32>,
+}
+
+impl From<&TcxTrack> for Route {
+    /// Builds the route from the track points that have a GPS position
+    fn from(track: &TcxTrack) -> Self {
+        let points = track
+            .points
+            .iter()
+            .filter_map(|point| {
+                Some(RoutePoint {
+                    time: point.time,
+                    latitude: point.latitude?,
+                    longitude: point.longitude?,
+                    altitude_meters: point.altitude_meters,
+                    heart_rate_bpm: point.heart_rate_bpm,
+                })
+            })
+            .collect();
+
+        Route { points }
+    }
+}
+
+impl Route {
+    /// Returns `true` if the route has no points, e.g. for exercises recorded without GPS
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Exports the route as a GPX 1.1 document with a single track
+    ///
+    /// Heart rates are written with the Garmin `TrackPointExtension`, which most mapping
+    /// and training tools understand.
+    ///
+    /// # Returns
+    ///
+    /// The GPX document
+    pub fn to_gpx(&self) -> String {
+        let mut gpx = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gpx version=\"1.1\" creator=\"fitbit-rs\" ",
+            "xmlns=\"http://www.topografix.com/GPX/1/1\" ",
+            "xmlns:gpxtpx=\"http://www.garmin.com/xmlschemas/TrackPointExtension/v1\">\n",
+            "  <trk>\n",
+            "    <trkseg>\n",
+        ));
+
+        // Writing to a String can't fail
+        for point in &self.points {
+            let _ = writeln!(
+                gpx,
+                "      <trkpt lat=\"{}\" lon=\"{}\">",
+                point.latitude, point.longitude
+            );
+            if let Some(altitude) = point.altitude_meters {
+                let _ = writeln!(gpx, "        <ele>{}</ele>", altitude);
+            }
+            let _ = writeln!(gpx, "        <time>{}</time>", point.time.to_rfc3339());
+            if let Some(heart_rate) = point.heart_rate_bpm {
+                let _ = writeln!(
+                    gpx,
+                    "        <extensions><gpxtpx:TrackPointExtension>\
+                     <gpxtpx:hr>{}</gpxtpx:hr>\
+                     </gpxtpx:TrackPointExtension></extensions>",
+                    heart_rate
+                );
+            }
+            gpx.push_str("      </trkpt>\n");
+        }
+
+        gpx.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+        gpx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tcx::TrackPoint;
+
+    #[test]
+    fn test_route_to_gpx() {
+        let time = DateTime::parse_from_rfc3339("2019-01-03T12:08:00-08:00").unwrap();
+        let track = TcxTrack {
+            points: vec![
+                TrackPoint {
+                    time,
+                    latitude: Some(37.7749),
+                    longitude: Some(-122.4194),
+                    altitude_meters: Some(16.2),
+                    distance_meters: Some(0.0),
+                    heart_rate_bpm: Some(92),
+                },
+                // Without a position, e.g. while the GPS signal was lost
+                TrackPoint {
+                    time,
+                    latitude: None,
+                    longitude: None,
+                    altitude_meters: None,
+                    distance_meters: Some(12.5),
+                    heart_rate_bpm: Some(95),
+                },
+            ],
+        };
+
+        let route = Route::from(&track);
+
+        assert_eq!(route.points.len(), 1);
+        assert_eq!(
+            route.to_gpx(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <gpx version=\"1.1\" creator=\"fitbit-rs\" \
+             xmlns=\"http://www.topografix.com/GPX/1/1\" \
+             xmlns:gpxtpx=\"http://www.garmin.com/xmlschemas/TrackPointExtension/v1\">\n\
+             \x20 <trk>\n\
+             \x20   <trkseg>\n\
+             \x20     <trkpt lat=\"37.7749\" lon=\"-122.4194\">\n\
+             \x20       <ele>16.2</ele>\n\
+             \x20       <time>2019-01-03T12:08:00-08:00</time>\n\
+             \x20       <extensions><gpxtpx:TrackPointExtension><gpxtpx:hr>92</gpxtpx:hr>\
+             </gpxtpx:TrackPointExtension></extensions>\n\
+             \x20     </trkpt>\n\
+             \x20   </trkseg>\n\
+             \x20 </trk>\n\
+             </gpx>\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_route() {
+        let route = Route::from(&TcxTrack::default());
+
+        assert!(route.is_empty());
+        assert!(route.to_gpx().contains("<trkseg>\n    </trkseg>"));
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let point = |minute, heart_rate_bpm| RoutePoint {
+            time: DateTime::parse_from_rfc3339(&format!("2019-01-03T12:{:02}:00-08:00", minute))
+                .unwrap(),
+            latitude: 37.7749,
+            longitude: -122.4194,
+            altitude_meters: (minute == 8).then_some(16.2),
+            heart_rate_bpm,
+        };
+        let route = Route {
+            points: vec![point(8, Some(92)), point(9, None)],
+        };
+
+        let serialized = serde_json::to_value(&route).expect("Failed to serialize");
+        assert_eq!(serialized["points"][0]["time"], "2019-01-03T12:08:00-08:00");
+        assert_eq!(serialized["points"][0]["altitudeMeters"], 16.2);
+        assert!(serialized["points"][1]["heartRateBpm"].is_null());
+        let round_tripped: Route =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, route);
+    }
+}
@@ -1,5 +1,8 @@
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
-use serde::{Deserialize, Serialize};
+use crate::pagination::Pagination;
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 
 pub trait SleepResponse {
     fn get_total_duration_asleep(&self) -> chrono::Duration;
@@ -7,26 +10,186 @@ pub trait SleepResponse {
     fn get_time_fell_asleep(&self) -> Option<NaiveDateTime>;
     fn get_wake_up_time(&self) -> Option<NaiveTime>;
     fn get_total_duration_awake_during_sleep(&self) -> Option<chrono::Duration>;
+    /// [`get_time_fell_asleep`](SleepResponse::get_time_fell_asleep) in the given time zone
+    fn get_time_fell_asleep_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>>
+    where
+        Self: Sized;
+    /// The wake-up date and time in the given time zone
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>>
+    where
+        Self: Sized;
 }
 
-#[derive(Debug, Default, Deserialize)]
+/// Analysis helpers for a single sleep record
+///
+/// [`SleepResponse`] applies these to the main sleep of a response; implementing them on the
+/// records themselves allows running the same analysis on naps or any other record.
+pub trait SleepRecord {
+    fn get_total_duration_asleep(&self) -> chrono::Duration;
+    fn get_sleep_efficiency(&self) -> Option<u8>;
+    fn get_time_fell_asleep(&self) -> Option<NaiveDateTime>;
+    fn get_wake_up_time(&self) -> NaiveTime;
+    /// The time spent in wake, awake or restless levels, including short wakes
+    fn get_total_duration_awake_during_sleep(&self) -> chrono::Duration;
+    /// The wake-up date and time in the given time zone
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Tz>
+    where
+        Self: Sized;
+
+    /// [`get_time_fell_asleep`](SleepRecord::get_time_fell_asleep) in the given time zone
+    fn get_time_fell_asleep_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>>
+    where
+        Self: Sized,
+    {
+        self.get_time_fell_asleep()
+            .map(|time| with_offset(time, tz))
+    }
+}
+
+/// Resolves a local timestamp from the API in the given time zone
+///
+/// Fitbit reports all timestamps in the user's local time; see
+/// [`UserProfile::utc_offset`](crate::UserProfile::utc_offset) for how to get the offset.
+/// A time that occurs twice because the clocks were set back resolves to the first of the
+/// two instants. A time that was skipped because the clocks were set forward is read with
+/// the offset from before the change, which lands it the length of the gap later.
+pub(crate) fn with_offset<Tz: TimeZone>(time: NaiveDateTime, tz: Tz) -> DateTime<Tz> {
+    match tz.from_local_datetime(&time) {
+        LocalResult::Single(local) | LocalResult::Ambiguous(local, _) => local,
+        LocalResult::None => {
+            // Gaps are at most a few hours long, so step back until the time exists again
+            let before = (1..=96)
+                .find_map(|quarters| {
+                    tz.offset_from_local_datetime(
+                        &(time - chrono::Duration::minutes(15 * quarters)),
+                    )
+                    .earliest()
+                })
+                .map_or(0, |offset| offset.fix().local_minus_utc());
+            tz.from_utc_datetime(&(time - chrono::Duration::seconds(before as i64)))
+        }
+    }
+}
+
+/// Tuning parameters for the sleep analysis helpers
+///
+/// The default configuration treats the first 5 minutes of uninterrupted sleep as falling
+/// asleep and counts every wake period.
+///
+/// # Example
+///
+/// ```
+/// use fitbit_rs::SleepAnalysisConfig;
+///
+/// // Require 10 minutes of sleep, but don't let wakes of up to 2 minutes interrupt it
+/// let config = SleepAnalysisConfig {
+///     min_sustained_sleep: chrono::Duration::minutes(10),
+///     ignore_short_wakes: Some(chrono::Duration::minutes(2)),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SleepAnalysisConfig {
+    /// Minimum length of sleep that counts as having fallen asleep
+    pub min_sustained_sleep: chrono::Duration,
+    /// Wake periods no longer than this neither interrupt falling asleep nor count as
+    /// awakenings
+    pub ignore_short_wakes: Option<chrono::Duration>,
+}
+
+impl Default for SleepAnalysisConfig {
+    fn default() -> Self {
+        Self {
+            min_sustained_sleep: chrono::Duration::minutes(5),
+            ignore_short_wakes: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
 pub struct SleepResponseV1_2 {
     pub sleep: Vec<SleepData>,
     pub summary: SleepSummary,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Sleep logs for a range of dates (`/1.2/user/-/sleep/date/{start}/{end}.json`)
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
+pub struct SleepRangeResponse {
+    pub sleep: Vec<SleepData>,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SleepRangeResponse {
+    /// Returns the sleep records whose `date_of_sleep` is the given date
+    pub fn sleep_on(&self, date: NaiveDate) -> impl Iterator<Item = &SleepData> {
+        self.sleep.iter().filter(move |s| s.date_of_sleep == date)
+    }
+}
+
+/// Response of the create sleep log endpoint (`POST /1.2/user/-/sleep.json`)
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreatedSleepLog {
+    pub sleep: SleepData,
+}
+
+/// Returns the form parameters for logging a sleep session
+///
+/// The API takes the start as a local date and `HH:mm` time, and the duration in
+/// milliseconds.
+pub(crate) fn log_sleep_form(
+    start_time: NaiveDateTime,
+    duration: chrono::Duration,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("startTime", start_time.format("%H:%M").to_string()),
+        ("duration", duration.num_milliseconds().to_string()),
+        ("date", start_time.format("%Y-%m-%d").to_string()),
+    ]
 }
 
-#[derive(Debug, Default, Deserialize)]
+/// A page of the sleep log list (`/1.2/user/-/sleep/list.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
+pub struct SleepLogListResponse {
+    pub pagination: Pagination,
+    pub sleep: Vec<SleepData>,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SleepData {
     pub date_of_sleep: NaiveDate,
     pub duration: u64,
-    pub efficiency: u8,
+    /// Not reported for some classic records
+    pub efficiency: Option<u8>,
     pub end_time: NaiveDateTime,
     pub info_code: u8,
     pub is_main_sleep: bool,
     pub levels: SleepLevels,
     pub log_id: u64,
-    pub log_type: String,
+    pub log_type: SleepLogType,
     pub minutes_after_wakeup: u32,
     pub minutes_asleep: u32,
     pub minutes_awake: u32,
@@ -34,19 +197,105 @@ pub struct SleepData {
     pub start_time: NaiveDateTime,
     pub time_in_bed: u32,
     #[serde(rename = "type")]
-    pub sleep_type: String,
+    pub sleep_type: SleepType,
+}
+
+/// How a sleep record was created
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum SleepLogType {
+    /// Detected automatically by the tracker
+    #[default]
+    AutoDetected,
+    /// Logged manually by the user
+    Manual,
+    /// A log type not known to this crate
+    Other(String),
+}
+
+impl SleepLogType {
+    /// Returns the value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            SleepLogType::AutoDetected => "auto_detected",
+            SleepLogType::Manual => "manual",
+            SleepLogType::Other(value) => value,
+        }
+    }
+}
+
+impl From<SleepLogType> for String {
+    fn from(value: SleepLogType) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for SleepLogType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "auto_detected" => SleepLogType::AutoDetected,
+            "manual" => SleepLogType::Manual,
+            _ => SleepLogType::Other(value),
+        }
+    }
+}
+
+/// The level format of a sleep record
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum SleepType {
+    /// Sleep stages (deep, light, REM, wake)
+    #[default]
+    Stages,
+    /// Classic levels (asleep, restless, awake)
+    Classic,
+    /// A sleep type not known to this crate
+    Other(String),
 }
 
-#[derive(Debug, Default, Deserialize)]
+impl SleepType {
+    /// Returns the value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            SleepType::Stages => "stages",
+            SleepType::Classic => "classic",
+            SleepType::Other(value) => value,
+        }
+    }
+}
+
+impl From<SleepType> for String {
+    fn from(value: SleepType) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for SleepType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "stages" => SleepType::Stages,
+            "classic" => SleepType::Classic,
+            _ => SleepType::Other(value),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SleepLevels {
     pub data: Vec<LevelData>,
+    /// Short wake periods; only present for stages records
+    #[serde(default)]
     pub short_data: Vec<LevelData>,
     pub summary: LevelsSummary,
 }
 
-#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
 pub enum SleepLevel {
     Deep,
     Light,
@@ -54,10 +303,68 @@ pub enum SleepLevel {
     #[default]
     Wake,
     Unknown,
+    /// Classic format: asleep
+    Asleep,
+    /// Classic format: restless
+    Restless,
+    /// Classic format: awake
+    Awake,
+    /// A level not known to this crate
+    Other(String),
 }
 
-#[derive(Debug, Deserialize)]
+impl SleepLevel {
+    /// Returns `true` unless the level is one of the wake or restless levels
+    ///
+    /// Like `Unknown`, levels not known to this crate count as sleep.
+    pub fn is_sleep(&self) -> bool {
+        !matches!(
+            self,
+            SleepLevel::Wake | SleepLevel::Awake | SleepLevel::Restless
+        )
+    }
+
+    /// Returns the value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            SleepLevel::Deep => "deep",
+            SleepLevel::Light => "light",
+            SleepLevel::Rem => "rem",
+            SleepLevel::Wake => "wake",
+            SleepLevel::Unknown => "unknown",
+            SleepLevel::Asleep => "asleep",
+            SleepLevel::Restless => "restless",
+            SleepLevel::Awake => "awake",
+            SleepLevel::Other(value) => value,
+        }
+    }
+}
+
+impl From<SleepLevel> for String {
+    fn from(value: SleepLevel) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for SleepLevel {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "deep" => SleepLevel::Deep,
+            "light" => SleepLevel::Light,
+            "rem" => SleepLevel::Rem,
+            "wake" => SleepLevel::Wake,
+            "unknown" => SleepLevel::Unknown,
+            "asleep" => SleepLevel::Asleep,
+            "restless" => SleepLevel::Restless,
+            "awake" => SleepLevel::Awake,
+            _ => SleepLevel::Other(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LevelData {
     pub date_time: NaiveDateTime,
     pub level: SleepLevel,
@@ -66,36 +373,276 @@ pub struct LevelData {
 
 impl LevelData {
     pub fn is_sleep(&self) -> bool {
-        self.level != SleepLevel::Wake
+        self.level.is_sleep()
+    }
+
+    /// Returns the time at which this level ends
+    pub fn end_time(&self) -> NaiveDateTime {
+        self.date_time + self.duration()
+    }
+
+    /// Returns the time spent in this level
+    pub fn duration(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.seconds as i64)
+    }
+}
+
+/// A half-open interval `[start, end)` spent in a single sleep level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageInterval {
+    pub level: SleepLevel,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl StageInterval {
+    /// Returns the length of the interval
+    pub fn duration(&self) -> chrono::Duration {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the interval contains the given time
+    pub fn contains(&self, time: NaiveDateTime) -> bool {
+        self.start <= time && time < self.end
+    }
+}
+
+impl From<&LevelData> for StageInterval {
+    fn from(level_data: &LevelData) -> Self {
+        StageInterval {
+            level: level_data.level.clone(),
+            start: level_data.date_time,
+            end: level_data.end_time(),
+        }
+    }
+}
+
+/// Appends an interval to a hypnogram, keeping it ordered and non-overlapping
+///
+/// The interval is clipped to start no earlier than the end of the previous one, and merged
+/// into the previous one if both have the same level and touch.
+fn push_interval(intervals: &mut Vec<StageInterval>, mut interval: StageInterval) {
+    if let Some(last) = intervals.last_mut() {
+        interval.start = interval.start.max(last.end);
+        if interval.start >= interval.end {
+            return;
+        }
+        if last.level == interval.level && last.end == interval.start {
+            last.end = interval.end;
+            return;
+        }
+    } else if interval.start >= interval.end {
+        return;
+    }
+
+    intervals.push(interval);
+}
+
+/// Number of transitions between each pair of sleep stages in a record
+///
+/// See [`SleepData::stage_transitions`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StageTransitions {
+    /// Transition counts keyed by `(from, to)`; pairs that never occur are absent
+    pub counts: HashMap<(SleepLevel, SleepLevel), u32>,
+}
+
+impl StageTransitions {
+    /// Returns the number of transitions from `from` directly to `to`
+    pub fn count(&self, from: SleepLevel, to: SleepLevel) -> u32 {
+        self.counts.get(&(from, to)).copied().unwrap_or(0)
+    }
+
+    /// Returns the total number of stage transitions
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+}
+
+/// Irregularities found in the `levels.data` timeline of a sleep record
+///
+/// See [`SleepData::validate_timeline`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TimelineReport {
+    /// Periods between `start_time` and `end_time` that no level covers, as `(start, end)`
+    pub gaps: Vec<(NaiveDateTime, NaiveDateTime)>,
+    /// Periods covered by more than one level, as `(start, end)`
+    pub overlaps: Vec<(NaiveDateTime, NaiveDateTime)>,
+    /// Indices into `levels.data` of entries that start before the entry preceding them
+    pub out_of_order: Vec<usize>,
+    /// Indices into `levels.data` of entries that start before `start_time`
+    pub before_start: Vec<usize>,
+}
+
+impl TimelineReport {
+    /// Returns `true` if the timeline has no gaps, overlaps, out-of-order entries or entries
+    /// before the start of the record
+    pub fn is_valid(&self) -> bool {
+        self.gaps.is_empty()
+            && self.overlaps.is_empty()
+            && self.out_of_order.is_empty()
+            && self.before_start.is_empty()
     }
 }
 
-#[derive(Debug, Default, Deserialize)]
+/// Per-level summary of a sleep record
+///
+/// Stages records fill in `deep`, `light`, `rem` and `wake`, classic records fill in
+/// `asleep`, `restless` and `awake`. The levels that don't apply are left at their defaults.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LevelsSummary {
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
     pub deep: LevelSummary,
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
     pub light: LevelSummary,
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
     pub rem: LevelSummary,
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
     pub wake: LevelSummary,
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
+    pub asleep: LevelSummary,
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
+    pub restless: LevelSummary,
+    #[serde(skip_serializing_if = "LevelSummary::is_empty")]
+    pub awake: LevelSummary,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct LevelSummary {
     pub count: u32,
     pub minutes: u32,
+    /// 0 if not available, i.e. for classic records and for accounts with less than 30 days
+    /// of sleep data
+    #[serde(default)]
+    pub thirty_day_avg_minutes: f32,
+}
+
+impl LevelSummary {
+    /// Returns `minutes` as a duration
+    pub fn duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes as i64)
+    }
+
+    /// Returns `thirty_day_avg_minutes` as a duration, rounded to the second
+    pub fn thirty_day_avg_duration(&self) -> chrono::Duration {
+        chrono::Duration::seconds((self.thirty_day_avg_minutes * 60.0).round() as i64)
+    }
+
+    /// Returns `true` for levels that don't apply to the record's format
+    fn is_empty(&self) -> bool {
+        self.count == 0 && self.minutes == 0 && self.thirty_day_avg_minutes == 0.0
+    }
+
+    /// Compares the minutes spent in this level to its 30-day average
+    ///
+    /// # Returns
+    ///
+    /// The comparison, or `None` if no 30-day average is reported for this level
+    pub fn compare_to_thirty_day_avg(&self, level: SleepLevel) -> Option<StageComparison> {
+        if self.thirty_day_avg_minutes <= 0.0 {
+            return None;
+        }
+
+        let delta_minutes = self.minutes as f32 - self.thirty_day_avg_minutes;
+        Some(StageComparison {
+            level,
+            minutes: self.minutes,
+            thirty_day_avg_minutes: self.thirty_day_avg_minutes,
+            delta_minutes,
+            relative_delta: delta_minutes / self.thirty_day_avg_minutes,
+        })
+    }
+}
+
+impl LevelsSummary {
+    /// Compares each sleep stage to its 30-day average
+    ///
+    /// Only stages records report 30-day averages, so this returns the `deep`, `light`, `rem`
+    /// and `wake` comparisons that are available, and nothing for classic records.
+    pub fn compare_to_thirty_day_avg(&self) -> Vec<StageComparison> {
+        [
+            (SleepLevel::Deep, &self.deep),
+            (SleepLevel::Light, &self.light),
+            (SleepLevel::Rem, &self.rem),
+            (SleepLevel::Wake, &self.wake),
+        ]
+        .into_iter()
+        .filter_map(|(level, summary)| summary.compare_to_thirty_day_avg(level))
+        .collect()
+    }
+}
+
+/// How the time spent in a sleep stage compares to the 30-day average
+///
+/// The `Display` implementation renders a short sentence such as
+/// `REM 40% below your 30-day average`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageComparison {
+    pub level: SleepLevel,
+    /// Minutes spent in the stage in this record
+    pub minutes: u32,
     pub thirty_day_avg_minutes: f32,
+    /// `minutes - thirty_day_avg_minutes`; negative if below the average
+    pub delta_minutes: f32,
+    /// `delta_minutes` as a fraction of the average, e.g. `-0.4` for 40% below
+    pub relative_delta: f32,
+}
+
+impl fmt::Display for StageComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match &self.level {
+            SleepLevel::Deep => "Deep",
+            SleepLevel::Light => "Light",
+            SleepLevel::Rem => "REM",
+            SleepLevel::Wake => "Wake",
+            SleepLevel::Unknown => "Unknown",
+            SleepLevel::Other(value) => value,
+            SleepLevel::Asleep => "Asleep",
+            SleepLevel::Restless => "Restless",
+            SleepLevel::Awake => "Awake",
+        };
+        let percent = (self.relative_delta * 100.0).round();
+
+        if percent == 0.0 {
+            write!(f, "{stage} in line with your 30-day average")
+        } else if percent < 0.0 {
+            write!(f, "{stage} {}% below your 30-day average", -percent)
+        } else {
+            write!(f, "{stage} {percent}% above your 30-day average")
+        }
+    }
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct SleepSummary {
+    /// Only reported when at least one stages record exists
+    #[serde(default)]
     pub stages: StagesSummary,
     pub total_minutes_asleep: u32,
     pub total_sleep_records: u32,
     pub total_time_in_bed: u32,
 }
 
-#[derive(Default, Debug, Deserialize)]
+impl SleepSummary {
+    /// Returns `total_minutes_asleep` as a duration
+    pub fn total_time_asleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.total_minutes_asleep as i64)
+    }
+
+    /// Returns `total_time_in_bed` (in minutes) as a duration
+    pub fn total_time_in_bed_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.total_time_in_bed as i64)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct StagesSummary {
     pub deep: u32,
     pub light: u32,
@@ -103,112 +650,815 @@ pub struct StagesSummary {
     pub wake: u32,
 }
 
-impl SleepResponse for SleepResponseV1_2 {
+impl StagesSummary {
+    /// Returns the total time spent in a sleep stage
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The stage, one of `Deep`, `Light`, `Rem` or `Wake`
+    ///
+    /// # Returns
+    ///
+    /// The time spent in the stage, or `None` if the summary doesn't cover `level`
+    pub fn duration(&self, level: &SleepLevel) -> Option<chrono::Duration> {
+        let minutes = match level {
+            SleepLevel::Deep => self.deep,
+            SleepLevel::Light => self.light,
+            SleepLevel::Rem => self.rem,
+            SleepLevel::Wake => self.wake,
+            _ => return None,
+        };
+
+        Some(chrono::Duration::minutes(minutes as i64))
+    }
+}
+
+impl SleepRecord for SleepData {
     fn get_total_duration_asleep(&self) -> chrono::Duration {
-        chrono::Duration::minutes(self.summary.total_minutes_asleep as i64)
+        chrono::Duration::minutes(self.minutes_asleep as i64)
     }
 
     fn get_sleep_efficiency(&self) -> Option<u8> {
-        self.sleep
-            .iter()
-            .find(|s| s.is_main_sleep)
-            .map(|main_sleep| main_sleep.efficiency)
+        self.efficiency
     }
 
     fn get_time_fell_asleep(&self) -> Option<NaiveDateTime> {
-        const MIN_SLEEP_DURATION: u32 = 300;
+        self.get_time_fell_asleep_with(&SleepAnalysisConfig::default())
+    }
+
+    fn get_wake_up_time(&self) -> NaiveTime {
+        self.end_time.time()
+    }
 
-        self.sleep
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Tz> {
+        self.end_time_local(tz)
+    }
+
+    fn get_total_duration_awake_during_sleep(&self) -> chrono::Duration {
+        self.stages_between(self.start_time, self.end_time)
             .iter()
-            .find(|s| s.is_main_sleep)
-            .and_then(|main_sleep| {
-                main_sleep
-                    .levels
-                    .data
-                    .iter()
-                    .find(|stage| stage.is_sleep() && stage.seconds > MIN_SLEEP_DURATION)
-                    .map(|level_data| level_data.date_time)
+            .filter(|interval| !interval.level.is_sleep())
+            .map(StageInterval::duration)
+            .sum()
+    }
+}
+
+impl SleepData {
+    /// Checks the `levels.data` timeline for gaps, overlaps, out-of-order entries and entries
+    /// before the start of the record
+    ///
+    /// Fitbit normally reports the levels in order, back to back, from `start_time` to
+    /// `end_time`. Summing the raw `seconds` of each level gives wrong answers when that
+    /// isn't the case, so the helpers of this crate sum the intervals of the
+    /// [`hypnogram`](Self::hypnogram) clipped to the record instead; callers can use this to
+    /// detect such records.
+    pub fn validate_timeline(&self) -> TimelineReport {
+        let mut report = TimelineReport::default();
+
+        for (index, pair) in self.levels.data.windows(2).enumerate() {
+            if pair[1].date_time < pair[0].date_time {
+                report.out_of_order.push(index + 1);
+            }
+        }
+        report.before_start = (0..self.levels.data.len())
+            .filter(|&index| self.levels.data[index].date_time < self.start_time)
+            .collect();
+
+        let mut data: Vec<&LevelData> = self.levels.data.iter().collect();
+        data.sort_by_key(|level| level.date_time);
+
+        let mut covered_until = self.start_time;
+        for level in data {
+            let (start, end) = (level.date_time, level.end_time());
+            if start > covered_until {
+                report.gaps.push((covered_until, start));
+            } else if start < covered_until && start >= self.start_time {
+                report.overlaps.push((start, end.min(covered_until)));
+            }
+            covered_until = covered_until.max(end);
+        }
+        if covered_until < self.end_time {
+            report.gaps.push((covered_until, self.end_time));
+        }
+
+        report
+    }
+
+    /// Returns the sleep stages of this record as a single timeline
+    ///
+    /// The short wake periods in `levels.short_data` are cut out of the overlapping
+    /// `levels.data` intervals, the way Fitbit's app renders the hypnogram. The result is
+    /// ordered by start time, non-overlapping, and adjacent intervals of the same level are
+    /// merged.
+    pub fn hypnogram(&self) -> Vec<StageInterval> {
+        let mut data: Vec<StageInterval> = self.levels.data.iter().map(Into::into).collect();
+        data.sort_by_key(|interval| interval.start);
+        let mut short_wakes: Vec<StageInterval> =
+            self.levels.short_data.iter().map(Into::into).collect();
+        short_wakes.sort_by_key(|interval| interval.start);
+
+        let mut hypnogram = Vec::with_capacity(data.len() + 2 * short_wakes.len());
+        for interval in data {
+            let mut cursor = interval.start;
+            for wake in short_wakes
+                .iter()
+                .filter(|wake| wake.start < interval.end && wake.end > interval.start)
+            {
+                let wake_start = wake.start.max(cursor);
+                let wake_end = wake.end.min(interval.end);
+                push_interval(
+                    &mut hypnogram,
+                    StageInterval {
+                        level: interval.level.clone(),
+                        start: cursor,
+                        end: wake_start,
+                    },
+                );
+                push_interval(
+                    &mut hypnogram,
+                    StageInterval {
+                        level: wake.level.clone(),
+                        start: wake_start,
+                        end: wake_end,
+                    },
+                );
+                cursor = cursor.max(wake_end);
+            }
+            push_interval(
+                &mut hypnogram,
+                StageInterval {
+                    start: cursor,
+                    ..interval
+                },
+            );
+        }
+
+        hypnogram
+    }
+
+    /// Returns the sleep level at the given time, or `None` if the time is outside the record
+    ///
+    /// Short wake periods take precedence over the level they interrupt.
+    pub fn stage_at(&self, time: NaiveDateTime) -> Option<SleepLevel> {
+        self.hypnogram()
+            .into_iter()
+            .find(|interval| interval.contains(time))
+            .map(|interval| interval.level)
+    }
+
+    /// Returns the hypnogram intervals overlapping `[start, end)`, clipped to that range
+    pub fn stages_between(&self, start: NaiveDateTime, end: NaiveDateTime) -> Vec<StageInterval> {
+        self.hypnogram()
+            .into_iter()
+            .filter(|interval| interval.start < end && interval.end > start)
+            .map(|interval| StageInterval {
+                start: interval.start.max(start),
+                end: interval.end.min(end),
+                ..interval
             })
+            .collect()
     }
 
-    fn get_wake_up_time(&self) -> Option<NaiveTime> {
-        self.sleep
+    /// Returns the time the sleeper fell asleep, as detected with the given configuration
+    ///
+    /// This is the start of the first run of sleep lasting at least
+    /// `config.min_sustained_sleep`. Consecutive sleep intervals of different stages (e.g.
+    /// light followed by deep) count as one run. Wake and restless intervals end the run,
+    /// unless they are no longer than `config.ignore_short_wakes`.
+    ///
+    /// `get_time_fell_asleep` uses the default configuration.
+    pub fn get_time_fell_asleep_with(&self, config: &SleepAnalysisConfig) -> Option<NaiveDateTime> {
+        let mut data: Vec<&LevelData> = self.levels.data.iter().collect();
+        data.sort_by_key(|level| level.date_time);
+
+        let mut run: Option<(NaiveDateTime, NaiveDateTime)> = None;
+        for level in data {
+            let contiguous = run.is_some_and(|(_, end)| end == level.date_time);
+
+            if !level.is_sleep() {
+                let bridged = contiguous
+                    && config
+                        .ignore_short_wakes
+                        .is_some_and(|max_wake| level.duration() <= max_wake);
+                run = run
+                    .filter(|_| bridged)
+                    .map(|(start, _)| (start, level.end_time()));
+                continue;
+            }
+
+            let start = match run {
+                Some((start, _)) if contiguous => start,
+                _ => level.date_time,
+            };
+            let end = level.end_time();
+            if end - start >= config.min_sustained_sleep {
+                return Some(start);
+            }
+            run = Some((start, end));
+        }
+
+        None
+    }
+
+    /// Returns the time from the start of the record to falling asleep
+    ///
+    /// Unlike `minutes_to_fall_asleep`, which Fitbit often reports as 0 for auto-detected
+    /// sleep, this is computed from the level data.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Controls how falling asleep is detected
+    ///
+    /// # Returns
+    ///
+    /// The onset latency, or `None` if the record never reaches sustained sleep
+    pub fn sleep_onset_latency(&self, config: &SleepAnalysisConfig) -> Option<chrono::Duration> {
+        self.get_time_fell_asleep_with(config)
+            .map(|onset| onset - self.start_time)
+    }
+
+    /// Counts the transitions between sleep stages in the hypnogram
+    ///
+    /// A higher number of transitions, particularly into wake, indicates more fragmented
+    /// sleep. Short wakes count as two transitions, into and out of wake.
+    pub fn stage_transitions(&self) -> StageTransitions {
+        let mut transitions = StageTransitions::default();
+        for pair in self.hypnogram().windows(2) {
+            *transitions
+                .counts
+                .entry((pair[0].level.clone(), pair[1].level.clone()))
+                .or_default() += 1;
+        }
+
+        transitions
+    }
+
+    /// Returns `start_time` in the given time zone
+    pub fn start_time_local<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Tz> {
+        with_offset(self.start_time, tz)
+    }
+
+    /// Returns `end_time` in the given time zone
+    pub fn end_time_local<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Tz> {
+        with_offset(self.end_time, tz)
+    }
+
+    /// Returns `start_time` in UTC, given the time zone it was recorded in
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fitbit_rs::{FitbitClient, FitbitClientTrait};
+    ///
+    /// # fn main() -> Result<(), fitbit_rs::FitbitError> {
+    /// let client = FitbitClient::new("your_access_token".to_string());
+    /// let offset = client.fetch_profile()?.user.utc_offset().unwrap();
+    ///
+    /// let today = chrono::Local::now().date_naive();
+    /// for sleep in client.fetch_sleep_data(today)?.sleep {
+    ///     println!("Went to bed at {}", sleep.start_time_utc(offset));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn start_time_utc<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Utc> {
+        self.start_time_local(tz).to_utc()
+    }
+
+    /// Returns `end_time` in UTC, given the time zone it was recorded in
+    pub fn end_time_utc<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Utc> {
+        self.end_time_local(tz).to_utc()
+    }
+
+    /// Returns the midpoint between the start and end of the record
+    pub fn sleep_midpoint(&self) -> NaiveDateTime {
+        self.start_time + (self.end_time - self.start_time) / 2
+    }
+
+    /// Returns the length of the record, from the `duration` field (in milliseconds)
+    pub fn total_duration(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.duration as i64)
+    }
+
+    /// Returns `time_in_bed` (in minutes) as a duration
+    pub fn time_in_bed_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.time_in_bed as i64)
+    }
+
+    /// Returns `minutes_to_fall_asleep` as a duration
+    ///
+    /// See [`SleepData::sleep_onset_latency`] for a value computed from the level data.
+    pub fn time_to_fall_asleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_to_fall_asleep as i64)
+    }
+
+    /// Returns `minutes_after_wakeup` as a duration
+    pub fn time_after_wakeup(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_after_wakeup as i64)
+    }
+
+    /// Returns the sleep efficiency computed from the level data, as a percentage
+    ///
+    /// This is the time asleep in the hypnogram divided by the time in bed (from
+    /// `start_time` to `end_time`). Fitbit's reported `efficiency` uses a different formula,
+    /// so the two usually differ. Periods with an unknown level count as time in bed but
+    /// not as time asleep.
+    ///
+    /// # Returns
+    ///
+    /// The efficiency between 0 and 100, or `None` if the record has no time in bed
+    pub fn computed_efficiency(&self) -> Option<f32> {
+        let time_in_bed = self.end_time - self.start_time;
+        if time_in_bed <= chrono::Duration::zero() {
+            return None;
+        }
+
+        let time_asleep: chrono::Duration = self
+            .hypnogram()
             .iter()
-            .find(|s| s.is_main_sleep)
-            .map(|main_sleep| main_sleep.end_time.time())
+            .filter(|interval| {
+                interval.level.is_sleep()
+                    && !matches!(interval.level, SleepLevel::Unknown | SleepLevel::Other(_))
+            })
+            .map(StageInterval::duration)
+            .sum();
+
+        Some(100.0 * time_asleep.num_seconds() as f32 / time_in_bed.num_seconds() as f32)
     }
 
-    fn get_total_duration_awake_during_sleep(&self) -> Option<chrono::Duration> {
-        let main_sleep = self.sleep.iter().find(|s| s.is_main_sleep);
-        if let Some(sleep) = main_sleep {
-            let total_seconds: u32 = sleep.levels.data.iter().map(|level| level.seconds).sum();
+    /// Returns the periods spent awake between falling asleep and the final awakening
+    ///
+    /// Consecutive non-sleep intervals (e.g. restless followed by awake) form a single
+    /// awakening. Time awake before falling asleep and after the last sleep interval is not
+    /// included, nor are awakenings no longer than `config.ignore_short_wakes`.
+    fn awakenings_after_onset(&self, config: &SleepAnalysisConfig) -> Vec<StageInterval> {
+        let Some(onset) = self.get_time_fell_asleep_with(config) else {
+            return Vec::new();
+        };
+        let hypnogram = self.hypnogram();
+        let Some(final_sleep_end) = hypnogram
+            .iter()
+            .rev()
+            .find(|interval| interval.level.is_sleep())
+            .map(|interval| interval.end)
+        else {
+            return Vec::new();
+        };
 
-            Some(chrono::Duration::seconds(total_seconds as i64))
-        } else {
-            None
+        let mut awakenings: Vec<StageInterval> = Vec::new();
+        for interval in hypnogram.into_iter().filter(|interval| {
+            !interval.level.is_sleep() && interval.start >= onset && interval.end <= final_sleep_end
+        }) {
+            match awakenings.last_mut() {
+                Some(last) if last.end == interval.start => last.end = interval.end,
+                _ => awakenings.push(interval),
+            }
+        }
+
+        if let Some(max_wake) = config.ignore_short_wakes {
+            awakenings.retain(|awakening| awakening.duration() > max_wake);
         }
+
+        awakenings
+    }
+
+    /// Returns the Wake After Sleep Onset (WASO)
+    ///
+    /// This is the total time awake between falling asleep and the final awakening, including
+    /// the short wake periods unless `config.ignore_short_wakes` filters them out. It is zero
+    /// if the record contains no sustained sleep.
+    pub fn wake_after_sleep_onset(&self, config: &SleepAnalysisConfig) -> chrono::Duration {
+        self.awakenings_after_onset(config)
+            .iter()
+            .map(StageInterval::duration)
+            .sum()
+    }
+
+    /// Returns the number of awakenings between falling asleep and the final awakening
+    pub fn awakening_count(&self, config: &SleepAnalysisConfig) -> usize {
+        self.awakenings_after_onset(config).len()
+    }
+
+    /// Returns the number of awakenings lasting longer than `min_duration`
+    pub fn count_awakenings_longer_than(
+        &self,
+        config: &SleepAnalysisConfig,
+        min_duration: chrono::Duration,
+    ) -> usize {
+        self.long_awakenings(config, min_duration).len()
+    }
+
+    /// Returns the awakenings lasting longer than `min_duration`, in chronological order
+    ///
+    /// Each awakening spans consecutive non-sleep intervals, so its `level` is that of the
+    /// first one. Only awakenings between falling asleep and the final awakening are
+    /// included.
+    pub fn long_awakenings(
+        &self,
+        config: &SleepAnalysisConfig,
+        min_duration: chrono::Duration,
+    ) -> Vec<StageInterval> {
+        self.awakenings_after_onset(config)
+            .into_iter()
+            .filter(|awakening| awakening.duration() > min_duration)
+            .collect()
     }
-}
 
-impl SleepResponseV1_2 {
     pub fn get_time_awake_between(
         &self,
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> chrono::Duration {
-        let main_sleep = self.sleep.iter().find(|s| s.is_main_sleep);
-        let total_awake = end - start;
+        let total_duration_not_awake = self
+            .levels
+            .data
+            .iter()
+            .filter(|level| level.is_sleep() && level.date_time < end)
+            .fold(chrono::Duration::zero(), |acc, level| {
+                let level_start = level.date_time.max(start);
+                let level_end =
+                    (level.date_time + chrono::Duration::seconds(level.seconds as i64)).min(end);
+                if level_start < level_end {
+                    acc + (level_end - level_start)
+                } else {
+                    acc
+                }
+            });
 
-        if let Some(sleep) = main_sleep {
-            let total_duration_not_awake = sleep
-                .levels
-                .data
-                .iter()
-                .filter(|level| level.level != SleepLevel::Wake && level.date_time < end)
-                .fold(chrono::Duration::zero(), |acc, level| {
-                    let level_start = level.date_time.max(start);
-                    let level_end = (level.date_time
-                        + chrono::Duration::seconds(level.seconds as i64))
-                    .min(end);
-                    if level_start < level_end {
-                        acc + (level_end - level_start)
-                    } else {
-                        acc
-                    }
-                });
-
-            total_awake - total_duration_not_awake
-        } else {
-            total_awake
-        }
+        (end - start) - total_duration_not_awake
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+impl SleepResponse for SleepResponseV1_2 {
+    fn get_total_duration_asleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.summary.total_minutes_asleep as i64)
+    }
 
-    #[test]
-    fn test_parse_real_fitbit_sleep_response() {
-        // JSON data from real Fitbit response
-        let json_str = r#"{
-  "sleep" : [ {
-    "dateOfSleep" : "2025-03-30",
-    "duration" : 32220000,
-    "efficiency" : 90,
-    "endTime" : "2025-03-30T07:09:00.000",
-    "infoCode" : 0,
-    "isMainSleep" : true,
-    "levels" : {
-      "data" : [ {
-        "dateTime" : "2025-03-29T22:11:30.000",
-        "level" : "wake",
-        "seconds" : 510
-      }, {
-        "dateTime" : "2025-03-29T22:20:00.000",
-        "level" : "light",
-        "seconds" : 150
+    fn get_sleep_efficiency(&self) -> Option<u8> {
+        self.main_sleep()
+            .and_then(SleepRecord::get_sleep_efficiency)
+    }
+
+    fn get_time_fell_asleep(&self) -> Option<NaiveDateTime> {
+        self.main_sleep()
+            .and_then(SleepRecord::get_time_fell_asleep)
+    }
+
+    fn get_wake_up_time(&self) -> Option<NaiveTime> {
+        self.main_sleep().map(SleepRecord::get_wake_up_time)
+    }
+
+    fn get_total_duration_awake_during_sleep(&self) -> Option<chrono::Duration> {
+        self.main_sleep()
+            .map(SleepRecord::get_total_duration_awake_during_sleep)
+    }
+
+    fn get_time_fell_asleep_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        self.main_sleep()
+            .and_then(|sleep| sleep.get_time_fell_asleep_local(tz))
+    }
+
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        self.main_sleep()
+            .map(|sleep| sleep.get_wake_up_time_local(tz))
+    }
+}
+
+impl SleepResponseV1_2 {
+    /// Returns the main sleep record, if there is one
+    pub fn main_sleep(&self) -> Option<&SleepData> {
+        self.sleep.iter().find(|s| s.is_main_sleep)
+    }
+
+    /// Returns the sleep records that are not the main sleep
+    pub fn naps(&self) -> impl Iterator<Item = &SleepData> {
+        self.sleep.iter().filter(|s| !s.is_main_sleep)
+    }
+
+    /// Returns all sleep records, main sleep and naps, ordered by start time
+    pub fn all_sessions(&self) -> Vec<&SleepData> {
+        let mut sessions: Vec<&SleepData> = self.sleep.iter().collect();
+        sessions.sort_by_key(|s| s.start_time);
+        sessions
+    }
+
+    /// Returns the total time asleep during naps
+    pub fn get_total_nap_duration(&self) -> chrono::Duration {
+        let minutes: u32 = self.naps().map(|nap| nap.minutes_asleep).sum();
+        chrono::Duration::minutes(minutes as i64)
+    }
+
+    /// Returns the total time asleep across all sleep records, including naps
+    pub fn get_total_duration_asleep_including_naps(&self) -> chrono::Duration {
+        let minutes: u32 = self.sleep.iter().map(|s| s.minutes_asleep).sum();
+        chrono::Duration::minutes(minutes as i64)
+    }
+
+    pub fn get_time_awake_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> chrono::Duration {
+        match self.main_sleep() {
+            Some(sleep) => sleep.get_time_awake_between(start, end),
+            None => end - start,
+        }
+    }
+}
+
+/// Sleep response in the classic v1 format (`/1/user/-/sleep/date/{date}.json`)
+///
+/// Older trackers and manually logged sleep only report the classic levels (asleep,
+/// restless, awake), which v1 returns as per-minute data.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
+pub struct SleepResponseV1 {
+    pub sleep: Vec<SleepDataV1>,
+    pub summary: SleepSummary,
+    /// Unmodeled fields of the response
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct SleepDataV1 {
+    pub awake_count: u32,
+    pub awake_duration: u32,
+    pub awakenings_count: u32,
+    pub date_of_sleep: NaiveDate,
+    pub duration: u64,
+    /// Not reported for some classic records
+    pub efficiency: Option<u8>,
+    pub is_main_sleep: bool,
+    pub log_id: u64,
+    pub minute_data: Vec<MinuteData>,
+    pub minutes_after_wakeup: u32,
+    pub minutes_asleep: u32,
+    pub minutes_awake: u32,
+    pub minutes_to_fall_asleep: u32,
+    pub restless_count: u32,
+    pub restless_duration: u32,
+    pub start_time: NaiveDateTime,
+    pub time_in_bed: u32,
+}
+
+impl SleepDataV1 {
+    /// Returns the time the sleep record ended
+    pub fn end_time(&self) -> NaiveDateTime {
+        self.start_time + self.total_duration()
+    }
+
+    /// Returns the length of the record, from the `duration` field (in milliseconds)
+    pub fn total_duration(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.duration as i64)
+    }
+
+    /// Returns `time_in_bed` (in minutes) as a duration
+    pub fn time_in_bed_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.time_in_bed as i64)
+    }
+
+    /// Returns `minutes_to_fall_asleep` as a duration
+    pub fn time_to_fall_asleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_to_fall_asleep as i64)
+    }
+
+    /// Returns `minutes_after_wakeup` as a duration
+    pub fn time_after_wakeup(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_after_wakeup as i64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MinuteData {
+    pub date_time: NaiveTime,
+    /// One of `Asleep`, `Restless` or `Awake`
+    #[serde(
+        deserialize_with = "deserialize_minute_level",
+        serialize_with = "serialize_minute_level"
+    )]
+    pub value: SleepLevel,
+}
+
+/// Deserializes the v1 minute data value ("1" = asleep, "2" = restless, "3" = awake)
+fn deserialize_minute_level<'de, D>(deserializer: D) -> Result<SleepLevel, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.as_str() {
+        "1" => Ok(SleepLevel::Asleep),
+        "2" => Ok(SleepLevel::Restless),
+        "3" => Ok(SleepLevel::Awake),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown minute data value: {}",
+            other
+        ))),
+    }
+}
+
+impl SleepRecord for SleepDataV1 {
+    fn get_total_duration_asleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_asleep as i64)
+    }
+
+    fn get_sleep_efficiency(&self) -> Option<u8> {
+        self.efficiency
+    }
+
+    fn get_time_fell_asleep(&self) -> Option<NaiveDateTime> {
+        Some(self.start_time + chrono::Duration::minutes(self.minutes_to_fall_asleep as i64))
+    }
+
+    fn get_wake_up_time(&self) -> NaiveTime {
+        self.end_time().time()
+    }
+
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> DateTime<Tz> {
+        with_offset(self.end_time(), tz)
+    }
+
+    fn get_total_duration_awake_during_sleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_awake as i64)
+    }
+}
+
+impl SleepResponseV1 {
+    /// Returns the main sleep record, if there is one
+    pub fn main_sleep(&self) -> Option<&SleepDataV1> {
+        self.sleep.iter().find(|s| s.is_main_sleep)
+    }
+}
+
+/// Serializes a classic level as a v1 minute data value
+fn serialize_minute_level<S>(level: &SleepLevel, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let value = match level {
+        SleepLevel::Asleep => "1",
+        SleepLevel::Restless => "2",
+        SleepLevel::Awake => "3",
+        other => {
+            return Err(serde::ser::Error::custom(format!(
+                "{:?} is not a classic sleep level",
+                other
+            )));
+        }
+    };
+    serializer.serialize_str(value)
+}
+
+impl SleepResponse for SleepResponseV1 {
+    fn get_total_duration_asleep(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.summary.total_minutes_asleep as i64)
+    }
+
+    fn get_sleep_efficiency(&self) -> Option<u8> {
+        self.main_sleep()
+            .and_then(SleepRecord::get_sleep_efficiency)
+    }
+
+    fn get_time_fell_asleep(&self) -> Option<NaiveDateTime> {
+        self.main_sleep()
+            .and_then(SleepRecord::get_time_fell_asleep)
+    }
+
+    fn get_wake_up_time(&self) -> Option<NaiveTime> {
+        self.main_sleep().map(SleepRecord::get_wake_up_time)
+    }
+
+    fn get_total_duration_awake_during_sleep(&self) -> Option<chrono::Duration> {
+        self.main_sleep()
+            .map(SleepRecord::get_total_duration_awake_during_sleep)
+    }
+
+    fn get_time_fell_asleep_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        self.main_sleep()
+            .and_then(|sleep| sleep.get_time_fell_asleep_local(tz))
+    }
+
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        self.main_sleep()
+            .map(|sleep| sleep.get_wake_up_time_local(tz))
+    }
+}
+
+/// A sleep response in either format, detected from the shape of the payload
+///
+/// Useful for reading archived responses without knowing which endpoint or tracker
+/// generation produced them. Responses with per-minute `minuteData` are read as
+/// [`SleepResponseV1`], all others (including ones without any records) as
+/// [`SleepResponseV1_2`]. Serializing writes the response back in its original format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Sleep {
+    /// A v1.2 response; its records may still be classic records
+    Stages(SleepResponseV1_2),
+    /// A v1 response with per-minute data
+    Classic(SleepResponseV1),
+}
+
+impl Sleep {
+    fn as_response(&self) -> &dyn SleepResponse {
+        match self {
+            Sleep::Stages(response) => response,
+            Sleep::Classic(response) => response,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Sleep {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let is_classic = value
+            .get("sleep")
+            .and_then(serde_json::Value::as_array)
+            .is_some_and(|records| {
+                records
+                    .iter()
+                    .any(|record| record.get("minuteData").is_some())
+            });
+
+        let sleep = if is_classic {
+            serde_json::from_value(value).map(Sleep::Classic)
+        } else {
+            serde_json::from_value(value).map(Sleep::Stages)
+        };
+        sleep.map_err(serde::de::Error::custom)
+    }
+}
+
+impl SleepResponse for Sleep {
+    fn get_total_duration_asleep(&self) -> chrono::Duration {
+        self.as_response().get_total_duration_asleep()
+    }
+
+    fn get_sleep_efficiency(&self) -> Option<u8> {
+        self.as_response().get_sleep_efficiency()
+    }
+
+    fn get_time_fell_asleep(&self) -> Option<NaiveDateTime> {
+        self.as_response().get_time_fell_asleep()
+    }
+
+    fn get_wake_up_time(&self) -> Option<NaiveTime> {
+        self.as_response().get_wake_up_time()
+    }
+
+    fn get_total_duration_awake_during_sleep(&self) -> Option<chrono::Duration> {
+        self.as_response().get_total_duration_awake_during_sleep()
+    }
+
+    fn get_time_fell_asleep_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        match self {
+            Sleep::Stages(response) => response.get_time_fell_asleep_local(tz),
+            Sleep::Classic(response) => response.get_time_fell_asleep_local(tz),
+        }
+    }
+
+    fn get_wake_up_time_local<Tz: TimeZone>(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        match self {
+            Sleep::Stages(response) => response.get_wake_up_time_local(tz),
+            Sleep::Classic(response) => response.get_wake_up_time_local(tz),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+
+    /// JSON data from real Fitbit response
+    const REAL_SLEEP_RESPONSE: &str = r#"{
+  "sleep" : [ {
+    "dateOfSleep" : "2025-03-30",
+    "duration" : 32220000,
+    "efficiency" : 90,
+    "endTime" : "2025-03-30T07:09:00.000",
+    "infoCode" : 0,
+    "isMainSleep" : true,
+    "levels" : {
+      "data" : [ {
+        "dateTime" : "2025-03-29T22:11:30.000",
+        "level" : "wake",
+        "seconds" : 510
+      }, {
+        "dateTime" : "2025-03-29T22:20:00.000",
+        "level" : "light",
+        "seconds" : 150
       }, {
         "dateTime" : "2025-03-29T22:22:30.000",
         "level" : "wake",
@@ -413,6 +1663,28 @@ mod tests {
   }
 }"#;
 
+    fn real_sleep_response() -> SleepResponseV1_2 {
+        serde_json::from_str(REAL_SLEEP_RESPONSE).expect("Failed to parse JSON")
+    }
+
+    #[test]
+    fn test_total_duration_awake_during_sleep() {
+        let response = real_sleep_response();
+
+        assert_eq!(
+            response.get_total_duration_awake_during_sleep(),
+            Some(chrono::Duration::minutes(86))
+        );
+        assert_eq!(
+            response.sleep[0].get_total_duration_awake_during_sleep(),
+            response.sleep[0].levels.summary.wake.duration()
+        );
+    }
+
+    #[test]
+    fn test_parse_real_fitbit_sleep_response() {
+        let json_str = REAL_SLEEP_RESPONSE;
+
         // Parse the JSON into our struct
         let response: SleepResponseV1_2 =
             serde_json::from_str(json_str).expect("Failed to parse JSON");
@@ -427,7 +1699,7 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 3, 30).unwrap()
         );
         assert_eq!(sleep.duration, 32220000);
-        assert_eq!(sleep.efficiency, 90);
+        assert_eq!(sleep.efficiency, Some(90));
         assert_eq!(
             sleep.end_time,
             NaiveDateTime::parse_from_str("2025-03-30T07:09:00.000", "%Y-%m-%dT%H:%M:%S%.3f")
@@ -436,7 +1708,7 @@ mod tests {
         assert_eq!(sleep.info_code, 0);
         assert!(sleep.is_main_sleep);
         assert_eq!(sleep.log_id, 48809009246);
-        assert_eq!(sleep.log_type, "auto_detected");
+        assert_eq!(sleep.log_type, SleepLogType::AutoDetected);
         assert_eq!(sleep.minutes_after_wakeup, 0);
         assert_eq!(sleep.minutes_asleep, 391);
         assert_eq!(sleep.minutes_awake, 86);
@@ -447,7 +1719,7 @@ mod tests {
                 .unwrap()
         );
         assert_eq!(sleep.time_in_bed, 537);
-        assert_eq!(sleep.sleep_type, "stages");
+        assert_eq!(sleep.sleep_type, SleepType::Stages);
 
         // Test sleep levels data
         assert_eq!(sleep.levels.data.len(), 23);
@@ -528,4 +1800,948 @@ mod tests {
             time_awake.num_minutes()
         );
     }
+
+    #[test]
+    fn test_sleep_range_response_sleep_on() {
+        let first = NaiveDate::from_ymd_opt(2025, 3, 29).unwrap();
+        let second = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+        let response = SleepRangeResponse {
+            sleep: vec![
+                SleepData {
+                    date_of_sleep: first,
+                    log_id: 1,
+                    ..Default::default()
+                },
+                SleepData {
+                    date_of_sleep: second,
+                    log_id: 2,
+                    ..Default::default()
+                },
+                SleepData {
+                    date_of_sleep: second,
+                    log_id: 3,
+                    ..Default::default()
+                },
+            ],
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        };
+
+        let log_ids: Vec<u64> = response.sleep_on(second).map(|s| s.log_id).collect();
+        assert_eq!(log_ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_parse_classic_sleep_record() {
+        let json_str = r#"{
+  "sleep" : [ {
+    "dateOfSleep" : "2025-03-30",
+    "duration" : 1800000,
+    "efficiency" : 93,
+    "endTime" : "2025-03-30T14:30:00.000",
+    "infoCode" : 0,
+    "isMainSleep" : false,
+    "levels" : {
+      "data" : [ {
+        "dateTime" : "2025-03-30T14:00:00.000",
+        "level" : "awake",
+        "seconds" : 60
+      }, {
+        "dateTime" : "2025-03-30T14:01:00.000",
+        "level" : "asleep",
+        "seconds" : 1200
+      }, {
+        "dateTime" : "2025-03-30T14:21:00.000",
+        "level" : "restless",
+        "seconds" : 60
+      }, {
+        "dateTime" : "2025-03-30T14:22:00.000",
+        "level" : "asleep",
+        "seconds" : 480
+      } ],
+      "summary" : {
+        "asleep" : { "count" : 0, "minutes" : 28 },
+        "awake" : { "count" : 1, "minutes" : 1 },
+        "restless" : { "count" : 1, "minutes" : 1 }
+      }
+    },
+    "logId" : 48809009247,
+    "logType" : "manual",
+    "minutesAfterWakeup" : 0,
+    "minutesAsleep" : 28,
+    "minutesAwake" : 2,
+    "minutesToFallAsleep" : 0,
+    "startTime" : "2025-03-30T14:00:00.000",
+    "timeInBed" : 30,
+    "type" : "classic"
+  } ],
+  "summary" : {
+    "totalMinutesAsleep" : 28,
+    "totalSleepRecords" : 1,
+    "totalTimeInBed" : 30
+  }
+}"#;
+
+        let response: SleepResponseV1_2 =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        let sleep = &response.sleep[0];
+        assert_eq!(sleep.sleep_type, SleepType::Classic);
+        assert_eq!(sleep.log_type, SleepLogType::Manual);
+        assert!(sleep.levels.short_data.is_empty());
+        assert_eq!(sleep.levels.data[1].level, SleepLevel::Asleep);
+        assert!(sleep.levels.data[1].is_sleep());
+        assert_eq!(sleep.levels.data[2].level, SleepLevel::Restless);
+        assert!(!sleep.levels.data[2].is_sleep());
+        assert!(!sleep.levels.data[0].is_sleep());
+        assert_eq!(sleep.levels.summary.asleep.minutes, 28);
+        assert_eq!(sleep.levels.summary.restless.count, 1);
+        assert_eq!(sleep.levels.summary.awake.minutes, 1);
+        assert_eq!(response.summary.stages.deep, 0);
+    }
+
+    #[test]
+    fn test_parse_v1_sleep_response() {
+        let json_str = r#"{
+  "sleep" : [ {
+    "awakeCount" : 1,
+    "awakeDuration" : 1,
+    "awakeningsCount" : 2,
+    "dateOfSleep" : "2017-04-02",
+    "duration" : 300000,
+    "efficiency" : 80,
+    "isMainSleep" : true,
+    "logId" : 14188293052,
+    "minuteData" : [
+      { "dateTime" : "23:58:00", "value" : "3" },
+      { "dateTime" : "23:59:00", "value" : "1" },
+      { "dateTime" : "00:00:00", "value" : "1" },
+      { "dateTime" : "00:01:00", "value" : "2" },
+      { "dateTime" : "00:02:00", "value" : "1" }
+    ],
+    "minutesAfterWakeup" : 0,
+    "minutesAsleep" : 3,
+    "minutesAwake" : 2,
+    "minutesToFallAsleep" : 1,
+    "restlessCount" : 1,
+    "restlessDuration" : 1,
+    "startTime" : "2017-04-01T23:58:00.000",
+    "timeInBed" : 5
+  } ],
+  "summary" : {
+    "totalMinutesAsleep" : 3,
+    "totalSleepRecords" : 1,
+    "totalTimeInBed" : 5
+  }
+}"#;
+
+        let response: SleepResponseV1 =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        let sleep = &response.sleep[0];
+        assert_eq!(sleep.minute_data.len(), 5);
+        assert_eq!(sleep.minute_data[0].value, SleepLevel::Awake);
+        assert_eq!(sleep.minute_data[1].value, SleepLevel::Asleep);
+        assert_eq!(sleep.minute_data[3].value, SleepLevel::Restless);
+        assert_eq!(sleep.restless_count, 1);
+
+        assert_eq!(
+            response.get_total_duration_asleep(),
+            chrono::Duration::minutes(3)
+        );
+        assert_eq!(response.get_sleep_efficiency(), Some(80));
+        assert_eq!(
+            response.get_time_fell_asleep(),
+            Some(
+                NaiveDateTime::parse_from_str("2017-04-01T23:59:00.000", "%Y-%m-%dT%H:%M:%S%.3f")
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            response.get_wake_up_time(),
+            Some(NaiveTime::from_hms_opt(0, 3, 0).unwrap())
+        );
+        assert_eq!(
+            response.get_total_duration_awake_during_sleep(),
+            Some(chrono::Duration::minutes(2))
+        );
+    }
+
+    #[test]
+    fn test_detect_sleep_format() {
+        let stages: Sleep =
+            serde_json::from_str(REAL_SLEEP_RESPONSE).expect("Failed to parse JSON");
+        assert!(matches!(stages, Sleep::Stages(_)));
+        assert_eq!(stages.get_sleep_efficiency(), Some(90));
+
+        let classic: Sleep = serde_json::from_str(
+            r#"{
+  "sleep" : [ {
+    "awakeCount" : 0,
+    "awakeDuration" : 0,
+    "awakeningsCount" : 0,
+    "dateOfSleep" : "2017-04-02",
+    "duration" : 120000,
+    "efficiency" : 100,
+    "isMainSleep" : true,
+    "logId" : 14188293054,
+    "minuteData" : [
+      { "dateTime" : "23:58:00", "value" : "1" },
+      { "dateTime" : "23:59:00", "value" : "1" }
+    ],
+    "minutesAfterWakeup" : 0,
+    "minutesAsleep" : 2,
+    "minutesAwake" : 0,
+    "minutesToFallAsleep" : 0,
+    "restlessCount" : 0,
+    "restlessDuration" : 0,
+    "startTime" : "2017-04-01T23:58:00.000",
+    "timeInBed" : 2
+  } ],
+  "summary" : { "totalMinutesAsleep" : 2, "totalSleepRecords" : 1, "totalTimeInBed" : 2 }
+}"#,
+        )
+        .expect("Failed to parse JSON");
+        assert!(matches!(classic, Sleep::Classic(_)));
+        assert_eq!(
+            classic.get_total_duration_asleep(),
+            chrono::Duration::minutes(2)
+        );
+        let serialized = serde_json::to_value(&classic).unwrap();
+        assert_eq!(serialized["sleep"][0]["minuteData"][1]["value"], "1");
+
+        let empty: Sleep = serde_json::from_str(
+            r#"{"sleep": [], "summary": {"totalMinutesAsleep": 0, "totalSleepRecords": 0, "totalTimeInBed": 0}}"#,
+        )
+        .expect("Failed to parse JSON");
+        assert!(matches!(empty, Sleep::Stages(_)));
+    }
+
+    #[test]
+    fn test_parse_v1_sleep_without_efficiency() {
+        // Manually logged classic records of some older trackers have no efficiency
+        let json_str = r#"{
+  "sleep" : [ {
+    "awakeCount" : 0,
+    "awakeDuration" : 0,
+    "awakeningsCount" : 0,
+    "dateOfSleep" : "2017-04-02",
+    "duration" : 25200000,
+    "isMainSleep" : true,
+    "logId" : 14188293053,
+    "minuteData" : [ ],
+    "minutesAfterWakeup" : 0,
+    "minutesAsleep" : 420,
+    "minutesAwake" : 0,
+    "minutesToFallAsleep" : 0,
+    "restlessCount" : 0,
+    "restlessDuration" : 0,
+    "startTime" : "2017-04-01T23:00:00.000",
+    "timeInBed" : 420
+  } ],
+  "summary" : {
+    "totalMinutesAsleep" : 420,
+    "totalSleepRecords" : 1,
+    "totalTimeInBed" : 420
+  }
+}"#;
+
+        let response: SleepResponseV1 =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.sleep[0].efficiency, None);
+        assert_eq!(response.get_sleep_efficiency(), None);
+        assert_eq!(
+            response.get_total_duration_asleep(),
+            chrono::Duration::minutes(420)
+        );
+    }
+
+    #[test]
+    fn test_nap_accessors() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+        let response = SleepResponseV1_2 {
+            sleep: vec![
+                SleepData {
+                    log_id: 2,
+                    minutes_asleep: 25,
+                    start_time: date.and_hms_opt(14, 0, 0).unwrap(),
+                    ..Default::default()
+                },
+                SleepData {
+                    log_id: 1,
+                    is_main_sleep: true,
+                    minutes_asleep: 391,
+                    start_time: date.pred_opt().unwrap().and_hms_opt(22, 11, 30).unwrap(),
+                    ..Default::default()
+                },
+                SleepData {
+                    log_id: 3,
+                    minutes_asleep: 40,
+                    start_time: date.and_hms_opt(18, 30, 0).unwrap(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let nap_ids: Vec<u64> = response.naps().map(|s| s.log_id).collect();
+        assert_eq!(nap_ids, vec![2, 3]);
+
+        let session_ids: Vec<u64> = response.all_sessions().iter().map(|s| s.log_id).collect();
+        assert_eq!(session_ids, vec![1, 2, 3]);
+
+        assert_eq!(
+            response.get_total_nap_duration(),
+            chrono::Duration::minutes(65)
+        );
+        assert_eq!(
+            response.get_total_duration_asleep_including_naps(),
+            chrono::Duration::minutes(456)
+        );
+    }
+
+    #[test]
+    fn test_sleep_record_analysis_on_nap() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 30)
+            .unwrap()
+            .and_hms_opt(14, 0, 0)
+            .unwrap();
+        let nap = SleepData {
+            efficiency: Some(95),
+            end_time: start + chrono::Duration::minutes(30),
+            levels: SleepLevels {
+                data: vec![
+                    LevelData {
+                        date_time: start,
+                        level: SleepLevel::Wake,
+                        seconds: 240,
+                    },
+                    LevelData {
+                        date_time: start + chrono::Duration::minutes(4),
+                        level: SleepLevel::Light,
+                        seconds: 1560,
+                    },
+                ],
+                ..Default::default()
+            },
+            start_time: start,
+            ..Default::default()
+        };
+        let response = SleepResponseV1_2 {
+            sleep: vec![nap],
+            ..Default::default()
+        };
+
+        assert!(response.main_sleep().is_none());
+        assert_eq!(response.get_time_fell_asleep(), None);
+
+        let nap = response.naps().next().unwrap();
+        assert_eq!(nap.get_sleep_efficiency(), Some(95));
+        assert_eq!(
+            nap.get_time_fell_asleep(),
+            Some(start + chrono::Duration::minutes(4))
+        );
+        assert_eq!(
+            nap.get_wake_up_time(),
+            NaiveTime::from_hms_opt(14, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hypnogram_merges_short_wakes() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        let hypnogram = sleep.hypnogram();
+
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+
+        // Ordered and non-overlapping
+        for pair in hypnogram.windows(2) {
+            assert!(pair[0].end <= pair[1].start);
+            assert!(pair[0].level != pair[1].level || pair[0].end != pair[1].start);
+        }
+
+        // The timeline still covers the whole record
+        assert_eq!(hypnogram.first().unwrap().start, sleep.start_time);
+        assert_eq!(hypnogram.last().unwrap().end, parse("2025-03-30T07:09:00"));
+        let total: chrono::Duration = hypnogram.iter().map(StageInterval::duration).sum();
+        let data_seconds: u32 = sleep.levels.data.iter().map(|l| l.seconds).sum();
+        assert_eq!(total, chrono::Duration::seconds(data_seconds as i64));
+
+        // The 60 second wake at 22:30:00 splits the light period starting at 22:27:30
+        let index = hypnogram
+            .iter()
+            .position(|i| i.start == parse("2025-03-29T22:27:30"))
+            .unwrap();
+        assert_eq!(
+            hypnogram[index..index + 3],
+            [
+                StageInterval {
+                    level: SleepLevel::Light,
+                    start: parse("2025-03-29T22:27:30"),
+                    end: parse("2025-03-29T22:30:00"),
+                },
+                StageInterval {
+                    level: SleepLevel::Wake,
+                    start: parse("2025-03-29T22:30:00"),
+                    end: parse("2025-03-29T22:31:00"),
+                },
+                StageInterval {
+                    level: SleepLevel::Light,
+                    start: parse("2025-03-29T22:31:00"),
+                    end: parse("2025-03-29T22:33:00"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_at_and_stages_between() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+
+        assert_eq!(
+            sleep.stage_at(parse("2025-03-30T00:20:00")),
+            Some(SleepLevel::Deep)
+        );
+        // Inside the short wake at 00:48:30
+        assert_eq!(
+            sleep.stage_at(parse("2025-03-30T00:49:00")),
+            Some(SleepLevel::Wake)
+        );
+        assert_eq!(sleep.stage_at(parse("2025-03-30T07:09:00")), None);
+        assert_eq!(sleep.stage_at(parse("2025-03-29T22:00:00")), None);
+
+        let stages =
+            sleep.stages_between(parse("2025-03-30T02:00:00"), parse("2025-03-30T02:30:00"));
+        assert_eq!(
+            stages,
+            vec![
+                StageInterval {
+                    level: SleepLevel::Light,
+                    start: parse("2025-03-30T02:00:00"),
+                    end: parse("2025-03-30T02:07:30"),
+                },
+                StageInterval {
+                    level: SleepLevel::Deep,
+                    start: parse("2025-03-30T02:07:30"),
+                    end: parse("2025-03-30T02:12:30"),
+                },
+                StageInterval {
+                    level: SleepLevel::Light,
+                    start: parse("2025-03-30T02:12:30"),
+                    end: parse("2025-03-30T02:14:30"),
+                },
+                StageInterval {
+                    level: SleepLevel::Wake,
+                    start: parse("2025-03-30T02:14:30"),
+                    end: parse("2025-03-30T02:15:00"),
+                },
+                StageInterval {
+                    level: SleepLevel::Light,
+                    start: parse("2025-03-30T02:15:00"),
+                    end: parse("2025-03-30T02:28:30"),
+                },
+                StageInterval {
+                    level: SleepLevel::Wake,
+                    start: parse("2025-03-30T02:28:30"),
+                    end: parse("2025-03-30T02:30:00"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_waso_and_awakenings() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+
+        // Everything awake after 22:27:30, including the short wakes and the 750 second wake
+        // before the final (unknown) interval
+        let config = SleepAnalysisConfig::default();
+        assert_eq!(
+            sleep.wake_after_sleep_onset(&config),
+            chrono::Duration::seconds(4350)
+        );
+        assert_eq!(sleep.awakening_count(&config), 24);
+        assert_eq!(
+            sleep.count_awakenings_longer_than(&config, chrono::Duration::minutes(5)),
+            4
+        );
+
+        assert_eq!(
+            SleepData::default().wake_after_sleep_onset(&config),
+            chrono::Duration::zero()
+        );
+        assert_eq!(SleepData::default().awakening_count(&config), 0);
+
+        // Ignoring wakes of up to a minute drops the short data wakes
+        let config = SleepAnalysisConfig {
+            ignore_short_wakes: Some(chrono::Duration::minutes(1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            sleep.wake_after_sleep_onset(&config),
+            chrono::Duration::seconds(3870)
+        );
+        assert_eq!(sleep.awakening_count(&config), 11);
+    }
+
+    #[test]
+    fn test_stage_transitions() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        let transitions = sleep.stage_transitions();
+
+        assert_eq!(transitions.total(), 56);
+        assert_eq!(transitions.count(SleepLevel::Light, SleepLevel::Wake), 23);
+        assert_eq!(transitions.count(SleepLevel::Wake, SleepLevel::Light), 24);
+        assert_eq!(transitions.count(SleepLevel::Light, SleepLevel::Deep), 3);
+        assert_eq!(transitions.count(SleepLevel::Deep, SleepLevel::Wake), 1);
+        assert_eq!(transitions.count(SleepLevel::Deep, SleepLevel::Rem), 0);
+
+        assert_eq!(SleepData::default().stage_transitions().total(), 0);
+    }
+
+    #[test]
+    fn test_timezone_conversions() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        let offset = FixedOffset::east_opt(3600).unwrap();
+
+        assert_eq!(
+            sleep.start_time_local(offset),
+            DateTime::parse_from_rfc3339("2025-03-29T22:11:30+01:00").unwrap()
+        );
+        assert_eq!(
+            sleep.start_time_utc(offset),
+            DateTime::parse_from_rfc3339("2025-03-29T21:11:30Z").unwrap()
+        );
+        assert_eq!(
+            sleep.end_time_utc(offset),
+            DateTime::parse_from_rfc3339("2025-03-30T06:09:00Z").unwrap()
+        );
+        assert_eq!(
+            response.get_wake_up_time_local(offset),
+            Some(DateTime::parse_from_rfc3339("2025-03-30T07:09:00+01:00").unwrap())
+        );
+        assert_eq!(
+            response.get_time_fell_asleep_local(offset),
+            Some(DateTime::parse_from_rfc3339("2025-03-29T22:27:30+01:00").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_timezone_conversions_across_dst() {
+        let tz = chrono_tz::Europe::Berlin;
+        let sleep = |start: &str, end: &str| SleepData {
+            start_time: NaiveDateTime::parse_from_str(start, "%Y-%m-%dT%H:%M:%S").unwrap(),
+            end_time: NaiveDateTime::parse_from_str(end, "%Y-%m-%dT%H:%M:%S").unwrap(),
+            ..SleepData::default()
+        };
+
+        // The clocks went forward from 02:00 to 03:00 during the night
+        let spring = sleep("2025-03-29T23:00:00", "2025-03-30T07:00:00");
+        assert_eq!(
+            spring.start_time_utc(tz),
+            DateTime::parse_from_rfc3339("2025-03-29T22:00:00Z").unwrap()
+        );
+        assert_eq!(
+            spring.end_time_utc(tz),
+            DateTime::parse_from_rfc3339("2025-03-30T05:00:00Z").unwrap()
+        );
+        assert_eq!(
+            spring.get_wake_up_time_local(tz).to_rfc3339(),
+            "2025-03-30T07:00:00+02:00"
+        );
+
+        // 02:30 was skipped and is read as 03:30
+        let skipped = sleep("2025-03-30T01:00:00", "2025-03-30T02:30:00");
+        assert_eq!(
+            skipped.end_time_local(tz).to_rfc3339(),
+            "2025-03-30T03:30:00+02:00"
+        );
+
+        // 02:30 happened twice when the clocks went back and is read as the first one
+        let autumn = sleep("2025-10-25T23:00:00", "2025-10-26T02:30:00");
+        assert_eq!(
+            autumn.end_time_local(tz).to_rfc3339(),
+            "2025-10-26T02:30:00+02:00"
+        );
+        assert_eq!(
+            autumn.end_time_utc(tz),
+            DateTime::parse_from_rfc3339("2025-10-26T00:30:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_long_awakenings() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+
+        let awakenings = sleep.long_awakenings(
+            &SleepAnalysisConfig::default(),
+            chrono::Duration::minutes(10),
+        );
+        let spans: Vec<_> = awakenings.iter().map(|a| (a.start, a.end)).collect();
+        assert_eq!(
+            spans,
+            [
+                (parse("2025-03-29T22:47:00"), parse("2025-03-29T22:58:00")),
+                (parse("2025-03-30T02:39:30"), parse("2025-03-30T02:51:00")),
+                (parse("2025-03-30T05:56:30"), parse("2025-03-30T06:09:00")),
+            ]
+        );
+        assert_eq!(awakenings[1].duration(), chrono::Duration::seconds(690));
+    }
+
+    #[test]
+    fn test_sleep_midpoint() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+
+        // 22:11:30 to 07:09:00
+        assert_eq!(
+            sleep.sleep_midpoint(),
+            NaiveDateTime::parse_from_str("2025-03-30T02:40:15", "%Y-%m-%dT%H:%M:%S").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_timeline() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        assert!(sleep.validate_timeline().is_valid());
+
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+        let level = |date_time: &str, seconds: u32| LevelData {
+            date_time: parse(date_time),
+            level: SleepLevel::Light,
+            seconds,
+        };
+        let sleep = SleepData {
+            start_time: parse("2025-03-29T22:00:00"),
+            end_time: parse("2025-03-29T23:00:00"),
+            levels: SleepLevels {
+                data: vec![
+                    level("2025-03-29T22:00:00", 600),
+                    level("2025-03-29T22:30:00", 600),
+                    // Out of order and overlapping the previous entry
+                    level("2025-03-29T22:05:00", 600),
+                    // Before the start of the record
+                    level("2025-03-29T21:55:00", 600),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let report = sleep.validate_timeline();
+        assert!(!report.is_valid());
+        assert_eq!(report.out_of_order, [2, 3]);
+        assert_eq!(report.before_start, [3]);
+        assert_eq!(
+            report.overlaps,
+            [
+                (parse("2025-03-29T22:00:00"), parse("2025-03-29T22:05:00")),
+                (parse("2025-03-29T22:05:00"), parse("2025-03-29T22:10:00")),
+            ]
+        );
+        assert_eq!(
+            report.gaps,
+            [
+                (parse("2025-03-29T22:15:00"), parse("2025-03-29T22:30:00")),
+                (parse("2025-03-29T22:40:00"), parse("2025-03-29T23:00:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_awake_is_clipped_to_the_record() {
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+        let level = |date_time: &str, level: SleepLevel, seconds: u32| LevelData {
+            date_time: parse(date_time),
+            level,
+            seconds,
+        };
+        let sleep = SleepData {
+            start_time: parse("2025-03-29T22:00:00"),
+            end_time: parse("2025-03-29T23:00:00"),
+            levels: SleepLevels {
+                data: vec![
+                    // Starts 10 minutes before the record
+                    level("2025-03-29T21:50:00", SleepLevel::Wake, 900),
+                    level("2025-03-29T22:05:00", SleepLevel::Light, 3000),
+                    // Overlaps the previous entry and runs past the end of the record
+                    level("2025-03-29T22:50:00", SleepLevel::Wake, 1200),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(sleep.validate_timeline().before_start, [0]);
+        assert_eq!(
+            sleep.get_total_duration_awake_during_sleep(),
+            chrono::Duration::minutes(10)
+        );
+    }
+
+    #[test]
+    fn test_computed_efficiency() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+
+        // 23490 seconds asleep excluding the final unknown hour, 32250 seconds in bed
+        assert_eq!(sleep.efficiency, Some(90));
+        let efficiency = sleep.computed_efficiency().unwrap();
+        assert!((efficiency - 100.0 * 23490.0 / 32250.0).abs() < 1e-3);
+
+        assert_eq!(SleepData::default().computed_efficiency(), None);
+    }
+
+    #[test]
+    fn test_unknown_log_and_sleep_types() {
+        let log_type: SleepLogType = serde_json::from_str(r#""ondemand""#).unwrap();
+        assert_eq!(log_type, SleepLogType::Other("ondemand".to_string()));
+        assert_eq!(log_type.as_str(), "ondemand");
+
+        let sleep_type: SleepType = serde_json::from_str(r#""hybrid""#).unwrap();
+        assert_eq!(sleep_type, SleepType::Other("hybrid".to_string()));
+    }
+
+    #[test]
+    fn test_parse_unknown_sleep_level() {
+        let level: SleepLevel = serde_json::from_str(r#""drowsy""#).expect("Failed to parse JSON");
+        assert_eq!(level, SleepLevel::Other("drowsy".to_string()));
+        assert!(level.is_sleep());
+        assert_eq!(
+            serde_json::to_string(&level).expect("Failed to serialize"),
+            r#""drowsy""#
+        );
+
+        let level: SleepLevel = serde_json::from_str(r#""rem""#).expect("Failed to parse JSON");
+        assert_eq!(level, SleepLevel::Rem);
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let response = real_sleep_response();
+
+        let serialized = serde_json::to_value(&response).expect("Failed to serialize");
+        assert_eq!(serialized["sleep"][0]["dateOfSleep"], "2025-03-30");
+        assert_eq!(serialized["sleep"][0]["logType"], "auto_detected");
+        assert_eq!(serialized["sleep"][0]["type"], "stages");
+        assert_eq!(serialized["sleep"][0]["levels"]["data"][0]["level"], "wake");
+        assert_eq!(
+            serialized["sleep"][0]["levels"]["summary"]["deep"]["count"],
+            3
+        );
+        assert!(serialized["sleep"][0]["levels"]["summary"]["asleep"].is_null());
+        assert_eq!(serialized["summary"]["totalMinutesAsleep"], 391);
+
+        let round_tripped: SleepResponseV1_2 =
+            serde_json::from_value(serialized.clone()).expect("Failed to parse serialized JSON");
+        assert_eq!(
+            serde_json::to_value(&round_tripped).expect("Failed to serialize"),
+            serialized
+        );
+        assert_eq!(round_tripped.sleep[0].levels.data.len(), 23);
+        assert_eq!(round_tripped.sleep[0].levels.short_data.len(), 18);
+        assert_eq!(round_tripped.sleep[0].end_time, response.sleep[0].end_time);
+        assert_eq!(round_tripped, response);
+    }
+
+    #[test]
+    fn test_sleep_onset_latency() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+
+        // Fitbit reports 0 minutes to fall asleep for this auto-detected record
+        assert_eq!(sleep.minutes_to_fall_asleep, 0);
+
+        // First 5 minute run is the light sleep at 22:27:30
+        assert_eq!(
+            sleep.sleep_onset_latency(&SleepAnalysisConfig::default()),
+            Some(chrono::Duration::minutes(16))
+        );
+        // First 10 minute run is the light sleep at 22:58:00
+        let config = SleepAnalysisConfig {
+            min_sustained_sleep: chrono::Duration::minutes(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            sleep.sleep_onset_latency(&config),
+            Some(chrono::Duration::seconds(2790))
+        );
+        let config = SleepAnalysisConfig {
+            min_sustained_sleep: chrono::Duration::hours(12),
+            ..Default::default()
+        };
+        assert_eq!(sleep.sleep_onset_latency(&config), None);
+    }
+
+    #[test]
+    fn test_compare_to_thirty_day_avg() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+
+        let comparisons = sleep.levels.summary.compare_to_thirty_day_avg();
+        let levels: Vec<SleepLevel> = comparisons.iter().map(|c| c.level.clone()).collect();
+        assert_eq!(
+            levels,
+            [
+                SleepLevel::Deep,
+                SleepLevel::Light,
+                SleepLevel::Rem,
+                SleepLevel::Wake
+            ]
+        );
+
+        let rem = &comparisons[2];
+        assert_eq!(rem.minutes, 4);
+        assert_eq!(rem.delta_minutes, -63.0);
+        assert!((rem.relative_delta + 63.0 / 67.0).abs() < 1e-6);
+        assert_eq!(rem.to_string(), "REM 94% below your 30-day average");
+        assert_eq!(
+            comparisons[1].to_string(),
+            "Light 23% above your 30-day average"
+        );
+
+        // Classic records have no 30-day averages
+        assert!(
+            LevelsSummary::default()
+                .compare_to_thirty_day_avg()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_duration_accessors() {
+        let summary = SleepSummary {
+            stages: StagesSummary {
+                deep: 62,
+                light: 245,
+                rem: 88,
+                wake: 41,
+            },
+            total_minutes_asleep: 395,
+            total_sleep_records: 1,
+            total_time_in_bed: 436,
+        };
+
+        assert_eq!(summary.total_time_asleep(), chrono::Duration::minutes(395));
+        assert_eq!(
+            summary.total_time_in_bed_duration(),
+            chrono::Duration::minutes(436)
+        );
+        assert_eq!(
+            summary.stages.duration(&SleepLevel::Rem),
+            Some(chrono::Duration::minutes(88))
+        );
+        assert_eq!(summary.stages.duration(&SleepLevel::Restless), None);
+
+        let level = LevelSummary {
+            count: 3,
+            minutes: 62,
+            thirty_day_avg_minutes: 70.5,
+        };
+        assert_eq!(level.duration(), chrono::Duration::minutes(62));
+        assert_eq!(
+            level.thirty_day_avg_duration(),
+            chrono::Duration::seconds(4230)
+        );
+    }
+
+    #[test]
+    fn test_fall_asleep_detection_ignores_short_wakes() {
+        let response = real_sleep_response();
+        let sleep = response.main_sleep().unwrap();
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+
+        // Bridging the 5 minute wakes lets the fragmented light sleep from 22:20:00 reach
+        // 10 minutes
+        let config = SleepAnalysisConfig {
+            min_sustained_sleep: chrono::Duration::minutes(10),
+            ignore_short_wakes: Some(chrono::Duration::minutes(5)),
+        };
+        assert_eq!(
+            sleep.get_time_fell_asleep_with(&config),
+            Some(parse("2025-03-29T22:20:00"))
+        );
+
+        let config = SleepAnalysisConfig {
+            ignore_short_wakes: Some(chrono::Duration::minutes(4)),
+            ..config
+        };
+        assert_eq!(
+            sleep.get_time_fell_asleep_with(&config),
+            Some(parse("2025-03-29T22:58:00"))
+        );
+    }
+
+    #[test]
+    fn test_fall_asleep_single_interval_vs_run() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 29)
+            .unwrap()
+            .and_hms_opt(23, 0, 0)
+            .unwrap();
+        let level = |minutes: i64, level: SleepLevel, seconds: u32| LevelData {
+            date_time: start + chrono::Duration::minutes(minutes),
+            level,
+            seconds,
+        };
+        let sleep = SleepData {
+            levels: SleepLevels {
+                data: vec![
+                    level(0, SleepLevel::Light, 180),
+                    level(3, SleepLevel::Deep, 180),
+                    level(6, SleepLevel::Wake, 60),
+                    level(7, SleepLevel::Light, 600),
+                ],
+                ..Default::default()
+            },
+            start_time: start,
+            ..Default::default()
+        };
+
+        // No single interval before 23:07 is longer than 5 minutes, but the light and deep
+        // sleep from 23:00 add up to 6 minutes
+        assert_eq!(sleep.get_time_fell_asleep(), Some(start));
+        assert_eq!(
+            sleep.get_time_fell_asleep_with(&SleepAnalysisConfig::default()),
+            Some(start)
+        );
+        assert_eq!(
+            sleep.get_time_fell_asleep_with(&SleepAnalysisConfig {
+                min_sustained_sleep: chrono::Duration::minutes(7),
+                ..SleepAnalysisConfig::default()
+            }),
+            Some(start + chrono::Duration::minutes(7))
+        );
+    }
+
+    #[test]
+    fn test_log_sleep_form() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 29)
+            .unwrap()
+            .and_hms_opt(23, 5, 30)
+            .unwrap();
+
+        // Seconds are dropped from the start time, but not from the duration
+        assert_eq!(
+            log_sleep_form(
+                start,
+                chrono::Duration::minutes(450) + chrono::Duration::seconds(15)
+            ),
+            [
+                ("startTime", "23:05".to_string()),
+                ("duration", "27015000".to_string()),
+                ("date", "2025-03-29".to_string()),
+            ]
+        );
+    }
 }
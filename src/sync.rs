@@ -0,0 +1,321 @@
+//! Incremental sync driven by Subscriptions API notifications.
+//!
+//! A [`SyncEngine`] holds a client per subscribed user. For each batch of notifications it
+//! fetches only the collections and dates that changed, and returns them as typed
+//! [`ChangeEvent`]s, e.g. to update a local mirror of the users' data.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fitbit_rs::FitbitClient;
+//! use fitbit_rs::sync::{Change, SyncEngine};
+//! use fitbit_rs::webhook::parse_notifications;
+//!
+//! let mut engine = SyncEngine::new();
+//! engine.add_user("228S74", FitbitClient::new("users_access_token".to_string()));
+//!
+//! # let body: &[u8] = b"[]";
+//! // `body` is the verified body of a subscriber request
+//! let outcome = engine.process(&parse_notifications(body)?);
+//! for event in outcome.events {
+//!     if let Change::Sleep { date, sleep } = event.change {
+//!         println!("{} has {} sleep logs on {}", event.owner_id, sleep.sleep.len(), date);
+//!     }
+//! }
+//! for failure in outcome.failures {
+//!     eprintln!("Failed to sync {:?}: {}", failure.notification, failure.error);
+//! }
+//! # Ok::<(), fitbit_rs::FitbitError>(())
+//! ```
+
+use crate::activity_summary::ActivitySummaryResponse;
+use crate::error::FitbitError;
+use crate::fitbit_client::FitbitClientTrait;
+use crate::food::FoodLogResponse;
+use crate::sleep::SleepResponseV1_2;
+use crate::water::WaterLogs;
+use crate::webhook::{CollectionType, Notification};
+use crate::weight::WeightLog;
+use chrono::NaiveDate;
+use std::collections::{HashMap, HashSet};
+
+/// A change of a user's data
+#[derive(Debug)]
+pub struct ChangeEvent {
+    /// The encoded id of the user whose data changed
+    pub owner_id: String,
+    pub change: Change,
+}
+
+/// The changed data, as fetched after the notification
+#[derive(Debug)]
+pub enum Change {
+    Activities {
+        date: NaiveDate,
+        summary: ActivitySummaryResponse,
+    },
+    Body {
+        date: NaiveDate,
+        weight_logs: Vec<WeightLog>,
+    },
+    Foods {
+        date: NaiveDate,
+        food_logs: FoodLogResponse,
+        water_logs: WaterLogs,
+    },
+    Sleep {
+        date: NaiveDate,
+        sleep: SleepResponseV1_2,
+    },
+    /// The user revoked the application's access; the user's client was removed
+    AccessRevoked,
+    /// The user deleted their account; the user's client was removed
+    UserDeleted,
+}
+
+/// A notification whose changes could not be fetched
+#[derive(Debug)]
+pub struct SyncFailure {
+    pub notification: Notification,
+    pub error: FitbitError,
+}
+
+/// The result of processing a batch of notifications
+#[derive(Debug, Default)]
+pub struct SyncOutcome {
+    /// The changes in the order of their first notification
+    pub events: Vec<ChangeEvent>,
+    pub failures: Vec<SyncFailure>,
+}
+
+/// Fetches the data that changed according to notifications
+///
+/// Duplicate notifications in a batch are fetched once, and notifications for collections
+/// this crate doesn't know are ignored.
+#[derive(Debug)]
+pub struct SyncEngine<C> {
+    clients: HashMap<String, C>,
+}
+
+impl<C> Default for SyncEngine<C> {
+    fn default() -> Self {
+        Self {
+            clients: HashMap::new(),
+        }
+    }
+}
+
+impl<C: FitbitClientTrait> SyncEngine<C> {
+    /// Creates an engine without users
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a user, replacing the user's previous client
+    ///
+    /// # Arguments
+    ///
+    /// * `owner_id` - The encoded id of the user, as used in notifications
+    /// * `client` - A client authorized by the user
+    pub fn add_user(&mut self, owner_id: impl Into<String>, client: C) {
+        self.clients.insert(owner_id.into(), client);
+    }
+
+    /// Removes a user
+    ///
+    /// # Returns
+    ///
+    /// The user's client, or `None` if the user was not added
+    pub fn remove_user(&mut self, owner_id: &str) -> Option<C> {
+        self.clients.remove(owner_id)
+    }
+
+    /// Fetches the changes of a batch of notifications
+    ///
+    /// A failed fetch doesn't stop the batch; it is reported with its notification so it
+    /// can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `notifications` - The notifications of a subscriber request
+    ///
+    /// # Returns
+    ///
+    /// The fetched changes and the notifications that failed
+    pub fn process(&mut self, notifications: &[Notification]) -> SyncOutcome {
+        let mut outcome = SyncOutcome::default();
+        let mut seen = HashSet::new();
+
+        for notification in notifications {
+            let key = (
+                notification.owner_id.as_str(),
+                notification.collection_type,
+                notification.date,
+            );
+            if !seen.insert(key) {
+                continue;
+            }
+
+            match self.fetch_change(notification) {
+                Ok(Some(change)) => outcome.events.push(ChangeEvent {
+                    owner_id: notification.owner_id.clone(),
+                    change,
+                }),
+                Ok(None) => {}
+                Err(error) => outcome.failures.push(SyncFailure {
+                    notification: notification.clone(),
+                    error,
+                }),
+            }
+        }
+
+        outcome
+    }
+
+    /// Fetches the change of a single notification
+    ///
+    /// # Returns
+    ///
+    /// The change, or `None` for collections this crate doesn't know
+    fn fetch_change(&mut self, notification: &Notification) -> Result<Option<Change>, FitbitError> {
+        let change = match notification.collection_type {
+            CollectionType::Activities => {
+                let (client, date) = self.client_and_date(notification)?;
+                Change::Activities {
+                    date,
+                    summary: client.fetch_activity_summary(date)?,
+                }
+            }
+            CollectionType::Body => {
+                let (client, date) = self.client_and_date(notification)?;
+                Change::Body {
+                    date,
+                    weight_logs: client.fetch_weight_logs(date)?,
+                }
+            }
+            CollectionType::Foods => {
+                let (client, date) = self.client_and_date(notification)?;
+                Change::Foods {
+                    date,
+                    food_logs: client.fetch_food_logs(date)?,
+                    water_logs: client.fetch_water_logs(date)?,
+                }
+            }
+            CollectionType::Sleep => {
+                let (client, date) = self.client_and_date(notification)?;
+                Change::Sleep {
+                    date,
+                    sleep: client.fetch_sleep_data(date)?,
+                }
+            }
+            CollectionType::UserRevokedAccess => {
+                self.clients.remove(&notification.owner_id);
+                Change::AccessRevoked
+            }
+            CollectionType::DeleteUser => {
+                self.clients.remove(&notification.owner_id);
+                Change::UserDeleted
+            }
+            CollectionType::Other => return Ok(None),
+        };
+
+        Ok(Some(change))
+    }
+
+    /// Returns the client of the notification's user and the date that changed
+    fn client_and_date(&self, notification: &Notification) -> Result<(&C, NaiveDate), FitbitError> {
+        let client = self.clients.get(&notification.owner_id).ok_or_else(|| {
+            FitbitError::ConfigurationError(format!("no client for user {}", notification.owner_id))
+        })?;
+        let date = notification.date.ok_or_else(|| {
+            FitbitError::InvalidArgument(format!(
+                "{:?} notification without a date",
+                notification.collection_type
+            ))
+        })?;
+
+        Ok((client, date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitbit_client::MockFitbitClientTrait;
+
+    fn notification(
+        collection_type: CollectionType,
+        owner_id: &str,
+        date: Option<NaiveDate>,
+    ) -> Notification {
+        Notification {
+            collection_type,
+            date,
+            owner_id: owner_id.to_string(),
+            owner_type: "user".to_string(),
+            subscription_id: "1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_process_fetches_changed_dates_once() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mut client = MockFitbitClientTrait::new();
+        client
+            .expect_fetch_sleep_data()
+            .times(1)
+            .returning(|_| Ok(SleepResponseV1_2::default()));
+        client
+            .expect_fetch_weight_logs()
+            .times(1)
+            .returning(|_| Err(FitbitError::RateLimitExceeded(60)));
+
+        let mut engine = SyncEngine::new();
+        engine.add_user("A", client);
+
+        let outcome = engine.process(&[
+            notification(CollectionType::Sleep, "A", Some(date)),
+            notification(CollectionType::Sleep, "A", Some(date)),
+            notification(CollectionType::Body, "A", Some(date)),
+            notification(CollectionType::Other, "A", Some(date)),
+            notification(CollectionType::Sleep, "B", Some(date)),
+        ]);
+
+        assert_eq!(outcome.events.len(), 1);
+        assert_eq!(outcome.events[0].owner_id, "A");
+        assert!(matches!(outcome.events[0].change, Change::Sleep { date: d, .. } if d == date));
+
+        assert_eq!(outcome.failures.len(), 2);
+        assert_eq!(
+            outcome.failures[0].notification.collection_type,
+            CollectionType::Body
+        );
+        assert!(matches!(
+            outcome.failures[0].error,
+            FitbitError::RateLimitExceeded(60)
+        ));
+        assert!(matches!(
+            outcome.failures[1].error,
+            FitbitError::ConfigurationError(_)
+        ));
+    }
+
+    #[test]
+    fn test_process_removes_revoked_users() {
+        let mut engine = SyncEngine::new();
+        engine.add_user("A", MockFitbitClientTrait::new());
+        engine.add_user("B", MockFitbitClientTrait::new());
+
+        let outcome = engine.process(&[
+            notification(CollectionType::UserRevokedAccess, "A", None),
+            notification(CollectionType::DeleteUser, "B", None),
+        ]);
+
+        assert_eq!(outcome.events.len(), 2);
+        assert!(matches!(outcome.events[0].change, Change::AccessRevoked));
+        assert!(matches!(outcome.events[1].change, Change::UserDeleted));
+        assert!(outcome.failures.is_empty());
+        assert!(engine.remove_user("A").is_none());
+        assert!(engine.remove_user("B").is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! Minimal parsing of TCX (Training Center XML) documents.
+//!
+//! Fitbit exports exercises recorded with GPS as TCX. Only the track points are extracted;
+//! use a full XML parser on the raw document for anything else.
+
+use crate::error::FitbitError;
+use chrono::{DateTime, FixedOffset};
+
+/// The recorded track of an exercise
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TcxTrack {
+    /// Track points in document order, which is chronological for Fitbit exports
+    pub points: Vec<TrackPoint>,
+}
+
+/// A single sample of a TCX track
+///
+/// Every field except `time` is only present if it was recorded for this sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackPoint {
+    pub time: DateTime<FixedOffset>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude_meters: Option<f64>,
+    /// Distance covered since the start of the exercise
+    pub distance_meters: Option<f64>,
+    pub heart_rate_bpm: Option<u32>,
+}
+
+impl TcxTrack {
+    /// Parses the track points of a TCX document
+    ///
+    /// # Arguments
+    ///
+    /// * `tcx` - The TCX document, e.g. as returned by
+    ///   [`fetch_activity_tcx`](crate::FitbitClientTrait::fetch_activity_tcx)
+    ///
+    /// # Returns
+    ///
+    /// The track, or an error if a track point has a missing or invalid value
+    pub fn parse(tcx: &str) -> Result<Self, FitbitError> {
+        let points = elements(tcx, "Trackpoint")
+            .map(TrackPoint::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(TcxTrack { points })
+    }
+
+    /// Returns the points that have a GPS position
+    pub fn positions(&self) -> impl Iterator<Item = &TrackPoint> {
+        self.points
+            .iter()
+            .filter(|point| point.latitude.is_some() && point.longitude.is_some())
+    }
+}
+
+impl TrackPoint {
+    fn parse(trackpoint: &str) -> Result<Self, FitbitError> {
+        let time = elements(trackpoint, "Time")
+            .next()
+            .ok_or_else(|| FitbitError::TcxError("track point without time".to_string()))?;
+        let time = DateTime::parse_from_rfc3339(time.trim())
+            .map_err(|e| FitbitError::TcxError(format!("invalid time {}: {}", time, e)))?;
+        let heart_rate = elements(trackpoint, "HeartRateBpm")
+            .next()
+            .map(|heart_rate| parse_element(heart_rate, "Value"))
+            .transpose()?
+            .flatten();
+
+        Ok(TrackPoint {
+            time,
+            latitude: parse_element(trackpoint, "LatitudeDegrees")?,
+            longitude: parse_element(trackpoint, "LongitudeDegrees")?,
+            altitude_meters: parse_element(trackpoint, "AltitudeMeters")?,
+            distance_meters: parse_element(trackpoint, "DistanceMeters")?,
+            heart_rate_bpm: heart_rate,
+        })
+    }
+}
+
+/// Returns the contents of each `<tag>...</tag>` element in `xml`
+///
+/// Attributes on the opening tag are skipped. Nested elements of the same name are not
+/// supported, which TCX does not use.
+fn elements<'a>(xml: &'a str, tag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut rest = xml;
+
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find(&open)?;
+            let after_name = &rest[start + open.len()..];
+            // Skip elements whose name only starts with `tag`, e.g. `<TimeZone>` for `<Time`
+            if !after_name.starts_with(['>', ' ', '\t', '\r', '\n']) {
+                rest = after_name;
+                continue;
+            }
+
+            let content_start = after_name.find('>')? + 1;
+            let content = &after_name[content_start..];
+            let end = content.find(&close)?;
+            rest = &content[end + close.len()..];
+            return Some(&content[..end]);
+        }
+    })
+}
+
+/// Parses the text of the first `<tag>` element in `xml`, if there is one
+fn parse_element<T>(xml: &str, tag: &str) -> Result<Option<T>, FitbitError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    elements(xml, tag)
+        .next()
+        .map(|text| {
+            text.trim()
+                .parse()
+                .map_err(|e| FitbitError::TcxError(format!("invalid {} {}: {}", tag, text, e)))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tcx_track() {
+        let tcx = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<TrainingCenterDatabase xmlns="http://www.garmin.com/xmlschemas/TrainingCenterDatabase/v2">
+  <Activities>
+    <Activity Sport="Running">
+      <Id>2019-01-03T12:08:00.000-08:00</Id>
+      <Lap StartTime="2019-01-03T12:08:00.000-08:00">
+        <TotalTimeSeconds>1536.0</TotalTimeSeconds>
+        <Track>
+          <Trackpoint>
+            <Time>2019-01-03T12:08:00.000-08:00</Time>
+            <Position>
+              <LatitudeDegrees>37.7749</LatitudeDegrees>
+              <LongitudeDegrees>-122.4194</LongitudeDegrees>
+            </Position>
+            <AltitudeMeters>16.2</AltitudeMeters>
+            <DistanceMeters>0.0</DistanceMeters>
+            <HeartRateBpm>
+              <Value>92</Value>
+            </HeartRateBpm>
+          </Trackpoint>
+          <Trackpoint>
+            <Time>2019-01-03T12:08:05.000-08:00</Time>
+            <DistanceMeters>12.5</DistanceMeters>
+          </Trackpoint>
+        </Track>
+      </Lap>
+    </Activity>
+  </Activities>
+</TrainingCenterDatabase>"#;
+
+        let track = TcxTrack::parse(tcx).expect("Failed to parse TCX");
+
+        assert_eq!(track.points.len(), 2);
+        assert_eq!(
+            track.points[0],
+            TrackPoint {
+                time: DateTime::parse_from_rfc3339("2019-01-03T12:08:00-08:00").unwrap(),
+                latitude: Some(37.7749),
+                longitude: Some(-122.4194),
+                altitude_meters: Some(16.2),
+                distance_meters: Some(0.0),
+                heart_rate_bpm: Some(92),
+            }
+        );
+        assert_eq!(track.points[1].distance_meters, Some(12.5));
+        assert_eq!(track.points[1].heart_rate_bpm, None);
+        assert_eq!(track.positions().count(), 1);
+    }
+
+    #[test]
+    fn test_parse_tcx_rejects_invalid_values() {
+        let tcx = "<Trackpoint><Time>2019-01-03T12:08:00Z</Time>\
+                   <HeartRateBpm><Value>fast</Value></HeartRateBpm></Trackpoint>";
+
+        assert!(TcxTrack::parse(tcx).is_err());
+    }
+}
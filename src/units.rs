@@ -0,0 +1,479 @@
+//! Measurement units of API responses.
+//!
+//! Fitbit reports measurements in the unit system selected by the `Accept-Language`
+//! header of the request, not in the units configured in the user's profile. The client
+//! sends the header for its [`UnitSystem`], and responses carry the unit system they were
+//! requested with so their values can be converted without guessing. [`Units`] converts
+//! them into the units the user prefers according to their profile, or into SI units.
+
+use crate::profile::UserProfile;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const KILOMETERS_PER_MILE: f64 = 1.609344;
+
+const MILLILITERS_PER_FLUID_OUNCE: f64 = 29.5735295625;
+
+const MILLILITERS_PER_CUP: f64 = 236.5882365;
+
+const KILOGRAMS_PER_POUND: f64 = 0.45359237;
+
+const KILOGRAMS_PER_STONE: f64 = 6.35029318;
+
+/// The unit system the API reports measurements in
+///
+/// In the user profile, unit preferences are named like the `Accept-Language` value of
+/// the unit system, or `METRIC`; unknown values are read as metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum UnitSystem {
+    /// Metric units; used when no `Accept-Language` header is sent
+    #[default]
+    Metric,
+    /// US units (`en_US`), e.g. miles and feet
+    Us,
+    /// UK units (`en_GB`); distances are still in kilometers
+    Uk,
+}
+
+impl From<String> for UnitSystem {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "en_US" => UnitSystem::Us,
+            "en_GB" => UnitSystem::Uk,
+            _ => UnitSystem::Metric,
+        }
+    }
+}
+
+impl From<UnitSystem> for String {
+    fn from(unit_system: UnitSystem) -> Self {
+        unit_system
+            .accept_language()
+            .unwrap_or("METRIC")
+            .to_string()
+    }
+}
+
+impl UnitSystem {
+    /// Returns the `Accept-Language` header value that selects this unit system, or `None`
+    /// if no header is needed
+    pub fn accept_language(&self) -> Option<&'static str> {
+        match self {
+            UnitSystem::Metric => None,
+            UnitSystem::Us => Some("en_US"),
+            UnitSystem::Uk => Some("en_GB"),
+        }
+    }
+
+    /// Returns the unit distances are reported in
+    pub fn distance_unit(&self) -> DistanceUnit {
+        match self {
+            UnitSystem::Metric | UnitSystem::Uk => DistanceUnit::Kilometers,
+            UnitSystem::Us => DistanceUnit::Miles,
+        }
+    }
+
+    /// Returns the unit weights are reported in
+    pub fn weight_unit(&self) -> WeightUnit {
+        match self {
+            UnitSystem::Metric => WeightUnit::Kilograms,
+            UnitSystem::Us => WeightUnit::Pounds,
+            UnitSystem::Uk => WeightUnit::Stone,
+        }
+    }
+
+    /// Returns the unit water is reported in
+    pub fn water_unit(&self) -> VolumeUnit {
+        match self {
+            UnitSystem::Metric | UnitSystem::Uk => VolumeUnit::Milliliters,
+            UnitSystem::Us => VolumeUnit::FluidOunces,
+        }
+    }
+}
+
+/// A unit of distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DistanceUnit {
+    Kilometers,
+    Miles,
+}
+
+impl DistanceUnit {
+    /// Returns the abbreviation of the unit, e.g. `km`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            DistanceUnit::Kilometers => "km",
+            DistanceUnit::Miles => "mi",
+        }
+    }
+}
+
+/// A distance with an explicit unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistanceValue {
+    pub value: f64,
+    pub unit: DistanceUnit,
+}
+
+impl DistanceValue {
+    /// Creates a distance in the given unit
+    pub fn new(value: f64, unit: DistanceUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Returns the distance in kilometers
+    pub fn kilometers(&self) -> f64 {
+        match self.unit {
+            DistanceUnit::Kilometers => self.value,
+            DistanceUnit::Miles => self.value * KILOMETERS_PER_MILE,
+        }
+    }
+
+    /// Returns the distance in miles
+    pub fn miles(&self) -> f64 {
+        match self.unit {
+            DistanceUnit::Kilometers => self.value / KILOMETERS_PER_MILE,
+            DistanceUnit::Miles => self.value,
+        }
+    }
+
+    /// Returns the distance converted to the given unit
+    pub fn to(&self, unit: DistanceUnit) -> Self {
+        let value = match unit {
+            DistanceUnit::Kilometers => self.kilometers(),
+            DistanceUnit::Miles => self.miles(),
+        };
+
+        Self { value, unit }
+    }
+}
+
+impl fmt::Display for DistanceValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.value, self.unit.symbol())
+    }
+}
+
+/// A unit of volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VolumeUnit {
+    Milliliters,
+    /// US fluid ounces
+    FluidOunces,
+    /// US cups
+    Cups,
+}
+
+impl VolumeUnit {
+    /// Returns the abbreviation of the unit as accepted by the API, e.g. `ml`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            VolumeUnit::Milliliters => "ml",
+            VolumeUnit::FluidOunces => "fl oz",
+            VolumeUnit::Cups => "cup",
+        }
+    }
+
+    fn milliliters_per_unit(&self) -> f64 {
+        match self {
+            VolumeUnit::Milliliters => 1.0,
+            VolumeUnit::FluidOunces => MILLILITERS_PER_FLUID_OUNCE,
+            VolumeUnit::Cups => MILLILITERS_PER_CUP,
+        }
+    }
+}
+
+/// A volume with an explicit unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeValue {
+    pub value: f64,
+    pub unit: VolumeUnit,
+}
+
+impl VolumeValue {
+    /// Creates a volume in the given unit
+    pub fn new(value: f64, unit: VolumeUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Returns the volume in milliliters
+    pub fn milliliters(&self) -> f64 {
+        self.value * self.unit.milliliters_per_unit()
+    }
+
+    /// Returns the volume converted to the given unit
+    pub fn to(&self, unit: VolumeUnit) -> Self {
+        Self {
+            value: self.milliliters() / unit.milliliters_per_unit(),
+            unit,
+        }
+    }
+}
+
+impl fmt::Display for VolumeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0} {}", self.value, self.unit.symbol())
+    }
+}
+
+/// A unit of weight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeightUnit {
+    Kilograms,
+    Pounds,
+    Stone,
+}
+
+impl WeightUnit {
+    /// Returns the abbreviation of the unit, e.g. `kg`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            WeightUnit::Kilograms => "kg",
+            WeightUnit::Pounds => "lb",
+            WeightUnit::Stone => "st",
+        }
+    }
+
+    fn kilograms_per_unit(&self) -> f64 {
+        match self {
+            WeightUnit::Kilograms => 1.0,
+            WeightUnit::Pounds => KILOGRAMS_PER_POUND,
+            WeightUnit::Stone => KILOGRAMS_PER_STONE,
+        }
+    }
+}
+
+/// A weight with an explicit unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightValue {
+    pub value: f64,
+    pub unit: WeightUnit,
+}
+
+impl WeightValue {
+    /// Creates a weight in the given unit
+    pub fn new(value: f64, unit: WeightUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Returns the weight in kilograms
+    pub fn kilograms(&self) -> f64 {
+        self.value * self.unit.kilograms_per_unit()
+    }
+
+    /// Returns the weight converted to the given unit
+    pub fn to(&self, unit: WeightUnit) -> Self {
+        Self {
+            value: self.kilograms() / unit.kilograms_per_unit(),
+            unit,
+        }
+    }
+}
+
+impl fmt::Display for WeightValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} {}", self.value, self.unit.symbol())
+    }
+}
+
+/// The units to present measurements in
+///
+/// # Example
+///
+/// ```no_run
+/// use fitbit_rs::{FitbitClient, FitbitClientTrait, Units};
+///
+/// let client = FitbitClient::new("your_access_token".to_string());
+/// let units = Units::from_profile(&client.fetch_profile()?.user);
+///
+/// let today = chrono::Local::now().date_naive();
+/// let summary = client.fetch_activity_summary(today)?;
+/// println!("Distance: {}", units.distance(summary.total_distance()));
+/// # Ok::<(), fitbit_rs::FitbitError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Units {
+    pub distance: DistanceUnit,
+    pub weight: WeightUnit,
+    pub water: VolumeUnit,
+}
+
+impl Default for Units {
+    /// Returns SI units
+    fn default() -> Self {
+        Self::si()
+    }
+}
+
+impl Units {
+    /// Returns SI units: kilometers, kilograms and milliliters
+    pub fn si() -> Self {
+        Self::of(UnitSystem::Metric)
+    }
+
+    /// Returns the units a unit system reports measurements in
+    pub fn of(unit_system: UnitSystem) -> Self {
+        Self {
+            distance: unit_system.distance_unit(),
+            weight: unit_system.weight_unit(),
+            water: unit_system.water_unit(),
+        }
+    }
+
+    /// Returns the units the user prefers for each kind of measurement
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The user's profile
+    pub fn from_profile(profile: &UserProfile) -> Self {
+        Self {
+            distance: profile.distance_unit.distance_unit(),
+            weight: profile.weight_unit.weight_unit(),
+            water: profile.water_unit.water_unit(),
+        }
+    }
+
+    /// Returns the distance in the preferred distance unit
+    pub fn distance(&self, distance: DistanceValue) -> DistanceValue {
+        distance.to(self.distance)
+    }
+
+    /// Returns the weight in the preferred weight unit
+    pub fn weight(&self, weight: WeightValue) -> WeightValue {
+        weight.to(self.weight)
+    }
+
+    /// Returns the volume in the preferred water unit
+    pub fn water(&self, volume: VolumeValue) -> VolumeValue {
+        volume.to(self.water)
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<DistanceValue> for uom::si::f64::Length {
+    fn from(distance: DistanceValue) -> Self {
+        uom::si::f64::Length::new::<uom::si::length::kilometer>(distance.kilometers())
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<WeightValue> for uom::si::f64::Mass {
+    fn from(weight: WeightValue) -> Self {
+        uom::si::f64::Mass::new::<uom::si::mass::kilogram>(weight.kilograms())
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<VolumeValue> for uom::si::f64::Volume {
+    fn from(volume: VolumeValue) -> Self {
+        uom::si::f64::Volume::new::<uom::si::volume::milliliter>(volume.milliliters())
+    }
+}
+
+/// Returns `kilocalories` as a dimensioned quantity
+#[cfg(feature = "uom")]
+pub(crate) fn energy(kilocalories: f64) -> uom::si::f64::Energy {
+    uom::si::f64::Energy::new::<uom::si::energy::kilocalorie>(kilocalories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_conversions() {
+        let run = DistanceValue::new(5.0, DistanceUnit::Miles);
+
+        assert_eq!(run.kilometers(), 8.04672);
+        assert_eq!(run.miles(), 5.0);
+        assert_eq!(
+            run.to(DistanceUnit::Kilometers).unit,
+            DistanceUnit::Kilometers
+        );
+        assert!((run.to(DistanceUnit::Kilometers).miles() - 5.0).abs() < 1e-9);
+        assert_eq!(run.to_string(), "5.00 mi");
+    }
+
+    #[cfg(feature = "uom")]
+    #[test]
+    fn test_uom_quantities() {
+        use uom::si::f64::{Length, Mass, Volume};
+        use uom::si::{length::kilometer, mass::kilogram, volume::milliliter};
+
+        let run = Length::from(DistanceValue::new(5.0, DistanceUnit::Miles));
+        assert!((run.get::<kilometer>() - 8.04672).abs() < 1e-9);
+
+        let weight = Mass::from(WeightValue::new(11.0, WeightUnit::Stone));
+        assert!((weight.get::<kilogram>() - 69.85322498).abs() < 1e-6);
+
+        let water = Volume::from(VolumeValue::new(2.0, VolumeUnit::Cups));
+        assert!((water.get::<milliliter>() - 473.176473).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unit_system_headers() {
+        assert_eq!(UnitSystem::Metric.accept_language(), None);
+        assert_eq!(UnitSystem::Us.accept_language(), Some("en_US"));
+        assert_eq!(UnitSystem::Uk.distance_unit(), DistanceUnit::Kilometers);
+        assert_eq!(UnitSystem::Us.distance_unit(), DistanceUnit::Miles);
+        assert_eq!(UnitSystem::Uk.water_unit(), VolumeUnit::Milliliters);
+        assert_eq!(UnitSystem::Us.water_unit(), VolumeUnit::FluidOunces);
+    }
+
+    #[test]
+    fn test_weight_conversions() {
+        let weight = WeightValue::new(70.0, WeightUnit::Kilograms);
+
+        assert!((weight.to(WeightUnit::Pounds).value - 154.3236).abs() < 1e-4);
+        assert!((weight.to(WeightUnit::Stone).value - 11.0231).abs() < 1e-4);
+        assert!((weight.to(WeightUnit::Stone).kilograms() - 70.0).abs() < 1e-9);
+        assert_eq!(weight.to_string(), "70.0 kg");
+    }
+
+    #[test]
+    fn test_units_from_profile() {
+        let profile: UserProfile = serde_json::from_str(
+            r#"{"encodedId": "ABC123", "displayName": "Jane D.", "timezone": "Europe/London",
+                "offsetFromUTCMillis": 0, "distanceUnit": "en_GB", "weightUnit": "en_GB",
+                "waterUnit": "en_US"}"#,
+        )
+        .expect("Failed to parse JSON");
+        let units = Units::from_profile(&profile);
+
+        assert_eq!(units.distance, DistanceUnit::Kilometers);
+        assert_eq!(units.weight, WeightUnit::Stone);
+        assert_eq!(units.water, VolumeUnit::FluidOunces);
+        assert_eq!(
+            units
+                .water(VolumeValue::new(500.0, VolumeUnit::Milliliters))
+                .unit,
+            VolumeUnit::FluidOunces
+        );
+        assert_eq!(Units::si(), Units::default());
+        assert_eq!(Units::si().weight, WeightUnit::Kilograms);
+    }
+
+    #[test]
+    fn test_parse_unit_system() {
+        let parse = |value: &str| serde_json::from_value::<UnitSystem>(value.into()).unwrap();
+
+        assert_eq!(parse("METRIC"), UnitSystem::Metric);
+        assert_eq!(parse("en_US"), UnitSystem::Us);
+        assert_eq!(parse("en_GB"), UnitSystem::Uk);
+        assert_eq!(parse("de_DE"), UnitSystem::Metric);
+    }
+
+    #[test]
+    fn test_volume_conversions() {
+        let glass = VolumeValue::new(8.0, VolumeUnit::FluidOunces);
+
+        assert!((glass.milliliters() - 236.588).abs() < 1e-3);
+        assert!((glass.to(VolumeUnit::Cups).value - 1.0).abs() < 1e-9);
+        assert_eq!(
+            glass.to(VolumeUnit::Milliliters).unit,
+            VolumeUnit::Milliliters
+        );
+        assert_eq!(glass.to_string(), "8 fl oz");
+    }
+}
@@ -0,0 +1,449 @@
+//! Checks of responses against the API contract.
+//!
+//! Responses that parse fine can still be wrong, e.g. when the API changes the meaning of a
+//! field. [`Validate`] checks the values the crate relies on, such as efficiencies between 0
+//! and 100 and chronological sleep levels, and reports every violation with its path.
+//!
+//! With the `strict` feature, the client validates sleep, activity summary and heart rate
+//! responses before returning them, and unknown fields in these responses fail parsing. If
+//! `extra-fields` is enabled as well, unknown top-level fields are kept in `extra` and fail
+//! validation instead.
+
+use crate::activity_summary::{ActivitySummaryResponse, HeartRateZone};
+use crate::error::FitbitError;
+use crate::heart_rate::HeartRateDay;
+use crate::sleep::{
+    LevelData, SleepData, SleepDataV1, SleepLogListResponse, SleepRangeResponse, SleepResponseV1,
+    SleepResponseV1_2,
+};
+use std::fmt;
+
+/// Minutes in a day, the most any per-day minute count can be
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// A value that violates the API contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Path of the value in the response, e.g. `sleep[0].levels.data[3].seconds`
+    pub path: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.path, self.message)
+    }
+}
+
+/// Responses that can be checked against the API contract
+pub trait Validate {
+    /// Returns every value of the response that violates the API contract
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the response, prefixed to the paths of the issues
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue>;
+
+    /// Returns every value of the response that violates the API contract
+    fn validation_issues(&self) -> Vec<ValidationIssue> {
+        self.validation_issues_at("")
+    }
+
+    /// Checks the response against the API contract
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the response is valid, or `FitbitError::ContractViolation` with every
+    /// violation
+    fn validate(&self) -> Result<(), FitbitError> {
+        let issues = self.validation_issues();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(FitbitError::ContractViolation(issues))
+        }
+    }
+}
+
+/// Joins the path of a response and the name of one of its fields
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+/// Checks that an efficiency is a percentage
+fn efficiency_issues(path: &str, efficiency: Option<u8>) -> Vec<ValidationIssue> {
+    match efficiency {
+        Some(efficiency) if efficiency > 100 => vec![ValidationIssue::new(
+            join(path, "efficiency"),
+            format!("must be at most 100, got {}", efficiency),
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Checks that the levels of a sleep record last and follow each other in time
+fn level_issues(path: &str, levels: &[LevelData]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (index, level) in levels.iter().enumerate() {
+        if level.seconds == 0 {
+            issues.push(ValidationIssue::new(
+                format!("{}[{}].seconds", path, index),
+                "must be positive",
+            ));
+        }
+        if index > 0 && level.date_time < levels[index - 1].date_time {
+            issues.push(ValidationIssue::new(
+                format!("{}[{}].dateTime", path, index),
+                format!(
+                    "must not be before the previous level, got {} after {}",
+                    level.date_time,
+                    levels[index - 1].date_time
+                ),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Checks that heart rate zones have a range and follow each other
+fn zone_issues(path: &str, zones: &[HeartRateZone]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (index, zone) in zones.iter().enumerate() {
+        let zone_path = format!("{}[{}]", path, index);
+        if zone.min > zone.max {
+            issues.push(ValidationIssue::new(
+                format!("{}.min", zone_path),
+                format!("must not exceed max {}, got {}", zone.max, zone.min),
+            ));
+        }
+        if !(0..=MINUTES_PER_DAY).contains(&zone.minutes) {
+            issues.push(ValidationIssue::new(
+                format!("{}.minutes", zone_path),
+                format!(
+                    "must be between 0 and {}, got {}",
+                    MINUTES_PER_DAY, zone.minutes
+                ),
+            ));
+        }
+        if index > 0 && zone.min < zones[index - 1].min {
+            issues.push(ValidationIssue::new(
+                format!("{}.min", zone_path),
+                "must not be below the previous zone",
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Reports the fields of a response that the crate doesn't model
+///
+/// Strict parsing rejects unknown fields, but not when `extra-fields` keeps them, so they
+/// are reported here instead.
+#[cfg(all(feature = "strict", feature = "extra-fields"))]
+fn unknown_field_issues(
+    path: &str,
+    extra: &serde_json::Map<String, serde_json::Value>,
+) -> Vec<ValidationIssue> {
+    extra
+        .keys()
+        .map(|field| ValidationIssue::new(join(path, field), "is not a known field"))
+        .collect()
+}
+
+impl Validate for SleepData {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let mut issues = efficiency_issues(path, self.efficiency);
+        if self.duration == 0 {
+            issues.push(ValidationIssue::new(
+                join(path, "duration"),
+                "must be positive",
+            ));
+        }
+        if self.end_time <= self.start_time {
+            issues.push(ValidationIssue::new(
+                join(path, "endTime"),
+                format!("must be after startTime {}", self.start_time),
+            ));
+        }
+        if self.minutes_asleep > self.time_in_bed {
+            issues.push(ValidationIssue::new(
+                join(path, "minutesAsleep"),
+                format!("must not exceed timeInBed {}", self.time_in_bed),
+            ));
+        }
+        issues.extend(level_issues(&join(path, "levels.data"), &self.levels.data));
+        issues.extend(level_issues(
+            &join(path, "levels.shortData"),
+            &self.levels.short_data,
+        ));
+
+        issues
+    }
+}
+
+impl Validate for SleepDataV1 {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let mut issues = efficiency_issues(path, self.efficiency);
+        if self.duration == 0 {
+            issues.push(ValidationIssue::new(
+                join(path, "duration"),
+                "must be positive",
+            ));
+        }
+        if self.minutes_asleep > self.time_in_bed {
+            issues.push(ValidationIssue::new(
+                join(path, "minutesAsleep"),
+                format!("must not exceed timeInBed {}", self.time_in_bed),
+            ));
+        }
+
+        issues
+    }
+}
+
+impl Validate for SleepResponseV1_2 {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let issues = self.sleep.validation_issues_at(&join(path, "sleep"));
+        #[cfg(all(feature = "strict", feature = "extra-fields"))]
+        let issues = [issues, unknown_field_issues(path, &self.extra)].concat();
+
+        issues
+    }
+}
+
+impl Validate for SleepResponseV1 {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let issues = self.sleep.validation_issues_at(&join(path, "sleep"));
+        #[cfg(all(feature = "strict", feature = "extra-fields"))]
+        let issues = [issues, unknown_field_issues(path, &self.extra)].concat();
+
+        issues
+    }
+}
+
+impl Validate for SleepRangeResponse {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let issues = self.sleep.validation_issues_at(&join(path, "sleep"));
+        #[cfg(all(feature = "strict", feature = "extra-fields"))]
+        let issues = [issues, unknown_field_issues(path, &self.extra)].concat();
+
+        issues
+    }
+}
+
+impl Validate for SleepLogListResponse {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let issues = self.sleep.validation_issues_at(&join(path, "sleep"));
+        #[cfg(all(feature = "strict", feature = "extra-fields"))]
+        let issues = [issues, unknown_field_issues(path, &self.extra)].concat();
+
+        issues
+    }
+}
+
+impl Validate for ActivitySummaryResponse {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let summary = &self.summary;
+        let mut issues = Vec::new();
+        let minutes = [
+            ("sedentaryMinutes", summary.sedentary_minutes),
+            ("lightlyActiveMinutes", summary.lightly_active_minutes),
+            ("fairlyActiveMinutes", summary.fairly_active_minutes),
+            ("veryActiveMinutes", summary.very_active_minutes),
+        ];
+        for (field, value) in minutes {
+            if !(0..=MINUTES_PER_DAY).contains(&value) {
+                issues.push(ValidationIssue::new(
+                    join(path, &format!("summary.{}", field)),
+                    format!("must be between 0 and {}, got {}", MINUTES_PER_DAY, value),
+                ));
+            }
+        }
+        if let Some(rate) = summary.resting_heart_rate.filter(|rate| *rate <= 0) {
+            issues.push(ValidationIssue::new(
+                join(path, "summary.restingHeartRate"),
+                format!("must be positive, got {}", rate),
+            ));
+        }
+        issues.extend(zone_issues(
+            &join(path, "summary.heartRateZones"),
+            &summary.heart_rate_zones,
+        ));
+        #[cfg(all(feature = "strict", feature = "extra-fields"))]
+        issues.extend(unknown_field_issues(path, &self.extra));
+
+        issues
+    }
+}
+
+impl Validate for HeartRateDay {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        let mut issues = zone_issues(&join(path, "heartRateZones"), &self.heart_rate_zones);
+        if self.resting_heart_rate == Some(0) {
+            issues.push(ValidationIssue::new(
+                join(path, "restingHeartRate"),
+                "must be positive",
+            ));
+        }
+
+        issues
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validation_issues_at(&self, path: &str) -> Vec<ValidationIssue> {
+        self.iter()
+            .enumerate()
+            .flat_map(|(index, item)| item.validation_issues_at(&format!("{}[{}]", path, index)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::SleepDataBuilder;
+    use crate::sleep::SleepLevel;
+    use chrono::NaiveDate;
+
+    fn night() -> SleepDataBuilder {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1)
+            .unwrap()
+            .and_hms_opt(23, 0, 0)
+            .unwrap();
+        SleepDataBuilder::new(start)
+            .stage(SleepLevel::Light, 30)
+            .stage(SleepLevel::Deep, 60)
+            .stage(SleepLevel::Wake, 5)
+    }
+
+    #[test]
+    fn test_valid_sleep_has_no_issues() {
+        let response = night().efficiency(95).build_response();
+
+        assert_eq!(response.validation_issues(), Vec::new());
+        assert!(response.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_sleep_issues() {
+        let mut response = night().efficiency(120).build_response();
+        let sleep = &mut response.sleep[0];
+        sleep.minutes_asleep = sleep.time_in_bed + 1;
+        sleep.levels.data.swap(0, 1);
+        sleep.levels.data[2].seconds = 0;
+
+        let paths: Vec<String> = response
+            .validation_issues()
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "sleep[0].efficiency",
+                "sleep[0].minutesAsleep",
+                "sleep[0].levels.data[1].dateTime",
+                "sleep[0].levels.data[2].seconds",
+            ]
+        );
+        assert!(matches!(
+            response.validate(),
+            Err(FitbitError::ContractViolation(issues)) if issues.len() == 4
+        ));
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn test_strict_rejects_unknown_fields() {
+        use crate::sleep::StagesSummary;
+
+        let json_str = r#"{"deep": 60, "light": 200, "rem": 90, "wake": 30, "core": 10}"#;
+
+        assert!(serde_json::from_str::<StagesSummary>(json_str).is_err());
+    }
+
+    #[cfg(all(feature = "strict", feature = "extra-fields"))]
+    #[test]
+    fn test_strict_reports_extra_fields() {
+        let json_str = r#"{
+            "sleep": [],
+            "summary": {"totalMinutesAsleep": 0, "totalSleepRecords": 0, "totalTimeInBed": 0},
+            "sleepScore": 82
+        }"#;
+
+        let response: SleepResponseV1_2 =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(response.extra["sleepScore"], 82);
+        let paths: Vec<String> = response
+            .validation_issues()
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        assert_eq!(paths, ["sleepScore"]);
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn test_strict_accepts_documented_activity_summary() {
+        let json_str = r#"{
+            "activities": [{
+                "activityId": 90013, "activityParentId": 90013, "activityParentName": "Walk",
+                "calories": 204, "description": "Walking less than 2 mph, strolling very slowly",
+                "distance": 3.2, "duration": 2460000, "hasActiveZoneMinutes": true,
+                "hasStartTime": true, "isFavorite": false,
+                "lastModified": "2019-01-01T12:30:00.000Z", "logId": 1154701,
+                "name": "Walk", "startDate": "2019-01-01", "startTime": "12:00", "steps": 4201
+            }],
+            "summary": {
+                "activeScore": -1, "activityCalories": 230, "caloriesBMR": 1913,
+                "caloriesEstimationMu": 2143, "caloriesOut": 2143,
+                "caloriesOutUnestimated": 2143,
+                "customHeartRateZones": [
+                    {"caloriesOut": 412.6, "max": 150, "min": 120, "minutes": 34, "name": "Tempo"}
+                ],
+                "distances": [{"activity": "total", "distance": 3.2}],
+                "elevation": 12.19, "fairlyActiveMinutes": 0, "floors": 4,
+                "heartRateZones": [
+                    {"caloriesOut": 1979.7, "max": 96, "min": 30, "minutes": 1224, "name": "Out of Range"},
+                    {"caloriesOut": 510.3, "max": 134, "min": 96, "minutes": 181, "name": "Fat Burn"},
+                    {"caloriesOut": 0, "max": 163, "min": 134, "minutes": 0, "name": "Cardio"},
+                    {"caloriesOut": 0, "max": 220, "min": 163, "minutes": 0, "name": "Peak"}
+                ],
+                "lightlyActiveMinutes": 67, "marginalCalories": 144, "restingHeartRate": 69,
+                "sedentaryMinutes": 1032, "steps": 4201, "useEstimation": true,
+                "veryActiveMinutes": 0
+            },
+            "goals": {
+                "activeMinutes": 30, "caloriesOut": 2826, "distance": 8.05, "floors": 10,
+                "steps": 10000
+            }
+        }"#;
+
+        let response: ActivitySummaryResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert!(response.summary.use_estimation);
+        assert_eq!(response.summary.calories_out_unestimated, Some(2143));
+        assert_eq!(response.summary.calories_estimation_mu, Some(2143));
+        assert_eq!(response.summary.custom_heart_rate_zones[0].name, "Tempo");
+        assert!(response.validate().is_ok());
+    }
+}
@@ -0,0 +1,141 @@
+//! Water logs.
+//!
+//! Fetched amounts are in the water unit of the client's unit system: milliliters for
+//! metric and UK units, fluid ounces for US units.
+
+use crate::units::{VolumeUnit, VolumeValue};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// A logged amount of water
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterLog {
+    pub log_id: u64,
+    pub amount: VolumeValue,
+}
+
+/// The water logs of a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterLogs {
+    pub date: NaiveDate,
+    /// Total amount of water logged on the day
+    pub total: VolumeValue,
+    pub logs: Vec<WaterLog>,
+}
+
+/// The daily water goal
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterGoal {
+    pub goal: VolumeValue,
+    /// The date the goal was set
+    pub start_date: NaiveDate,
+}
+
+/// Raw water log response
+#[derive(Debug, Deserialize)]
+pub(crate) struct WaterLogResponse {
+    summary: WaterSummary,
+    water: Vec<RawWaterLog>,
+}
+
+/// Response of the create water log endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatedWaterLog {
+    pub water_log: RawWaterLog,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaterSummary {
+    water: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawWaterLog {
+    pub log_id: u64,
+    amount: f64,
+}
+
+/// Raw water goal response
+#[derive(Debug, Deserialize)]
+pub(crate) struct WaterGoalResponse {
+    goal: RawWaterGoal,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawWaterGoal {
+    goal: f64,
+    start_date: NaiveDate,
+}
+
+impl WaterGoalResponse {
+    /// Returns the water goal with the amount in the given unit
+    pub(crate) fn into_water_goal(self, unit: VolumeUnit) -> WaterGoal {
+        WaterGoal {
+            goal: VolumeValue::new(self.goal.goal, unit),
+            start_date: self.goal.start_date,
+        }
+    }
+}
+
+impl WaterLogResponse {
+    /// Returns the water logs of the requested date with amounts in the given unit
+    pub(crate) fn into_water_logs(self, date: NaiveDate, unit: VolumeUnit) -> WaterLogs {
+        WaterLogs {
+            date,
+            total: VolumeValue::new(self.summary.water, unit),
+            logs: self
+                .water
+                .into_iter()
+                .map(|log| WaterLog {
+                    log_id: log.log_id,
+                    amount: VolumeValue::new(log.amount, unit),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_water_logs() {
+        let json_str = r#"{
+            "summary": {"water": 800},
+            "water": [
+                {"amount": 500, "logId": 508693835},
+                {"amount": 300, "logId": 508693836}
+            ]
+        }"#;
+
+        let response: WaterLogResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let date = NaiveDate::from_ymd_opt(2019, 3, 1).unwrap();
+        let logs = response.into_water_logs(date, VolumeUnit::Milliliters);
+
+        assert_eq!(logs.date, date);
+        assert_eq!(logs.total, VolumeValue::new(800.0, VolumeUnit::Milliliters));
+        assert_eq!(logs.logs.len(), 2);
+        assert_eq!(logs.logs[1].log_id, 508693836);
+        assert_eq!(logs.logs[1].amount.value, 300.0);
+    }
+
+    #[test]
+    fn test_parse_water_goal() {
+        let json_str = r#"{"goal": {"goal": 64, "startDate": "2019-03-01"}}"#;
+
+        let response: WaterGoalResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+        let goal = response.into_water_goal(VolumeUnit::FluidOunces);
+
+        assert_eq!(goal.goal, VolumeValue::new(64.0, VolumeUnit::FluidOunces));
+        assert_eq!(
+            goal.start_date,
+            NaiveDate::from_ymd_opt(2019, 3, 1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Notifications of the Subscriptions API.
+//!
+//! Fitbit notifies subscribers about new data by POSTing a JSON array of notifications to
+//! the subscriber endpoint. Each request is signed with the application's client secret;
+//! requests with an invalid signature must be ignored, since anyone can reach the endpoint.
+//!
+//! Before sending notifications, Fitbit verifies the subscriber endpoint with two GET
+//! requests carrying a `verify` query parameter, one with the verification code configured
+//! for the application and one with a wrong code; see [`verify_subscriber`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fitbit_rs::webhook::{SIGNATURE_HEADER, parse_notifications, verify_signature};
+//!
+//! fn handle_notification(body: &[u8], signature: &str) -> Result<(), fitbit_rs::FitbitError> {
+//!     // `signature` is the value of the `SIGNATURE_HEADER` request header
+//!     if !verify_signature(body, signature, "your_client_secret") {
+//!         // Respond with 404 and ignore the notification
+//!         return Ok(());
+//!     }
+//!
+//!     for notification in parse_notifications(body)? {
+//!         println!("New {:?} data for {:?}", notification.collection_type, notification.date);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::error::{FitbitError, parse_json};
+use base64::Engine;
+use chrono::NaiveDate;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+/// The request header carrying the signature of a notification
+pub const SIGNATURE_HEADER: &str = "X-Fitbit-Signature";
+
+/// A notification that a user's data changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub collection_type: CollectionType,
+    /// The date of the changed data; not reported for account notifications
+    pub date: Option<NaiveDate>,
+    /// The encoded id of the user whose data changed
+    pub owner_id: String,
+    /// The kind of owner, `user`
+    pub owner_type: String,
+    /// The id the subscription was created with
+    pub subscription_id: String,
+}
+
+/// The kind of data a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollectionType {
+    Activities,
+    Body,
+    Foods,
+    Sleep,
+    /// The user revoked the application's access
+    UserRevokedAccess,
+    /// The user deleted their account
+    DeleteUser,
+    /// A collection this crate doesn't know yet
+    #[serde(other)]
+    Other,
+}
+
+/// The answer to a subscriber verification request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriberVerification {
+    /// The verification code is correct
+    Verified,
+    /// The verification code is wrong
+    Rejected,
+}
+
+impl SubscriberVerification {
+    /// Returns the HTTP status code to respond with: 204 if verified, 404 if rejected
+    pub fn status_code(&self) -> u16 {
+        match self {
+            SubscriberVerification::Verified => 204,
+            SubscriberVerification::Rejected => 404,
+        }
+    }
+}
+
+/// Checks a subscriber verification request
+///
+/// # Arguments
+///
+/// * `query` - The query string of the GET request, with or without the leading `?`
+/// * `verification_code` - The subscriber verification code of the application
+///
+/// # Returns
+///
+/// Whether the request carries the verification code, or `None` if it has no `verify`
+/// parameter and is not a verification request
+///
+/// # Example
+///
+/// ```
+/// use fitbit_rs::webhook::{SubscriberVerification, verify_subscriber};
+///
+/// let code = "d49c1ad3c0e6b2aa2ed23b4c4a0c3bc4";
+/// let verification = verify_subscriber("verify=d49c1ad3c0e6b2aa2ed23b4c4a0c3bc4", code);
+/// assert_eq!(verification, Some(SubscriberVerification::Verified));
+/// assert_eq!(verification.unwrap().status_code(), 204);
+/// assert_eq!(verify_subscriber("verify=wrong", code).unwrap().status_code(), 404);
+/// ```
+pub fn verify_subscriber(query: &str, verification_code: &str) -> Option<SubscriberVerification> {
+    let code = query
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("verify="))?;
+
+    if code == verification_code {
+        Some(SubscriberVerification::Verified)
+    } else {
+        Some(SubscriberVerification::Rejected)
+    }
+}
+
+/// Parses the notifications of a subscriber request
+///
+/// # Arguments
+///
+/// * `body` - The raw request body
+///
+/// # Returns
+///
+/// The notifications or an error if the body is not a notification array
+pub fn parse_notifications(body: &[u8]) -> Result<Vec<Notification>, FitbitError> {
+    parse_json(body)
+}
+
+/// Verifies the signature of a subscriber request
+///
+/// The signature is the Base64 encoded HMAC-SHA1 of the raw body, keyed with the client
+/// secret followed by `&`. The comparison takes constant time.
+///
+/// # Arguments
+///
+/// * `body` - The raw request body, exactly as received
+/// * `signature` - The value of the [`SIGNATURE_HEADER`] header
+/// * `client_secret` - The client secret of the application
+///
+/// # Returns
+///
+/// `true` if the request was signed with the client secret
+pub fn verify_signature(body: &[u8], signature: &str, client_secret: &str) -> bool {
+    let Ok(signature) = base64::engine::general_purpose::STANDARD.decode(signature.trim()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(format!("{}&", client_secret).as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = br#"[{"collectionType":"foods","date":"2020-06-01","ownerId":"228S74","ownerType":"user","subscriptionId":"1234"}]"#;
+
+    #[test]
+    fn test_verify_signature() {
+        let signature = "P2fK4HkiMRUo+hhpM+RIQmiQqdE=";
+
+        assert!(verify_signature(BODY, signature, "0123456789abcdef"));
+        assert!(!verify_signature(BODY, signature, "another secret"));
+        assert!(!verify_signature(&BODY[1..], signature, "0123456789abcdef"));
+        assert!(!verify_signature(BODY, "not base64!", "0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_verify_subscriber() {
+        let code = "d49c1ad3c0e6b2aa2ed23b4c4a0c3bc4";
+
+        assert_eq!(
+            verify_subscriber("?verify=d49c1ad3c0e6b2aa2ed23b4c4a0c3bc4", code),
+            Some(SubscriberVerification::Verified)
+        );
+        assert_eq!(
+            verify_subscriber(
+                "source=fitbit&verify=d49c1ad3c0e6b2aa2ed23b4c4a0c3bc4",
+                code
+            ),
+            Some(SubscriberVerification::Verified)
+        );
+        assert_eq!(
+            verify_subscriber("verify=incorrect", code),
+            Some(SubscriberVerification::Rejected)
+        );
+        assert_eq!(
+            verify_subscriber("verify=", code),
+            Some(SubscriberVerification::Rejected)
+        );
+        assert_eq!(verify_subscriber("", code), None);
+        assert_eq!(verify_subscriber("verified=true", code), None);
+    }
+
+    #[test]
+    fn test_parse_notifications() {
+        let notifications = parse_notifications(BODY).expect("Failed to parse notifications");
+
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].collection_type, CollectionType::Foods);
+        assert_eq!(notifications[0].owner_id, "228S74");
+        assert_eq!(notifications[0].subscription_id, "1234");
+
+        let notifications = parse_notifications(
+            br#"[{"collectionType":"userRevokedAccess","ownerId":"228S74","ownerType":"user","subscriptionId":"1234"}]"#,
+        )
+        .expect("Failed to parse notifications");
+        assert_eq!(
+            notifications[0].collection_type,
+            CollectionType::UserRevokedAccess
+        );
+        assert_eq!(notifications[0].date, None);
+    }
+}
@@ -0,0 +1,255 @@
+//! Body weight logs and time series.
+//!
+//! Weights are reported in the weight unit of the client's unit system: kilograms for
+//! metric, pounds for US and stone for UK units.
+
+use crate::activity_time_series::deserialize_string_number;
+use crate::units::{UnitSystem, WeightValue};
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// A weight measurement, logged manually or by a smart scale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightLog {
+    pub log_id: u64,
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+    pub weight: f64,
+    pub bmi: f64,
+    /// Body fat percentage; only reported if it was measured or logged
+    pub fat: Option<f64>,
+    pub source: WeightLogSource,
+    /// The unit system `weight` was requested in; set by the client
+    #[serde(default)]
+    pub unit_system: UnitSystem,
+}
+
+impl WeightLog {
+    /// Returns the weight with its unit
+    pub fn weight_value(&self) -> WeightValue {
+        WeightValue::new(self.weight, self.unit_system.weight_unit())
+    }
+}
+
+/// Where a weight log came from
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum WeightLogSource {
+    /// Logged by a third-party app through the API
+    Api,
+    /// Measured by a Fitbit Aria scale
+    Aria,
+    /// Measured by a Fitbit Aria Air scale
+    AriaAir,
+    /// Logged manually on the Fitbit website or app
+    Web,
+    /// Synced from a Withings scale
+    Withings,
+    /// Any source this crate doesn't know yet
+    Other(String),
+}
+
+impl WeightLogSource {
+    /// Returns the source value used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            WeightLogSource::Api => "API",
+            WeightLogSource::Aria => "Aria",
+            WeightLogSource::AriaAir => "AriaAir",
+            WeightLogSource::Web => "Web",
+            WeightLogSource::Withings => "Withings",
+            WeightLogSource::Other(value) => value,
+        }
+    }
+}
+
+impl From<WeightLogSource> for String {
+    fn from(value: WeightLogSource) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for WeightLogSource {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "API" => WeightLogSource::Api,
+            "Aria" => WeightLogSource::Aria,
+            "AriaAir" => WeightLogSource::AriaAir,
+            "Web" => WeightLogSource::Web,
+            "Withings" => WeightLogSource::Withings,
+            _ => WeightLogSource::Other(value),
+        }
+    }
+}
+
+/// Raw weight log response
+#[derive(Debug, Deserialize)]
+pub(crate) struct WeightLogResponse {
+    pub weight: Vec<WeightLog>,
+}
+
+/// Response of the create weight log endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatedWeightLog {
+    pub weight_log: WeightLog,
+}
+
+/// Raw weight time series response
+#[derive(Debug, Deserialize)]
+pub(crate) struct WeightTimeSeriesResponse {
+    #[serde(rename = "body-weight")]
+    values: Vec<WeightTimeSeriesEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WeightTimeSeriesEntry {
+    date_time: NaiveDate,
+    #[serde(deserialize_with = "deserialize_string_number")]
+    value: f64,
+}
+
+impl WeightTimeSeriesResponse {
+    /// Returns the daily weights in chronological order
+    pub(crate) fn into_values(self) -> Vec<(NaiveDate, f64)> {
+        let mut values: Vec<(NaiveDate, f64)> = self
+            .values
+            .into_iter()
+            .map(|entry| (entry.date_time, entry.value))
+            .collect();
+        values.sort_by_key(|(date, _)| *date);
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::WeightUnit;
+
+    fn weight_log_response() -> WeightLogResponse {
+        let json_str = r#"{
+            "weight": [
+                {
+                    "bmi": 23.57,
+                    "date": "2019-03-01",
+                    "fat": 18.2,
+                    "logId": 1551425894000,
+                    "source": "Aria",
+                    "time": "07:38:14",
+                    "weight": 72.5
+                },
+                {
+                    "bmi": 23.41,
+                    "date": "2019-03-01",
+                    "logId": 1551469200000,
+                    "source": "Garmin",
+                    "time": "19:40:00",
+                    "weight": 72.0
+                }
+            ]
+        }"#;
+
+        serde_json::from_str(json_str).expect("Failed to parse JSON")
+    }
+
+    #[test]
+    fn test_parse_weight_logs() {
+        let response = weight_log_response();
+
+        assert_eq!(response.weight.len(), 2);
+        let aria = &response.weight[0];
+        assert_eq!(aria.log_id, 1551425894000);
+        assert_eq!(aria.time, NaiveTime::from_hms_opt(7, 38, 14).unwrap());
+        assert_eq!(aria.weight, 72.5);
+        assert_eq!(
+            aria.weight_value(),
+            WeightValue::new(72.5, WeightUnit::Kilograms)
+        );
+        assert_eq!(aria.fat, Some(18.2));
+        assert_eq!(aria.source, WeightLogSource::Aria);
+
+        assert_eq!(response.weight[1].fat, None);
+        assert_eq!(
+            response.weight[1].source,
+            WeightLogSource::Other("Garmin".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let logs = weight_log_response().weight;
+
+        let serialized = serde_json::to_value(&logs).expect("Failed to serialize");
+        assert_eq!(serialized[0]["logId"], 1551425894000u64);
+        assert_eq!(serialized[0]["time"], "07:38:14");
+        assert_eq!(serialized[0]["source"], "Aria");
+        assert_eq!(serialized[1]["source"], "Garmin");
+        assert!(serialized[1]["fat"].is_null());
+
+        let round_tripped: Vec<WeightLog> =
+            serde_json::from_value(serialized).expect("Failed to parse serialized JSON");
+        assert_eq!(round_tripped, logs);
+    }
+
+    #[test]
+    fn test_serialize_unknown_source() {
+        let json = serde_json::json!({
+            "bmi": 23.41,
+            "date": "2019-03-01",
+            "fat": 17.9,
+            "logId": 1551469200000u64,
+            "source": "Garmin",
+            "time": "19:40:00",
+            "unitSystem": "en_GB",
+            "weight": 72.0
+        });
+
+        let log: WeightLog = serde_json::from_value(json.clone()).expect("Failed to parse JSON");
+
+        assert_eq!(
+            serde_json::to_value(&log).expect("Failed to serialize"),
+            json
+        );
+    }
+
+    #[test]
+    fn test_parse_created_weight_log() {
+        let json_str = r#"{
+            "weightLog": {
+                "bmi": 23.57,
+                "date": "2019-03-01",
+                "logId": 1551425894000,
+                "source": "API",
+                "time": "07:38:14",
+                "weight": 72.5
+            }
+        }"#;
+
+        let created: CreatedWeightLog =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        assert_eq!(created.weight_log.source, WeightLogSource::Api);
+        assert_eq!(created.weight_log.bmi, 23.57);
+    }
+
+    #[test]
+    fn test_parse_weight_time_series() {
+        let json_str = r#"{
+            "body-weight": [
+                {"dateTime": "2019-03-02", "value": "72.1"},
+                {"dateTime": "2019-03-01", "value": "72.5"}
+            ]
+        }"#;
+
+        let response: WeightTimeSeriesResponse =
+            serde_json::from_str(json_str).expect("Failed to parse JSON");
+
+        let date = |day| NaiveDate::from_ymd_opt(2019, 3, day).unwrap();
+        assert_eq!(response.into_values(), [(date(1), 72.5), (date(2), 72.1)]);
+    }
+}